@@ -0,0 +1,71 @@
+//! Times `Node::k_nearest` against scanning and sorting every node, run with `cargo bench --bench k_nearest`.
+
+use std::{hint::black_box, time::{Duration, Instant}};
+
+use cgmath::Vector3;
+use forte_world::{define_world, dimensions::Dimensions};
+
+pub struct BenchApp;
+
+#[derive(Debug)]
+pub struct Point;
+
+define_world!(BenchApp, [Point => { DATA => Point }]);
+
+// a small xorshift generator, so every run times the same tree
+fn xorshift(mut state: u64) -> impl FnMut() -> f32 {
+    move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % 10_000) as f32 / 100.0
+    }
+}
+
+// a tree of clusters of points spread over a 100 unit cube, so whole clusters can be skipped
+fn world(clusters: usize, per_cluster: usize) -> World {
+    let mut next = xorshift(0x9e37_79b9_7f4a_7c15);
+    let mut world = World::new();
+    let root = world.root_id();
+    for _ in 0..clusters {
+        let mut cluster = Node::default();
+        cluster.transform.position = Vector3::new(next(), next(), next());
+        let cluster = world.spawn(root, cluster).unwrap();
+        for _ in 0..per_cluster {
+            let mut point = Node::with_point(Point);
+            point.transform.position = Vector3::new(next() / 20.0, next() / 20.0, next() / 20.0);
+            point.rel_min_dimensions = Dimensions { from: Vector3::new(-0.1, -0.1, -0.1), to: Vector3::new(0.1, 0.1, 0.1) };
+            world.spawn(cluster, point).unwrap();
+        }
+    }
+    world.update(&mut BenchApp, 0.0);
+    world
+}
+
+// runs the given function over and over for about a second, giving the average time of a run
+fn time(mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    let mut runs = 0;
+    while start.elapsed() < Duration::from_secs(1) {
+        f();
+        runs += 1;
+    }
+    start.elapsed() / runs
+}
+
+fn main() {
+    for (clusters, per_cluster) in [(10, 100), (100, 100), (100, 1000)] {
+        let world = world(clusters, per_cluster);
+        let point = Vector3::new(50.0, 50.0, 50.0);
+        let is_point = |node: &Node| node.get::<Point>().is_some();
+
+        let tree = time(|| { black_box(world.root().k_nearest(black_box(point), 8, f32::INFINITY, is_point)); });
+        let scan = time(|| {
+            let mut all: Vec<(&Node, f32)> = world.iter(false).filter(|node| is_point(node)).map(|node| (node, node.own_dimensions().distance_to(point))).collect();
+            all.sort_by(|a, b| a.1.total_cmp(&b.1));
+            all.truncate(8);
+            black_box(all);
+        });
+        println!("{} nodes: k_nearest {:?}, scan {:?}", clusters * (per_cluster + 1), tree, scan);
+    }
+}
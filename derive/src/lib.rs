@@ -0,0 +1,615 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse::ParseStream, parse_macro_input, parse_quote, DeriveInput, Error, Expr, Fields, Ident, ItemEnum, Token, Type};
+
+// every key a world_component attribute may give other than app and pass, in the order of the keys of define_world!
+const KEYS: &[&str] = &[
+    "added", "update", "render", "removed", "start", "late_update", "fixed_update", "input", "resize", "on_enable",
+    "on_disable", "prepare", "priority", "casts_shadow", "inspect", "debug_draw", "sort_key", "remap_assets", "save",
+    "load"
+];
+
+/// Implements `forte_world::components::WorldComponent` for a component data type, so it can be used as a variant of
+/// an enum assembled with `#[world]`.
+///
+/// The callbacks are given by a `#[world_component(...)]` attribute, with a key for each callback of `define_world!`
+/// in lower case, IE `update = Self::tick` or `priority = -10`.  Each callback is a function or a closure that captures
+/// nothing, taking the data first and then the same arguments as the matching `WorldComponent` function.  An
+/// `app = App` key fixes the app the component is used with, otherwise the component can be used with any app.  A
+/// `render` callback is given a `wgpu::RenderPass<'a>` unless another pass is given with `pass = Shadows<'a>`, and
+/// the component can then only be used in worlds rendering into that pass.  The `save` and `load` keys need
+/// forte_world's serde feature.
+#[proc_macro_derive(WorldComponent, attributes(world_component))]
+pub fn derive_world_component(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    world_component(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Assembles a component enum out of data types that implement `WorldComponent`, IE with
+/// `#[derive(WorldComponent)]`, generating the same items as `define_world!` for it.
+///
+/// The attribute is given the app type, and each variant of the enum must hold a single distinct data type.  An
+/// `Empty` default variant is added to the enum, other attributes on the enum, IE derives, are kept.  Components render
+/// into a `wgpu::RenderPass<'a>` unless another pass is given after the app, IE `#[world(App, pass = Shadows<'a>)]`,
+/// like PASS in `define_world!`.
+#[proc_macro_attribute]
+pub fn world(args: TokenStream, input: TokenStream) -> TokenStream {
+    let (app, pass) = parse_macro_input!(args with world_args);
+    let item = parse_macro_input!(input as ItemEnum);
+    world_enum(app, pass, item).unwrap_or_else(Error::into_compile_error).into()
+}
+
+// reads the app type given to #[world] and the optional pass type after it
+fn world_args(input: ParseStream) -> syn::Result<(Type, Type)> {
+    let app = input.parse()?;
+    let mut pass = parse_quote!(wgpu::RenderPass<'a>);
+    if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+        let key: Ident = input.parse()?;
+        if key != "pass" { return Err(Error::new_spanned(&key, format!("unknown world key `{key}`, expected pass"))) }
+        input.parse::<Token![=]>()?;
+        pass = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+    }
+    Ok((app, pass))
+}
+
+// reads the world_component attributes of a data type and implements WorldComponent with them
+fn world_component(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let mut app: Option<Type> = None;
+    let mut pass: Type = parse_quote!(wgpu::RenderPass<'a>);
+    let mut hooks: Vec<(Ident, Expr)> = Vec::new();
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("world_component")) {
+        attr.parse_nested_meta(|meta| {
+            let Some(key) = meta.path.get_ident().cloned() else { return Err(meta.error("expected a world_component key")) };
+            if key == "app" {
+                app = Some(meta.value()?.parse()?);
+                return Ok(())
+            }
+            if key == "pass" {
+                pass = meta.value()?.parse()?;
+                return Ok(())
+            }
+
+            if !KEYS.contains(&key.to_string().as_str()) {
+                return Err(meta.error(format!("unknown world_component key `{key}`, expected one of: app, pass, {}", KEYS.join(", "))))
+            }
+            if hooks.iter().any(|(other, _)| *other == key) { return Err(meta.error(format!("world_component key `{key}` is given more than once"))) }
+            if (key == "save" || key == "load") && !cfg!(feature = "serde") {
+                return Err(meta.error(format!("world_component key `{key}` needs forte_world's serde feature")))
+            }
+            hooks.push((key, meta.value()?.parse()?));
+            Ok(())
+        })?;
+    }
+
+    // components without a fixed app are implemented for every app
+    let mut generics = input.generics.clone();
+    let app = match app {
+        Some(app) => app,
+        None => {
+            generics.params.push(parse_quote!(__A));
+            parse_quote!(__A)
+        }
+    };
+    generics.params.push(parse_quote!(__C: ::forte_world::nodes::ComponentDef<#app>));
+
+    // a render callback takes a known pass, so it can only be used by components rendering into that pass, which
+    // needs the component and app to be 'static to name the pass for every lifetime
+    if hooks.iter().any(|(key, _)| key == "render") {
+        let predicates = &mut generics.make_where_clause().predicates;
+        predicates.push(parse_quote!(__C: 'static + for<'a> ::forte_world::nodes::ComponentDef<#app, Pass<'a> = #pass>));
+        predicates.push(parse_quote!(#app: 'static));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let items = hooks.iter().map(|(key, hook)| hook_item(key, hook, &app, &pass));
+
+    Ok(quote! {
+        impl #impl_generics ::forte_world::components::WorldComponent<__C, #app> for #name #ty_generics #where_clause {
+            #(#items)*
+        }
+    })
+}
+
+// the items of the WorldComponent implementation for the given key, each callback is coerced to a function pointer
+// first so closures do not need their argument types written out
+fn hook_item(key: &Ident, hook: &Expr, app: &Type, pass: &Type) -> TokenStream2 {
+    let ctx = quote!(&mut ::forte_world::nodes::NodeContext<'_, __C, #app>);
+    match key.to_string().as_str() {
+        "added" | "start" | "on_enable" | "on_disable" => quote! {
+            fn #key(&mut self, ctx: #ctx) {
+                let hook: fn(&mut Self, #ctx) = #hook;
+                hook(self, ctx)
+            }
+        },
+        "late_update" => quote! {
+            const LATE_UPDATES: bool = true;
+            fn late_update(&mut self, ctx: #ctx) {
+                let hook: fn(&mut Self, #ctx) = #hook;
+                hook(self, ctx)
+            }
+        },
+        "fixed_update" => quote! {
+            const FIXED_UPDATES: bool = true;
+            fn fixed_update(&mut self, ctx: #ctx) {
+                let hook: fn(&mut Self, #ctx) = #hook;
+                hook(self, ctx)
+            }
+        },
+        "update" => quote! {
+            fn update(&mut self, app: &mut #app, ctx: #ctx, dt: f32) {
+                let hook: fn(&mut Self, &mut #app, #ctx, f32) = #hook;
+                hook(self, app, ctx, dt)
+            }
+        },
+        "removed" => quote! {
+            fn removed(&mut self, ctx: #ctx, reason: &::forte_world::callbacks::RemovalReason) {
+                let hook: fn(&mut Self, #ctx, &::forte_world::callbacks::RemovalReason) = #hook;
+                hook(self, ctx, reason)
+            }
+        },
+        "input" => quote! {
+            const INPUTS: bool = true;
+            fn input(&mut self, ctx: #ctx, input: &::forte_engine::render::input::EngineInput) -> bool {
+                let hook: fn(&mut Self, #ctx, &::forte_engine::render::input::EngineInput) -> bool = #hook;
+                hook(self, ctx, input)
+            }
+        },
+        "prepare" => quote! {
+            const PREPARES: bool = true;
+            fn prepare(&mut self, ctx: #ctx, engine: &mut ::forte_engine::render::render_engine::RenderEngine) {
+                let hook: fn(&mut Self, #ctx, &mut ::forte_engine::render::render_engine::RenderEngine) = #hook;
+                hook(self, ctx, engine)
+            }
+        },
+        "resize" => quote! {
+            const RESIZES: bool = true;
+            fn resize(&mut self, ctx: #ctx, size: ::forte_world::winit::dpi::PhysicalSize<u32>) {
+                let hook: fn(&mut Self, #ctx, ::forte_world::winit::dpi::PhysicalSize<u32>) = #hook;
+                hook(self, ctx, size)
+            }
+        },
+        "render" => quote! {
+            const RENDERS: bool = true;
+            fn render<'a, 'b>(&'b self, pass: &mut __C::Pass<'a>, app: &'b #app) where 'b: 'a {
+                let hook: fn(&'b Self, &mut #pass, &'b #app) = #hook;
+                hook(self, pass, app)
+            }
+        },
+        "priority" => quote! { const PRIORITY: i32 = #hook; },
+        "casts_shadow" => quote! { const CASTS_SHADOW: bool = #hook; },
+        "sort_key" => quote! {
+            const SORTED: bool = true;
+            fn sort_key(&self) -> Option<u64> {
+                let hook: fn(&Self) -> u64 = #hook;
+                Some(hook(self))
+            }
+        },
+        "debug_draw" => quote! {
+            fn debug_draw(&self, transform: &::forte_engine::math::transforms::Transform, out: &mut ::forte_world::debug::DebugLines) -> bool {
+                let hook: fn(&Self, &::forte_engine::math::transforms::Transform, &mut ::forte_world::debug::DebugLines) = #hook;
+                hook(self, transform, out);
+                true
+            }
+        },
+        "inspect" => quote! {
+            fn inspect(&mut self, ui: &mut dyn ::forte_world::inspector::Inspector) -> bool {
+                let hook: fn(&mut Self, &mut dyn ::forte_world::inspector::Inspector) = #hook;
+                hook(self, ui);
+                true
+            }
+        },
+        "remap_assets" => quote! {
+            fn remap_assets(&mut self, remap: &dyn Fn(&str) -> Option<String>) -> usize {
+                let hook: fn(&mut Self, &dyn Fn(&str) -> Option<String>) -> usize = #hook;
+                hook(self, remap)
+            }
+        },
+        "save" => quote! {
+            fn save(&self) -> Option<::forte_world::scenes::SceneValue> {
+                let hook: fn(&Self) -> ::forte_world::scenes::SceneValue = #hook;
+                Some(hook(self))
+            }
+        },
+        "load" => quote! {
+            fn load(variant: &str, data: &::forte_world::scenes::SceneValue) -> Result<Self, ::forte_world::scenes::SceneError> {
+                let hook: fn(&::forte_world::scenes::SceneValue) -> Option<Self> = #hook;
+                hook(data).ok_or_else(|| ::forte_world::scenes::SceneError::InvalidData(variant.to_string()))
+            }
+        },
+        _ => unreachable!("keys are checked when read")
+    }
+}
+
+// the snake case form of a variant name, IE cube_model for CubeModel, matching paste's snake case
+fn snake(name: &str) -> String {
+    let mut out = String::new();
+    for (idx, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() && idx > 0 && !out.ends_with('_') { out.push('_'); }
+        out.extend(ch.to_lowercase());
+    }
+    out
+}
+
+// assembles the component enum and generates its ComponentDef implementation, lookups, accessors and aliases
+fn world_enum(app: Type, pass: Type, item: ItemEnum) -> syn::Result<TokenStream2> {
+    if !item.generics.params.is_empty() { return Err(Error::new_spanned(&item.generics, "world component enums cannot be generic")) }
+
+    let mut variants: Vec<(Ident, Type)> = Vec::new();
+    for variant in &item.variants {
+        if variant.ident == "Empty" { return Err(Error::new_spanned(&variant.ident, "the `Empty` variant is added by #[world] and cannot be given")) }
+        if let Some((_, discriminant)) = &variant.discriminant { return Err(Error::new_spanned(discriminant, "world component variants cannot have discriminants")) }
+        match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => variants.push((variant.ident.clone(), fields.unnamed[0].ty.clone())),
+            _ => return Err(Error::new_spanned(variant, "each world component variant must hold a single data type, IE `CubeModel(CubeModel)`"))
+        }
+    }
+
+    let ItemEnum { attrs, vis, ident: name, .. } = &item;
+    let variant_attrs = item.variants.iter().map(|variant| &variant.attrs);
+    let idents: Vec<&Ident> = variants.iter().map(|(ident, _)| ident).collect();
+    let types: Vec<&Type> = variants.iter().map(|(_, ty)| ty).collect();
+    let names: Vec<String> = idents.iter().map(|ident| ident.to_string()).collect();
+    let indices = (0..variants.len()).map(Literal::usize_unsuffixed);
+    let wc: Vec<TokenStream2> = types.iter().map(|ty| quote!(<#ty as ::forte_world::components::WorldComponent<#name, #app>>)).collect();
+
+    // the match arms of a callback given the node, with the callback of each variant given its data and context
+    let node_arms = |call: &dyn Fn(&TokenStream2) -> TokenStream2| -> Vec<TokenStream2> {
+        variants.iter().zip(&wc).map(|((ident, ty), wc)| {
+            let call = call(wc);
+            quote! { #name::#ident(_) => if let Some((data, mut ctx)) = node.split_component::<#ty>() { #call } }
+        }).collect()
+    };
+    let added = node_arms(&|wc| quote!(#wc::added(data, &mut ctx)));
+    let update = node_arms(&|wc| quote!(#wc::update(data, app, &mut ctx, dt)));
+    let start = node_arms(&|wc| quote!(#wc::start(data, &mut ctx)));
+    let late_update = node_arms(&|wc| quote!(#wc::late_update(data, &mut ctx)));
+    let fixed_update = node_arms(&|wc| quote!(#wc::fixed_update(data, &mut ctx)));
+    let on_enable = node_arms(&|wc| quote!(#wc::on_enable(data, &mut ctx)));
+    let on_disable = node_arms(&|wc| quote!(#wc::on_disable(data, &mut ctx)));
+    let resize = node_arms(&|wc| quote!(#wc::resize(data, &mut ctx, size)));
+    let prepare = node_arms(&|wc| quote!(#wc::prepare(data, &mut ctx, engine)));
+    let removed = node_arms(&|wc| quote!(#wc::removed(data, &mut ctx, reason)));
+
+    // scene codecs only exist with forte_world's serde feature, which turns on the same feature here
+    let serde_items = if cfg!(feature = "serde") {
+        quote! {
+            fn save_data(&self) -> Option<::forte_world::scenes::SceneValue> {
+                match self {
+                    #name::Empty => None,
+                    #(#name::#idents(data) => #wc::save(data),)*
+                }
+            }
+
+            fn load_data(variant: &str, data: &::forte_world::scenes::SceneValue) -> Result<Self, ::forte_world::scenes::SceneError> {
+                match variant {
+                    #(#names => #wc::load(variant, data).map(#name::#idents),)*
+                    _ => Err(::forte_world::scenes::SceneError::UnknownVariant(variant.to_string()))
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    let snakes: Vec<String> = names.iter().map(|name| snake(name)).collect();
+    let is = snakes.iter().map(|snake| format_ident!("is_{}", snake));
+    let as_ref = snakes.iter().map(|snake| format_ident!("as_{}", snake));
+    let as_mut = snakes.iter().map(|snake| format_ident!("as_{}_mut", snake));
+    let find: Vec<Ident> = snakes.iter().map(|snake| format_ident!("find_data_{}", snake)).collect();
+    let find_path: Vec<Ident> = snakes.iter().map(|snake| format_ident!("find_data_{}_path", snake)).collect();
+    let with: Vec<Ident> = snakes.iter().map(|snake| format_ident!("with_{}", snake)).collect();
+
+    Ok(quote! {
+        #(#attrs)*
+        #[derive(Default)]
+        #vis enum #name {
+            #[default]
+            Empty,
+            #(#(#variant_attrs)* #idents(#types),)*
+        }
+
+        impl ::forte_world::nodes::ComponentDef<#app> for #name {
+            type Pass<'a> = #pass;
+
+            const VARIANT_NAMES: &'static [&'static str] = &[#(#names),*];
+            const PRIORITIZED: bool = false #(|| #wc::PRIORITY != 0)*;
+            const SORTED: bool = false #(|| #wc::SORTED)*;
+            const SORT_KEYED: &'static [bool] = &[#(#wc::SORTED),*];
+            const LATE_UPDATES: bool = false #(|| #wc::LATE_UPDATES)*;
+            const FIXED_UPDATES: bool = false #(|| #wc::FIXED_UPDATES)*;
+            const INPUTS: bool = false #(|| #wc::INPUTS)*;
+            const RESIZES: bool = false #(|| #wc::RESIZES)*;
+            const PREPARES: bool = false #(|| #wc::PREPARES)*;
+            const RENDERED: &'static [bool] = &[#(#wc::RENDERS),*];
+
+            fn variant_index(&self) -> Option<usize> {
+                match self {
+                    #name::Empty => None,
+                    #(#name::#idents(_) => Some(#indices),)*
+                }
+            }
+
+            fn priority(&self) -> i32 {
+                match self {
+                    #name::Empty => 0,
+                    #(#name::#idents(_) => #wc::PRIORITY,)*
+                }
+            }
+
+            fn casts_shadow(&self) -> bool {
+                match self {
+                    #name::Empty => false,
+                    #(#name::#idents(_) => #wc::CASTS_SHADOW,)*
+                }
+            }
+
+            fn sort_key(&self) -> Option<u64> {
+                match self {
+                    #name::Empty => None,
+                    #(#name::#idents(data) => #wc::sort_key(data),)*
+                }
+            }
+
+            fn debug_draw(&self, transform: &::forte_engine::math::transforms::Transform, out: &mut ::forte_world::debug::DebugLines) -> bool {
+                match self {
+                    #name::Empty => false,
+                    #(#name::#idents(data) => #wc::debug_draw(data, transform, out),)*
+                }
+            }
+
+            fn inspect(&mut self, ui: &mut dyn ::forte_world::inspector::Inspector) -> bool {
+                match self {
+                    #name::Empty => false,
+                    #(#name::#idents(data) => #wc::inspect(data, ui),)*
+                }
+            }
+
+            fn remap_assets(&mut self, remap: &dyn Fn(&str) -> Option<String>) -> usize {
+                match self {
+                    #name::Empty => 0,
+                    #(#name::#idents(data) => #wc::remap_assets(data, remap),)*
+                }
+            }
+
+            #serde_items
+
+            fn added(node: &mut Node) {
+                match &node.component {
+                    #name::Empty => {},
+                    #(#added)*
+                }
+            }
+
+            fn update(node: &mut Node, app: &mut #app, dt: f32) {
+                match &node.component {
+                    #name::Empty => {},
+                    #(#update)*
+                }
+            }
+
+            fn start(node: &mut Node) {
+                match &node.component {
+                    #name::Empty => {},
+                    #(#start)*
+                }
+            }
+
+            fn late_update(node: &mut Node) {
+                match &node.component {
+                    #name::Empty => {},
+                    #(#late_update)*
+                }
+            }
+
+            fn fixed_update(node: &mut Node) {
+                match &node.component {
+                    #name::Empty => {},
+                    #(#fixed_update)*
+                }
+            }
+
+            fn input(node: &mut Node, input: &::forte_engine::render::input::EngineInput) -> bool {
+                match &node.component {
+                    #name::Empty => false,
+                    #(#name::#idents(_) => node.split_component::<#types>().is_some_and(|(data, mut ctx)| #wc::input(data, &mut ctx, input)),)*
+                }
+            }
+
+            fn resize(node: &mut Node, size: ::forte_world::winit::dpi::PhysicalSize<u32>) {
+                match &node.component {
+                    #name::Empty => {},
+                    #(#resize)*
+                }
+            }
+
+            fn prepare(node: &mut Node, engine: &mut ::forte_engine::render::render_engine::RenderEngine) {
+                match &node.component {
+                    #name::Empty => {},
+                    #(#prepare)*
+                }
+            }
+
+            fn on_enable(node: &mut Node) {
+                match &node.component {
+                    #name::Empty => {},
+                    #(#on_enable)*
+                }
+            }
+
+            fn on_disable(node: &mut Node) {
+                match &node.component {
+                    #name::Empty => {},
+                    #(#on_disable)*
+                }
+            }
+
+            fn removed(node: &mut Node, reason: &::forte_world::callbacks::RemovalReason) {
+                match &node.component {
+                    #name::Empty => {},
+                    #(#removed)*
+                }
+            }
+
+            fn render<'a, 'b>(&'b self, pass: &mut Self::Pass<'a>, app: &'b #app) where 'b: 'a {
+                match self {
+                    #name::Empty => {},
+                    #(#name::#idents(data) => #wc::render(data, pass, app),)*
+                }
+            }
+        }
+
+        // the node and world types for this component
+        pub type Node = ::forte_world::nodes::Node<#name, #app>;
+        pub type World = ::forte_world::nodes::World<#name, #app>;
+        pub type NodeContext<'n> = ::forte_world::nodes::NodeContext<'n, #name, #app>;
+
+        // give each variants data type a lookup from the component
+        #(
+            impl ::forte_world::nodes::ComponentData<#name> for #types {
+                fn from_component(component: &#name) -> Option<&Self> {
+                    match component {
+                        #name::#idents(data) => Some(data),
+                        #[allow(unreachable_patterns)]
+                        _ => None
+                    }
+                }
+
+                fn from_component_mut(component: &mut #name) -> Option<&mut Self> {
+                    match component {
+                        #name::#idents(data) => Some(data),
+                        #[allow(unreachable_patterns)]
+                        _ => None
+                    }
+                }
+
+                fn try_from_component(component: #name) -> Result<Self, #name> {
+                    match component {
+                        #name::#idents(data) => Ok(data),
+                        #[allow(unreachable_patterns)]
+                        other => Err(other)
+                    }
+                }
+
+                fn into_component(self) -> #name { #name::#idents(self) }
+            }
+        )*
+
+        // variant checks and accessors for each variant
+        impl #name {
+            // the names of all variants, indexed by their variant index
+            pub const VARIANT_NAMES: &'static [&'static str] = &[#(#names),*];
+
+            #(
+                pub fn #is(&self) -> bool { matches!(self, #name::#idents(_)) }
+
+                pub fn #as_ref(&self) -> Option<&#types> {
+                    match self {
+                        #name::#idents(data) => Some(data),
+                        #[allow(unreachable_patterns)]
+                        _ => None
+                    }
+                }
+
+                pub fn #as_mut(&mut self) -> Option<&mut #types> {
+                    match self {
+                        #name::#idents(data) => Some(data),
+                        #[allow(unreachable_patterns)]
+                        _ => None
+                    }
+                }
+            )*
+        }
+
+        // a kind for each variant
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #vis enum ComponentKind {
+            Empty,
+            #(#idents,)*
+        }
+
+        impl ComponentKind {
+            pub const ALL: &'static [ComponentKind] = &[ComponentKind::Empty, #(ComponentKind::#idents),*];
+
+            pub const fn name(self) -> &'static str {
+                match self {
+                    ComponentKind::Empty => "Empty",
+                    #(ComponentKind::#idents => #names,)*
+                }
+            }
+
+            pub const fn id(self) -> u64 {
+                match self {
+                    ComponentKind::Empty => 0,
+                    #(ComponentKind::#idents => ::forte_world::ids::component_kind_id(#names),)*
+                }
+            }
+
+            pub fn from_id(id: u64) -> Option<Self> { Self::ALL.iter().copied().find(|kind| kind.id() == id) }
+
+            pub fn from_name(name: &str) -> Option<Self> { Self::ALL.iter().copied().find(|kind| kind.name() == name) }
+        }
+
+        impl ::std::fmt::Display for ComponentKind {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result { f.write_str(self.name()) }
+        }
+
+        impl #name {
+            pub fn kind(&self) -> ComponentKind {
+                match self {
+                    #name::Empty => ComponentKind::Empty,
+                    #(#name::#idents(_) => ComponentKind::#idents,)*
+                }
+            }
+        }
+
+        // conversions from each variants data
+        #(
+            impl From<#types> for #name {
+                fn from(data: #types) -> Self { #name::#idents(data) }
+            }
+        )*
+
+        // node constructors for each variant
+        pub trait WithData {
+            #(fn #with(data: #types) -> Self;)*
+        }
+
+        impl WithData for Node {
+            #(
+                fn #with(data: #types) -> Self {
+                    let mut node = Node::default();
+                    node.component = #name::#idents(data);
+                    node
+                }
+            )*
+        }
+
+        // typed search functions for each variant
+        pub trait FindData {
+            #(
+                fn #find(&self, pred: impl Fn(&#types) -> bool) -> Option<(&Node, &#types)>;
+                fn #find_path(&self, pred: impl Fn(&#types) -> bool) -> Option<::forte_world::paths::NodePath>;
+            )*
+        }
+
+        impl FindData for Node {
+            #(
+                fn #find(&self, pred: impl Fn(&#types) -> bool) -> Option<(&Node, &#types)> {
+                    let node = self.find_component(|component| matches!(component, #name::#idents(data) if pred(data)))?;
+                    match &node.component {
+                        #name::#idents(data) => Some((node, data)),
+                        _ => None
+                    }
+                }
+
+                fn #find_path(&self, pred: impl Fn(&#types) -> bool) -> Option<::forte_world::paths::NodePath> {
+                    self.find_component_path(|component| matches!(component, #name::#idents(data) if pred(data)))
+                }
+            )*
+        }
+    })
+}
@@ -1,7 +1,7 @@
-use cgmath::ElementWise;
 use forte_cubes::models::{CubeEngine, cubes::CubeModel, file::SBFile, DrawCubes};
-use forte_engine::{render::{render_engine::RenderEngine, primitives::cameras::{Camera, CameraController}, render_utils}, lights::{LightEngine, SetupLights}, EngineApp, run_app};
-use forte_world::define_world;
+use cgmath::Vector3;
+use forte_engine::{math::transforms::Transform, render::{render_engine::RenderEngine, primitives::cameras::{Camera, CameraController}, render_utils}, lights::{LightEngine, SetupLights}, EngineApp, run_app};
+use forte_world::{define_world, dimensions::Dimensions, nodes::DrawNodes, scene};
 
 define_world!(
     TestApp,
@@ -46,10 +46,10 @@ impl EngineApp for TestApp {
 
         // create nodes
         let mut root = Node::default();
-        let mut model = Node::default();
-        model.component = Component::CubeModel(SBFile::load("assets/warrior.json").as_model(&mut engine));
-        model.rel_min_dimensions = Dimensions { from: Vector3 { x: -1.0, y: -1.0, z: -1.0 }, to: Vector3 { x: 1.0, y: 1.0, z: 1.0 } };
-        root.add_child(model);
+        root.add_child(scene! {
+            component: Component::CubeModel(SBFile::load("assets/warrior.json").as_model(&mut engine)),
+            rel_min_dimensions: Dimensions { from: Vector3 { x: -1.0, y: -1.0, z: -1.0 }, to: Vector3 { x: 1.0, y: 1.0, z: 1.0 } }
+        });
 
         // create final app
         Self {
@@ -60,11 +60,16 @@ impl EngineApp for TestApp {
     }
 
     fn update(&mut self) {
-        self.root.update(&Transform::default());
+        // the root is taken out of the app while it updates, as its update functions are handed the app
+        let mut root = std::mem::take(&mut self.root);
+        root.update(&Transform::default(), self, 1.0 / 60.0);
+        self.root = root;
+
+        // let components upload their buffers before the render pass borrows the engine
+        self.root.prepare(&mut self.render_engine);
 
         // start render
-        let resources = render_utils::prepare_render(&self.render_engine);
-        let mut resources = if resources.is_ok() { resources.unwrap() } else { return };
+        let Ok(mut resources) = render_utils::prepare_render(&self.render_engine) else { return };
 
         {
             // create render pass
@@ -99,7 +104,7 @@ impl EngineApp for TestApp {
             pass.load_lights(&self.light_engine);
 
             // have nodes render to renderables
-            pass.draw_node(&self, &self.root);
+            pass.draw_node(self, &self.root);
         }
 
         // end render
@@ -0,0 +1,73 @@
+use std::{collections::HashMap, fmt};
+
+/// The result of `Node::remap_assets`, counting the asset paths each variant rewrote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemapReport {
+    /// The name of each variant and the number of paths it rewrote, in the order the variants were defined.
+    pub counts: Vec<(&'static str, usize)>
+}
+
+impl RemapReport {
+    /// Creates a new report with a count of 0 for every given variant.
+    pub fn new(variant_names: &[&'static str]) -> Self {
+        Self { counts: variant_names.iter().map(|name| (*name, 0)).collect() }
+    }
+
+    /// Adds to the count of the variant with the given variant index.
+    pub fn add(&mut self, variant: usize, rewritten: usize) { self.counts[variant].1 += rewritten; }
+
+    /// The number of paths the variant with the given name rewrote, 0 if there is no such variant.
+    pub fn for_variant(&self, name: &str) -> usize {
+        self.counts.iter().find(|(variant, _)| *variant == name).map_or(0, |(_, count)| *count)
+    }
+
+    /// The number of paths rewritten by all variants.
+    pub fn total(&self) -> usize { self.counts.iter().map(|(_, count)| count).sum() }
+}
+
+/// A table of old asset paths to their new paths, IE for `Node::remap_assets` after assets are moved.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RemapTable {
+    pub paths: HashMap<String, String>
+}
+
+impl RemapTable {
+    /// Parses a table from lines of `old=new`.  Whitespace around each path is ignored, as are empty lines and lines
+    /// starting with `#`.
+    ///
+    /// Arguments:
+    /// * text: &str - The text of the table.
+    ///
+    /// Returns the table, or an error with the first line that is not `old=new`.
+    pub fn parse(text: &str) -> Result<Self, RemapTableError> {
+        let mut paths = HashMap::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue }
+
+            let Some((old, new)) = line.split_once('=') else { return Err(RemapTableError { line: idx + 1 }) };
+            let (old, new) = (old.trim(), new.trim());
+            if old.is_empty() || new.is_empty() { return Err(RemapTableError { line: idx + 1 }) }
+            paths.insert(old.to_string(), new.to_string());
+        }
+        Ok(Self { paths })
+    }
+
+    /// Gets the new path of the given old path, None if it has not moved.
+    pub fn remap(&self, path: &str) -> Option<String> { self.paths.get(path).cloned() }
+}
+
+/// The error given when a line of a remap table is not `old=new`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemapTableError {
+    /// The line number, starting at 1.
+    pub line: usize
+}
+
+impl fmt::Display for RemapTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {} of the remap table is not of the form old=new", self.line)
+    }
+}
+
+impl std::error::Error for RemapTableError {}
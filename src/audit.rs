@@ -0,0 +1,157 @@
+use std::cell::RefCell;
+
+use crate::nodes::{ComponentDef, Node, World};
+
+thread_local! {
+    // the running counts of the world whose functions are running, see `collect`
+    static COUNTS: RefCell<Option<Vec<i64>>> = const { RefCell::new(None) };
+}
+
+// adds to the count of the given variant if a world is collecting counts, called as ADDED and REMOVED callbacks run
+pub(crate) fn record(variant: usize, change: i64) {
+    COUNTS.with(|counts| {
+        let mut counts = counts.borrow_mut();
+        let Some(counts) = counts.as_mut() else { return };
+        if counts.len() <= variant { counts.resize(variant + 1, 0); }
+        counts[variant] += change;
+    });
+}
+
+// runs the given function with the given counts collecting every ADDED and REMOVED, returning them afterwards.  A
+// function of a world run by another keeps adding to the counts of the outer one.
+pub(crate) fn collect<T>(counts: Vec<i64>, f: impl FnOnce() -> T) -> (T, Vec<i64>) {
+    if COUNTS.with(|outer| outer.borrow().is_some()) { return (f(), counts) }
+
+    COUNTS.with(|outer| *outer.borrow_mut() = Some(counts));
+    let result = f();
+    (result, COUNTS.with(|outer| outer.borrow_mut().take()).unwrap_or_default())
+}
+
+// counts the nodes of each variant in the given tree, adding the given change for each of them
+pub(crate) fn count_tree<C: ComponentDef<A>, A>(node: &Node<C, A>, counts: &mut Vec<i64>, change: i64) {
+    for variant in node.iter().flat_map(|node| node.components()).filter_map(|component| component.variant_index()) {
+        if counts.len() <= variant { counts.resize(variant + 1, 0); }
+        counts[variant] += change;
+    }
+}
+
+/// The ADDED and REMOVED callbacks of one variant counted by a world, see `World::lifecycle_report`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LifecycleCount {
+    /// The name of the variant.
+    pub variant: &'static str,
+
+    /// The number of ADDED callbacks minus the number of REMOVED callbacks, starting from the nodes the world was
+    /// created with.
+    pub live: i64,
+
+    /// The number of nodes in the tree holding the variant.
+    pub in_tree: usize
+}
+
+impl LifecycleCount {
+    /// True if every node holding the variant was added once and not yet removed.
+    pub fn is_balanced(&self) -> bool { self.live == self.in_tree as i64 }
+}
+
+/// The result of `World::lifecycle_report`, a count for each variant in the order the variants were defined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifecycleReport {
+    /// The count of each variant.
+    pub counts: Vec<LifecycleCount>
+}
+
+impl LifecycleReport {
+    /// True if every variant is balanced.
+    pub fn is_balanced(&self) -> bool { self.counts.iter().all(LifecycleCount::is_balanced) }
+
+    /// The counts of the variants that are not balanced.
+    pub fn unbalanced(&self) -> impl Iterator<Item = &LifecycleCount> + '_ { self.counts.iter().filter(|count| !count.is_balanced()) }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Compares the ADDED and REMOVED callbacks fired by the functions of this world with the nodes in its tree, with the
+    /// `lifecycle-audit` feature.  Nodes the world is created or restored with count as added, and nodes taken out with
+    /// `take` as removed.  A variant is unbalanced when a component was replaced without its callbacks, IE by setting
+    /// the `component` field directly, or when callbacks fired through a node borrowed from the world, IE with
+    /// `Node::add_child` on `root_mut`, as only the functions of the world are counted.
+    pub fn lifecycle_report(&self) -> LifecycleReport {
+        let mut in_tree = Vec::new();
+        count_tree(self.root(), &mut in_tree, 1);
+        let counts = C::VARIANT_NAMES.iter().enumerate().map(|(idx, variant)| LifecycleCount {
+            variant,
+            live: self.audit.get(idx).copied().unwrap_or(0),
+            in_tree: in_tree.get(idx).copied().unwrap_or(0) as usize
+        });
+        LifecycleReport { counts: counts.collect() }
+    }
+
+    /// Panics if any variant of the `lifecycle_report` is unbalanced, naming each of them along with its counts.
+    #[track_caller]
+    pub fn assert_balanced(&self) {
+        let report = self.lifecycle_report();
+        if report.is_balanced() { return }
+
+        let unbalanced: Vec<String> = report.unbalanced()
+            .map(|count| format!("{} has {} live from callbacks but {} in the tree", count.variant, count.live, count.in_tree))
+            .collect();
+        panic!("unbalanced component lifecycles: {}", unbalanced.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use crate::test_support::*;
+
+    fn counts(world: &World) -> Vec<(i64, usize)> { world.lifecycle_report().counts.iter().map(|count| (count.live, count.in_tree)).collect() }
+
+    #[test]
+    fn world_functions_stay_balanced() {
+        let mut root = Node::with_marker(Marker(0));
+        root.add_child(Node::with_other(Other));
+        let mut world = World::from_root(root);
+        assert_eq!(counts(&world), [(1, 1), (1, 1)]);
+
+        let a = world.spawn(world.root_id(), Node::with_marker(Marker(1))).unwrap();
+        let b = world.spawn(a, Node::with_marker(Marker(2))).unwrap();
+        world.set_component(b, Component::Other(Other));
+        assert_eq!(counts(&world), [(2, 2), (2, 2)]);
+        world.assert_balanced();
+
+        let snapshot = world.snapshot();
+        let taken = world.take(a).unwrap();
+        assert_eq!(counts(&world), [(1, 1), (1, 1)]);
+        let again = world.spawn(world.root_id(), taken).unwrap();
+        assert_eq!(counts(&world), [(2, 2), (2, 2)]);
+        assert!(world.despawn(again));
+        assert_eq!(counts(&world), [(1, 1), (1, 1)]);
+
+        world.restore(&snapshot);
+        assert_eq!(counts(&world), [(2, 2), (2, 2)]);
+        world.assert_balanced();
+    }
+
+    #[test]
+    fn raw_component_changes_are_caught() {
+        let mut world = World::new();
+        let leaked = world.spawn(world.root_id(), Node::with_marker(Marker(1))).unwrap();
+        let forged = world.spawn(world.root_id(), Node::default()).unwrap();
+
+        // the marker is dropped without its REMOVED, and the other is never ADDED
+        world.get_mut(leaked).unwrap().component = Component::Empty;
+        world.get_mut(forged).unwrap().component = Component::Other(Other);
+        assert!(world.despawn(leaked));
+        assert!(world.despawn(forged));
+        assert_eq!(counts(&world), [(1, 0), (-1, 0)]);
+
+        let report = world.lifecycle_report();
+        assert!(!report.is_balanced());
+        assert_eq!(report.unbalanced().map(|count| count.variant).collect::<Vec<_>>(), ["Marker", "Other"]);
+
+        let message = catch_unwind(AssertUnwindSafe(|| world.assert_balanced())).unwrap_err();
+        let message = message.downcast_ref::<String>().unwrap();
+        assert!(message.contains("Marker has 1 live from callbacks but 0 in the tree"), "{message}");
+    }
+}
@@ -0,0 +1,107 @@
+use cgmath::{Quaternion, Vector3};
+use forte_engine::math::transforms::Transform;
+
+use crate::{
+    dimensions::Dimensions,
+    ids::{NodeId, NodeUuid},
+    math::TransformMode,
+    nodes::{ComponentDef, Node, World}
+};
+
+/// Builds a node one field at a time, see `Node::builder`.
+///
+/// Like `scene!`, no added functions are fired while building, they are fired for every node in the built tree once
+/// it is attached with `add_child` or spawned with `spawn_into`.
+pub struct NodeBuilder<C, A> {
+    node: Node<C, A>
+}
+
+impl<C: ComponentDef<A>, A> Node<C, A> {
+    /// Starts building a new node from the default node.
+    pub fn builder() -> NodeBuilder<C, A> { NodeBuilder { node: Node::default() } }
+}
+
+impl<C: ComponentDef<A>, A> NodeBuilder<C, A> {
+    pub fn transform(mut self, transform: Transform) -> Self {
+        self.node.transform = transform;
+        self
+    }
+
+    pub fn position(mut self, position: Vector3<f32>) -> Self {
+        self.node.transform.position = position;
+        self
+    }
+
+    pub fn rotation(mut self, rotation: Quaternion<f32>) -> Self {
+        self.node.transform.rotation = rotation;
+        self
+    }
+
+    pub fn scale(mut self, scale: Vector3<f32>) -> Self {
+        self.node.transform.scale = scale;
+        self
+    }
+
+    pub fn component(mut self, component: C) -> Self {
+        self.node.component = component;
+        self
+    }
+
+    pub fn rel_min_dimensions(mut self, dimensions: Dimensions) -> Self {
+        self.node.rel_min_dimensions = dimensions;
+        self
+    }
+
+    pub fn transform_mode(mut self, mode: TransformMode) -> Self {
+        self.node.transform_mode = mode;
+        self
+    }
+
+    pub fn confine_to_parent(mut self, confine: bool) -> Self {
+        self.node.confine_to_parent = confine;
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.node.name = Some(name.into());
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.node.enabled = enabled;
+        self
+    }
+
+    pub fn uuid(mut self, uuid: NodeUuid) -> Self {
+        self.node.uuid = Some(uuid);
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.node.add_tag(tag);
+        self
+    }
+
+    pub fn meta(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.node.set_meta(key, value);
+        self
+    }
+
+    /// Adds a child to the end of the children of the node, either a built node or another builder.
+    pub fn child(mut self, child: impl Into<Node<C, A>>) -> Self {
+        self.node.push_detached_child(child.into());
+        self
+    }
+
+    /// Finishes building the node.
+    pub fn build(self) -> Node<C, A> { self.node }
+
+    /// Finishes building the node and spawns it under the given parent, see `World::spawn`.
+    ///
+    /// Returns the id of the spawned node, or None if it could not be spawned.
+    pub fn spawn_into(self, world: &mut World<C, A>, parent: NodeId) -> Option<NodeId> { world.spawn(parent, self.node) }
+}
+
+impl<C: ComponentDef<A>, A> From<NodeBuilder<C, A>> for Node<C, A> {
+    fn from(builder: NodeBuilder<C, A>) -> Self { builder.build() }
+}
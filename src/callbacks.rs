@@ -0,0 +1,319 @@
+use std::{any::Any, cell::RefCell};
+
+use forte_engine::render::{input::EngineInput, render_engine::RenderEngine};
+use winit::dpi::PhysicalSize;
+
+use crate::{ids::NodeId, nodes::{ComponentData, ComponentDef, Node, NodeContext}};
+
+/// Runs a component callback.  When the `catch-callbacks` feature is enabled, a panic in the callback is caught and
+/// returned as an error instead of unwinding through the rest of the update.
+///
+/// The callback is wrapped in `AssertUnwindSafe`, so a caught panic may leave the nodes component half way through a
+/// change.  This is why the node is poisoned afterwards rather than being called again.
+///
+/// Arguments:
+/// * callback: impl FnOnce() - The callback to run.
+///
+/// Returns Ok if the callback finished, or the panic message if it panicked.
+pub fn run_callback(callback: impl FnOnce()) -> Result<(), String> {
+    #[cfg(feature = "catch-callbacks")]
+    {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(callback)).map_err(|payload| panic_message(&*payload))
+    }
+
+    #[cfg(not(feature = "catch-callbacks"))]
+    {
+        callback();
+        Ok(())
+    }
+}
+
+/// A callback panic caught with the `catch-callbacks` feature, given to the observer of `World::set_panic_observer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallbackPanic {
+    /// The id of the poisoned node, None if it has not been given one.
+    pub node: Option<NodeId>,
+
+    /// The name of the variant whose callback panicked.
+    pub variant: &'static str,
+
+    /// The panic message.
+    pub message: String
+}
+
+/// A function given each callback panic caught during the functions of a world, see `World::set_panic_observer`.
+pub type PanicObserver = Box<dyn FnMut(&CallbackPanic) + Send + Sync>;
+
+thread_local! {
+    // the panics caught while a world is collecting them, see `collect_panics`
+    static CAUGHT: RefCell<Option<Vec<CallbackPanic>>> = const { RefCell::new(None) };
+}
+
+/// Reports a caught callback panic through the log, and to the world running the callback if there is one.
+///
+/// Arguments:
+/// * node: Option<NodeId> - The id of the node whose callback panicked.
+/// * variant: &'static str - The name of the variant whose callback panicked.
+/// * message: &str - The panic message.
+pub fn report_panic(node: Option<NodeId>, variant: &'static str, message: &str) {
+    log::error!("{} callback panicked, its node has been poisoned: {}", variant, message);
+    CAUGHT.with(|caught| {
+        if let Some(caught) = caught.borrow_mut().as_mut() { caught.push(CallbackPanic { node, variant, message: message.to_string() }); }
+    });
+}
+
+// runs the given function, returning the panics reported while it ran along with its result
+pub(crate) fn collect_panics<T>(f: impl FnOnce() -> T) -> (T, Vec<CallbackPanic>) {
+    let outer = CAUGHT.with(|caught| caught.borrow_mut().replace(Vec::new()));
+    let result = f();
+    let panics = CAUGHT.with(|caught| std::mem::replace(&mut *caught.borrow_mut(), outer));
+    (result, panics.unwrap_or_default())
+}
+
+/// Gets the message of a panic payload.
+///
+/// Arguments:
+/// * payload: &(dyn Any + Send) - The payload given by `catch_unwind`.
+///
+/// Returns the message if the panic was given a string, otherwise a placeholder.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() { return message.to_string() }
+    if let Some(message) = payload.downcast_ref::<String>() { return message.clone() }
+    "non-string panic payload".to_string()
+}
+
+/// Why a node is being removed, passed to REMOVED callbacks that take a second argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RemovalReason {
+    /// The node itself was removed, IE by `Node::remove_child`.
+    Despawned,
+
+    /// An ancestor of the node was despawned, taking the node with it.
+    ParentDespawned,
+
+    /// All children were cleared at once with `Node::clear_children`.
+    WorldCleared,
+
+    /// The component was replaced with `Node::set_component` or removed with `Node::remove_component`, the node itself
+    /// stays.
+    Replaced,
+
+    /// The node was unloaded, IE with a streamed chunk.  This is never given by the crate itself, it is for apps that
+    /// unload their own content.
+    Unloaded
+}
+
+impl RemovalReason {
+    /// The reason given to the descendants of a node removed for this reason.
+    pub fn cascaded(&self) -> Self {
+        match self {
+            RemovalReason::Despawned => RemovalReason::ParentDespawned,
+            other => *other
+        }
+    }
+}
+
+/// An ADDED, START, LATE_UPDATE or FIXED_UPDATE callback, either taking just the node, or the data of its component and
+/// the rest of the node, see `Node::split_component`.  The last type parameter only tells the kinds apart and is always
+/// inferred.
+pub trait NodeCallback<C, T, A, Kind> {
+    /// Calls this callback with the given node, splitting off the data of its component if this callback takes it.
+    fn call_node(self, node: &mut Node<C, A>);
+}
+
+impl<C, T, A, F: FnOnce(&mut Node<C, A>)> NodeCallback<C, T, A, fn(&mut Node<C, A>)> for F {
+    fn call_node(self, node: &mut Node<C, A>) { self(node) }
+}
+
+impl<C: ComponentDef<A>, T: ComponentData<C>, A, F: FnOnce(&mut T, &mut NodeContext<'_, C, A>)> NodeCallback<C, T, A, fn(&mut T, &mut NodeContext<'_, C, A>)> for F {
+    fn call_node(self, node: &mut Node<C, A>) {
+        if let Some((data, mut ctx)) = node.split_component::<T>() { self(data, &mut ctx) }
+    }
+}
+
+/// An UPDATE callback, either taking just the node, or the app, the data of the nodes component, the rest of the node
+/// and the seconds since the last update.  The last type parameter only tells the kinds apart and is always inferred.
+pub trait UpdateCallback<C, T, A, Kind> {
+    /// Calls this callback with the given node, app and seconds since the last update, dropping the app and time if
+    /// this callback does not take them.
+    fn call_update(self, node: &mut Node<C, A>, app: &mut A, dt: f32);
+}
+
+impl<C, T, A, F: FnOnce(&mut Node<C, A>)> UpdateCallback<C, T, A, fn(&mut Node<C, A>)> for F {
+    fn call_update(self, node: &mut Node<C, A>, _: &mut A, _: f32) { self(node) }
+}
+
+impl<C: ComponentDef<A>, T: ComponentData<C>, A, F: FnOnce(&mut A, &mut T, &mut NodeContext<'_, C, A>, f32)> UpdateCallback<C, T, A, fn(&mut A, &mut T, &mut NodeContext<'_, C, A>, f32)> for F {
+    fn call_update(self, node: &mut Node<C, A>, app: &mut A, dt: f32) {
+        if let Some((data, mut ctx)) = node.split_component::<T>() { self(app, data, &mut ctx, dt) }
+    }
+}
+
+/// A REMOVED callback, either taking just the node, the node and why it is being removed, or the data of the nodes
+/// component, the rest of the node and why it is being removed.  The last type parameter only tells the kinds apart and
+/// is always inferred.
+pub trait RemovedCallback<C, T, A, Kind> {
+    /// Calls this callback with the given node and reason, dropping the reason if this callback does not take it.
+    fn call_removed(self, node: &mut Node<C, A>, reason: &RemovalReason);
+}
+
+impl<C, T, A, F: FnOnce(&mut Node<C, A>)> RemovedCallback<C, T, A, fn(&mut Node<C, A>)> for F {
+    fn call_removed(self, node: &mut Node<C, A>, _: &RemovalReason) { self(node) }
+}
+
+impl<C, T, A, F: FnOnce(&mut Node<C, A>, &RemovalReason)> RemovedCallback<C, T, A, fn(&mut Node<C, A>, &RemovalReason)> for F {
+    fn call_removed(self, node: &mut Node<C, A>, reason: &RemovalReason) { self(node, reason) }
+}
+
+impl<C: ComponentDef<A>, T: ComponentData<C>, A, F: FnOnce(&mut T, &mut NodeContext<'_, C, A>, &RemovalReason)> RemovedCallback<C, T, A, fn(&mut T, &mut NodeContext<'_, C, A>, &RemovalReason)> for F {
+    fn call_removed(self, node: &mut Node<C, A>, reason: &RemovalReason) {
+        if let Some((data, mut ctx)) = node.split_component::<T>() { self(data, &mut ctx, reason) }
+    }
+}
+
+/// An INPUT callback, either taking the node and the input, or the data of the nodes component, the rest of the node and
+/// the input.  Either returns true if it consumed the input.  The last type parameter only tells the kinds apart and is
+/// always inferred.
+pub trait InputCallback<C, T, A, Kind> {
+    /// Calls this callback with the given node and input, returning true if it consumed the input.
+    fn call_input(self, node: &mut Node<C, A>, input: &EngineInput) -> bool;
+}
+
+impl<C, T, A, F: FnOnce(&mut Node<C, A>, &EngineInput) -> bool> InputCallback<C, T, A, fn(&mut Node<C, A>, &EngineInput)> for F {
+    fn call_input(self, node: &mut Node<C, A>, input: &EngineInput) -> bool { self(node, input) }
+}
+
+impl<C: ComponentDef<A>, T: ComponentData<C>, A, F: FnOnce(&mut T, &mut NodeContext<'_, C, A>, &EngineInput) -> bool> InputCallback<C, T, A, fn(&mut T, &mut NodeContext<'_, C, A>, &EngineInput)> for F {
+    fn call_input(self, node: &mut Node<C, A>, input: &EngineInput) -> bool {
+        node.split_component::<T>().is_some_and(|(data, mut ctx)| self(data, &mut ctx, input))
+    }
+}
+
+/// A RESIZE callback, either taking the node and the new size, or the data of the nodes component, the rest of the node
+/// and the new size.  The last type parameter only tells the kinds apart and is always inferred.
+pub trait ResizeCallback<C, T, A, Kind> {
+    /// Calls this callback with the given node and new size.
+    fn call_resize(self, node: &mut Node<C, A>, size: PhysicalSize<u32>);
+}
+
+impl<C, T, A, F: FnOnce(&mut Node<C, A>, PhysicalSize<u32>)> ResizeCallback<C, T, A, fn(&mut Node<C, A>, PhysicalSize<u32>)> for F {
+    fn call_resize(self, node: &mut Node<C, A>, size: PhysicalSize<u32>) { self(node, size) }
+}
+
+impl<C: ComponentDef<A>, T: ComponentData<C>, A, F: FnOnce(&mut T, &mut NodeContext<'_, C, A>, PhysicalSize<u32>)> ResizeCallback<C, T, A, fn(&mut T, &mut NodeContext<'_, C, A>, PhysicalSize<u32>)> for F {
+    fn call_resize(self, node: &mut Node<C, A>, size: PhysicalSize<u32>) {
+        if let Some((data, mut ctx)) = node.split_component::<T>() { self(data, &mut ctx, size) }
+    }
+}
+
+/// A PREPARE callback, either taking the node and the render engine, or the data of the nodes component, the rest of
+/// the node and the render engine.  The last type parameter only tells the kinds apart and is always inferred.
+pub trait PrepareCallback<C, T, A, Kind> {
+    /// Calls this callback with the given node and render engine.
+    fn call_prepare(self, node: &mut Node<C, A>, engine: &mut RenderEngine);
+}
+
+impl<C, T, A, F: FnOnce(&mut Node<C, A>, &mut RenderEngine)> PrepareCallback<C, T, A, fn(&mut Node<C, A>, &mut RenderEngine)> for F {
+    fn call_prepare(self, node: &mut Node<C, A>, engine: &mut RenderEngine) { self(node, engine) }
+}
+
+impl<C: ComponentDef<A>, T: ComponentData<C>, A, F: FnOnce(&mut T, &mut NodeContext<'_, C, A>, &mut RenderEngine)> PrepareCallback<C, T, A, fn(&mut T, &mut NodeContext<'_, C, A>, &mut RenderEngine)> for F {
+    fn call_prepare(self, node: &mut Node<C, A>, engine: &mut RenderEngine) {
+        if let Some((data, mut ctx)) = node.split_component::<T>() { self(data, &mut ctx, engine) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::RemovalReason;
+    use self::world::*;
+
+    thread_local! {
+        static REMOVED: RefCell<Vec<(u32, RemovalReason)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    // takes every removal recorded so far on this thread
+    fn removed() -> Vec<(u32, RemovalReason)> { REMOVED.with(|removed| removed.take()) }
+
+    #[allow(dead_code)]
+    mod world {
+        use super::REMOVED;
+        use crate::callbacks::RemovalReason;
+
+        pub struct TestApp;
+
+        #[derive(Debug)]
+        pub struct Marker(pub u32);
+
+        crate::define_world!(TestApp, [
+            Marker => {
+                DATA => Marker,
+                REMOVED => |data: &mut Marker, _: &mut NodeContext, reason: &RemovalReason| REMOVED.with(|removed| removed.borrow_mut().push((data.0, *reason)))
+            }
+        ]);
+    }
+
+    // a marker node holding a marker child holding a marker grandchild, numbered from the given id
+    fn family(id: u32) -> Node {
+        let mut child = Node::with_marker(Marker(id + 1));
+        child.add_child(Node::with_marker(Marker(id + 2)));
+        let mut node = Node::with_marker(Marker(id));
+        node.add_child(child);
+        node
+    }
+
+    #[test]
+    fn despawn_cascades_to_descendants() {
+        let mut world = World::new();
+        let id = world.spawn(world.root_id(), family(0)).unwrap();
+        assert!(world.despawn(id));
+        assert_eq!(removed(), [(0, RemovalReason::Despawned), (1, RemovalReason::ParentDespawned), (2, RemovalReason::ParentDespawned)]);
+
+        // a lifetime running out despawns the same way
+        let id = world.spawn(world.root_id(), family(10)).unwrap();
+        world.get_mut(id).unwrap().despawn_after(0.5);
+        world.tick(&mut TestApp, 1.0);
+        assert_eq!(removed(), [(10, RemovalReason::Despawned), (11, RemovalReason::ParentDespawned), (12, RemovalReason::ParentDespawned)]);
+    }
+
+    #[test]
+    fn clear_children_gives_every_node_world_cleared() {
+        let mut root = Node::default();
+        root.add_child(family(0));
+        root.add_child(Node::with_marker(Marker(3)));
+        root.clear_children();
+        assert_eq!(removed(), [(0, RemovalReason::WorldCleared), (1, RemovalReason::WorldCleared), (2, RemovalReason::WorldCleared), (3, RemovalReason::WorldCleared)]);
+    }
+
+    #[test]
+    fn replacing_a_component_only_removes_the_old_one() {
+        let mut world = World::new();
+        let id = world.spawn(world.root_id(), family(0)).unwrap();
+        world.set_component(id, Component::Marker(Marker(5)));
+        assert_eq!(removed(), [(0, RemovalReason::Replaced)]);
+
+        world.get_mut(id).unwrap().clear_component();
+        assert_eq!(removed(), [(5, RemovalReason::Replaced)]);
+
+        // an empty node has nothing to remove
+        world.get_mut(id).unwrap().clear_component();
+        assert_eq!(removed(), []);
+    }
+
+    #[test]
+    fn take_fires_nothing_and_reasons_cascade() {
+        let mut world = World::new();
+        let id = world.spawn(world.root_id(), family(0)).unwrap();
+        let taken = world.take(id).unwrap();
+        assert_eq!(removed(), []);
+        drop(taken);
+        assert_eq!(removed(), []);
+
+        assert_eq!(RemovalReason::Despawned.cascaded(), RemovalReason::ParentDespawned);
+        for reason in [RemovalReason::ParentDespawned, RemovalReason::WorldCleared, RemovalReason::Replaced, RemovalReason::Unloaded] {
+            assert_eq!(reason.cascaded(), reason);
+        }
+    }
+}
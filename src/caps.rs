@@ -0,0 +1,357 @@
+use std::collections::VecDeque;
+
+use crate::{
+    errors::WorldError,
+    ids::NodeId,
+    nodes::{ComponentDef, Node, World}
+};
+
+/// What a world does when a spawn would take a variant over its cap, see `World::set_variant_cap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapPolicy {
+    /// The spawn fails with `WorldError::VariantCapped`, and the node is dropped without any callbacks firing.
+    Reject,
+
+    /// The oldest live nodes of the variant are despawned to make room, firing their remove functions.
+    ReplaceOldest,
+
+    /// The spawn goes ahead, and the observer of `World::set_cap_observer` is told.
+    Warn
+}
+
+/// The cap on the live nodes of one variant, see `World::set_variant_cap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VariantCap {
+    pub max_live: usize,
+    pub policy: CapPolicy
+}
+
+/// A spawn that took a variant with the `Warn` policy over its cap, given to the observer of `World::set_cap_observer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapWarning {
+    /// The name of the variant.
+    pub variant: &'static str,
+
+    /// The number of live nodes of the variant once the spawn is done.
+    pub live: usize,
+    pub max_live: usize
+}
+
+/// A function given each `CapWarning` of a world, see `World::set_cap_observer`.
+pub type CapObserver = Box<dyn FnMut(&CapWarning) + Send + Sync>;
+
+/// Names a variant of a component enum, either by its index in the order the variants were defined or by its name.
+pub trait VariantKey {
+    /// The index of the variant among the given variant names, None if there is no such variant.
+    fn index_in(&self, variant_names: &[&'static str]) -> Option<usize>;
+}
+
+impl VariantKey for usize {
+    fn index_in(&self, variant_names: &[&'static str]) -> Option<usize> { Some(*self).filter(|idx| *idx < variant_names.len()) }
+}
+
+impl VariantKey for &str {
+    fn index_in(&self, variant_names: &[&'static str]) -> Option<usize> { variant_names.iter().position(|name| name == self) }
+}
+
+// the caps of a world, and for each variant replaced oldest first the ids of its nodes in the order they were spawned.
+// Ids of nodes that were despawned or changed variant some other way are only dropped once they are reached, the
+// counts of the caps come from the stat counters of the world, so they are exact either way.
+#[derive(Default)]
+pub(crate) struct VariantCaps {
+    caps: Vec<Option<VariantCap>>,
+    order: Vec<VecDeque<NodeId>>,
+    observer: Option<CapObserver>
+}
+
+impl VariantCaps {
+    fn get(&self, variant: usize) -> Option<VariantCap> { self.caps.get(variant).copied().flatten() }
+
+    fn replaces(&self, variant: Option<usize>) -> bool {
+        variant.and_then(|variant| self.get(variant)).is_some_and(|cap| cap.policy == CapPolicy::ReplaceOldest)
+    }
+
+    // records a node becoming the given variant, IE by being spawned
+    pub(crate) fn created(&mut self, variant: Option<usize>, id: NodeId) {
+        if self.replaces(variant) { self.order[variant.unwrap()].push_back(id); }
+    }
+
+    // drops the recorded order of every variant, after the tree was replaced in ways it could not follow
+    pub(crate) fn forget_order(&mut self) { self.order.iter_mut().for_each(VecDeque::clear); }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Caps the number of live nodes of the given variant, named by its index or its name, IE so a runaway particle
+    /// emitter cannot spawn ten thousand nodes in a frame.  The cap is checked by `spawn`, `try_spawn`,
+    /// `insert_child_at`, `merge_subtree_incremental` and spawn commands, counting every node of the variant in the
+    /// spawned subtree, and the policy says what happens to a spawn that would go over it.  Spawns staged in a
+    /// transaction are not capped.
+    ///
+    /// A cap lower than the number of live nodes despawns nothing right away.  Under `ReplaceOldest` the next spawn
+    /// despawns enough of the oldest nodes to bring the count back down to the cap, skipping the parent of the spawn and
+    /// its ancestors, nodes spawned before the cap was set count as older than every node spawned after.
+    ///
+    /// Returns false if there is no such variant.
+    pub fn set_variant_cap(&mut self, variant: impl VariantKey, max_live: usize, policy: CapPolicy) -> bool {
+        let Some(variant) = variant.index_in(C::VARIANT_NAMES) else { return false };
+        let caps = &mut self.caps;
+        if caps.caps.len() <= variant {
+            caps.caps.resize(variant + 1, None);
+            caps.order.resize(variant + 1, VecDeque::new());
+        }
+        caps.caps[variant] = Some(VariantCap { max_live, policy });
+        caps.order[variant].clear();
+        if policy == CapPolicy::ReplaceOldest { self.recollect_order(variant); }
+        true
+    }
+
+    /// Removes the cap on the given variant.
+    ///
+    /// Returns false if there is no such variant.
+    pub fn clear_variant_cap(&mut self, variant: impl VariantKey) -> bool {
+        let Some(variant) = variant.index_in(C::VARIANT_NAMES) else { return false };
+        if let Some(cap) = self.caps.caps.get_mut(variant) { *cap = None; }
+        if let Some(order) = self.caps.order.get_mut(variant) { *order = VecDeque::new(); }
+        true
+    }
+
+    /// The cap on the given variant, None if it has none or there is no such variant.
+    pub fn variant_cap(&self, variant: impl VariantKey) -> Option<VariantCap> { self.caps.get(variant.index_in(C::VARIANT_NAMES)?) }
+
+    /// Sets the function given each spawn that takes a variant with the `Warn` policy over its cap.  The warning is
+    /// still logged.
+    pub fn set_cap_observer(&mut self, observer: impl FnMut(&CapWarning) + Send + Sync + 'static) {
+        self.caps.observer = Some(Box::new(observer));
+    }
+
+    /// Removes the function given to `set_cap_observer`.
+    pub fn clear_cap_observer(&mut self) { self.caps.observer = None; }
+
+    // checks the given subtree against the caps before it is spawned under the parent, replacing or warning as the
+    // policies say.  Nothing is changed if a cap rejects the subtree.
+    pub(crate) fn enforce_caps(&mut self, parent: NodeId, node: &Node<C, A>) -> Result<(), WorldError> {
+        if self.caps.caps.iter().all(Option::is_none) { return Ok(()) }
+
+        let mut adding = vec![0; self.caps.caps.len()];
+        for variant in node.iter().flat_map(|node| node.components()).filter_map(|component| component.variant_index()) {
+            if let Some(count) = adding.get_mut(variant) { *count += 1; }
+        }
+
+        let over: Vec<(usize, VariantCap)> = adding.iter().enumerate()
+            .filter(|(_, adding)| **adding > 0)
+            .filter_map(|(variant, adding)| Some((variant, self.caps.get(variant)?)).filter(|(variant, cap)| self.counters.variant(*variant) + adding > cap.max_live))
+            .collect();
+        for (variant, cap) in &over {
+            let fits = cap.policy == CapPolicy::ReplaceOldest && adding[*variant] <= cap.max_live;
+            if cap.policy != CapPolicy::Warn && !fits { return Err(WorldError::VariantCapped(C::VARIANT_NAMES[*variant])) }
+        }
+
+        for (variant, cap) in over {
+            match cap.policy {
+                CapPolicy::ReplaceOldest => self.replace_oldest(variant, cap.max_live - adding[variant], parent)?,
+                _ => {
+                    let warning = CapWarning { variant: C::VARIANT_NAMES[variant], live: self.counters.variant(variant) + adding[variant], max_live: cap.max_live };
+                    log::warn!("spawning took {} to {} live nodes, over its cap of {}", warning.variant, warning.live, warning.max_live);
+                    if let Some(observer) = self.caps.observer.as_mut() { observer(&warning); }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // despawns the oldest nodes of the variant until at most the given number are left, keeping the parent and its
+    // ancestors so the spawn still has somewhere to go
+    fn replace_oldest(&mut self, variant: usize, keep: usize, parent: NodeId) -> Result<(), WorldError> {
+        let parent_path = self.path_of(parent).ok_or(WorldError::NotFound(parent))?;
+        let mut kept = Vec::new();
+        let mut recollected = false;
+        while self.counters.variant(variant) > keep {
+            let Some(id) = self.caps.order[variant].pop_front() else {
+                // nodes of the variant were added without the world seeing them, so the tree is searched once
+                if recollected { break }
+                recollected = true;
+                self.recollect_order(variant);
+                continue
+            };
+            let Some(path) = self.path_of(id) else { continue };
+            if !self.root().resolve_path(&path).unwrap().holds_variant(variant) { continue }
+            if parent_path.starts_with(&path) { kept.push(id); continue }
+            self.despawn_inner(id);
+        }
+
+        kept.into_iter().rev().for_each(|id| self.caps.order[variant].push_front(id));
+        if self.counters.variant(variant) > keep { return Err(WorldError::VariantCapped(C::VARIANT_NAMES[variant])) }
+        Ok(())
+    }
+
+    // records every live node of the variant in pre-order, the best guess at their spawn order
+    fn recollect_order(&mut self, variant: usize) {
+        let ids: VecDeque<NodeId> = self.iter(true).filter(|node| node.holds_variant(variant)).filter_map(|node| node.id()).collect();
+        self.caps.order[variant] = ids;
+    }
+
+    // records the nodes of a freshly spawned subtree for the caps that replace the oldest nodes first, dropping the ids
+    // of nodes gone some other way once they far outnumber the live nodes so the order cannot grow without bound
+    pub(crate) fn record_created(&mut self, id: NodeId) {
+        if !self.caps.caps.iter().flatten().any(|cap| cap.policy == CapPolicy::ReplaceOldest) { return }
+        let Some(node) = self.get(id) else { return };
+        let created: Vec<(Option<usize>, NodeId)> = node.iter()
+            .filter_map(|node| Some((node, node.id()?)))
+            .flat_map(|(node, id)| node.components().map(move |component| (component.variant_index(), id)))
+            .collect();
+        created.into_iter().for_each(|(variant, id)| self.caps.created(variant, id));
+
+        for variant in 0..self.caps.order.len() {
+            if self.caps.order[variant].len() <= 2 * self.counters.variant(variant) + 64 { continue }
+            let mut order = std::mem::take(&mut self.caps.order[variant]);
+            order.retain(|id| self.is_alive(*id));
+            self.caps.order[variant] = order;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::errors::WorldError;
+
+    use super::{CapPolicy, CapWarning, VariantCap};
+    use self::world::*;
+
+    #[allow(dead_code)]
+    mod world {
+        use std::cell::RefCell;
+
+        pub struct TestApp;
+
+        #[derive(Debug)]
+        pub struct Emitter;
+
+        #[derive(Debug)]
+        pub struct Particle(pub u32);
+
+        thread_local! {
+            pub static REMOVED: RefCell<Vec<u32>> = const { RefCell::new(Vec::new()) };
+        }
+
+        crate::define_world!(TestApp, [
+            Emitter => { DATA => Emitter },
+            Particle => {
+                DATA => Particle,
+                REMOVED => |node: &mut Node| REMOVED.with(|removed| removed.borrow_mut().push(node.get::<Particle>().unwrap().0))
+            }
+        ]);
+    }
+
+    fn particle(marker: u32) -> Node { Node::with_particle(Particle(marker)) }
+
+    fn take_removed() -> Vec<u32> { REMOVED.with(|removed| std::mem::take(&mut *removed.borrow_mut())) }
+
+    fn live(world: &World) -> Vec<u32> { world.iter(false).filter_map(|node| node.get::<Particle>()).map(|particle| particle.0).collect() }
+
+    #[test]
+    fn caps_are_named_by_index_or_name() {
+        let mut world = World::new();
+        assert!(world.set_variant_cap("Particle", 2, CapPolicy::Reject));
+        assert!(world.set_variant_cap(0, 5, CapPolicy::Warn));
+        assert!(!world.set_variant_cap("Smoke", 1, CapPolicy::Reject));
+        assert!(!world.set_variant_cap(2, 1, CapPolicy::Reject));
+        assert_eq!(world.variant_cap(1), Some(VariantCap { max_live: 2, policy: CapPolicy::Reject }));
+        assert_eq!(world.variant_cap("Emitter").map(|cap| cap.policy), Some(CapPolicy::Warn));
+
+        assert!(world.clear_variant_cap("Particle"));
+        assert_eq!(world.variant_cap("Particle"), None);
+    }
+
+    #[test]
+    fn reject_stops_spawns_at_the_cap() {
+        let mut world = World::new();
+        let root = world.root_id();
+        world.set_variant_cap("Particle", 2, CapPolicy::Reject);
+        let first = world.spawn(root, particle(1)).unwrap();
+        world.spawn(root, particle(2)).unwrap();
+
+        assert!(world.spawn(root, particle(3)).is_none());
+        assert_eq!(world.try_spawn(root, particle(3)).err(), Some(WorldError::VariantCapped("Particle")));
+        world.queue_command(crate::commands::WorldCommand::Spawn { parent: root, node: Box::new(particle(3)) });
+        world.apply_commands();
+        assert_eq!(live(&world), [1, 2]);
+
+        // other variants are not capped, but particles hidden in a subtree are counted
+        let emitter = world.spawn(root, Node::with_emitter(Emitter)).unwrap();
+        let mut holder = Node::with_emitter(Emitter);
+        holder.push_detached_child(particle(4));
+        assert_eq!(world.try_spawn(emitter, holder).err(), Some(WorldError::VariantCapped("Particle")));
+
+        // despawning by any means makes room again
+        assert!(world.despawn(first));
+        assert!(world.spawn(emitter, particle(5)).is_some());
+        assert_eq!(live(&world), [2, 5]);
+        assert_eq!(world.stats().for_variant("Particle"), 2);
+        assert!(take_removed().contains(&1));
+    }
+
+    #[test]
+    fn replace_oldest_despawns_in_spawn_order() {
+        let mut world = World::new();
+        let root = world.root_id();
+        world.set_variant_cap("Particle", 3, CapPolicy::ReplaceOldest);
+        let ids: Vec<_> = (1..=5).map(|marker| world.spawn(root, particle(marker)).unwrap()).collect();
+        assert_eq!(live(&world), [3, 4, 5]);
+        assert_eq!(take_removed(), [1, 2]);
+
+        // nodes gone some other way are not replaced, and are not counted
+        assert!(world.despawn(ids[3]));
+        assert!(world.take(ids[2]).is_some());
+        take_removed();
+        let six = world.spawn(root, particle(6)).unwrap();
+        world.spawn(root, particle(7)).unwrap();
+        assert!(take_removed().is_empty());
+        world.spawn(root, particle(8)).unwrap();
+        assert_eq!(take_removed(), [5]);
+        assert_eq!(live(&world), [6, 7, 8]);
+
+        // the parent of a spawn is never replaced, the next oldest goes instead
+        world.spawn(six, particle(9)).unwrap();
+        assert_eq!(take_removed(), [7]);
+        assert_eq!(live(&world), [6, 9, 8]);
+        assert_eq!(world.stats().for_variant("Particle"), 3);
+
+        // a subtree bigger than the cap cannot fit however many are replaced
+        let mut burst = particle(10);
+        (11..=13).for_each(|marker| burst.push_detached_child(particle(marker)));
+        assert_eq!(world.try_spawn(root, burst).err(), Some(WorldError::VariantCapped("Particle")));
+        assert!(take_removed().is_empty());
+        assert_eq!(live(&world), [6, 9, 8]);
+
+        // ids of nodes despawned directly do not pile up
+        for marker in 0..500 {
+            let id = world.spawn(root, particle(marker)).unwrap();
+            assert!(world.despawn(id));
+        }
+        assert!(world.caps.order[1].len() < 100);
+        assert_eq!(world.stats().for_variant("Particle"), live(&world).len());
+    }
+
+    #[test]
+    fn warn_tells_the_observer() {
+        let mut world = World::new();
+        let root = world.root_id();
+        world.set_variant_cap("Particle", 1, CapPolicy::Warn);
+        world.spawn(root, particle(1));
+        world.spawn(root, particle(2));
+
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let seen = warnings.clone();
+        world.set_cap_observer(move |warning| seen.lock().unwrap().push(*warning));
+        world.spawn(root, particle(3));
+        world.spawn(root, Node::with_emitter(Emitter));
+        assert_eq!(live(&world), [1, 2, 3]);
+        assert_eq!(*warnings.lock().unwrap(), [CapWarning { variant: "Particle", live: 3, max_live: 1 }]);
+
+        world.clear_cap_observer();
+        world.spawn(root, particle(4));
+        assert_eq!(warnings.lock().unwrap().len(), 1);
+    }
+}
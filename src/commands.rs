@@ -0,0 +1,203 @@
+use std::sync::{mpsc::{self, Receiver, Sender}, Mutex};
+
+use forte_engine::math::transforms::Transform;
+
+use crate::{
+    ids::NodeId,
+    nodes::{ComponentDef, Node, World}
+};
+
+/// A change to a world that is applied later by `World::apply_commands`, either queued on the world with
+/// `World::queue_command` or sent from another thread with a `WorldCommandSender`.
+pub enum WorldCommand<C, A> {
+    /// Spawns the node as the last child of the parent, see `World::spawn`.  The node is boxed so commands stay small.
+    Spawn { parent: NodeId, node: Box<Node<C, A>> },
+
+    /// Despawns the node and its children, see `World::despawn`.
+    Despawn(NodeId),
+
+    /// Moves the child to the end of the children of the new parent, see `World::reparent`.
+    Reparent { child: NodeId, parent: NodeId },
+
+    /// Replaces the local transform of the node.
+    SetTransform(NodeId, Transform),
+
+    /// Enables or disables the node, see `World::set_enabled`.
+    SetEnabled(NodeId, bool),
+
+    /// Replaces the component of the node, see `World::set_component`.
+    SetComponent(NodeId, C)
+}
+
+impl<C, A> WorldCommand<C, A> {
+    // the ids that must still be in the world for this command to be applied
+    fn targets(&self) -> [Option<NodeId>; 2] {
+        match self {
+            WorldCommand::Spawn { parent, .. } => [Some(*parent), None],
+            WorldCommand::Reparent { child, parent } => [Some(*child), Some(*parent)],
+            WorldCommand::Despawn(id) | WorldCommand::SetTransform(id, _) | WorldCommand::SetEnabled(id, _) | WorldCommand::SetComponent(id, _) => [Some(*id), None]
+        }
+    }
+}
+
+/// Sends commands to a world from any thread, created with `World::command_sender`.  The commands are applied in the
+/// order they were sent, after the commands queued on the world itself, by the next `World::apply_commands`.
+pub struct WorldCommandSender<C, A>(Sender<WorldCommand<C, A>>);
+
+impl<C, A> Clone for WorldCommandSender<C, A> {
+    fn clone(&self) -> Self { Self(self.0.clone()) }
+}
+
+impl<C, A> WorldCommandSender<C, A> {
+    /// Sends the given command to the world.
+    ///
+    /// Returns the command back if the world has been dropped.
+    pub fn send(&self, command: WorldCommand<C, A>) -> Result<(), WorldCommand<C, A>> { self.0.send(command).map_err(|error| error.0) }
+}
+
+/// The result of `World::apply_commands`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CommandReport {
+    /// The number of commands applied.
+    pub applied: usize,
+
+    /// The number of commands dropped because a node they name is no longer in the world.
+    pub dropped: usize
+}
+
+// both ends of the channel of a world, the receiver is locked so the world stays `Sync`
+type CommandChannel<C, A> = (Sender<WorldCommand<C, A>>, Mutex<Receiver<WorldCommand<C, A>>>);
+
+// the commands waiting to be applied to a world, the channel is only opened once a sender is asked for
+pub(crate) struct CommandQueue<C, A> {
+    local: Vec<WorldCommand<C, A>>,
+    channel: Option<CommandChannel<C, A>>
+}
+
+impl<C, A> Default for CommandQueue<C, A> {
+    fn default() -> Self { Self { local: Vec::new(), channel: None } }
+}
+
+impl<C, A> CommandQueue<C, A> {
+    // makes room for the given number of queued commands
+    pub(crate) fn reserve(&mut self, capacity: usize) { self.local.reserve(capacity.saturating_sub(self.local.len())); }
+
+    // the number of commands queued on the world itself
+    pub(crate) fn len(&self) -> usize { self.local.len() }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Queues the given command to be applied by the next `apply_commands`, IE from code that cannot change the world
+    /// right away.
+    pub fn queue_command(&mut self, command: WorldCommand<C, A>) { self.commands.local.push(command); }
+
+    /// Creates a sender that other threads can send commands to this world with.  Every sender feeds the same channel,
+    /// sends from a sender that outlives the world fail without panicking.
+    pub fn command_sender(&mut self) -> WorldCommandSender<C, A> {
+        let (sender, _) = self.commands.channel.get_or_insert_with(|| {
+            let (sender, receiver) = mpsc::channel();
+            (sender, Mutex::new(receiver))
+        });
+        WorldCommandSender(sender.clone())
+    }
+
+    /// Applies the commands queued with `queue_command` in the order they were queued, then the commands sent to this
+    /// world in the order they were received, IE once a frame on the main thread.  A command naming a node that is no
+    /// longer in the world when it is reached is dropped with a warning.
+    pub fn apply_commands(&mut self) -> CommandReport {
+        let mut report = CommandReport::default();
+        let local = std::mem::take(&mut self.commands.local);
+        local.into_iter().for_each(|command| self.apply_command(command, &mut report));
+
+        let received: Vec<WorldCommand<C, A>> = match &self.commands.channel {
+            Some((_, receiver)) => receiver.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).try_iter().collect(),
+            None => Vec::new()
+        };
+        received.into_iter().for_each(|command| self.apply_command(command, &mut report));
+        report
+    }
+
+    // applies one command unless a node it names is gone
+    fn apply_command(&mut self, command: WorldCommand<C, A>, report: &mut CommandReport) {
+        if let Some(missing) = command.targets().into_iter().flatten().find(|id| !self.is_alive(*id)) {
+            log::warn!("dropped a world command for node {:?}, which is no longer in the world", missing);
+            report.dropped += 1;
+            return
+        }
+
+        match command {
+            WorldCommand::Spawn { parent, node } => { self.spawn(parent, *node); },
+            WorldCommand::Despawn(id) => { self.despawn(id); },
+            WorldCommand::Reparent { child, parent } => { self.reparent(child, parent); },
+            WorldCommand::SetTransform(id, transform) => { self.get_mut(id).unwrap().transform = transform; },
+            WorldCommand::SetEnabled(id, enabled) => { self.set_enabled(id, enabled); },
+            WorldCommand::SetComponent(id, component) => { self.set_component(id, component); }
+        }
+        report.applied += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Vector3;
+    use forte_engine::math::transforms::Transform;
+
+    use super::{CommandReport, WorldCommand};
+    use crate::test_support::*;
+
+    fn at(x: f32) -> Transform { Transform { position: Vector3::new(x, 0.0, 0.0), ..Transform::default() } }
+
+    #[test]
+    fn commands_from_a_thread_are_applied() {
+        let mut world = World::new();
+        let moved = world.spawn(world.root_id(), Node::with_marker(Marker(1))).unwrap();
+        let gone = world.spawn(world.root_id(), Node::with_marker(Marker(2))).unwrap();
+        let root = world.root_id();
+
+        let sender = world.command_sender();
+        std::thread::spawn(move || {
+            assert!(sender.send(WorldCommand::SetTransform(moved, at(3.0))).is_ok());
+            assert!(sender.send(WorldCommand::Despawn(gone)).is_ok());
+            assert!(sender.send(WorldCommand::Spawn { parent: root, node: Box::new(Node::with_marker(Marker(3))) }).is_ok());
+        }).join().unwrap();
+
+        // nothing changes until the commands are applied
+        assert!(world.is_alive(gone));
+        assert_eq!(world.apply_commands(), CommandReport { applied: 3, dropped: 0 });
+        assert_eq!(world.get(moved).unwrap().transform.position.x, 3.0);
+        assert!(!world.is_alive(gone));
+        assert_eq!(world.root().children().iter().map(|child| child.get::<Marker>().unwrap().0).collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(world.apply_commands(), CommandReport::default());
+    }
+
+    #[test]
+    fn local_commands_come_first() {
+        let mut world = World::new();
+        let a = world.spawn(world.root_id(), Node::with_marker(Marker(1))).unwrap();
+        let b = world.spawn(world.root_id(), Node::with_marker(Marker(2))).unwrap();
+
+        // sent before anything is queued, but still applied after the queue
+        let sender = world.command_sender();
+        assert!(sender.clone().send(WorldCommand::SetTransform(a, at(2.0))).is_ok());
+        assert!(sender.send(WorldCommand::SetEnabled(b, false)).is_ok());
+        world.queue_command(WorldCommand::SetTransform(a, at(1.0)));
+        world.queue_command(WorldCommand::Despawn(b));
+
+        // the despawn runs first, so disabling the despawned node is dropped
+        assert_eq!(world.apply_commands(), CommandReport { applied: 3, dropped: 1 });
+        assert_eq!(world.get(a).unwrap().transform.position.x, 2.0);
+    }
+
+    #[test]
+    fn senders_outliving_the_world_fail_quietly() {
+        let mut world = World::new();
+        let root = world.root_id();
+        let sender = world.command_sender();
+        let other = sender.clone();
+        drop(world);
+
+        let rejected = sender.send(WorldCommand::SetEnabled(root, false)).err();
+        assert!(matches!(rejected, Some(WorldCommand::SetEnabled(id, false)) if id == root));
+        assert!(std::thread::spawn(move || other.send(WorldCommand::Despawn(root)).is_err()).join().unwrap());
+    }
+}
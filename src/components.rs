@@ -0,0 +1,83 @@
+use forte_engine::{math::transforms::Transform, render::{input::EngineInput, render_engine::RenderEngine}};
+use winit::dpi::PhysicalSize;
+
+use crate::{
+    callbacks::RemovalReason,
+    debug::DebugLines,
+    inspector::Inspector,
+    nodes::{ComponentDef, NodeContext}
+};
+
+#[cfg(feature = "serde")]
+use crate::scenes::{SceneError, SceneValue};
+
+/// A component data type that gives its own callbacks, so a component enum can be put together with `#[world]` from
+/// data types defined in other files or crates.  This is usually implemented with `#[derive(WorldComponent)]`.
+///
+/// Each callback matches the one of the same name in `define_world!`, taking the data, the rest of the node as a
+/// `NodeContext` and the same arguments, and does nothing by default.  The flags tell the world which of the optional
+/// callbacks are given, so the callbacks nothing uses can be skipped.
+pub trait WorldComponent<C: ComponentDef<A>, A>: Sized {
+    /// The render priority of this component, lower priorities are drawn first, see PRIORITY.
+    const PRIORITY: i32 = 0;
+
+    /// False if this component should not be collected for shadow passes, see CASTS_SHADOW.
+    const CASTS_SHADOW: bool = true;
+
+    /// True if `sort_key` is given.
+    const SORTED: bool = false;
+
+    /// True if `late_update` is given.
+    const LATE_UPDATES: bool = false;
+
+    /// True if `fixed_update` is given.
+    const FIXED_UPDATES: bool = false;
+
+    /// True if `input` is given.
+    const INPUTS: bool = false;
+
+    /// True if `resize` is given.
+    const RESIZES: bool = false;
+
+    /// True if `prepare` is given.
+    const PREPARES: bool = false;
+
+    /// True if `render` is given.
+    const RENDERS: bool = false;
+
+    fn added(&mut self, _: &mut NodeContext<'_, C, A>) {}
+    fn update(&mut self, _: &mut A, _: &mut NodeContext<'_, C, A>, _: f32) {}
+    fn removed(&mut self, _: &mut NodeContext<'_, C, A>, _: &RemovalReason) {}
+    fn start(&mut self, _: &mut NodeContext<'_, C, A>) {}
+    fn late_update(&mut self, _: &mut NodeContext<'_, C, A>) {}
+    fn fixed_update(&mut self, _: &mut NodeContext<'_, C, A>) {}
+    fn on_enable(&mut self, _: &mut NodeContext<'_, C, A>) {}
+    fn on_disable(&mut self, _: &mut NodeContext<'_, C, A>) {}
+    fn resize(&mut self, _: &mut NodeContext<'_, C, A>, _: PhysicalSize<u32>) {}
+    fn prepare(&mut self, _: &mut NodeContext<'_, C, A>, _: &mut RenderEngine) {}
+
+    /// Reacts to an input, returns true if it consumed the input.
+    fn input(&mut self, _: &mut NodeContext<'_, C, A>, _: &EngineInput) -> bool { false }
+
+    fn render<'a, 'b>(&'b self, _: &mut C::Pass<'a>, _: &'b A) where 'b: 'a {}
+
+    /// The draw sort key of this component, only used if `SORTED` is true.
+    fn sort_key(&self) -> Option<u64> { None }
+
+    /// Adds this components debug shapes to the given lines, returns false if it has no debug shapes.
+    fn debug_draw(&self, _: &Transform, _: &mut DebugLines) -> bool { false }
+
+    /// Passes this components fields to the given inspector, returns false if it has nothing to inspect.
+    fn inspect(&mut self, _: &mut dyn Inspector) -> bool { false }
+
+    /// Passes this components asset paths to the given remap function, returns how many paths were rewritten.
+    fn remap_assets(&mut self, _: &dyn Fn(&str) -> Option<String>) -> usize { 0 }
+
+    /// The saved data of this component, None if it cannot be saved, see SAVE.
+    #[cfg(feature = "serde")]
+    fn save(&self) -> Option<SceneValue> { None }
+
+    /// Creates this component from saved data for the variant with the given name, see LOAD.
+    #[cfg(feature = "serde")]
+    fn load(variant: &str, _: &SceneValue) -> Result<Self, SceneError> { Err(SceneError::NoCodec(variant.to_string())) }
+}
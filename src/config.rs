@@ -0,0 +1,295 @@
+use forte_engine::render::primitives::cameras::Camera;
+
+use crate::{
+    culling::{DrawCulling, Frustum, FrustumDepth},
+    names::NameCollisionPolicy,
+    nodes::{ComponentDef, World, DEFAULT_FIXED_TIMESTEP}
+};
+
+/// The initial capacities of the buffers a world reuses, see `WorldConfig::scratch_capacities`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct ScratchCapacities {
+    /// The hierarchy events waiting to be drained by `World::drain_events`.
+    pub events: usize,
+
+    /// The commands queued with `World::queue_command`.
+    pub commands: usize
+}
+
+/// The settings of a `World`, given to `World::with_config` and changed at runtime with `World::set_config`.  Each
+/// field says what happens when it is changed on a world that is already running.
+///
+/// With the `serde` feature, configs can be loaded from any serde format, IE a `worldsettings.ron`, and missing
+/// fields are given their defaults.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct WorldConfig {
+    /// The deepest a node can be below the root, or None for no limit.  Spawns and moves that would put a node deeper
+    /// fail with `WorldError::TooDeep`.  Lowering it at runtime leaves deeper nodes where they are, only new spawns and
+    /// moves are checked.
+    pub max_depth: Option<usize>,
+
+    /// The most nodes with ids the world can hold, or None for no limit.  Spawns that would go over it fail with
+    /// `WorldError::TooManyNodes`.  Lowering it below the current count at runtime despawns nothing, it only blocks new
+    /// spawns until enough nodes are gone.
+    pub max_nodes: Option<usize>,
+
+    /// What to do when a node is spawned next to a sibling with the same name, see `NameCollisionPolicy`.  Changing it
+    /// at runtime applies to the next spawn, and nodes already in the tree keep their names.
+    pub name_collision_policy: NameCollisionPolicy,
+
+    /// How far past the edges of the screen `World::draw_culling` keeps nodes, in NDC units, see
+    /// `Frustum::from_matrix_with`.  Changes apply to the next culling made.
+    pub default_cull_margin: f32,
+
+    /// How far any part of the global transform of a node must move in an update before `Node::is_transform_changed`
+    /// reports it, 0 to report every change.  Changes apply from the next update.
+    pub transform_changed_epsilon: f32,
+
+    /// The initial capacities of the buffers the world reuses.  Raising them at runtime reserves the extra space right
+    /// away, lowering them frees nothing.
+    pub scratch_capacities: ScratchCapacities,
+
+    /// If false, updates do not check what changed, so `Node::is_transform_changed` and `Node::is_component_changed`
+    /// are always false, saving the comparisons for apps that never ask.  Turning it back on at runtime reports every
+    /// change from the next update on.
+    pub track_changes: bool,
+
+    /// If false, `World::draw` does not stamp nodes with the frame they were drawn in, so `World::was_rendered` and
+    /// `World::rendered_count` are not kept up to date.  Stamps from before it was turned off are kept.
+    pub render_stamps: bool,
+
+    /// The number of seconds between the fixed updates run by `World::tick`, which must be above 0.  Changing it at
+    /// runtime keeps the time left over from earlier ticks.
+    pub fixed_timestep: f32
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            max_nodes: None,
+            name_collision_policy: NameCollisionPolicy::default(),
+            default_cull_margin: 0.0,
+            transform_changed_epsilon: 0.0,
+            scratch_capacities: ScratchCapacities::default(),
+            track_changes: true,
+            render_stamps: true,
+            fixed_timestep: DEFAULT_FIXED_TIMESTEP
+        }
+    }
+}
+
+impl WorldConfig {
+    /// Creates a new builder starting from the default config, see `WorldConfigBuilder`.
+    pub fn builder() -> WorldConfigBuilder { WorldConfigBuilder { config: WorldConfig::default() } }
+}
+
+/// Builds a `WorldConfig` one setting at a time, IE `WorldConfig::builder().max_nodes(10_000).build()`.  Every setting not
+/// given keeps its default.
+#[derive(Debug, Clone)]
+pub struct WorldConfigBuilder {
+    config: WorldConfig
+}
+
+impl WorldConfigBuilder {
+    /// Sets the deepest a node can be below the root.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.config.max_depth = Some(depth);
+        self
+    }
+
+    /// Sets the most nodes the world can hold.
+    pub fn max_nodes(mut self, nodes: usize) -> Self {
+        self.config.max_nodes = Some(nodes);
+        self
+    }
+
+    /// Sets what to do when a node is spawned next to a sibling with the same name.
+    pub fn name_collision_policy(mut self, policy: NameCollisionPolicy) -> Self {
+        self.config.name_collision_policy = policy;
+        self
+    }
+
+    /// Sets how far past the edges of the screen culling keeps nodes.
+    pub fn default_cull_margin(mut self, margin: f32) -> Self {
+        self.config.default_cull_margin = margin;
+        self
+    }
+
+    /// Sets how far a global transform must move to count as changed.
+    pub fn transform_changed_epsilon(mut self, epsilon: f32) -> Self {
+        self.config.transform_changed_epsilon = epsilon;
+        self
+    }
+
+    /// Sets the initial capacities of the buffers the world reuses.
+    pub fn scratch_capacities(mut self, capacities: ScratchCapacities) -> Self {
+        self.config.scratch_capacities = capacities;
+        self
+    }
+
+    /// Sets whether updates check what changed.
+    pub fn track_changes(mut self, track: bool) -> Self {
+        self.config.track_changes = track;
+        self
+    }
+
+    /// Sets whether drawing stamps nodes with the frame.
+    pub fn render_stamps(mut self, stamps: bool) -> Self {
+        self.config.render_stamps = stamps;
+        self
+    }
+
+    /// Sets the number of seconds between fixed updates.
+    pub fn fixed_timestep(mut self, seconds: f32) -> Self {
+        self.config.fixed_timestep = seconds;
+        self
+    }
+
+    /// Finishes building the config.
+    pub fn build(self) -> WorldConfig { self.config }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Creates a new world with an empty root and the given config.
+    ///
+    /// Panics if the fixed timestep of the config is not above 0.
+    pub fn with_config(config: WorldConfig) -> Self {
+        let mut world = Self::new();
+        world.set_config(config);
+        world
+    }
+
+    /// The settings of this world.
+    pub fn config(&self) -> &WorldConfig { &self.config }
+
+    /// Replaces the settings of this world, each of which takes effect as its field describes, see `WorldConfig`.
+    ///
+    /// Panics if the fixed timestep of the config is not above 0.
+    pub fn set_config(&mut self, config: WorldConfig) {
+        assert!(config.fixed_timestep > 0.0, "the fixed timestep must be above 0, was {}", config.fixed_timestep);
+        if config.name_collision_policy == NameCollisionPolicy::Allow { self.names.clear(); }
+        self.reserve_scratch(&config.scratch_capacities);
+        self.config = config;
+    }
+
+    /// Creates the draw culling for the given camera with the `default_cull_margin` of this world, see
+    /// `DrawCulling::from_camera`.
+    pub fn draw_culling(&self, camera: &Camera, viewport_height: u32, min_screen_size: Option<f32>) -> DrawCulling {
+        let frustum = Frustum::from_matrix_with(camera.build_view_projection_matrix(), FrustumDepth::Standard, self.config.default_cull_margin);
+        DrawCulling { frustum, ..DrawCulling::from_camera(camera, viewport_height, min_screen_size) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Vector3;
+
+    use crate::{errors::WorldError, names::NameCollisionPolicy};
+
+    use super::{ScratchCapacities, WorldConfig};
+    use crate::test_support::*;
+
+    fn chain(length: usize) -> Node {
+        (1..length).fold(Node::default(), |node, _| Node::builder().child(node).build())
+    }
+
+    #[test]
+    fn builder_starts_from_the_defaults() {
+        let config = WorldConfig::builder().build();
+        assert_eq!(config, WorldConfig::default());
+        assert_eq!(World::new().config(), &config);
+        assert!(config.track_changes && config.render_stamps);
+        assert_eq!((config.max_depth, config.max_nodes), (None, None));
+
+        let config = WorldConfig::builder()
+            .max_depth(3)
+            .name_collision_policy(NameCollisionPolicy::Rename)
+            .scratch_capacities(ScratchCapacities { events: 64, commands: 8 })
+            .build();
+        assert_eq!(config.max_depth, Some(3));
+        assert_eq!(config.name_collision_policy, NameCollisionPolicy::Rename);
+        assert_eq!(config.fixed_timestep, WorldConfig::default().fixed_timestep);
+
+        let world = World::with_config(config.clone());
+        assert_eq!(world.config(), &config);
+        assert_eq!(world.name_collision_policy(), NameCollisionPolicy::Rename);
+    }
+
+    #[test]
+    fn limits_only_block_new_nodes() {
+        let mut world = World::new();
+        let root = world.root_id();
+        let deep = world.spawn(root, chain(4)).unwrap();
+        (0..3).for_each(|_| { world.spawn(root, Node::default()); });
+        assert_eq!(world.id_count(), 8);
+
+        // tightening below what is already there removes nothing
+        world.set_config(WorldConfig::builder().max_nodes(6).max_depth(2).build());
+        assert_eq!(world.id_count(), 8);
+        assert_eq!(world.try_spawn(root, Node::default()).err(), Some(WorldError::TooManyNodes));
+        assert!(world.spawn(root, Node::default()).is_none());
+        assert_eq!(world.transaction(|tx| tx.spawn(root, Node::default()).map(|_| ())), Err(WorldError::TooManyNodes));
+
+        // once enough nodes are gone spawning works again, up to the limit
+        assert!(world.despawn(deep));
+        assert_eq!(world.id_count(), 4);
+        assert_eq!(world.try_spawn(root, Node::default()).map(|_| ()), Ok(()));
+        assert_eq!(world.try_spawn(root, chain(3)).err(), Some(WorldError::TooManyNodes));
+        let parent = world.spawn(root, Node::default()).unwrap();
+        assert_eq!(world.id_count(), 6);
+
+        // depth counts the whole subtree
+        world.set_config(WorldConfig::builder().max_depth(2).build());
+        assert_eq!(world.try_spawn(parent, chain(2)).err(), Some(WorldError::TooDeep(parent)));
+        let child = world.spawn(parent, Node::default()).unwrap();
+        let other = world.spawn(root, chain(2)).unwrap();
+        assert!(!world.reparent(other, parent));
+        assert_eq!(world.transaction(|tx| tx.reparent(other, parent)), Err(WorldError::TooDeep(parent)));
+        assert_eq!(world.transaction(|tx| tx.spawn(child, Node::default()).map(|_| ())), Err(WorldError::TooDeep(child)));
+
+        // nodes staged below a moved node count towards its height
+        let staged = world.transaction(|tx| {
+            let moved = tx.spawn(root, Node::default())?;
+            tx.spawn(moved, Node::default())?;
+            tx.reparent(moved, parent)
+        });
+        assert_eq!(staged, Err(WorldError::TooDeep(parent)));
+    }
+
+    #[test]
+    fn change_tracking_follows_the_config() {
+        let mut world = World::new();
+        let id = world.spawn(world.root_id(), Node::default()).unwrap();
+        world.update(&mut TestApp, 0.1);
+
+        // small moves are ignored with an epsilon
+        world.set_config(WorldConfig::builder().transform_changed_epsilon(0.5).build());
+        world.get_mut(id).unwrap().transform.position = Vector3::new(0.25, 0.0, 0.0);
+        world.update(&mut TestApp, 0.1);
+        assert!(!world.get(id).unwrap().is_transform_changed());
+        world.get_mut(id).unwrap().transform.position = Vector3::new(1.0, 0.0, 0.0);
+        world.update(&mut TestApp, 0.1);
+        assert!(world.get(id).unwrap().is_transform_changed());
+
+        // nothing is reported while tracking is off
+        world.set_config(WorldConfig::builder().track_changes(false).build());
+        world.get_mut(id).unwrap().transform.position = Vector3::new(5.0, 0.0, 0.0);
+        world.update(&mut TestApp, 0.1);
+        assert!(!world.get(id).unwrap().is_transform_changed());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn configs_round_trip_through_serde() {
+        let config = WorldConfig::builder().max_nodes(100).render_stamps(false).name_collision_policy(NameCollisionPolicy::Reject).build();
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::from_str::<WorldConfig>(&json).unwrap(), config);
+
+        // missing fields keep their defaults
+        let partial: WorldConfig = serde_json::from_str(r#"{ "max_depth": 4 }"#).unwrap();
+        assert_eq!(partial, WorldConfig::builder().max_depth(4).build());
+    }
+}
@@ -0,0 +1,298 @@
+use cgmath::{InnerSpace, Matrix4, Vector3, Vector4};
+use forte_engine::render::primitives::cameras::Camera;
+
+use crate::{dimensions::Dimensions, utils::projected_radius};
+
+/// The depth convention of the projection matrix a frustum is extracted from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FrustumDepth {
+    /// Depth goes from 0 at the near plane to 1 at the far plane, as WGPU expects.
+    #[default]
+    Standard,
+
+    /// Like standard, but the far plane is at infinity.
+    Infinite,
+
+    /// Depth goes from 1 at the near plane to 0 at the far plane.
+    Reversed,
+
+    /// Like reversed, but the far plane is at infinity.
+    ReversedInfinite
+}
+
+impl FrustumDepth {
+    /// Checks if this depth convention has a far plane.
+    pub fn has_far(&self) -> bool { matches!(self, FrustumDepth::Standard | FrustumDepth::Reversed) }
+}
+
+/// A view frustum represented by six inward facing planes.
+///
+/// Each plane is stored as (normal.x, normal.y, normal.z, distance) so that a point p is inside the plane when
+/// `dot(normal, p) + distance >= 0`.  The planes are in the order left, right, bottom, top, near, far.  When far is
+/// false, the projection had no far plane and the last plane is ignored, so nothing is ever rejected by distance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    pub planes: [Vector4<f32>; 6],
+    pub far: bool
+}
+
+impl Frustum {
+    /// Extracts a frustum from a view projection matrix that uses WGPU's 0 to 1 depth range.
+    ///
+    /// Arguments:
+    /// * view_proj: Matrix4<f32> - The view projection matrix to extract the planes from.
+    ///
+    /// Returns the frustum of the given matrix.
+    pub fn from_matrix(view_proj: Matrix4<f32>) -> Self { Self::from_matrix_with(view_proj, FrustumDepth::Standard, 0.0) }
+
+    /// Extracts a frustum from a view projection matrix with the given depth convention and screen edge margin.
+    ///
+    /// Arguments:
+    /// * view_proj: Matrix4<f32> - The view projection matrix to extract the planes from.
+    /// * depth: FrustumDepth - The depth convention of the projection.
+    /// * margin: f32 - How far to extend the left, right, bottom and top planes past the edge of the screen in NDC units,
+    ///   IE 0.1 keeps objects up to a tenth of the screens half width outside of the screen.
+    ///
+    /// Returns the frustum of the given matrix.
+    pub fn from_matrix_with(view_proj: Matrix4<f32>, depth: FrustumDepth, margin: f32) -> Self {
+        let row = |i: usize| Vector4::new(view_proj.x[i], view_proj.y[i], view_proj.z[i], view_proj.w[i]);
+        let (r0, r1, r2, r3) = (row(0), row(1), row(2), row(3));
+
+        // the edges are moved out by scaling w, IE -w * (1 + margin) <= x
+        let edge = r3 * (1.0 + margin);
+        let (near, far) = match depth {
+            FrustumDepth::Standard | FrustumDepth::Infinite => (r2, r3 - r2),
+            FrustumDepth::Reversed | FrustumDepth::ReversedInfinite => (r3 - r2, r2)
+        };
+
+        let planes = [edge + r0, edge - r0, edge + r1, edge - r1, near, far]
+            .map(|plane| {
+                let magnitude = plane.truncate().magnitude();
+                if magnitude > 0.0 { plane / magnitude } else { plane }
+            });
+        Self { planes, far: depth.has_far() }
+    }
+
+    /// Extracts a frustum from the given cameras view projection matrix.
+    ///
+    /// Arguments:
+    /// * camera: &Camera - The camera whose frustum will be extracted.
+    ///
+    /// Returns the frustum of the given camera.
+    pub fn from_camera(camera: &Camera) -> Self { Self::from_matrix(camera.build_view_projection_matrix()) }
+
+    /// Checks if the given dimensions are at least partially inside this frustum.
+    ///
+    /// Arguments:
+    /// * &self - The frustum to check against.
+    /// * dimensions: &Dimensions - The box to check.
+    ///
+    /// Returns true if the box is not entirely outside any one of the planes.
+    pub fn intersects(&self, dimensions: &Dimensions) -> bool {
+        let count = if self.far { 6 } else { 5 };
+        self.planes[..count].iter().all(|plane| {
+            // the corner of the box furthest along the planes normal
+            let corner = Vector3 {
+                x: if plane.x >= 0.0 { dimensions.to.x } else { dimensions.from.x },
+                y: if plane.y >= 0.0 { dimensions.to.y } else { dimensions.from.y },
+                z: if plane.z >= 0.0 { dimensions.to.z } else { dimensions.from.z }
+            };
+            plane.truncate().dot(corner) + plane.w >= 0.0
+        })
+    }
+}
+
+/// A volume that nodes can be collected from, IE a cameras frustum, a directional lights orthographic box, or a point lights sphere.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CullVolume {
+    Frustum(Frustum),
+    Aabb(Dimensions),
+    Sphere(Vector3<f32>, f32)
+}
+
+impl CullVolume {
+    /// Checks if the given dimensions intersect with this volume.
+    ///
+    /// Arguments:
+    /// * &self - The volume to check against.
+    /// * dimensions: &Dimensions - The box to check.
+    ///
+    /// Returns true if the box and this volume intersect.
+    pub fn intersects(&self, dimensions: &Dimensions) -> bool {
+        match self {
+            CullVolume::Frustum(frustum) => frustum.intersects(dimensions),
+            CullVolume::Aabb(other) => other.overlap(dimensions),
+            CullVolume::Sphere(center, radius) => {
+                (dimensions.closest_point(*center) - center).magnitude2() <= radius * radius
+            }
+        }
+    }
+}
+
+/// The settings used by `draw_node_culled` to skip nodes that would not be visible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DrawCulling {
+    /// The frustum nodes must intersect to be drawn.
+    pub frustum: Frustum,
+
+    /// The position of the camera.
+    pub eye: Vector3<f32>,
+
+    /// The vertical field of view of the camera in radians.
+    pub fov_y: f32,
+
+    /// The height of the viewport in pixels.
+    pub viewport_height: u32,
+
+    /// If some, nodes whose bounding sphere has a projected radius smaller than this many pixels are not drawn.
+    pub min_screen_size: Option<f32>
+}
+
+impl DrawCulling {
+    /// Creates new draw culling settings from the given camera.
+    ///
+    /// Arguments:
+    /// * camera: &Camera - The camera that will be drawn with.
+    /// * viewport_height: u32 - The height of the viewport in pixels.
+    /// * min_screen_size: Option<f32> - The smallest projected radius in pixels to draw, if any.
+    ///
+    /// Returns the new draw culling settings.
+    pub fn from_camera(camera: &Camera, viewport_height: u32, min_screen_size: Option<f32>) -> Self {
+        Self {
+            frustum: Frustum::from_camera(camera),
+            eye: camera.position,
+            fov_y: camera.fovy.to_radians(),
+            viewport_height,
+            min_screen_size
+        }
+    }
+
+    /// Checks if anything inside the given merged dimensions of a subtree could be drawn.  As any node in the subtree
+    /// may be closer to the camera than the center of the dimensions, its size is measured from the closest point.
+    ///
+    /// Arguments:
+    /// * &self - The culling settings.
+    /// * dimensions: &Dimensions - The merged dimensions of the subtree.
+    ///
+    /// Returns false if the subtree can be skipped entirely.
+    pub fn subtree_visible(&self, dimensions: &Dimensions) -> bool {
+        if !self.frustum.intersects(dimensions) { return false }
+        match self.min_screen_size {
+            Some(min) => {
+                let radius = (dimensions.to - dimensions.from).magnitude() * 0.5;
+                projected_radius(radius, dimensions.distance_to(self.eye), self.fov_y, self.viewport_height) >= min
+            },
+            None => true
+        }
+    }
+
+    /// Checks if a node with the given own dimensions should be drawn.
+    ///
+    /// Arguments:
+    /// * &self - The culling settings.
+    /// * dimensions: &Dimensions - The dimensions of the node alone, without its children.
+    ///
+    /// Returns true if the node should be drawn.
+    pub fn node_visible(&self, dimensions: &Dimensions) -> bool {
+        if !self.frustum.intersects(dimensions) { return false }
+        match self.min_screen_size {
+            Some(min) => {
+                let radius = (dimensions.to - dimensions.from).magnitude() * 0.5;
+                let center = (dimensions.from + dimensions.to) * 0.5;
+                projected_radius(radius, (center - self.eye).magnitude(), self.fov_y, self.viewport_height) >= min
+            },
+            None => true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Matrix4, SquareMatrix, Vector3};
+
+    use crate::dimensions::Dimensions;
+
+    use super::{CullVolume, Frustum, FrustumDepth};
+
+    fn cube(from: [f32; 3], to: [f32; 3]) -> Dimensions { Dimensions { from: Vector3::from(from), to: Vector3::from(to) } }
+
+    // a small box around the given point
+    fn at(x: f32, y: f32, z: f32) -> Dimensions { cube([x - 0.01, y - 0.01, z - 0.01], [x + 0.01, y + 0.01, z + 0.01]) }
+
+    // a 90 degree projection looking down -z with a near plane at 1 and a far plane at 100 for the given convention
+    fn projection(depth: FrustumDepth) -> Matrix4<f32> {
+        let (near, far) = (1.0, 100.0);
+        let (a, b) = match depth {
+            FrustumDepth::Standard => (far / (near - far), near * far / (near - far)),
+            FrustumDepth::Infinite => (-1.0, -near),
+            FrustumDepth::Reversed => (near / (far - near), near * far / (far - near)),
+            FrustumDepth::ReversedInfinite => (0.0, near)
+        };
+        Matrix4::new(1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, a, -1.0, 0.0, 0.0, b, 0.0)
+    }
+
+    #[test]
+    fn frustum_volume() {
+        // the identity keeps x and y within -1 to 1 and depth within 0 to 1
+        let volume = CullVolume::Frustum(Frustum::from_matrix(Matrix4::identity()));
+        assert!(volume.intersects(&cube([-0.5, -0.5, 0.2], [0.5, 0.5, 0.8])));
+        assert!(volume.intersects(&cube([0.9, 0.9, 0.9], [3.0, 3.0, 3.0])));
+        assert!(!volume.intersects(&cube([1.1, 0.0, 0.5], [2.0, 0.5, 0.6])));
+        assert!(!volume.intersects(&cube([0.0, 0.0, -2.0], [0.5, 0.5, -0.1])));
+    }
+
+    #[test]
+    fn aabb_volume() {
+        let volume = CullVolume::Aabb(cube([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]));
+        assert!(volume.intersects(&cube([0.5, 0.5, 0.5], [2.0, 2.0, 2.0])));
+        assert!(!volume.intersects(&cube([1.1, 0.0, 0.0], [2.0, 1.0, 1.0])));
+
+        // boxes that only touch do not overlap, but a flat box on the surface does, see `Dimensions::overlap`
+        assert!(!volume.intersects(&cube([1.0, 0.0, 0.0], [2.0, 1.0, 1.0])));
+        assert!(volume.intersects(&cube([1.0, 0.0, 0.0], [1.0, 1.0, 1.0])));
+    }
+
+    #[test]
+    fn sphere_volume() {
+        // measured to the closest point of the box, so a box can be hit without any of its corners being inside
+        let volume = CullVolume::Sphere(Vector3::new(0.0, 0.0, 0.0), 1.0);
+        assert!(volume.intersects(&cube([-5.0, -5.0, -5.0], [5.0, 5.0, 5.0])));
+        assert!(volume.intersects(&cube([0.5, -3.0, -3.0], [2.0, 3.0, 3.0])));
+        assert!(volume.intersects(&cube([1.0, 0.0, 0.0], [2.0, 1.0, 1.0])));
+        assert!(!volume.intersects(&cube([0.8, 0.8, 0.0], [2.0, 2.0, 2.0])));
+    }
+
+    #[test]
+    fn frustum_depth_modes() {
+        for depth in [FrustumDepth::Standard, FrustumDepth::Infinite, FrustumDepth::Reversed, FrustumDepth::ReversedInfinite] {
+            let frustum = Frustum::from_matrix_with(projection(depth), depth, 0.0);
+            assert_eq!(frustum.far, depth.has_far());
+
+            // every convention keeps the space between the planes and drops what is behind the near plane
+            assert!(frustum.intersects(&at(0.0, 0.0, -50.0)), "{:?}", depth);
+            assert!(frustum.intersects(&at(0.0, 0.0, -1.5)), "{:?}", depth);
+            assert!(!frustum.intersects(&at(0.0, 0.0, -0.5)), "{:?}", depth);
+            assert!(!frustum.intersects(&at(0.0, 0.0, 5.0)), "{:?}", depth);
+
+            // only the finite conventions drop what is past the far plane
+            assert_eq!(frustum.intersects(&at(0.0, 0.0, -150.0)), !depth.has_far(), "{:?}", depth);
+            assert_eq!(frustum.intersects(&at(0.0, 0.0, -1.0e6)), !depth.has_far(), "{:?}", depth);
+        }
+    }
+
+    #[test]
+    fn frustum_margin_widens_the_edges_only() {
+        // at a depth of 10 the screen edges are 10 units from the center
+        let tight = Frustum::from_matrix_with(projection(FrustumDepth::Standard), FrustumDepth::Standard, 0.0);
+        let loose = Frustum::from_matrix_with(projection(FrustumDepth::Standard), FrustumDepth::Standard, 0.1);
+        for (x, y) in [(10.5, 0.0), (-10.5, 0.0), (0.0, 10.5), (0.0, -10.5)] {
+            assert!(!tight.intersects(&at(x, y, -10.0)) && loose.intersects(&at(x, y, -10.0)), "{} {}", x, y);
+        }
+        assert!(!loose.intersects(&at(11.5, 0.0, -10.0)));
+
+        // the near and far planes do not move
+        assert!(!loose.intersects(&at(0.0, 0.0, -0.5)));
+        assert!(!loose.intersects(&at(0.0, 0.0, -150.0)));
+        assert_eq!(Frustum::from_matrix(projection(FrustumDepth::Standard)), tight);
+    }
+}
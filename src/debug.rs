@@ -0,0 +1,180 @@
+use cgmath::{InnerSpace, Rotation, Vector3};
+use forte_engine::math::transforms::Transform;
+
+use crate::dimensions::Dimensions;
+
+/// A list of debug lines, stored as pairs of vertices, that can be drawn as a line list.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DebugLines {
+    pub vertices: Vec<Vector3<f32>>
+}
+
+impl DebugLines {
+    /// Creates a new empty list of debug lines.
+    pub fn new() -> Self { Self::default() }
+
+    /// The number of lines in this list.
+    pub fn len(&self) -> usize { self.vertices.len() / 2 }
+
+    /// Checks if this list contains no lines.
+    pub fn is_empty(&self) -> bool { self.vertices.is_empty() }
+
+    /// Removes all lines from this list while keeping its allocation.
+    pub fn clear(&mut self) { self.vertices.clear(); }
+
+    /// Adds a single line.
+    ///
+    /// Arguments:
+    /// * from: Vector3<f32> - The start of the line.
+    /// * to: Vector3<f32> - The end of the line.
+    pub fn line(&mut self, from: Vector3<f32>, to: Vector3<f32>) {
+        self.vertices.push(from);
+        self.vertices.push(to);
+    }
+
+    /// Adds the 12 edges of the given box.
+    ///
+    /// Arguments:
+    /// * dimensions: &Dimensions - The box to outline.
+    pub fn aabb(&mut self, dimensions: &Dimensions) {
+        let (a, b) = (dimensions.from, dimensions.to);
+        let corner = |x: bool, y: bool, z: bool| Vector3 {
+            x: if x { b.x } else { a.x },
+            y: if y { b.y } else { a.y },
+            z: if z { b.z } else { a.z }
+        };
+
+        for i in 0..4 {
+            let (p, q) = (i & 1 == 1, i & 2 == 2);
+            self.line(corner(false, p, q), corner(true, p, q));
+            self.line(corner(p, false, q), corner(p, true, q));
+            self.line(corner(p, q, false), corner(p, q, true));
+        }
+    }
+
+    /// Adds a sphere drawn as three circles, one in each of the XY, XZ and YZ planes.
+    ///
+    /// Arguments:
+    /// * center: Vector3<f32> - The center of the sphere.
+    /// * radius: f32 - The radius of the sphere.
+    /// * segments: usize - The number of lines used for each circle, at least 3 are always used.
+    pub fn sphere(&mut self, center: Vector3<f32>, radius: f32, segments: usize) {
+        let segments = segments.max(3);
+        let point = |i: usize| {
+            let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+            (angle.cos() * radius, angle.sin() * radius)
+        };
+
+        for i in 0..segments {
+            let ((a, b), (c, d)) = (point(i), point(i + 1));
+            self.line(center + Vector3::new(a, b, 0.0), center + Vector3::new(c, d, 0.0));
+            self.line(center + Vector3::new(a, 0.0, b), center + Vector3::new(c, 0.0, d));
+            self.line(center + Vector3::new(0.0, a, b), center + Vector3::new(0.0, c, d));
+        }
+    }
+
+    /// Adds an arrow made of a shaft and a four line head at the end.
+    ///
+    /// Arguments:
+    /// * from: Vector3<f32> - The tail of the arrow.
+    /// * to: Vector3<f32> - The tip of the arrow.
+    ///
+    /// Nothing is added if from and to are the same point.
+    pub fn arrow(&mut self, from: Vector3<f32>, to: Vector3<f32>) {
+        let shaft = to - from;
+        let length = shaft.magnitude();
+        if length == 0.0 { return }
+
+        // find two directions perpendicular to the shaft for the head to spread along
+        let direction = shaft / length;
+        let reference = if direction.y.abs() < 0.99 { Vector3::unit_y() } else { Vector3::unit_x() };
+        let side = direction.cross(reference).normalize();
+        let up = side.cross(direction);
+
+        let head = length * 0.25;
+        let base = to - direction * head;
+        self.line(from, to);
+        for offset in [side, -side, up, -up] { self.line(to, base + offset * head * 0.5); }
+    }
+
+    /// Adds the X, Y and Z axes of the given transform from its position.
+    ///
+    /// Arguments:
+    /// * transform: &Transform - The transform whose position and rotation are drawn.
+    /// * size: f32 - The length of each axis line.
+    pub fn axes(&mut self, transform: &Transform, size: f32) {
+        for axis in [Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()] {
+            self.line(transform.position, transform.position + transform.rotation.rotate_vector(axis) * size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Deg, InnerSpace, Quaternion, Rotation3, Vector3};
+    use forte_engine::math::transforms::Transform;
+
+    use crate::dimensions::Dimensions;
+
+    use super::DebugLines;
+
+    #[test]
+    fn lines_and_clear() {
+        let mut lines = DebugLines::new();
+        assert!(lines.is_empty());
+        lines.line(Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!((lines.len(), lines.vertices.len()), (1, 2));
+
+        lines.clear();
+        assert!(lines.is_empty() && lines.vertices.capacity() >= 2);
+    }
+
+    #[test]
+    fn aabb_draws_each_edge_once() {
+        let mut lines = DebugLines::new();
+        lines.aabb(&Dimensions { from: Vector3::new(0.0, 0.0, 0.0), to: Vector3::new(1.0, 2.0, 3.0) });
+        assert_eq!(lines.len(), 12);
+
+        // every edge runs along one axis for the full size of the box
+        let mut lengths: Vec<f32> = lines.vertices.chunks(2).map(|line| (line[1] - line[0]).magnitude()).collect();
+        lengths.sort_by(f32::total_cmp);
+        assert_eq!(lengths, [1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, 3.0, 3.0, 3.0, 3.0]);
+    }
+
+    #[test]
+    fn sphere_draws_three_circles() {
+        let mut lines = DebugLines::new();
+        lines.sphere(Vector3::new(1.0, 1.0, 1.0), 2.0, 16);
+        assert_eq!(lines.len(), 48);
+        assert!(lines.vertices.iter().all(|vertex| ((vertex - Vector3::new(1.0, 1.0, 1.0)).magnitude() - 2.0).abs() < 1e-5));
+
+        // too few segments are raised to 3
+        let mut lines = DebugLines::new();
+        lines.sphere(Vector3::new(0.0, 0.0, 0.0), 1.0, 1);
+        assert_eq!(lines.len(), 9);
+    }
+
+    #[test]
+    fn arrow_has_a_shaft_and_four_head_lines() {
+        let mut lines = DebugLines::new();
+        let tip = Vector3::new(0.0, 4.0, 0.0);
+        lines.arrow(Vector3::new(0.0, 0.0, 0.0), tip);
+        assert_eq!(lines.len(), 5);
+        assert!(lines.vertices.chunks(2).skip(1).all(|line| line[0] == tip));
+
+        // a zero length arrow has no direction to draw
+        lines.arrow(tip, tip);
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    fn axes_follow_the_rotation() {
+        let mut lines = DebugLines::new();
+        let transform = Transform { position: Vector3::new(1.0, 0.0, 0.0), rotation: Quaternion::from_angle_z(Deg(90.0)), scale: Vector3::new(1.0, 1.0, 1.0) };
+        lines.axes(&transform, 2.0);
+        assert_eq!(lines.len(), 3);
+
+        // the x axis is turned to point along y
+        assert!((lines.vertices[1] - Vector3::new(1.0, 2.0, 0.0)).magnitude() < 1e-5);
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    io::{self, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    sync::{atomic::{AtomicBool, Ordering}, mpsc::{self, Receiver, SyncSender, TrySendError}, Arc, Mutex, MutexGuard},
+    thread::{self, JoinHandle},
+    time::Duration
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ids::NodeId,
+    nodes::{ComponentDef, Node, World},
+    reports::FrameReport
+};
+
+/// The version of the wire format of a `DebugServer`, sent with every line so clients can skip lines they do not
+/// understand.  It goes up whenever a field of `DebugSnapshot` changes meaning or is removed.
+pub const DEBUG_WIRE_VERSION: u32 = 2;
+
+/// One node of a `DebugSnapshot`, with just enough of it to find it in the tree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebugNode {
+    pub id: Option<NodeId>,
+    pub name: Option<String>,
+
+    /// The name of the variant of the component of the node, None if it is empty.
+    pub variant: Option<String>,
+    pub enabled: bool,
+    pub children: Vec<DebugNode>
+}
+
+impl DebugNode {
+    fn capture<C: ComponentDef<A>, A>(node: &Node<C, A>) -> Self {
+        Self {
+            id: node.id(),
+            name: node.name.clone(),
+            variant: node.component.variant_index().map(|idx| C::VARIANT_NAMES[idx].to_owned()),
+            enabled: node.enabled,
+            children: node.children().iter().map(DebugNode::capture).collect()
+        }
+    }
+}
+
+/// The shape of the tree a `DebugSnapshot` was captured from.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreeReport {
+    /// The number of nodes drawn in the frame, see `World::rendered_count`.
+    pub rendered: usize,
+
+    /// The number of levels below the root of the deepest node.
+    pub max_depth: usize,
+
+    /// The name of each variant and the number of nodes holding it, in the order the variants were defined.
+    pub components: Vec<(String, usize)>
+}
+
+/// A read only copy of the shape of a world, which a `DebugServer` sends to its clients as one line of JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebugSnapshot {
+    /// The `DEBUG_WIRE_VERSION` the snapshot was written with.
+    pub version: u32,
+
+    /// The report of the frame the snapshot was captured in, see `World::frame_report`.
+    pub report: FrameReport,
+    pub tree: TreeReport,
+    pub root: DebugNode
+}
+
+impl DebugSnapshot {
+    /// Captures the tree of the given world along with the report of its current frame, see `World::frame_report`.  This walks the whole tree, so
+    /// it is meant to be called every second or so rather than every frame.
+    pub fn capture<C: ComponentDef<A>, A>(world: &World<C, A>) -> Self {
+        let stats = world.stats();
+        let tree = TreeReport {
+            rendered: world.rendered_count(),
+            max_depth: stats.max_depth,
+            components: stats.components.iter().map(|(name, count)| (name.to_string(), *count)).collect()
+        };
+        Self { version: DEBUG_WIRE_VERSION, report: world.frame_report(), tree, root: DebugNode::capture(world.root()) }
+    }
+}
+
+/// How a `DebugServer` treats its clients, see `DebugServer::spawn_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DebugServerConfig {
+    /// The most clients connected at once, any more are sent an error line and closed.
+    pub max_clients: usize,
+
+    /// The number of published snapshots that can wait to be sent before `publish` drops them, at least 1.
+    pub queue: usize,
+
+    /// How long a client may stall a send before it is dropped, so one slow client cannot hold back the others.
+    pub write_timeout: Duration
+}
+
+impl Default for DebugServerConfig {
+    fn default() -> Self { Self { max_clients: 4, queue: 1, write_timeout: Duration::from_secs(1) } }
+}
+
+// the line sent to clients over the limit
+#[derive(Serialize)]
+struct Rejected {
+    version: u32,
+    error: &'static str
+}
+
+// the state both threads of a server share
+#[derive(Default)]
+struct Shared {
+    clients: Mutex<Vec<TcpStream>>,
+    // the last line sent, given to clients as they connect so they do not wait for the next publish
+    latest: Mutex<Option<Arc<str>>>,
+    stopped: AtomicBool
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> { mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) }
+
+// accepts clients until the server is stopped, clients are never read from so they cannot change anything
+fn accept(listener: TcpListener, shared: &Shared, config: DebugServerConfig) {
+    for stream in listener.incoming() {
+        if shared.stopped.load(Ordering::Acquire) { break }
+        let Ok(mut stream) = stream else { continue };
+        if stream.set_write_timeout(Some(config.write_timeout)).is_err() { continue }
+
+        // the clients are locked before the latest line, like `broadcast`, so no line is sent twice or missed
+        let mut clients = lock(&shared.clients);
+        if clients.len() >= config.max_clients {
+            let rejected = serde_json::to_string(&Rejected { version: DEBUG_WIRE_VERSION, error: "too many clients" }).unwrap();
+            let _ = stream.write_all(format!("{rejected}\n").as_bytes());
+            continue
+        }
+        let latest = lock(&shared.latest).clone();
+        if latest.map_or(Ok(()), |line| stream.write_all(line.as_bytes())).is_ok() { clients.push(stream); }
+    }
+}
+
+// sends every published line to every client until the server is dropped, dropping clients that fail a send
+fn broadcast(lines: Receiver<Arc<str>>, shared: &Shared) {
+    for line in lines {
+        let mut clients = lock(&shared.clients);
+        *lock(&shared.latest) = Some(line.clone());
+        clients.retain_mut(|client| client.write_all(line.as_bytes()).is_ok());
+    }
+}
+
+/// A blocking TCP server that sends the snapshots published to it to every connected client, IE to inspect a game
+/// running on another machine.  Each snapshot is sent as one line of JSON, see `DebugSnapshot`, and a client that
+/// connects is sent the last one right away.  The server never reads from its clients, so it cannot be used to change
+/// the world.
+///
+/// The server runs on two threads of its own, which are stopped when it is dropped.
+pub struct DebugServer {
+    addr: SocketAddr,
+    lines: Option<SyncSender<Arc<str>>>,
+    shared: Arc<Shared>,
+    dropped: usize,
+    threads: Vec<JoinHandle<()>>
+}
+
+impl DebugServer {
+    /// Starts a server listening on the given port of every interface with the default config, port 0 picks a free
+    /// port, see `local_addr`.
+    ///
+    /// Returns an error if the port could not be bound.
+    pub fn spawn(port: u16) -> io::Result<Self> { Self::spawn_with(port, DebugServerConfig::default()) }
+
+    /// Starts a server like `spawn` with the given config.
+    pub fn spawn_with(port: u16, config: DebugServerConfig) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, port))?;
+        let addr = listener.local_addr()?;
+        let (lines, received) = mpsc::sync_channel(config.queue.max(1));
+        let shared = Arc::new(Shared::default());
+
+        let accepting = { let shared = shared.clone(); thread::spawn(move || accept(listener, &shared, config)) };
+        let broadcasting = { let shared = shared.clone(); thread::spawn(move || broadcast(received, &shared)) };
+        Ok(Self { addr, lines: Some(lines), shared, dropped: 0, threads: vec![accepting, broadcasting] })
+    }
+
+    /// The address this server is listening on.
+    pub fn local_addr(&self) -> SocketAddr { self.addr }
+
+    /// The number of clients connected.
+    pub fn client_count(&self) -> usize { lock(&self.shared.clients).len() }
+
+    /// The number of snapshots `publish` has dropped so far.
+    pub fn dropped_frames(&self) -> usize { self.dropped }
+
+    /// Queues the given snapshot to be sent to every client without waiting for them.  If the queue is full because
+    /// the clients are slow, the snapshot is dropped so the frame is never held up.
+    ///
+    /// Returns true if the snapshot was queued.
+    pub fn publish(&mut self, snapshot: &DebugSnapshot) -> bool {
+        let line = serde_json::to_string(snapshot).expect("debug snapshots always serialize") + "\n";
+        match self.lines.as_ref().unwrap().try_send(line.into()) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_) | TrySendError::Disconnected(_)) => {
+                self.dropped += 1;
+                false
+            }
+        }
+    }
+}
+
+impl Drop for DebugServer {
+    fn drop(&mut self) {
+        // closing the queue stops the broadcasting thread, and a connection wakes the accepting thread to stop it
+        self.lines = None;
+        self.shared.stopped.store(true, Ordering::Release);
+        let _ = TcpStream::connect((Ipv4Addr::LOCALHOST, self.addr.port()));
+        self.threads.drain(..).for_each(|thread| { let _ = thread.join(); });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader},
+        net::{Ipv4Addr, TcpStream},
+        time::{Duration, Instant}
+    };
+
+    use super::{DebugServer, DebugServerConfig, DebugSnapshot, DEBUG_WIRE_VERSION};
+    use crate::test_support::*;
+
+    fn world() -> World {
+        let mut world = World::new();
+        let a = world.spawn(world.root_id(), Node::builder().name("a").build()).unwrap();
+        world.spawn(a, marker(0));
+        world
+    }
+
+    fn connect(server: &DebugServer, clients: usize) -> BufReader<TcpStream> {
+        let stream = TcpStream::connect((Ipv4Addr::LOCALHOST, server.local_addr().port())).unwrap();
+        let start = Instant::now();
+        while server.client_count() < clients {
+            assert!(start.elapsed() < Duration::from_secs(5), "the client was never accepted");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        BufReader::new(stream)
+    }
+
+    fn read(client: &mut BufReader<TcpStream>) -> String {
+        let mut line = String::new();
+        client.read_line(&mut line).unwrap();
+        line
+    }
+
+    #[test]
+    fn published_snapshots_reach_every_client() {
+        let mut server = DebugServer::spawn(0).unwrap();
+        let mut first = connect(&server, 1);
+
+        let mut world = world();
+        world.begin_frame();
+        world.update(&mut TestApp, 0.1);
+        let snapshot = DebugSnapshot::capture(&world);
+        assert_eq!(snapshot.version, DEBUG_WIRE_VERSION);
+        assert_eq!((snapshot.report.nodes, snapshot.report.updated), (3, 3));
+        assert_eq!(snapshot.report, world.frame_report());
+        assert_eq!(snapshot.tree.max_depth, 2);
+        assert_eq!(snapshot.root.children[0].children[0].variant.as_deref(), Some("Marker"));
+        assert!(server.publish(&snapshot));
+        assert_eq!(serde_json::from_str::<DebugSnapshot>(&read(&mut first)).unwrap(), snapshot);
+
+        // a late client is sent the last snapshot right away
+        let mut second = connect(&server, 2);
+        assert_eq!(serde_json::from_str::<DebugSnapshot>(&read(&mut second)).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn extra_clients_are_turned_away() {
+        let server = DebugServer::spawn_with(0, DebugServerConfig { max_clients: 1, ..DebugServerConfig::default() }).unwrap();
+        let _first = connect(&server, 1);
+
+        let mut second = BufReader::new(TcpStream::connect((Ipv4Addr::LOCALHOST, server.local_addr().port())).unwrap());
+        let rejected: serde_json::Value = serde_json::from_str(&read(&mut second)).unwrap();
+        assert_eq!(rejected["version"], DEBUG_WIRE_VERSION);
+        assert_eq!(rejected["error"], "too many clients");
+        assert_eq!(read(&mut second), "");
+        assert_eq!(server.client_count(), 1);
+    }
+
+    #[test]
+    fn publish_drops_frames_when_the_queue_is_full() {
+        let mut server = DebugServer::spawn(0).unwrap();
+        let mut client = connect(&server, 1);
+        let mut snapshot = DebugSnapshot::capture(&world());
+
+        // while the clients are locked nothing can be sent, so at most one snapshot is taken and one waits
+        let shared = server.shared.clone();
+        let clients = super::lock(&shared.clients);
+        let queued: Vec<u64> = (1..=3)
+            .filter(|frame| {
+                snapshot.report.frame = *frame;
+                server.publish(&snapshot)
+            })
+            .collect();
+        drop(clients);
+        assert!(!queued.is_empty() && queued.len() < 3);
+        assert_eq!(server.dropped_frames(), 3 - queued.len());
+
+        // the queued snapshots still arrive in order
+        for frame in queued {
+            assert_eq!(serde_json::from_str::<DebugSnapshot>(&read(&mut client)).unwrap().report.frame, frame);
+        }
+    }
+}
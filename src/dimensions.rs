@@ -1,10 +1,11 @@
-use cgmath::{Vector3, Zero};
+use cgmath::{InnerSpace, Vector3, Zero};
 
 /// A simple AABB box to determine the dimensions of a node.
 /// 
 /// From should be the smallest point. IE (-1, -1, -1).
 /// To should be the largest point.  IE (1, 1, 1).
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Dimensions {
     pub from: Vector3<f32>,
     pub to: Vector3<f32>
@@ -21,7 +22,8 @@ impl Default for Dimensions {
 }
 
 impl Dimensions {
-    /// Checks if two dimensions overlap.
+    /// Checks if two dimensions overlap.  On an axis where either dimension is flat, IE the z axis of two dimensional
+    /// nodes, they only have to touch, so flat nodes on the same layer can overlap.
     /// 
     /// Arguments:
     /// * &self - Our dimension
@@ -29,8 +31,180 @@ impl Dimensions {
     /// 
     /// Returns true of the two dimensions overlap.  False if they do not.
     pub fn overlap(&self, other: &Dimensions) -> bool {
-        return self.from.x < other.to.x && other.from.x < self.to.x && 
-                self.from.y < other.to.y && other.from.y < self.to.y &&
-                self.from.z < other.to.z && other.from.z < self.to.z
+        (0..3).all(|axis| axis_overlap(self.from[axis], self.to[axis], other.from[axis], other.to[axis]))
     }
-}
\ No newline at end of file
+
+    /// Checks if the other dimensions are entirely inside of these dimensions, sharing a boundary counts as inside.
+    /// 
+    /// Arguments:
+    /// * &self - Our dimension
+    /// * other: &Dimensions - Other dimension to check.
+    /// 
+    /// Returns true if every point of other is inside of self.
+    pub fn contains(&self, other: &Dimensions) -> bool {
+        self.from.x <= other.from.x && other.to.x <= self.to.x &&
+                self.from.y <= other.from.y && other.to.y <= self.to.y &&
+                self.from.z <= other.from.z && other.to.z <= self.to.z
+    }
+
+    /// Finds the closest point inside these dimensions to the given point.
+    /// 
+    /// Arguments:
+    /// * &self - Our dimension
+    /// * point: Vector3<f32> - The point to find the closest point too.
+    /// 
+    /// Returns the given point if it is inside these dimensions, otherwise the closest point on the surface.
+    pub fn closest_point(&self, point: Vector3<f32>) -> Vector3<f32> {
+        Vector3 {
+            x: point.x.max(self.from.x).min(self.to.x),
+            y: point.y.max(self.from.y).min(self.to.y),
+            z: point.z.max(self.from.z).min(self.to.z)
+        }
+    }
+
+    /// Calculates the distance from the given point to these dimensions.
+    /// 
+    /// Arguments:
+    /// * &self - Our dimension
+    /// * point: Vector3<f32> - The point to measure from.
+    /// 
+    /// Returns 0 if the point is inside these dimensions, otherwise the distance to the closest point on the surface.
+    pub fn distance_to(&self, point: Vector3<f32>) -> f32 {
+        (self.closest_point(point) - point).magnitude()
+    }
+
+    /// Finds where a ray first touches these dimensions.
+    /// 
+    /// Arguments:
+    /// * &self - Our dimension
+    /// * origin: Vector3<f32> - The start of the ray.
+    /// * direction: Vector3<f32> - The direction of the ray, this should be normalized so the returned distance is in world units.
+    /// 
+    /// Returns the distance along the ray to the first point inside these dimensions, 0 if the origin is inside, or None if the ray misses.
+    pub fn ray_intersection(&self, origin: Vector3<f32>, direction: Vector3<f32>) -> Option<f32> {
+        let mut near = 0.0_f32;
+        let mut far = f32::INFINITY;
+
+        for axis in 0..3 {
+            let (origin, direction) = (origin[axis], direction[axis]);
+            let (from, to) = (self.from[axis], self.to[axis]);
+
+            // parallel rays must already be between the two slabs
+            if direction == 0.0 {
+                if origin < from || origin > to { return None }
+                continue;
+            }
+
+            let a = (from - origin) / direction;
+            let b = (to - origin) / direction;
+            near = near.max(a.min(b));
+            far = far.min(a.max(b));
+            if near > far { return None }
+        }
+
+        Some(near)
+    }
+}
+
+// checks if two ranges on one axis overlap, a flat range only has to touch the other
+fn axis_overlap(from: f32, to: f32, other_from: f32, other_to: f32) -> bool {
+    if from == to || other_from == other_to { from <= other_to && other_from <= to } else { from < other_to && other_from < to }
+}
+
+/// How a node has to be placed relative to a region to be picked, see `Node::despawn_in_region`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapMode {
+    /// The node and all of its children must be entirely inside the region.
+    #[default]
+    Contained,
+
+    /// The node itself only has to overlap the region, its children are removed along with it.
+    Touching
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Vector3;
+
+    use super::{Dimensions, OverlapMode};
+
+    fn dims(from: (f32, f32, f32), to: (f32, f32, f32)) -> Dimensions { Dimensions { from: from.into(), to: to.into() } }
+
+    #[test]
+    fn overlap_is_strict_for_boxes() {
+        let a = dims((0.0, 0.0, 0.0), (1.0, 1.0, 1.0));
+        assert!(a.overlap(&dims((0.5, 0.5, 0.5), (2.0, 2.0, 2.0))));
+        assert!(!a.overlap(&dims((1.0, 0.0, 0.0), (2.0, 1.0, 1.0))));
+        assert!(!a.overlap(&dims((2.0, 2.0, 2.0), (3.0, 3.0, 3.0))));
+    }
+
+    #[test]
+    fn flat_dimensions_overlap_on_their_layer() {
+        let a = dims((0.0, 0.0, 5.0), (2.0, 2.0, 5.0));
+        assert!(a.overlap(&dims((1.0, 1.0, 5.0), (3.0, 3.0, 5.0))));
+        assert!(!a.overlap(&dims((1.0, 1.0, 6.0), (3.0, 3.0, 6.0))));
+        assert!(!a.overlap(&dims((3.0, 3.0, 5.0), (4.0, 4.0, 5.0))));
+        assert!(a.overlap(&dims((1.0, 1.0, 0.0), (3.0, 3.0, 10.0))));
+        assert!(Dimensions::default().overlap(&dims((-1.0, -1.0, -1.0), (1.0, 1.0, 1.0))));
+        assert!(!Dimensions { from: Vector3::new(2.0, 0.0, 0.0), to: Vector3::new(2.0, 0.0, 0.0) }.overlap(&a));
+    }
+
+    #[test]
+    fn closest_point_and_distance() {
+        let a = dims((0.0, 0.0, 0.0), (2.0, 2.0, 2.0));
+
+        // points inside are their own closest point
+        assert_eq!(a.closest_point(Vector3::new(1.0, 0.5, 2.0)), Vector3::new(1.0, 0.5, 2.0));
+        assert_eq!(a.distance_to(Vector3::new(1.0, 1.0, 1.0)), 0.0);
+
+        // outside on one axis, then past an edge and past a corner
+        assert_eq!(a.closest_point(Vector3::new(5.0, 1.0, 1.0)), Vector3::new(2.0, 1.0, 1.0));
+        assert_eq!(a.distance_to(Vector3::new(5.0, 1.0, 1.0)), 3.0);
+        assert_eq!(a.distance_to(Vector3::new(-3.0, -4.0, 1.0)), 5.0);
+        assert_eq!(a.closest_point(Vector3::new(-1.0, 3.0, 4.0)), Vector3::new(0.0, 2.0, 2.0));
+
+        // a flat box is measured to its plane
+        assert_eq!(dims((0.0, 0.0, 1.0), (2.0, 2.0, 1.0)).distance_to(Vector3::new(1.0, 1.0, 4.0)), 3.0);
+    }
+
+    #[test]
+    fn ray_intersection() {
+        let a = dims((0.0, 0.0, 0.0), (2.0, 2.0, 2.0));
+
+        // from outside, the distance to the first face hit
+        assert_eq!(a.ray_intersection(Vector3::new(-3.0, 1.0, 1.0), Vector3::unit_x()), Some(3.0));
+        assert_eq!(a.ray_intersection(Vector3::new(1.0, 5.0, 1.0), -Vector3::unit_y()), Some(3.0));
+
+        // from inside, or on a face, the ray starts in the box
+        assert_eq!(a.ray_intersection(Vector3::new(1.0, 1.0, 1.0), Vector3::unit_z()), Some(0.0));
+        assert_eq!(a.ray_intersection(Vector3::new(0.0, 1.0, 1.0), Vector3::unit_x()), Some(0.0));
+
+        // pointing away, beside the box, or parallel to a face outside of it misses
+        assert_eq!(a.ray_intersection(Vector3::new(-3.0, 1.0, 1.0), -Vector3::unit_x()), None);
+        assert_eq!(a.ray_intersection(Vector3::new(-3.0, 3.0, 1.0), Vector3::unit_x()), None);
+        assert_eq!(a.ray_intersection(Vector3::new(-3.0, 2.5, 1.0), Vector3::new(1.0, 0.0, 0.0)), None);
+
+        // a diagonal ray through a corner
+        let diagonal = Vector3::new(1.0, 1.0, 0.0) / 2.0f32.sqrt();
+        let hit = a.ray_intersection(Vector3::new(-1.0, -1.0, 1.0), diagonal).unwrap();
+        assert!((hit - 2.0f32.sqrt()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn contains_includes_the_boundary() {
+        let a = dims((0.0, 0.0, 0.0), (2.0, 2.0, 2.0));
+        assert!(a.contains(&a));
+        assert!(a.contains(&dims((0.5, 0.5, 0.5), (1.5, 1.5, 1.5))));
+        assert!(a.contains(&dims((0.0, 1.0, 1.0), (2.0, 1.0, 1.0))));
+
+        // sticking out on any one side is not inside, nor is containing the other way around
+        assert!(!a.contains(&dims((-0.1, 0.5, 0.5), (1.0, 1.0, 1.0))));
+        assert!(!a.contains(&dims((0.5, 0.5, 0.5), (1.0, 1.0, 2.1))));
+        assert!(!dims((0.5, 0.5, 0.5), (1.5, 1.5, 1.5)).contains(&a));
+    }
+
+    #[test]
+    fn overlap_mode_defaults_to_contained() {
+        assert_eq!(OverlapMode::default(), OverlapMode::Contained);
+    }
+}
@@ -0,0 +1,198 @@
+use std::any::Any;
+
+use forte_engine::math::transforms::Transform;
+
+use crate::{culling::DrawCulling, ids::NodeId, nodes::{ComponentDef, World}};
+
+// the number of buffers of each payload type kept for reuse, one being filled while the other is drawn
+const SPARES: usize = 2;
+
+/// A rendered component copied out of a world by `World::finalize_frame`, see `FrameDrawData`.
+#[derive(Debug, Clone)]
+pub struct FrameDrawItem<P> {
+    /// The id of the node of the component, None if the node was added without going through the world and has not
+    /// been given an id yet, see `World::refresh_ids`.
+    pub node: Option<NodeId>,
+
+    /// The index of the variant of the component, see `ComponentDef::variant_index`.
+    pub variant: usize,
+
+    /// The global transform of the node as of its last update.
+    pub transform: Transform,
+
+    /// True if the component should be collected for shadow passes, see `ComponentDef::casts_shadow`.
+    pub casts_shadow: bool,
+
+    /// The render priority of the component, see `ComponentDef::priority`.
+    pub priority: i32,
+
+    /// The draw sort key of the component, see `ComponentDef::sort_key`.
+    pub sort_key: Option<u64>,
+
+    /// The data copied out of the component by the payload function given to `World::finalize_frame_with`.
+    pub payload: Option<P>
+}
+
+/// The draw list of a world copied out at the end of its update by `World::finalize_frame`, so a render thread can
+/// record the frame while the world already updates the next one.  Nothing in it borrows the world, so it can be sent
+/// to another thread, and once drawn it should be given back with `World::recycle_frame` so its buffer is reused.
+#[derive(Debug, Clone)]
+pub struct FrameDrawData<P = ()> {
+    frame: u64,
+    items: Vec<FrameDrawItem<P>>
+}
+
+impl<P> FrameDrawData<P> {
+    /// The frame of the world this was finalized in, see `World::frame`.
+    pub fn frame(&self) -> u64 { self.frame }
+
+    /// The rendered components of the frame, in the order `World::draw` would render them.
+    pub fn items(&self) -> &[FrameDrawItem<P>] { &self.items }
+
+    /// The number of rendered components in the frame.
+    pub fn len(&self) -> usize { self.items.len() }
+
+    /// True if nothing is rendered in the frame.
+    pub fn is_empty(&self) -> bool { self.items.is_empty() }
+}
+
+// the buffers of the frames given back with `World::recycle_frame`, each box holds the buffer for one payload type and
+// is left in place while its buffer is out, so giving a buffer back does not allocate
+#[derive(Default)]
+pub(crate) struct FrameSpares(Vec<Box<dyn Any + Send + Sync>>);
+
+impl FrameSpares {
+    // takes a spare buffer for the given payload type, or an empty one if none was given back
+    fn take<P: Send + Sync + 'static>(&mut self) -> Vec<FrameDrawItem<P>> {
+        self.0.iter_mut()
+            .filter_map(|spare| spare.downcast_mut::<Vec<FrameDrawItem<P>>>())
+            .find(|spare| spare.capacity() > 0)
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    // keeps the given buffer for reuse, dropping it if enough buffers of its payload type are kept already
+    fn give<P: Send + Sync + 'static>(&mut self, mut items: Vec<FrameDrawItem<P>>) {
+        if items.capacity() == 0 { return }
+        items.clear();
+
+        let mut kept = 0;
+        for spare in self.0.iter_mut().filter_map(|spare| spare.downcast_mut::<Vec<FrameDrawItem<P>>>()) {
+            if spare.capacity() == 0 {
+                *spare = items;
+                return;
+            }
+            kept += 1;
+        }
+        if kept < SPARES { self.0.push(Box::new(items)); }
+    }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Copies the draw list of the tree below the root out of the world without any component data, see
+    /// `finalize_frame_with`.
+    pub fn finalize_frame(&mut self) -> FrameDrawData { self.finalize_frame_with(None, |_| None::<()>) }
+
+    /// Copies the draw list of the tree below the root out of the world, for drawing on another thread while the
+    /// world updates the next frame.  Each component whose variant gives a RENDER callback becomes one item, in the
+    /// order `draw` would render it and skipping those that fail the culling if given, with its global transform,
+    /// render flags and sort key copied by value.  Nothing is stamped or counted, as nothing is drawn yet.
+    ///
+    /// RENDER callbacks borrow the data of their components, which cannot be done from another thread while the world
+    /// updates, so the given payload function copies out what the render thread needs instead.  The payload must own
+    /// its data and be `Send` and `Sync`, IE `Copy` data or data wrapped in an `Arc`, which the compiler checks, and
+    /// variants the function returns None for are drawn without a payload.
+    ///
+    /// The buffer of the returned data is one given back with `recycle_frame` when there is one, so a world that
+    /// recycles each frame once it is drawn does not allocate for it after the first frames.
+    pub fn finalize_frame_with<P: Send + Sync + 'static>(&mut self, culling: Option<&DrawCulling>, mut payload: impl FnMut(&C) -> Option<P>) -> FrameDrawData<P> {
+        let mut items = self.frame_spares.take::<P>();
+        self.with_draw_list(culling, |list| {
+            items.extend(list.iter().flat_map(|node| node.components().map(move |component| (*node, component))).filter_map(|(node, component)| {
+                let variant = component.variant_index().filter(|variant| C::RENDERED[*variant])?;
+                Some(FrameDrawItem {
+                    node: node.id(),
+                    variant,
+                    transform: *node.global_transform(),
+                    casts_shadow: component.casts_shadow(),
+                    priority: component.priority(),
+                    sort_key: component.sort_key(),
+                    payload: payload(component)
+                })
+            }));
+        });
+        FrameDrawData { frame: self.frame(), items }
+    }
+
+    /// Gives the buffer of the given frame back to this world once it is drawn, for the next `finalize_frame` with the
+    /// same payload type to reuse.  Two buffers of each payload type are kept, one for the frame being drawn and one
+    /// for the frame being finalized.
+    pub fn recycle_frame<P: Send + Sync + 'static>(&mut self, frame: FrameDrawData<P>) { self.frame_spares.give(frame.items); }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use cgmath::Vector3;
+
+    use super::FrameDrawData;
+    use crate::test_support::*;
+
+    #[test]
+    fn finalized_frames_copy_the_draw_list() {
+        let mut world = World::new();
+        let root = world.root_id();
+        let mut parent = marker(1);
+        parent.add_child(marker(2));
+        let parent = world.spawn(root, parent).unwrap();
+        world.spawn(root, Node::with_other(Other)).unwrap();
+        world.get_mut(parent).unwrap().transform.position = Vector3::new(1.0, 0.0, 0.0);
+        world.update(&mut TestApp, 0.0);
+
+        let payload = |component: &Component| match component {
+            Component::Marker(marker) => Some(Arc::new(marker.0)),
+            _ => None
+        };
+        let frame = world.finalize_frame_with(None, payload);
+        assert_eq!(frame.frame(), world.frame());
+        assert_eq!(frame.items().iter().map(|item| item.payload.as_deref().copied()).collect::<Vec<_>>(), [Some(1), Some(2)]);
+        assert_eq!(frame.items()[0].node, Some(parent));
+
+        // the frame keeps what it copied while the world moves on
+        world.get_mut(parent).unwrap().transform.position = Vector3::new(5.0, 0.0, 0.0);
+        *world.get_mut(parent).unwrap().get_mut::<Marker>().unwrap() = Marker(9);
+        world.update(&mut TestApp, 0.0);
+        assert_eq!(frame.items().iter().map(|item| item.transform.position.x).collect::<Vec<_>>(), [1.0, 1.0]);
+        assert_eq!(*frame.items()[0].payload.clone().unwrap(), 1);
+
+        // frames can be drawn on another thread
+        let frame = std::thread::spawn(move || frame).join().unwrap();
+        world.recycle_frame(frame);
+        let next = world.finalize_frame_with(None, payload);
+        assert_eq!(next.items().iter().map(|item| item.transform.position.x).collect::<Vec<_>>(), [5.0, 5.0]);
+        assert_eq!(next.items()[0].payload.as_deref(), Some(&9));
+    }
+
+    #[test]
+    fn recycled_frames_reuse_their_buffers() {
+        let mut world = World::new();
+        let root = world.root_id();
+        (0..8).for_each(|id| { world.spawn(root, marker(id)).unwrap(); });
+
+        let first = world.finalize_frame();
+        let buffer = first.items().as_ptr();
+        world.recycle_frame(first);
+        let second = world.finalize_frame();
+        assert_eq!((second.items().as_ptr(), second.len()), (buffer, 8));
+
+        // two frames can be out at once, and both come back for reuse
+        let third = world.finalize_frame();
+        assert_ne!(third.items().as_ptr(), buffer);
+        let other = third.items().as_ptr();
+        world.recycle_frame(second);
+        world.recycle_frame(third);
+        let buffers = [world.finalize_frame(), world.finalize_frame()].map(|frame: FrameDrawData| frame.items().as_ptr());
+        assert!(buffers.contains(&buffer) && buffers.contains(&other));
+    }
+}
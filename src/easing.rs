@@ -0,0 +1,103 @@
+use std::f32::consts::PI;
+
+// the overshoot used by the back easings
+const BACK: f32 = 1.70158;
+
+/// An easing curve mapping progress through an animation to how far the animated value has moved.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+
+    /// Pulls back slightly below 0 before moving.
+    BackIn,
+
+    /// Overshoots slightly past 1 before settling.
+    BackOut,
+
+    /// Pulls back at the start and overshoots at the end.
+    BackInOut
+}
+
+impl Easing {
+    /// Applies this easing to the given progress.
+    ///
+    /// Arguments:
+    /// * t: f32 - The progress through the animation, this is clamped between 0 and 1.
+    ///
+    /// Returns the eased progress, which is exactly 0 at t <= 0 and exactly 1 at t >= 1.  The back easings go outside of
+    /// 0 to 1 in between.
+    pub fn ease(&self, t: f32) -> f32 {
+        if t <= 0.0 || t.is_nan() { return 0.0 }
+        if t >= 1.0 { return 1.0 }
+
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => if t < 0.5 { 2.0 * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0 },
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => if t < 0.5 { 4.0 * t * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0 },
+            Easing::SineIn => 1.0 - (t * PI / 2.0).cos(),
+            Easing::SineOut => (t * PI / 2.0).sin(),
+            Easing::SineInOut => -((t * PI).cos() - 1.0) / 2.0,
+            Easing::BackIn => (BACK + 1.0) * t * t * t - BACK * t * t,
+            Easing::BackOut => 1.0 + (BACK + 1.0) * (t - 1.0).powi(3) + BACK * (t - 1.0).powi(2),
+            Easing::BackInOut => {
+                let c = BACK * 1.525;
+                if t < 0.5 { (2.0 * t).powi(2) * ((c + 1.0) * 2.0 * t - c) / 2.0 }
+                else { ((2.0 * t - 2.0).powi(2) * ((c + 1.0) * (t * 2.0 - 2.0) + c) + 2.0) / 2.0 }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Easing;
+
+    const ALL: [Easing; 13] = [
+        Easing::Linear, Easing::QuadIn, Easing::QuadOut, Easing::QuadInOut, Easing::CubicIn, Easing::CubicOut,
+        Easing::CubicInOut, Easing::SineIn, Easing::SineOut, Easing::SineInOut, Easing::BackIn, Easing::BackOut,
+        Easing::BackInOut
+    ];
+
+    #[test]
+    fn easings_start_at_0_and_end_at_1() {
+        for easing in ALL {
+            assert_eq!(easing.ease(0.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.ease(1.0), 1.0, "{:?}", easing);
+            assert_eq!(easing.ease(-1.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.ease(2.0), 1.0, "{:?}", easing);
+            assert_eq!(easing.ease(f32::NAN), 0.0, "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn easings_without_overshoot_are_monotonic() {
+        for easing in ALL.into_iter().filter(|easing| !matches!(easing, Easing::BackIn | Easing::BackOut | Easing::BackInOut)) {
+            let mut last = 0.0;
+            for step in 1..=1000 {
+                let eased = easing.ease(step as f32 / 1000.0);
+                assert!(eased >= last && eased <= 1.0, "{:?} went from {} to {} at step {}", easing, last, eased, step);
+                last = eased;
+            }
+        }
+    }
+
+    #[test]
+    fn back_easings_overshoot() {
+        assert!((1..100).any(|step| Easing::BackIn.ease(step as f32 / 100.0) < 0.0));
+        assert!((1..100).any(|step| Easing::BackOut.ease(step as f32 / 100.0) > 1.0));
+    }
+}
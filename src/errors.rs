@@ -0,0 +1,54 @@
+use std::fmt;
+
+use crate::ids::NodeId;
+
+/// The errors that can occur when changing a `World` through the ids of its nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorldError {
+    /// No node in the world has the given id, it was never given out or has been despawned.
+    NotFound(NodeId),
+
+    /// The two ids are the same node, or the first is an ancestor of the second, so both cannot be borrowed mutably at
+    /// once as the ancestor contains the descendant.
+    AliasedNodes(NodeId, NodeId),
+
+    /// The root of the world cannot be despawned or moved.
+    RootImmutable,
+
+    /// The first node cannot be moved under the second, as the second is the first or one of its descendants.
+    WouldCycle(NodeId, NodeId),
+
+    /// The node already has 65536 children, the most a node can have.
+    TooManyChildren(NodeId),
+
+    /// The node already has a child with the given name, and the `Reject` name collision policy is in use.
+    NameTaken(NodeId, String),
+
+    /// A node spawned or moved under the given node would be deeper than the `max_depth` of the `WorldConfig`.
+    TooDeep(NodeId),
+
+    /// The world already holds the `max_nodes` of its `WorldConfig`, or the spawned nodes would take it over.
+    TooManyNodes,
+
+    /// Spawning would take the variant with the given name over its cap, see `World::set_variant_cap`.
+    VariantCapped(&'static str)
+}
+
+impl fmt::Display for WorldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorldError::NotFound(id) => write!(f, "no node has the id {:?}", id),
+            WorldError::AliasedNodes(a, b) if a == b => write!(f, "ids {:?} and {:?} are the same node", a, b),
+            WorldError::AliasedNodes(a, b) => write!(f, "node {:?} is an ancestor of {:?}, so both cannot be borrowed mutably", a, b),
+            WorldError::RootImmutable => write!(f, "the root of the world cannot be despawned or moved"),
+            WorldError::WouldCycle(child, parent) => write!(f, "node {:?} cannot be moved under {:?}, which is itself or one of its descendants", child, parent),
+            WorldError::TooManyChildren(id) => write!(f, "node {:?} already has 65536 children", id),
+            WorldError::NameTaken(id, name) => write!(f, "node {:?} already has a child named {}", id, name),
+            WorldError::TooDeep(id) => write!(f, "a node spawned or moved under {:?} would be deeper than the max depth", id),
+            WorldError::TooManyNodes => write!(f, "the world would hold more than its max number of nodes"),
+            WorldError::VariantCapped(variant) => write!(f, "spawning would take {} over its cap of live nodes", variant)
+        }
+    }
+}
+
+impl std::error::Error for WorldError {}
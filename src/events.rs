@@ -0,0 +1,16 @@
+use crate::ids::NodeId;
+
+/// A structural change to the tree of a `World`, recorded by the functions of the world that change its tree, IE
+/// `spawn`, `despawn`, `take` and `reparent`, and drained with `World::drain_events`.  Changes made directly through
+/// `World::root_mut` are not recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HierarchyEvent {
+    /// The child, and any children of its own, was added to the parent.
+    ChildAdded { parent: NodeId, child: NodeId },
+
+    /// The child and all of its children were removed from the parent, their ids no longer resolve.
+    ChildRemoved { parent: NodeId, child: NodeId },
+
+    /// The child and all of its children were moved from the old parent to the new parent, keeping their ids.
+    Reparented { child: NodeId, old_parent: NodeId, new_parent: NodeId }
+}
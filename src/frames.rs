@@ -0,0 +1,203 @@
+use cgmath::{Matrix, Matrix3, Quaternion, Rotation, SquareMatrix, Vector3};
+use forte_engine::math::transforms::Transform;
+
+/// A signed axis of a coordinate frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    PosX,
+    NegX,
+    PosY,
+    NegY,
+    PosZ,
+    NegZ
+}
+
+impl Axis {
+    /// The unit vector pointing along this axis.
+    pub fn vector(&self) -> Vector3<f32> {
+        match self {
+            Axis::PosX => Vector3::unit_x(),
+            Axis::NegX => -Vector3::unit_x(),
+            Axis::PosY => Vector3::unit_y(),
+            Axis::NegY => -Vector3::unit_y(),
+            Axis::PosZ => Vector3::unit_z(),
+            Axis::NegZ => -Vector3::unit_z()
+        }
+    }
+
+    /// Checks if this axis and the given axis lie on the same line, pointing either the same or opposite ways.
+    pub fn parallel(&self, other: &Axis) -> bool {
+        let (a, b) = (self.vector(), other.vector());
+        a.x * b.x + a.y * b.y + a.z * b.z != 0.0
+    }
+}
+
+/// Whether right is found with the right or left hand rule from forward and up.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Handedness {
+    /// Right is forward cross up, as used by OpenGL and glTF.
+    #[default]
+    Right,
+
+    /// Right is up cross forward, as used by Direct3D.
+    Left
+}
+
+/// The conventions a piece of content was authored in, given as which axes point up and forward and the handedness
+/// that decides which way right points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CoordinateFrame {
+    pub up: Axis,
+    pub forward: Axis,
+    pub handed: Handedness
+}
+
+impl CoordinateFrame {
+    /// The frame used by glTF, +Y up, +Z forward and right handed.
+    pub const GLTF: CoordinateFrame = CoordinateFrame { up: Axis::PosY, forward: Axis::PosZ, handed: Handedness::Right };
+
+    /// Checks if this frame is usable, up and forward must not lie on the same line.
+    pub fn is_valid(&self) -> bool { !self.up.parallel(&self.forward) }
+
+    /// The direction right points in this frame.
+    pub fn right(&self) -> Vector3<f32> {
+        let (up, forward) = (self.up.vector(), self.forward.vector());
+        match self.handed {
+            Handedness::Right => forward.cross(up),
+            Handedness::Left => up.cross(forward)
+        }
+    }
+
+    // the matrix whose columns are right, up and forward of this frame
+    fn basis(&self) -> Matrix3<f32> { Matrix3::from_cols(self.right(), self.up.vector(), self.forward.vector()) }
+}
+
+/// The correction that converts content from one coordinate frame to another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameConversion {
+    /// Both frames have the same handedness, so a rotation alone converts between them.
+    Rotation(Quaternion<f32>),
+
+    /// The frames have different handedness, so the content has to be mirrored.  A rotation can't mirror, so the
+    /// content is first scaled by -1 along its X axis and then rotated.
+    Mirrored(Quaternion<f32>)
+}
+
+impl FrameConversion {
+    /// The rotation applied by this conversion, after the mirror if there is one.
+    pub fn rotation(&self) -> Quaternion<f32> {
+        match self {
+            FrameConversion::Rotation(rotation) | FrameConversion::Mirrored(rotation) => *rotation
+        }
+    }
+
+    /// The scale applied by this conversion before rotating, -1 along X when mirrored, otherwise 1.
+    pub fn scale(&self) -> Vector3<f32> {
+        match self {
+            FrameConversion::Rotation(_) => Vector3::new(1.0, 1.0, 1.0),
+            FrameConversion::Mirrored(_) => Vector3::new(-1.0, 1.0, 1.0)
+        }
+    }
+
+    /// The conversion as a transform with no translation.
+    pub fn transform(&self) -> Transform {
+        Transform { position: Vector3::new(0.0, 0.0, 0.0), rotation: self.rotation(), scale: self.scale() }
+    }
+
+    /// Converts a vector given in the source frame into the target frame.
+    ///
+    /// Arguments:
+    /// * vector: Vector3<f32> - The vector in the source frame.
+    ///
+    /// Returns the vector in the target frame.
+    pub fn convert_vector(&self, vector: Vector3<f32>) -> Vector3<f32> {
+        let scale = self.scale();
+        let scaled = Vector3::new(vector.x * scale.x, vector.y * scale.y, vector.z * scale.z);
+        self.rotation().rotate_vector(scaled)
+    }
+}
+
+/// Finds the correction that converts content authored in one coordinate frame into another, so that the sources up
+/// becomes the targets up, forward becomes forward and right becomes right.
+///
+/// Arguments:
+/// * from: CoordinateFrame - The frame the content was authored in.
+/// * to: CoordinateFrame - The frame the content should be in.
+///
+/// Returns the conversion, or None if either frame is not valid.
+pub fn frame_conversion(from: CoordinateFrame, to: CoordinateFrame) -> Option<FrameConversion> {
+    if !from.is_valid() || !to.is_valid() { return None }
+
+    // both bases are orthonormal, so the inverse of the source basis is its transpose
+    let conversion = to.basis() * from.basis().transpose();
+    if conversion.determinant() > 0.0 { return Some(FrameConversion::Rotation(Quaternion::from(conversion))) }
+
+    // undo the mirror along X so that the rest is a proper rotation
+    let mirror = Matrix3::from_diagonal(Vector3::new(-1.0, 1.0, 1.0));
+    Some(FrameConversion::Mirrored(Quaternion::from(conversion * mirror)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3};
+
+    use super::{frame_conversion, Axis, CoordinateFrame, FrameConversion, Handedness};
+
+    fn near(a: Vector3<f32>, b: Vector3<f32>) -> bool { (a - b).magnitude() < 1e-5 }
+
+    // every valid frame, with each up, forward and handedness
+    fn frames() -> Vec<CoordinateFrame> {
+        let axes = [Axis::PosX, Axis::NegX, Axis::PosY, Axis::NegY, Axis::PosZ, Axis::NegZ];
+        let mut out = Vec::new();
+        for up in axes {
+            for forward in axes {
+                for handed in [Handedness::Right, Handedness::Left] {
+                    let frame = CoordinateFrame { up, forward, handed };
+                    if frame.is_valid() { out.push(frame); }
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn conversions_map_up_forward_and_right() {
+        let frames = frames();
+        assert_eq!(frames.len(), 48);
+        for from in &frames {
+            for to in &frames {
+                let conversion = frame_conversion(*from, *to).unwrap();
+                assert!(near(conversion.convert_vector(from.up.vector()), to.up.vector()), "{:?} to {:?}", from, to);
+                assert!(near(conversion.convert_vector(from.forward.vector()), to.forward.vector()), "{:?} to {:?}", from, to);
+                assert!(near(conversion.convert_vector(from.right()), to.right()), "{:?} to {:?}", from, to);
+
+                // only a change of handedness mirrors
+                assert_eq!(matches!(conversion, FrameConversion::Mirrored(_)), from.handed != to.handed);
+                assert!((conversion.rotation().magnitude() - 1.0).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn z_up_to_gltf() {
+        let z_up = CoordinateFrame { up: Axis::PosZ, forward: Axis::NegY, handed: Handedness::Right };
+        let conversion = frame_conversion(z_up, CoordinateFrame::GLTF).unwrap();
+        assert!(matches!(conversion, FrameConversion::Rotation(_)));
+        assert_eq!(conversion.scale(), Vector3::new(1.0, 1.0, 1.0));
+        assert!(near(conversion.convert_vector(Vector3::new(1.0, 2.0, 3.0)), Vector3::new(1.0, 3.0, -2.0)));
+
+        let left = CoordinateFrame { handed: Handedness::Left, ..CoordinateFrame::GLTF };
+        let conversion = frame_conversion(left, CoordinateFrame::GLTF).unwrap();
+        assert_eq!(conversion.transform().scale, Vector3::new(-1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn parallel_axes_are_invalid() {
+        let invalid = CoordinateFrame { up: Axis::PosY, forward: Axis::NegY, handed: Handedness::Right };
+        assert!(!invalid.is_valid());
+        assert_eq!(frame_conversion(invalid, CoordinateFrame::GLTF), None);
+        assert_eq!(frame_conversion(CoordinateFrame::GLTF, invalid), None);
+        assert!(Axis::PosX.parallel(&Axis::NegX) && !Axis::PosX.parallel(&Axis::PosZ));
+    }
+}
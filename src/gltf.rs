@@ -0,0 +1,221 @@
+use cgmath::{Quaternion, Vector3};
+use serde_json::{json, Map};
+
+use crate::{
+    frames::{frame_conversion, CoordinateFrame},
+    nodes::{ComponentDef, Node, World},
+    scenes::{SceneError, SceneValue}
+};
+
+fn vec3(vector: Vector3<f32>) -> [f32; 3] { vector.into() }
+
+// reads a list of numbers of the given length from the given field of a glTF node, the default if it is missing
+fn floats<const N: usize>(node: &SceneValue, field: &str, default: [f32; N]) -> Result<[f32; N], SceneError> {
+    let Some(value) = node.get(field) else { return Ok(default) };
+    let invalid = || SceneError::Parse(format!("the glTF node field {:?} is not a list of {} numbers", field, N));
+    let list = value.as_array().filter(|list| list.len() == N).ok_or_else(invalid)?;
+    let mut out = default;
+    for (out, value) in out.iter_mut().zip(list) { *out = value.as_f64().ok_or_else(invalid)? as f32; }
+    Ok(out)
+}
+
+impl<C: ComponentDef<A>, A> Node<C, A> {
+    /// Exports this node and all of its children as a glTF 2.0 document, so trees built at runtime can be inspected in
+    /// external tools.  This node becomes the single root node of the default scene.
+    ///
+    /// Each glTF node has the name and local transform of its node.  As the document has no buffers, everything else
+    /// is given in the `extras` of each node, the world space `aabb` of its dimensions as of the last update with `min`
+    /// and `max`, the name of the variant of its `component`, and the `mesh` reference given by the mesh function if it
+    /// gives one, IE the path of the model the component draws.
+    ///
+    /// Arguments:
+    /// * mesh: impl Fn(&Node) -> Option<String> - Gives the mesh reference of a node, None if it has none.
+    pub fn to_gltf(&self, mesh: impl Fn(&Node<C, A>) -> Option<String>) -> SceneValue {
+        let mut nodes = Vec::new();
+        self.gltf_nodes_recr(&mesh, &mut nodes);
+        json!({
+            "asset": { "version": "2.0", "generator": "forte_world" },
+            "scene": 0,
+            "scenes": [{ "nodes": [0] }],
+            "nodes": nodes
+        })
+    }
+
+    // pushes this node and then its children, giving back the index of this node
+    fn gltf_nodes_recr(&self, mesh: &impl Fn(&Node<C, A>) -> Option<String>, nodes: &mut Vec<SceneValue>) -> usize {
+        let idx = nodes.len();
+        nodes.push(SceneValue::Null);
+
+        let rotation = self.transform.rotation;
+        let dimensions = self.dimensions();
+        let mut node = Map::new();
+        if let Some(name) = &self.name { node.insert("name".to_string(), json!(name)); }
+        node.insert("translation".to_string(), json!(vec3(self.transform.position)));
+        node.insert("rotation".to_string(), json!([rotation.v.x, rotation.v.y, rotation.v.z, rotation.s]));
+        node.insert("scale".to_string(), json!(vec3(self.transform.scale)));
+
+        let mut extras = Map::new();
+        extras.insert("aabb".to_string(), json!({
+            "min": vec3(dimensions.from),
+            "max": vec3(dimensions.to)
+        }));
+        if let Some(variant) = self.component.variant_index() { extras.insert("component".to_string(), json!(C::VARIANT_NAMES[variant])); }
+        if let Some(mesh) = mesh(self) { extras.insert("mesh".to_string(), json!(mesh)); }
+        node.insert("extras".to_string(), SceneValue::Object(extras));
+
+        let children: Vec<usize> = self.children().iter().map(|child| child.gltf_nodes_recr(mesh, nodes)).collect();
+        if !children.is_empty() { node.insert("children".to_string(), json!(children)); }
+
+        nodes[idx] = SceneValue::Object(node);
+        idx
+    }
+}
+
+impl<C: ComponentDef<A>, A> Node<C, A> {
+    /// Imports the node hierarchy of the default scene of a glTF 2.0 document as empty nodes with the name and local
+    /// transform of each glTF node, IE a document given by `to_gltf`.  A scene with a single root node gives that node,
+    /// otherwise the roots are given as the children of a new empty node.  Meshes and extras are not imported.
+    ///
+    /// glTF content is +Y up, +Z forward and right handed.  If a target frame is given, the imported root is converted
+    /// into it with `apply_frame_conversion`, see `frame_conversion`.
+    ///
+    /// Arguments:
+    /// * document: &SceneValue - The parsed glTF document.
+    /// * frame: Option<CoordinateFrame> - The frame to convert the content into, None to keep it as authored.
+    ///
+    /// Returns the root of the imported tree, or a `Parse` error if the document is not a valid glTF hierarchy, uses
+    /// matrix transforms or the target frame is not valid.
+    pub fn from_gltf(document: &SceneValue, frame: Option<CoordinateFrame>) -> Result<Self, SceneError> {
+        let invalid = |error: &str| SceneError::Parse(format!("invalid glTF document: {}", error));
+        let nodes = document.get("nodes").and_then(SceneValue::as_array).map_or(&[][..], Vec::as_slice);
+        let scene = document.get("scene").map_or(Some(0), SceneValue::as_u64).ok_or_else(|| invalid("scene is not an index"))?;
+        let roots = match document.get("scenes").and_then(SceneValue::as_array).and_then(|scenes| scenes.get(scene as usize)) {
+            Some(scene) => scene.get("nodes").and_then(SceneValue::as_array).map_or(&[][..], Vec::as_slice),
+            None => return Err(invalid("the default scene does not exist"))
+        };
+
+        let mut visited = vec![false; nodes.len()];
+        let mut roots: Vec<Node<C, A>> = roots.iter().map(|root| Self::gltf_node_recr(nodes, root, &mut visited)).collect::<Result<_, _>>()?;
+        let mut root = if roots.len() == 1 { roots.pop().unwrap() } else {
+            let mut root = Node::default();
+            roots.into_iter().for_each(|child| root.push_detached_child(child));
+            root
+        };
+
+        if let Some(frame) = frame {
+            let conversion = frame_conversion(CoordinateFrame::GLTF, frame).ok_or_else(|| invalid("the target frame is not valid"))?;
+            root.apply_frame_conversion(&conversion);
+        }
+        Ok(root)
+    }
+
+    // reads the glTF node with the given index and its children, each node can only be used once so cycles are errors
+    fn gltf_node_recr(nodes: &[SceneValue], idx: &SceneValue, visited: &mut [bool]) -> Result<Self, SceneError> {
+        let invalid = |error: String| SceneError::Parse(format!("invalid glTF document: {}", error));
+        let idx = idx.as_u64().map(|idx| idx as usize).filter(|idx| *idx < nodes.len()).ok_or_else(|| invalid(format!("{} is not a node", idx)))?;
+        if std::mem::replace(&mut visited[idx], true) { return Err(invalid(format!("node {} is used more than once", idx))) }
+
+        let gltf = &nodes[idx];
+        if gltf.get("matrix").is_some() { return Err(invalid(format!("node {} uses a matrix transform", idx))) }
+        let mut node = Node::default();
+        node.name = gltf.get("name").and_then(SceneValue::as_str).map(str::to_string);
+        node.transform.position = Vector3::from(floats(gltf, "translation", [0.0; 3])?);
+        let [x, y, z, w] = floats(gltf, "rotation", [0.0, 0.0, 0.0, 1.0])?;
+        node.transform.rotation = Quaternion::new(w, x, y, z);
+        node.transform.scale = Vector3::from(floats(gltf, "scale", [1.0; 3])?);
+
+        for child in gltf.get("children").and_then(SceneValue::as_array).map_or(&[][..], Vec::as_slice) {
+            node.push_detached_child(Self::gltf_node_recr(nodes, child, visited)?);
+        }
+        Ok(node)
+    }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Creates a new world from the node hierarchy of a glTF 2.0 document in JSON, see `Node::from_gltf`.
+    pub fn import_gltf(json: &str, frame: Option<CoordinateFrame>) -> Result<Self, SceneError> {
+        let document: SceneValue = serde_json::from_str(json).map_err(|error| SceneError::Parse(error.to_string()))?;
+        Ok(World::from_root(Node::from_gltf(&document, frame)?))
+    }
+
+    /// Exports the whole tree as a glTF 2.0 document in JSON, see `Node::to_gltf`.
+    pub fn export_gltf(&self, mesh: impl Fn(&Node<C, A>) -> Option<String>) -> String {
+        serde_json::to_string_pretty(&self.root().to_gltf(mesh)).expect("gltf documents always serialize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Deg, InnerSpace, Quaternion, Rotation, Rotation3, Vector3};
+
+    use crate::{
+        frames::{Axis, CoordinateFrame, Handedness},
+        scenes::SceneError
+    };
+
+    use crate::test_support::*;
+
+    fn near(a: Vector3<f32>, b: Vector3<f32>) -> bool { (a - b).magnitude() < 1e-5 }
+
+    // a root holding a child holding a grandchild
+    fn tree() -> Node {
+        let mut grandchild = Node::default();
+        grandchild.name = Some("grandchild".to_string());
+        grandchild.transform.position = Vector3::new(0.0, 0.0, 2.0);
+        let mut child = marker(0);
+        child.name = Some("child".to_string());
+        child.transform.position = Vector3::new(0.0, 1.0, 0.0);
+        child.transform.rotation = Quaternion::from_angle_y(Deg(90.0));
+        child.transform.scale = Vector3::new(2.0, 2.0, 2.0);
+        child.add_child(grandchild);
+        let mut root = Node::default();
+        root.add_child(child);
+        root
+    }
+
+    #[test]
+    fn gltf_round_trip() {
+        let imported = Node::from_gltf(&tree().to_gltf(|_| None), None).unwrap();
+        let child = &imported.children()[0];
+        assert_eq!(child.name.as_deref(), Some("child"));
+        assert!(near(child.transform.position, Vector3::new(0.0, 1.0, 0.0)));
+        assert!(near(child.transform.scale, Vector3::new(2.0, 2.0, 2.0)));
+        assert!((child.transform.rotation - Quaternion::from_angle_y(Deg(90.0))).magnitude() < 1e-5);
+        assert_eq!(child.children()[0].name.as_deref(), Some("grandchild"));
+
+        // components are not part of the document
+        assert!(child.get::<Marker>().is_none());
+    }
+
+    #[test]
+    fn gltf_import_converts_into_the_target_frame() {
+        let z_up = CoordinateFrame { up: Axis::PosZ, forward: Axis::NegY, handed: Handedness::Right };
+        let world = World::import_gltf(&World::from_root(tree()).export_gltf(|_| None), Some(z_up)).unwrap();
+
+        // the glTF up of the child is now along +Z, and the rotation of the root turns glTF up to +Z as well
+        let child = &world.root().children()[0];
+        assert!(near(child.transform.position, Vector3::new(0.0, 0.0, 1.0)));
+        assert!(near(world.root().transform.rotation.rotate_vector(Vector3::unit_y()), Vector3::unit_z()));
+        assert!(near(child.children()[0].transform.position, Vector3::new(0.0, -2.0, 0.0)));
+
+        let invalid = CoordinateFrame { up: Axis::PosZ, forward: Axis::NegZ, handed: Handedness::Right };
+        assert!(matches!(Node::from_gltf(&tree().to_gltf(|_| None), Some(invalid)), Err(SceneError::Parse(_))));
+    }
+
+    #[test]
+    fn gltf_import_errors() {
+        let import = |json: &str| Node::from_gltf(&serde_json::from_str(json).unwrap(), None).map(|node| node.children().len());
+
+        // several roots are held by a new root
+        assert_eq!(import(r#"{ "scenes": [{ "nodes": [0, 1] }], "nodes": [{}, {}] }"#), Ok(2));
+        for json in [
+            r#"{ "scenes": [{ "nodes": [0] }], "nodes": [{ "children": [0] }] }"#,
+            r#"{ "scenes": [{ "nodes": [3] }], "nodes": [{}] }"#,
+            r#"{ "scenes": [{ "nodes": [0] }], "nodes": [{ "matrix": [] }] }"#,
+            r#"{ "scenes": [{ "nodes": [0] }], "nodes": [{ "translation": [1, 2] }] }"#,
+            r#"{ "scene": 1, "scenes": [{ "nodes": [0] }], "nodes": [{}] }"#
+        ] {
+            assert!(matches!(import(json), Err(SceneError::Parse(_))), "{}", json);
+        }
+    }
+}
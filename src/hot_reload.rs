@@ -0,0 +1,128 @@
+use std::{fs, path::{Path, PathBuf}, time::SystemTime};
+
+use crate::{
+    nodes::{ComponentDef, World},
+    patches::ScenePatch,
+    scenes::{SceneError, SceneFile, SceneNode}
+};
+
+/// A scene file watched by a world for changes, see `World::watch_scene`.
+#[derive(Debug, Clone)]
+pub struct SceneWatch {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    scene: SceneFile
+}
+
+impl SceneWatch {
+    /// The path of the watched scene file.
+    pub fn path(&self) -> &Path { &self.path }
+
+    /// The scene as it was when the file was last read.
+    pub fn scene(&self) -> &SceneFile { &self.scene }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> { fs::metadata(path).and_then(|metadata| metadata.modified()).ok() }
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Starts watching the given scene file for changes, replacing any scene watched before.  The tree is expected to
+    /// already hold the scene, IE from `World::load_scene`, the file is read now so later versions can be compared
+    /// against it.
+    ///
+    /// Returns an error if the file could not be read.
+    pub fn watch_scene(&mut self, path: impl AsRef<Path>) -> Result<(), SceneError> {
+        let path = path.as_ref().to_path_buf();
+        let scene = SceneFile::read(&path)?;
+        self.watch = Some(SceneWatch { modified: modified(&path), path, scene });
+        Ok(())
+    }
+
+    /// Stops watching the scene file given to `watch_scene`.
+    pub fn stop_watching_scene(&mut self) { self.watch = None; }
+
+    /// The scene file being watched, None if no file is being watched.
+    pub fn watched_scene(&self) -> Option<&SceneWatch> { self.watch.as_ref() }
+
+    /// Checks if the watched scene file has been modified since it was last read, and if so applies the changes to the
+    /// tree in place, see `apply_scene_changes`.  This reads the modification time of the file, so it is meant to be
+    /// called every so often during development rather than every frame.
+    ///
+    /// Returns true if the file had changed, or an error if it could not be read or applied, in which case the tree is
+    /// left as it is and the file is read again on the next poll.
+    pub fn poll_scene_watch(&mut self) -> Result<bool, SceneError> {
+        let Some(watch) = &self.watch else { return Ok(false) };
+        let now = modified(&watch.path);
+        if now == watch.modified { return Ok(false) }
+
+        let scene = SceneFile::read(&watch.path)?;
+        let old = watch.scene.root.clone();
+        self.apply_scene_changes(&old, &scene.root)?;
+
+        let watch = self.watch.as_mut().unwrap();
+        watch.modified = now;
+        watch.scene = scene;
+        Ok(true)
+    }
+
+    /// Applies the differences between two versions of a scene to the tree, which is expected to have been loaded from
+    /// the old version.  Only the fields that differ between the versions are set, so runtime state like ids, tweens,
+    /// lifetimes and anything changed since loading that the new version did not touch are kept.
+    ///
+    /// This applies the `ScenePatch::diff` of the versions, so children are matched between the versions and the tree
+    /// by uuid, then by name, then by their order amongst the children without names.  Children only in the new version
+    /// are spawned at the end of their parent, and children only in the old version are despawned.  A changed component
+    /// is replaced with `Node::set_component`.
+    ///
+    /// Returns the first error found in the new version, in which case none of the changes are applied.
+    pub fn apply_scene_changes(&mut self, old: &SceneNode, new: &SceneNode) -> Result<(), SceneError> {
+        self.apply_patch(&ScenePatch::diff(old, new))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, time::{Duration, SystemTime}};
+
+    use crate::scenes::{SceneComponent, SceneError, SceneFile, SceneNode, SceneValue, SCENE_VERSION};
+
+    use crate::test_support::*;
+
+    fn write(path: &std::path::Path, scene: &SceneFile, age: u64) {
+        fs::write(path, scene.to_json()).unwrap();
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(age)).unwrap();
+    }
+
+    fn scene(names: &[&str]) -> SceneFile {
+        let children = names.iter().map(|name| SceneNode { name: Some(name.to_string()), ..SceneNode::default() }).collect();
+        SceneFile { version: SCENE_VERSION, schema_version: 0, root: SceneNode { children, ..SceneNode::default() } }
+    }
+
+    #[test]
+    fn failed_poll_keeps_the_tree_and_watch() {
+        let path = std::env::temp_dir().join(format!("forte_world_hot_reload_{}.json", std::process::id()));
+        let first = scene(&["a"]);
+        write(&path, &first, 1);
+        let mut world = World::from_root(Node::from_scene(&first.root).unwrap());
+        world.watch_scene(&path).unwrap();
+
+        // a rename followed by a child with an unknown component
+        let mut broken = scene(&["b"]);
+        let component = SceneComponent { variant: "Missing".to_string(), data: SceneValue::Null };
+        broken.root.children.push(SceneNode { component: Some(component), ..SceneNode::default() });
+        write(&path, &broken, 2);
+        assert_eq!(world.poll_scene_watch(), Err(SceneError::UnknownVariant("Missing".to_string())));
+        assert_eq!(world.root().children().len(), 1);
+        assert_eq!(world.root().children()[0].name.as_deref(), Some("a"));
+        assert_eq!(world.watched_scene().unwrap().scene(), &first);
+
+        // the fixed file is diffed against the scene the tree still holds
+        let fixed = scene(&["b", "c"]);
+        write(&path, &fixed, 3);
+        assert_eq!(world.poll_scene_watch(), Ok(true));
+        let names: Vec<_> = world.root().children().iter().map(|child| child.name.as_deref()).collect();
+        assert_eq!(names, [Some("b"), Some("c")]);
+        assert_eq!(world.watched_scene().unwrap().scene(), &fixed);
+        fs::remove_file(path).unwrap();
+    }
+}
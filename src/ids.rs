@@ -0,0 +1,225 @@
+use std::{
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH}
+};
+
+use crate::nodes::{ComponentDef, Node, World};
+
+/// A stable handle to a node in a `World`.
+///
+/// Unlike a `NodePath`, an id keeps pointing at the same node as its siblings are added and removed or as it is moved
+/// around the tree.  Ids are generational, once a node is despawned its slot may be reused, but the new node is given
+/// a new generation so the old id no longer resolves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeId {
+    index: u32,
+    generation: u32
+}
+
+impl NodeId {
+    /// The id of the reserved root of a world created with `World::new`, the first id any world gives out.
+    pub const ROOT: NodeId = NodeId { index: 0, generation: 0 };
+
+    /// The slot this id points to.
+    pub fn index(&self) -> u32 { self.index }
+
+    /// The generation of the slot this id was allocated in.
+    pub fn generation(&self) -> u32 { self.generation }
+}
+
+/// A weak reference to a node that can be kept anywhere, IE inside component data, and upgraded through the world
+/// when it is needed.  Upgrading gives None once the node has been despawned or taken out of the world, even if its
+/// slot has been reused since.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeRef(NodeId);
+
+impl NodeRef {
+    /// Creates a new reference to the node with the given id.
+    pub fn new(id: NodeId) -> Self { Self(id) }
+
+    /// The id of the node this refers to.
+    pub fn id(&self) -> NodeId { self.0 }
+
+    /// Checks if the node this refers to is still in the given world, see `World::is_alive`.
+    pub fn is_alive<C: ComponentDef<A>, A>(&self, world: &World<C, A>) -> bool { world.is_alive(self.0) }
+
+    /// Finds the node this refers to in the given world.
+    ///
+    /// Returns None if the node is no longer in the world.
+    pub fn upgrade<'a, C: ComponentDef<A>, A>(&self, world: &'a World<C, A>) -> Option<&'a Node<C, A>> { world.get(self.0) }
+
+    pub fn upgrade_mut<'a, C: ComponentDef<A>, A>(&self, world: &'a mut World<C, A>) -> Option<&'a mut Node<C, A>> { world.get_mut(self.0) }
+}
+
+impl From<NodeId> for NodeRef {
+    fn from(id: NodeId) -> Self { Self(id) }
+}
+
+/// Allocates generational node ids, reusing the slots of freed ids with a new generation.
+#[derive(Debug, Default, Clone)]
+pub struct IdAllocator {
+    generations: Vec<u32>,
+    live: Vec<bool>,
+    free: Vec<u32>,
+    // the slots of every live id packed together, and the position of each live slot in it
+    dense: Vec<u32>,
+    positions: Vec<u32>
+}
+
+impl IdAllocator {
+    /// Creates a new allocator with no ids allocated.
+    pub fn new() -> Self { Self::default() }
+
+    /// Allocates a new id, reusing a free slot if there is one.
+    pub fn alloc(&mut self) -> NodeId {
+        match self.free.pop() {
+            Some(index) => {
+                self.live[index as usize] = true;
+                self.positions[index as usize] = self.dense.len() as u32;
+                self.dense.push(index);
+                NodeId { index, generation: self.generations[index as usize] }
+            },
+            None => {
+                let index = self.generations.len() as u32;
+                self.generations.push(0);
+                self.live.push(true);
+                self.positions.push(self.dense.len() as u32);
+                self.dense.push(index);
+                NodeId { index, generation: 0 }
+            }
+        }
+    }
+
+    /// Frees the given id so that it no longer resolves and its slot can be reused.
+    ///
+    /// Returns false if the id was not live.
+    pub fn free(&mut self, id: NodeId) -> bool {
+        if !self.is_live(id) { return false }
+
+        let index = id.index as usize;
+        self.live[index] = false;
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.free.push(id.index);
+
+        // move the last live slot into the freed position
+        let position = self.positions[index] as usize;
+        self.dense.swap_remove(position);
+        if let Some(&moved) = self.dense.get(position) { self.positions[moved as usize] = position as u32; }
+        true
+    }
+
+    /// Checks if the given id was allocated by this allocator and has not been freed.
+    pub fn is_live(&self, id: NodeId) -> bool {
+        let index = id.index as usize;
+        index < self.live.len() && self.live[index] && self.generations[index] == id.generation
+    }
+
+    /// The number of live ids.
+    pub fn len(&self) -> usize { self.live.len() - self.free.len() }
+
+    /// Checks if there are no live ids.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// The number of slots, live or free, IE one more than the largest index given out.
+    pub fn slots(&self) -> usize { self.live.len() }
+
+    /// The live id at the given position, for positions below `len`.  Positions are not stable, freeing an id moves
+    /// the last live id into its position.
+    pub fn live_at(&self, position: usize) -> Option<NodeId> {
+        let index = *self.dense.get(position)?;
+        Some(NodeId { index, generation: self.generations[index as usize] })
+    }
+
+    /// Iterates over all live ids in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.live.iter().enumerate()
+            .filter(|(_, live)| **live)
+            .map(|(index, _)| NodeId { index: index as u32, generation: self.generations[index] })
+    }
+}
+
+static UUID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A persistent id of a node that stays the same across runs, IE for saved scenes, save games or network replication.
+/// Unlike a `NodeId`, which is only valid in the world that gave it out, this is stored with the node itself.
+///
+/// Uuids are random version 4 uuids, written in the usual hyphenated hex form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeUuid(pub u128);
+
+impl NodeUuid {
+    /// Creates a new random uuid.
+    pub fn new_v4() -> Self {
+        // seed each half from the random keys of a new std hasher, the time and a counter so no two calls match
+        let half = |salt: u64| {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u64(salt);
+            hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_nanos()));
+            hasher.write_u64(UUID_COUNTER.fetch_add(1, Ordering::Relaxed));
+            hasher.finish() as u128
+        };
+        let bits = (half(0) << 64) | half(1);
+
+        // set the version to 4 and the variant to RFC 4122
+        Self((bits & !(0xF << 76) & !(0x3 << 62)) | (0x4 << 76) | (0x2 << 62))
+    }
+}
+
+impl fmt::Display for NodeUuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = format!("{:032x}", self.0);
+        write!(f, "{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+    }
+}
+
+/// The error given when a string is not a hyphenated or plain 32 digit hex uuid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeUuidError(pub String);
+
+impl fmt::Display for NodeUuidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{:?} is not a valid uuid", self.0) }
+}
+
+impl std::error::Error for NodeUuidError {}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for NodeUuid {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> { serializer.collect_str(self) }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for NodeUuid {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for NodeUuid {
+    type Err = NodeUuidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex: String = s.chars().filter(|c| *c != '-').collect();
+        if hex.len() != 32 { return Err(NodeUuidError(s.to_string())) }
+        u128::from_str_radix(&hex, 16).map(Self).map_err(|_| NodeUuidError(s.to_string()))
+    }
+}
+
+/// The stable id of the component variant with the given name, the 64 bit FNV-1a hash of the name.  These are the ids
+/// of `ComponentKind` in `define_world!`, so they stay the same as variants are added, removed or reordered, and only
+/// change if a variant is renamed.  0 is kept for the empty component.
+pub const fn component_kind_id(name: &str) -> u64 {
+    let bytes = name.as_bytes();
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut idx = 0;
+    while idx < bytes.len() {
+        hash ^= bytes[idx] as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+        idx += 1;
+    }
+    hash
+}
@@ -0,0 +1,22 @@
+use cgmath::{Quaternion, Vector3};
+
+/// A property grid that components and nodes can expose their fields to, IE an editor's inspector panel.
+///
+/// Each function is given the name of the field and a mutable reference to its value so that the inspector
+/// can both display the value and write back any changes made to it.
+pub trait Inspector {
+    /// Inspects a single f32 field.
+    fn field_f32(&mut self, name: &str, value: &mut f32);
+
+    /// Inspects a vector 3 field, IE a position or scale.
+    fn field_vec3(&mut self, name: &str, value: &mut Vector3<f32>);
+
+    /// Inspects a quaternion field, IE a rotation.
+    fn field_quat(&mut self, name: &str, value: &mut Quaternion<f32>);
+
+    /// Inspects a boolean field.
+    fn field_bool(&mut self, name: &str, value: &mut bool);
+
+    /// Inspects a string field.
+    fn field_string(&mut self, name: &str, value: &mut String);
+}
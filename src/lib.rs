@@ -1,192 +1,990 @@
+pub mod assets;
+#[cfg(feature = "lifecycle-audit")]
+pub mod audit;
+pub mod builder;
+pub mod callbacks;
+pub mod caps;
+pub mod commands;
+pub mod components;
+pub mod config;
+pub mod culling;
+pub mod debug;
+#[cfg(feature = "remote-debug")]
+pub mod debug_server;
 pub mod dimensions;
+pub mod draw_data;
+pub mod easing;
+pub mod errors;
+pub mod events;
+pub mod frames;
+#[cfg(feature = "serde")]
+pub mod gltf;
+#[cfg(feature = "serde")]
+pub mod hot_reload;
+pub mod ids;
+pub mod inspector;
+pub mod math;
+pub mod merges;
+#[cfg(feature = "serde")]
+pub mod migrations;
+pub mod names;
+pub mod nodes;
+pub mod path_follower;
+#[cfg(feature = "serde")]
+pub mod patches;
+pub mod paths;
+pub mod poses;
+#[cfg(feature = "serde")]
+pub mod prefabs;
+pub mod queries;
+pub mod reports;
+pub mod sampling;
+#[cfg(feature = "serde")]
+pub mod scenes;
+pub mod scripts;
+#[cfg(feature = "serde")]
+pub mod sources;
+pub mod stats;
+#[cfg(feature = "serde")]
+pub mod streaming;
+#[cfg(test)]
+mod test_support;
+pub mod transactions;
+pub mod tweens;
+pub mod utils;
+pub mod validation;
+
+#[doc(hidden)]
+pub use paste;
+
+#[doc(hidden)]
+pub use winit;
+
+#[doc(hidden)]
+pub use forte_engine;
+
+#[doc(hidden)]
+pub use wgpu;
+
+#[doc(hidden)]
+pub use cgmath;
+
+/// Implements `components::WorldComponent` for a component data type from the callbacks given in a
+/// `#[world_component(...)]` attribute, see `world`.
+#[cfg(feature = "derive")]
+pub use forte_world_derive::WorldComponent;
+
+/// Assembles a component enum out of data types implementing `components::WorldComponent`, generating the same items
+/// as `define_world!`.  With `#[derive(WorldComponent)]` the data types can be defined in other files or crates, with
+/// the callbacks of each given in its own `#[world_component(...)]` attribute.
+///
+/// The attribute keys are the callbacks of `define_world!` in lower case, along with `app` to use a component with
+/// only one app.  Each callback is a function or a closure that captures nothing, taking the data, then a
+/// `NodeContext` for callbacks that take one, then the remaining arguments of the callback in `define_world!`.
+/// Components without an `app` can be used with any app, so their callbacks are generic over the component enum.
+///
+/// This needs the `derive` feature, which is enabled by default.
+///
+/// Example:
+/// ```rust
+/// use forte_world::{scene, world, WorldComponent};
+///
+/// pub struct TestApp { frames: u32 }
+///
+/// #[derive(Debug, WorldComponent)]
+/// #[world_component(
+///     app = TestApp,
+///     update = |spinner, app, ctx, dt| {
+///         app.frames += 1;
+///         ctx.transform.position.y += spinner.speed * dt;
+///     },
+///     priority = -10
+/// )]
+/// pub struct Spinner { speed: f32 }
+///
+/// // usable with any app
+/// #[derive(Debug, WorldComponent)]
+/// #[world_component(added = |marker, _| marker.seen = true)]
+/// pub struct Marker { seen: bool }
+///
+/// #[world(TestApp)]
+/// #[derive(Debug)]
+/// pub enum Component {
+///     Spinner(Spinner),
+///     Marker(Marker)
+/// }
+///
+/// let mut world = World::new();
+/// let spinner = world.spawn(world.root_id(), scene! { component: Component::Spinner(Spinner { speed: 2.0 }) }).unwrap();
+/// let marker = world.spawn(world.root_id(), scene! { component: Component::Marker(Marker { seen: false }) }).unwrap();
+///
+/// let mut app = TestApp { frames: 0 };
+/// world.update(&mut app, 0.5);
+/// assert_eq!(app.frames, 1);
+/// assert_eq!(world.get(spinner).unwrap().transform.position.y, 1.0);
+/// assert!(world.get(marker).unwrap().component.as_marker().unwrap().seen);
+/// ```
+#[cfg(feature = "derive")]
+pub use forte_world_derive::world;
 
 /// Generates a component definition with its ComponentDef supporting functions and render functions.
 /// 
+/// This also creates the `Node` and `World` type aliases of `forte_world::nodes` for the component, a `FindData`
+/// trait with a `find_data_<variant>` search function for each variant, and a `WithData` trait with a
+/// `Node::with_<variant>(data)` constructor for each variant.  See `world` for an alternative that assembles
+/// the component enum from data types with `#[derive(WorldComponent)]`, and `compose_world!` to add variants from
+/// other crates.
+/// 
+/// Nothing else is imported into the calling module, so the types named by the callbacks, IE `RemovalReason` or
+/// `Transform`, need their own `use` lines.
+/// 
+/// The app can be any type, it is only handed to RENDER and UPDATE, so worlds can be defined and updated headlessly
+/// without forte_engine's EngineApp or a GPU device.
+/// 
+/// Each of the ADDED, UPDATE, RENDER and REMOVED callbacks of a variant is optional, a variant without one does
+/// nothing at that point.  The callbacks that are given must come in that order after DATA.
+/// 
+/// After those, a variant may also give any of these callbacks, in this order, taking the same forms as ADDED:
+/// * START - Called in the first update after the component is added, before its UPDATE.
+/// * LATE_UPDATE - Called after every node in the updated tree has had its UPDATE, IE for cameras following other nodes.
+/// * FIXED_UPDATE - Called for each fixed timestep that has passed during `World::tick`, see
+///   `World::set_fixed_timestep`, IE for physics.
+/// 
+/// An optional `INPUT => |data: &mut Data, ctx: &mut NodeContext, input: &EngineInput| -> bool { ... }`, or
+/// `|node: &mut Node, input: &EngineInput| -> bool`, reacts to the inputs given to `World::input`, IE for player
+/// controllers or UI widgets.  Inputs are passed down the tree from parents to children until a callback returns true
+/// to consume it.  `EngineInput` is `forte_engine::render::input::EngineInput`.
+/// 
+/// An optional `RESIZE => |data: &mut Data, ctx: &mut NodeContext, size: PhysicalSize<u32>| { ... }`, or
+/// `|node: &mut Node, size: PhysicalSize<u32>|`, is given the new size of the window by `World::resize`, IE for
+/// cameras, render targets or screen space elements.  Every node is resized, including disabled ones.  `PhysicalSize`
+/// is `winit::dpi::PhysicalSize`.
+/// 
+/// Optional `ON_ENABLE` and `ON_DISABLE` callbacks, taking the same forms as ADDED, are called when the node or one of
+/// its ancestors is enabled or disabled with `Node::set_enabled` or `World::set_enabled`, IE to pause sounds, timers or
+/// animations.  Setting the `enabled` field directly calls neither.
+/// 
+/// An optional `PREPARE => |data: &mut Data, ctx: &mut NodeContext, engine: &mut RenderEngine| { ... }`, or
+/// `|node: &mut Node, engine: &mut RenderEngine|`, is called by `World::prepare` before the render pass is begun, IE to
+/// upload buffers or update bind groups, so RENDER only needs to read the component.  Disabled nodes are not prepared.
+/// `RenderEngine` is `forte_engine::render::render_engine::RenderEngine`.
+/// 
+/// Each variant may optionally give a `PRIORITY` (defaults to 0).  Within a single `draw_node` call,
+/// all components with a lower priority are drawn before those with a higher priority, otherwise tree
+/// order is preserved.
+/// 
+/// Each variant may also set `CASTS_SHADOW => false` so that `Node::collect_in_volume` skips it, IE for
+/// particles or UI.
+/// 
+/// An optional `INSPECT => |data: &mut Data, ui: &mut dyn Inspector| { ... }` exposes the components fields to
+/// an `Inspector` through `Component::inspect`.
+/// 
+/// An optional `DEBUG_DRAW => |data: &Data, transform: &Transform, out: &mut DebugLines| { ... }` adds debug shapes
+/// for the component when collected with `Node::collect_debug_variant`.
+/// 
+/// An optional `SORT_KEY => |data: &Data| -> u64 { ... }` sorts the nodes of that variant by the key when drawn, IE to
+/// group nodes that share a texture.  The nodes of the variant are reordered amongst the draw slots they already take
+/// up, with tree order kept between nodes with equal keys.
+/// 
+/// An optional `REMAP_ASSETS => |data: &mut Data, remap: &dyn Fn(&str) -> Option<String>| -> usize { ... }` passes
+/// each asset path of the component to remap, applies any new path it gives and returns how many paths were rewritten.
+/// This is used by `Node::remap_assets` after assets have moved.
+/// 
+/// With the `serde` feature, an optional pair of `SAVE => |data: &Data| -> SceneValue { ... }` and
+/// `LOAD => |value: &SceneValue| -> Option<Data> { ... }` codecs lets the component be saved to and loaded from scenes
+/// with `World::save_scene` and `World::load_scene`, through `Component::save_data` and `Component::load_data`.  The
+/// scene modules only exist with the feature, so giving the codecs without it is a compile error.
+/// Components without codecs are saved with null data and cannot be loaded back.  Data that already implements serde's
+/// traits can use `SAVE => forte_world::scenes::save_serde, LOAD => forte_world::scenes::load_serde` instead of writing
+/// its own codecs, so only the variants that are saved need to be serde capable.
+/// 
+/// Every callback other than RENDER may take just the node, `|node: &mut Node|`, or the data of the component along
+/// with a `NodeContext` holding the rest of the node, so both can be changed at once:
+/// * ADDED, START, LATE_UPDATE and FIXED_UPDATE - `|data: &mut Data, ctx: &mut NodeContext|`.
+/// * UPDATE - `|app: &mut App, data: &mut Data, ctx: &mut NodeContext, dt: f32|`, with the app given to `World::update`
+///   and the seconds since the last update.
+/// * REMOVED - `|data: &mut Data, ctx: &mut NodeContext, reason: &RemovalReason|`, with why it is being removed.  It may
+///   also take the node and the reason, `|node: &mut Node, reason: &RemovalReason|`.
+/// 
+/// Each variant must have a distinct DATA type, as component data can be looked up by its type, IE with
+/// `World::iter_components` or `Node::get`.  A `Component::is_<variant>` check and `Component::as_<variant>` and
+/// `as_<variant>_mut` accessors are also created for each variant, along with `From<Data> for Component`.
+/// 
+/// A fieldless `ComponentKind` enum is created with an `Empty` kind and a kind for each variant, given by
+/// `Component::kind`, so inspectors, saved data and profilers can key things by component type.  Each kind has its
+/// `name` and a stable numeric `id`, the hash of its name from `forte_world::ids::component_kind_id`, which can be
+/// turned back into a kind with `ComponentKind::from_id` and `from_name`.
+/// 
+/// Right after the variants, an optional `SCRIPT => Script` adds a variant of that name holding a
+/// `forte_world::scripts::Script`, a boxed `NodeBehavior` whose added, update and removed functions are called as its
+/// callbacks, so one-off behaviors do not each need their own variant.  Scripts are created with `Script::new`, IE
+/// `Component::Script(Script::new(Spin { speed: 1.0 }))`, and cannot be cloned or serialized.
+/// 
+/// After those, an optional `DERIVE => [Clone, ...]` adds derives to the `Component` enum, deriving `Clone`
+/// allows nodes to be copied with `Node::duplicate`.  After that, an optional `ATTRS => [#[non_exhaustive], ...]`
+/// adds any other attributes to the enum, IE `#[serde(tag = "type")]` alongside serde's derives.  `Node` and `World`
+/// are aliases of the generic types in `forte_world::nodes`, so they cannot be given derives themselves, instead their
+/// `Debug`, `Node::duplicate` and serde impls follow whatever the `Component` enum derives.
+/// 
+/// RENDER callbacks are given a `&mut wgpu::RenderPass<'a>` by default.  A last optional `PASS => Type<'a>` renders
+/// the components into another pass type instead, IE a shadow or picking pass wrapping its own encoder, with every
+/// RENDER callback then taking `&mut Type<'a>`.  `DrawNodes` is implemented for whichever pass type is given, so
+/// `pass.draw_node(app, &root)` works the same with either.
+/// 
+/// With the `serde` feature, nodes can be saved with their children in any serde format once the `Component` enum
+/// derives serde's traits with `DERIVE => [serde::Serialize, serde::Deserialize]`, which needs every DATA type to
+/// implement them too.
+/// 
+/// With the `catch-callbacks` feature, a panic in any of the callbacks that can take the node is caught and logged, and
+/// the node is poisoned so its callbacks are skipped until `Node::clear_poison` or `World::clear_poison` is called.
+/// Poisoned nodes still update their transforms and still render.  Worlds pass each caught panic to the observer
+/// given to `World::set_panic_observer`.
+/// 
+/// With the `lifecycle-audit` feature, worlds count the ADDED and REMOVED callbacks their functions fire for each
+/// variant, so components that were added without being removed, or removed without being added, can be found with
+/// `World::lifecycle_report` and `World::assert_balanced`.
+/// 
 /// Example:
 /// ```rust 
-/// define_components!(
-/// Components,
-/// TestApp,
-/// DrawNodes,
-/// draw_node,
-/// [
-///     CubeModel => {
-///         DATA => CubeModel,
-///         ADDED => |_: &mut TestApp, _: &mut Node<Components, TestApp>| { println!("Added"); },
-///         UPDATE => |_: &mut TestApp, _: &mut Node<Components, TestApp>| { println!("Updated"); },
-///         RENDER => |pass: &mut wgpu::RenderPass<'a>, app: &'b TestApp, data: &'b CubeModel| {
-///             pass.prepare_cube_engine(&app.cube_engine, &app.camera);
-///             pass.draw_cube_model(&app.render_engine, &app.cube_engine, data);
+/// # use forte_engine::math::transforms::Transform;
+/// # use forte_world::{callbacks::RemovalReason, debug::DebugLines, define_world, inspector::Inspector};
+/// # pub struct TestApp;
+/// # #[derive(Debug, Clone)]
+/// # pub struct Skybox { brightness: f32, texture: String }
+/// # #[derive(Debug, Clone)]
+/// # pub struct CubeModel;
+/// define_world!(
+///     TestApp,
+///     [
+///         Skybox => {
+///             DATA => Skybox,
+///             ADDED => |_: &mut Node| { println!("Added"); },
+///             UPDATE => |_: &mut TestApp, data: &mut Skybox, ctx: &mut NodeContext, dt: f32| {
+///                 data.brightness -= dt;
+///                 println!("Updated {:?}", ctx.name);
+///             },
+///             RENDER => |_: &mut wgpu::RenderPass<'a>, _: &'b TestApp, _: &'b Skybox| {},
+///             REMOVED => |_: &mut Node, reason: &RemovalReason| { println!("Removed, {:?}", reason); },
+///             PRIORITY => -10,
+///             CASTS_SHADOW => false,
+///             INSPECT => |data: &mut Skybox, ui: &mut dyn Inspector| { ui.field_f32("brightness", &mut data.brightness); },
+///             DEBUG_DRAW => |_: &Skybox, transform: &Transform, out: &mut DebugLines| { out.axes(transform, 1.0); },
+///             SORT_KEY => |data: &Skybox| data.brightness.to_bits() as u64,
+///             REMAP_ASSETS => |data: &mut Skybox, remap: &dyn Fn(&str) -> Option<String>| {
+///                 let Some(path) = remap(&data.texture) else { return 0 };
+///                 data.texture = path;
+///                 1
+///             }
 ///         },
-///         REMOVED => |_: &mut TestApp, _: &mut Node<Components, TestApp>| { println!("Removed"); }
-///     }
-/// ]
+///         CubeModel => {
+///             DATA => CubeModel,
+///             RENDER => |_: &mut wgpu::RenderPass<'a>, _: &'b TestApp, _: &'b CubeModel| {}
+///         }
+///     ],
+///     DERIVE => [Clone],
+///     ATTRS => [#[non_exhaustive]]
 /// );
 /// ```
 #[macro_export]
 macro_rules! define_world {
+    // add the script variant to the end of the others
+    ($app:ident, [$($variant:ident => { $($body:tt)* }),* $(,)?], SCRIPT => $script:ident $($rest:tt)*) => {
+        $crate::define_world!(
+            $app,
+            [
+                $($variant => { $($body)* },)*
+                $script => {
+                    DATA => $crate::scripts::Script<Component, $app>,
+                    ADDED => |script: &mut $crate::scripts::Script<Component, $app>, ctx: &mut NodeContext| {
+                        $crate::scripts::NodeBehavior::added(&mut *script.0, ctx)
+                    },
+                    UPDATE => |app: &mut $app, script: &mut $crate::scripts::Script<Component, $app>, ctx: &mut NodeContext, dt: f32| {
+                        $crate::scripts::NodeBehavior::update(&mut *script.0, app, ctx, dt)
+                    },
+                    REMOVED => |script: &mut $crate::scripts::Script<Component, $app>, ctx: &mut NodeContext, reason: &$crate::callbacks::RemovalReason| {
+                        $crate::scripts::NodeBehavior::removed(&mut *script.0, ctx, reason)
+                    }
+                }
+            ]
+            $($rest)*
+        );
+    };
+
     (
         $app:ident,
         [$(
             $variant:ident => {
-                DATA => $data:ty,
-                ADDED => $added:expr,
-                UPDATE => $update:expr,
-                RENDER => $render:expr,
-                REMOVED => $removed:expr
+                DATA => $data:ty
+                $(, ADDED => $added:expr)?
+                $(, UPDATE => $update:expr)?
+                $(, RENDER => $render:expr)?
+                $(, REMOVED => $removed:expr)?
+                $(, START => $start:expr)?
+                $(, LATE_UPDATE => $late_update:expr)?
+                $(, FIXED_UPDATE => $fixed_update:expr)?
+                $(, INPUT => $input:expr)?
+                $(, RESIZE => $resize:expr)?
+                $(, ON_ENABLE => $on_enable:expr)?
+                $(, ON_DISABLE => $on_disable:expr)?
+                $(, PREPARE => $prepare:expr)?
+                $(, PRIORITY => $priority:expr)?
+                $(, CASTS_SHADOW => $casts_shadow:expr)?
+                $(, INSPECT => $inspect:expr)?
+                $(, DEBUG_DRAW => $debug_draw:expr)?
+                $(, SORT_KEY => $sort_key:expr)?
+                $(, REMAP_ASSETS => $remap_assets:expr)?
+                $(, SAVE => $save:expr, LOAD => $load:expr)?
             }
-        ),*]
+        ),* $(,)?]
+        $(, DERIVE => [$($derive:path),* $(,)?])?
+        $(, ATTRS => [$(#[$attr:meta]),* $(,)?])?
+        $(, PASS => $pass:ty)?
     ) => {
-        use std::marker::PhantomData;
-        use cgmath::Vector3;
-        use forte_engine::math::transforms::Transform;
-        use forte_world::dimensions::Dimensions;
+        $($($crate::__serde_codec!($variant $save);)?)*
 
         // Create full enum
-        #[derive(Default, Debug)]
+        #[derive(Default, Debug $($(, $derive)*)?)]
+        $($(#[$attr])*)?
         pub enum Component {
             #[default]
             Empty,
             $($variant($data),)*
         }
 
-        // create node
-        #[derive(Debug)]
-        pub struct Node {
-            // public
-            pub transform: Transform,
-            pub component: Component,
-            pub rel_min_dimensions: Dimensions,
-        
-            // non-public
-            global_transform: Transform,
-            dimensions: Dimensions,
-            children: Vec<Node>
-        }
+        impl Component {
+            // the names of all variants, indexed by their variant index
+            pub const VARIANT_NAMES: &'static [&'static str] = &[$(stringify!($variant)),*];
+
+            // the index of this components variant in the order they were defined, none for empty
+            pub fn variant_index(&self) -> Option<usize> {
+                #[allow(dead_code)]
+                enum Index { $($variant,)* }
 
-        // Give node a default
-        impl Default for Node {
-            fn default() -> Self {
-                Self {
-                    transform: Transform::default(),
-                    global_transform: Transform::default(),
-                    rel_min_dimensions: Dimensions::default(),
-                    dimensions: Dimensions::default(),
-                    component: Component::default(),
-                    children: Vec::new()
+                match self {
+                    Component::Empty => None,
+                    $(Component::$variant(_) => Some(Index::$variant as usize),)*
                 }
             }
-        }
 
-        // create node functions
-        impl Node {
-            // accessor functions
-            pub fn global_transform(&self) -> &Transform { &self.transform }
-            pub fn rel_min_dimensions(&self) -> &Dimensions { &self.rel_min_dimensions }
-            pub fn dimensions(&self) -> &Dimensions { &self.dimensions }
-            pub fn children(&self) -> &Vec<Node> { &self.children }
-
-            // modification functions
-            pub fn add_child(&mut self, mut child: Node) {
-                self.children.push(child);
-                self.children.last_mut().as_mut().unwrap().call_add_recr();
-            }
-
-            pub fn remove_child(&mut self, idx: usize) {
-                self.children[idx].call_remove_recr();
-                self.children.remove(idx);
-            }
-
-            pub fn update(&mut self, previous: &Transform) {
-                // calculate new global transform
-                let global_transform = Transform {
-                    position: self.transform.position + previous.position,
-                    rotation: previous.rotation * self.transform.rotation,
-                    scale: self.transform.scale.mul_element_wise(previous.scale)
-                };
-
-                // calculate starting dimensions
-                let mut dimensions = Dimensions {
-                    from: Vector3 {
-                        x: global_transform.position.x + self.rel_min_dimensions.from.x,
-                        y: global_transform.position.y + self.rel_min_dimensions.from.y,
-                        z: global_transform.position.z + self.rel_min_dimensions.from.z,
-                    },
-                    to: Vector3 {
-                        x: global_transform.position.x + self.rel_min_dimensions.to.x,
-                        y: global_transform.position.y + self.rel_min_dimensions.to.y,
-                        z: global_transform.position.z + self.rel_min_dimensions.to.z,
+            // true if any variant overrides the default priority of 0
+            pub const PRIORITIZED: bool = false $($(|| $priority != 0)?)*;
+
+            // true if any variant gives a sort key
+            pub const SORTED: bool = false $($(|| !stringify!($sort_key).is_empty())?)*;
+
+            // true if any variant gives a late update callback
+            pub const LATE_UPDATES: bool = false $($(|| !stringify!($late_update).is_empty())?)*;
+
+            // true if any variant gives a fixed update callback
+            pub const FIXED_UPDATES: bool = false $($(|| !stringify!($fixed_update).is_empty())?)*;
+
+            // true if any variant gives an input callback
+            pub const INPUTS: bool = false $($(|| !stringify!($input).is_empty())?)*;
+
+            // true if any variant gives a resize callback
+            pub const RESIZES: bool = false $($(|| !stringify!($resize).is_empty())?)*;
+
+            // true if any variant gives a prepare callback
+            pub const PREPARES: bool = false $($(|| !stringify!($prepare).is_empty())?)*;
+
+            // true for each variant, indexed by variant index, that gives a render callback
+            pub const RENDERED: &'static [bool] = &[$(false $(|| !stringify!($render).is_empty())?),*];
+
+            // true for each variant, indexed by variant index, that gives a sort key
+            pub const SORT_KEYED: &'static [bool] = &[$(false $(|| !stringify!($sort_key).is_empty())?),*];
+
+            // the draw sort key of this component, none if its variant does not give one
+            pub fn sort_key(&self) -> Option<u64> {
+                match self {
+                    Component::Empty => None,
+                    $(Component::$variant(_data) => { let _key: Option<u64> = None; $(let _key = Some($sort_key(_data));)? _key },)*
+                }
+            }
+
+            // passes this components asset paths to the given remap function, returns how many paths were rewritten
+            pub fn remap_assets(&mut self, remap: &dyn Fn(&str) -> Option<String>) -> usize {
+                let _ = remap;
+                match self {
+                    Component::Empty => 0,
+                    $(Component::$variant(_data) => { 0 $(+ $remap_assets(_data, remap))? },)*
+                }
+            }
+
+            $crate::__serde_items! {
+                // the data of this component given by its variants SAVE codec, none if it is empty or has no codec
+                pub fn save_data(&self) -> Option<$crate::scenes::SceneValue> {
+                    match self {
+                        Component::Empty => None,
+                        $(Component::$variant(_data) => { let _value: Option<$crate::scenes::SceneValue> = None; $(let _value = Some($save(_data));)? _value },)*
                     }
-                };
-
-                // update children first, and update dimensions if/when necessary
-                self.children.iter_mut().for_each(|child| {
-                    child.update(&global_transform);
-
-                    // check for dimension updates
-                    if child.dimensions.from.x < dimensions.from.x { dimensions.from.x = child.dimensions.from.x; }
-                    if child.dimensions.from.y < dimensions.from.y { dimensions.from.y = child.dimensions.from.y; }
-                    if child.dimensions.from.z < dimensions.from.z { dimensions.from.z = child.dimensions.from.z; }
-                    if child.dimensions.to.x > dimensions.to.x { dimensions.to.x = child.dimensions.to.x; }
-                    if child.dimensions.to.y > dimensions.to.y { dimensions.to.y = child.dimensions.to.y; }
-                    if child.dimensions.to.z > dimensions.to.z { dimensions.to.z = child.dimensions.to.z; }
-                });
-
-                // update global transform and dimensions
-                self.global_transform = global_transform;
-                self.dimensions = dimensions;
-
-                // call component update
-                match &self.component {
+                }
+
+                // creates a component of the named variant from saved data with the variants LOAD codec
+                pub fn load_data(variant: &str, data: &$crate::scenes::SceneValue) -> Result<Self, $crate::scenes::SceneError> {
+                    match variant {
+                        $(stringify!($variant) => {
+                            let _loaded: Result<Component, $crate::scenes::SceneError> = Err($crate::scenes::SceneError::NoCodec(variant.to_string()));
+                            $(let _loaded = ($load)(data).map(Component::$variant).ok_or_else(|| $crate::scenes::SceneError::InvalidData(variant.to_string()));)?
+                            _loaded
+                        },)*
+                        _ => { let _ = data; Err($crate::scenes::SceneError::UnknownVariant(variant.to_string())) }
+                    }
+                }
+            }
+
+            // the render priority of this component, lower priorities are drawn first
+            pub fn priority(&self) -> i32 {
+                match self {
+                    Component::Empty => 0,
+                    $(Component::$variant(_) => 0 $(+ $priority)?,)*
+                }
+            }
+
+            // true if this component should be collected for shadow passes
+            pub fn casts_shadow(&self) -> bool {
+                match self {
+                    Component::Empty => false,
+                    $(Component::$variant(_) => true $(&& $casts_shadow)?,)*
+                }
+            }
+
+            // adds this components debug shapes to the given lines, returns false if this component has no debug shapes
+            pub fn debug_draw(&self, transform: &$crate::forte_engine::math::transforms::Transform, out: &mut $crate::debug::DebugLines) -> bool {
+                let _ = (transform, &out);
+                match self {
+                    Component::Empty => false,
+                    $(Component::$variant(_data) => { false $(|| { $debug_draw(_data, transform, out); true })? },)*
+                }
+            }
+
+            // passes this components fields to the given inspector, returns false if this component has nothing to inspect
+            pub fn inspect(&mut self, ui: &mut dyn $crate::inspector::Inspector) -> bool {
+                let _ = &ui;
+                match self {
+                    Component::Empty => false,
+                    $(Component::$variant(_data) => { false $(|| { $inspect(_data, ui); true })? },)*
+                }
+            }
+        }
+
+        // connect the component to the generic node functions
+        impl $crate::nodes::ComponentDef<$app> for Component {
+            type Pass<'a> = $crate::__pass_type!($($pass)?);
+
+            const VARIANT_NAMES: &'static [&'static str] = Component::VARIANT_NAMES;
+            const PRIORITIZED: bool = Component::PRIORITIZED;
+            const SORTED: bool = Component::SORTED;
+            const SORT_KEYED: &'static [bool] = Component::SORT_KEYED;
+            const LATE_UPDATES: bool = Component::LATE_UPDATES;
+            const FIXED_UPDATES: bool = Component::FIXED_UPDATES;
+            const INPUTS: bool = Component::INPUTS;
+            const RESIZES: bool = Component::RESIZES;
+            const PREPARES: bool = Component::PREPARES;
+            const RENDERED: &'static [bool] = Component::RENDERED;
+
+            fn variant_index(&self) -> Option<usize> { Component::variant_index(self) }
+            fn priority(&self) -> i32 { Component::priority(self) }
+            fn casts_shadow(&self) -> bool { Component::casts_shadow(self) }
+            fn sort_key(&self) -> Option<u64> { Component::sort_key(self) }
+            fn debug_draw(&self, transform: &$crate::forte_engine::math::transforms::Transform, out: &mut $crate::debug::DebugLines) -> bool { Component::debug_draw(self, transform, out) }
+            fn inspect(&mut self, ui: &mut dyn $crate::inspector::Inspector) -> bool { Component::inspect(self, ui) }
+            fn remap_assets(&mut self, remap: &dyn Fn(&str) -> Option<String>) -> usize { Component::remap_assets(self, remap) }
+            $crate::__serde_items! {
+                fn save_data(&self) -> Option<$crate::scenes::SceneValue> { Component::save_data(self) }
+                fn load_data(variant: &str, data: &$crate::scenes::SceneValue) -> Result<Self, $crate::scenes::SceneError> { Component::load_data(variant, data) }
+            }
+
+            fn added(node: &mut Node) {
+                match &node.component {
                     Component::Empty => {},
-                    $(Component::$variant(data) => { $update(self) },)*
+                    $(Component::$variant(_) => { $($crate::callbacks::NodeCallback::<Component, $data, $app, _>::call_node($added, node);)? },)*
                 }
             }
 
-            // calls the add functions recursively for this node and all its children
-            pub(crate) fn call_add_recr(&mut self) {
-                match &self.component {
+            fn update(node: &mut Node, app: &mut $app, dt: f32) {
+                let _ = (&app, dt);
+                match &node.component {
                     Component::Empty => {},
-                    $(Component::$variant(data) => { $added(self) },)*
+                    $(Component::$variant(_) => { $($crate::callbacks::UpdateCallback::<Component, $data, $app, _>::call_update($update, node, app, dt);)? },)*
                 }
+            }
 
-                self.children.iter_mut().for_each(|child| child.call_add_recr());
+            fn start(node: &mut Node) {
+                match &node.component {
+                    Component::Empty => {},
+                    $(Component::$variant(_) => { $($crate::callbacks::NodeCallback::<Component, $data, $app, _>::call_node($start, node);)? },)*
+                }
             }
 
-            // calls the remove functions recursively for this node and all its children
-            pub(crate) fn call_remove_recr(&mut self) {
-                match &self.component {
+            fn on_enable(node: &mut Node) {
+                match &node.component {
                     Component::Empty => {},
-                    $(Component::$variant(data) => { $removed(self) },)*
+                    $(Component::$variant(_) => { $($crate::callbacks::NodeCallback::<Component, $data, $app, _>::call_node($on_enable, node);)? },)*
                 }
+            }
 
-                self.children.iter_mut().for_each(|child| child.call_remove_recr());
+            fn on_disable(node: &mut Node) {
+                match &node.component {
+                    Component::Empty => {},
+                    $(Component::$variant(_) => { $($crate::callbacks::NodeCallback::<Component, $data, $app, _>::call_node($on_disable, node);)? },)*
+                }
             }
-        }
-    
-        // create render trait
-        pub trait DrawNodes <'a,'b> where 'b: 'a {
-            fn draw_node(
-                &mut self,
-                app: &'b $app,
-                node: &'b Node
-            );
-        }
 
-        // draw trait for render pass
-        impl<'a, 'b> DrawNodes <'a, 'b> for wgpu::RenderPass<'a> where 'b: 'a {
-            fn draw_node(
-                &mut self,
-                app: &'b $app,
-                node: &'b Node
-            ) {
+            fn late_update(node: &mut Node) {
                 match &node.component {
                     Component::Empty => {},
-                    $(Component::$variant(data) => { $render(self, app, data) },)*
+                    $(Component::$variant(_) => { $($crate::callbacks::NodeCallback::<Component, $data, $app, _>::call_node($late_update, node);)? },)*
                 }
+            }
 
-                node.children().iter().for_each(|child| self.draw_node(app, child));
+            fn fixed_update(node: &mut Node) {
+                match &node.component {
+                    Component::Empty => {},
+                    $(Component::$variant(_) => { $($crate::callbacks::NodeCallback::<Component, $data, $app, _>::call_node($fixed_update, node);)? },)*
+                }
+            }
+
+            fn input(node: &mut Node, input: &$crate::forte_engine::render::input::EngineInput) -> bool {
+                let _ = input;
+                match &node.component {
+                    Component::Empty => false,
+                    $(Component::$variant(_) => { false $(|| $crate::callbacks::InputCallback::<Component, $data, $app, _>::call_input($input, node, input))? },)*
+                }
+            }
+
+            fn resize(node: &mut Node, size: $crate::winit::dpi::PhysicalSize<u32>) {
+                let _ = size;
+                match &node.component {
+                    Component::Empty => {},
+                    $(Component::$variant(_) => { $($crate::callbacks::ResizeCallback::<Component, $data, $app, _>::call_resize($resize, node, size);)? },)*
+                }
+            }
+
+            fn prepare(node: &mut Node, engine: &mut $crate::forte_engine::render::render_engine::RenderEngine) {
+                let _ = &engine;
+                match &node.component {
+                    Component::Empty => {},
+                    $(Component::$variant(_) => { $($crate::callbacks::PrepareCallback::<Component, $data, $app, _>::call_prepare($prepare, node, engine);)? },)*
+                }
+            }
+
+            fn removed(node: &mut Node, reason: &$crate::callbacks::RemovalReason) {
+                let _ = reason;
+                match &node.component {
+                    Component::Empty => {},
+                    $(Component::$variant(_) => { $($crate::callbacks::RemovedCallback::<Component, $data, $app, _>::call_removed($removed, node, reason);)? },)*
+                }
+            }
+
+            fn render<'a, 'b>(&'b self, pass: &mut Self::Pass<'a>, app: &'b $app) where 'b: 'a {
+                let _ = (&pass, app);
+                match self {
+                    Component::Empty => {},
+                    $(Component::$variant(_data) => { $($render(pass, app, _data);)? },)*
+                }
+            }
+        }
+
+        // the node and world types for this component
+        pub type Node = $crate::nodes::Node<Component, $app>;
+        pub type World = $crate::nodes::World<Component, $app>;
+        pub type NodeContext<'n> = $crate::nodes::NodeContext<'n, Component, $app>;
+
+        // give each variants data type a lookup from the component
+        $(
+            impl $crate::nodes::ComponentData<Component> for $data {
+                fn from_component(component: &Component) -> Option<&Self> {
+                    match component {
+                        Component::$variant(data) => Some(data),
+                        #[allow(unreachable_patterns)]
+                        _ => None
+                    }
+                }
+
+                fn from_component_mut(component: &mut Component) -> Option<&mut Self> {
+                    match component {
+                        Component::$variant(data) => Some(data),
+                        #[allow(unreachable_patterns)]
+                        _ => None
+                    }
+                }
+
+                fn try_from_component(component: Component) -> Result<Self, Component> {
+                    match component {
+                        Component::$variant(data) => Ok(data),
+                        #[allow(unreachable_patterns)]
+                        other => Err(other)
+                    }
+                }
+
+                fn into_component(self) -> Component { Component::$variant(self) }
+            }
+        )*
+
+        // create variant checks and accessors for each variant
+        $crate::paste::paste! {
+            impl Component {
+                $(
+                    // true if this component is of this variant
+                    pub fn [<is_ $variant:snake>](&self) -> bool { matches!(self, Component::$variant(_)) }
+
+                    // the data of this component if it is of this variant
+                    pub fn [<as_ $variant:snake>](&self) -> Option<&$data> {
+                        match self {
+                            Component::$variant(data) => Some(data),
+                            #[allow(unreachable_patterns)]
+                            _ => None
+                        }
+                    }
+
+                    pub fn [<as_ $variant:snake _mut>](&mut self) -> Option<&mut $data> {
+                        match self {
+                            Component::$variant(data) => Some(data),
+                            #[allow(unreachable_patterns)]
+                            _ => None
+                        }
+                    }
+                )*
+            }
+        }
+
+        // create a kind for each variant, so components can be keyed by their variant alone
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum ComponentKind {
+            Empty,
+            $($variant,)*
+        }
+
+        impl ComponentKind {
+            // every kind, the empty kind first and then the variants in the order they were defined
+            pub const ALL: &'static [ComponentKind] = &[ComponentKind::Empty, $(ComponentKind::$variant),*];
+
+            // the name of this kind, the same as its variant
+            pub const fn name(self) -> &'static str {
+                match self {
+                    ComponentKind::Empty => "Empty",
+                    $(ComponentKind::$variant => stringify!($variant),)*
+                }
+            }
+
+            // the stable id of this kind, see `$crate::ids::component_kind_id`
+            pub const fn id(self) -> u64 {
+                match self {
+                    ComponentKind::Empty => 0,
+                    $(ComponentKind::$variant => $crate::ids::component_kind_id(stringify!($variant)),)*
+                }
+            }
+
+            // the kind with the given stable id
+            pub fn from_id(id: u64) -> Option<Self> { Self::ALL.iter().copied().find(|kind| kind.id() == id) }
+
+            // the kind with the given name
+            pub fn from_name(name: &str) -> Option<Self> { Self::ALL.iter().copied().find(|kind| kind.name() == name) }
+        }
+
+        impl std::fmt::Display for ComponentKind {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str(self.name()) }
+        }
+
+        impl Component {
+            // the kind of this components variant
+            pub fn kind(&self) -> ComponentKind {
+                match self {
+                    Component::Empty => ComponentKind::Empty,
+                    $(Component::$variant(_) => ComponentKind::$variant,)*
+                }
+            }
+        }
+
+        // let each variants data be turned into its component
+        $(
+            impl From<$data> for Component {
+                fn from(data: $data) -> Self { Component::$variant(data) }
+            }
+        )*
+
+        // create node constructors for each variant
+        $crate::paste::paste! {
+            pub trait WithData {
+                $(
+                    // creates a default node with a component of this variant holding the given data
+                    fn [<with_ $variant:snake>](data: $data) -> Self;
+                )*
+            }
+
+            impl WithData for Node {
+                $(
+                    fn [<with_ $variant:snake>](data: $data) -> Self {
+                        let mut node = Node::default();
+                        node.component = Component::$variant(data);
+                        node
+                    }
+                )*
+            }
+        }
+
+        // create typed search functions for each variant
+        $crate::paste::paste! {
+            pub trait FindData {
+                $(
+                    // finds the first node in pre-order, including this one, with a matching component of this variant
+                    fn [<find_data_ $variant:snake>](&self, pred: impl Fn(&$data) -> bool) -> Option<(&Node, &$data)>;
+
+                    // finds the path to the first node in pre-order, including this one, with a matching component of this variant
+                    fn [<find_data_ $variant:snake _path>](&self, pred: impl Fn(&$data) -> bool) -> Option<$crate::paths::NodePath>;
+                )*
+            }
+
+            impl FindData for Node {
+                $(
+                    fn [<find_data_ $variant:snake>](&self, pred: impl Fn(&$data) -> bool) -> Option<(&Node, &$data)> {
+                        let node = self.find_component(|component| matches!(component, Component::$variant(data) if pred(data)))?;
+                        match &node.component {
+                            Component::$variant(data) => Some((node, data)),
+                            _ => None
+                        }
+                    }
+
+                    fn [<find_data_ $variant:snake _path>](&self, pred: impl Fn(&$data) -> bool) -> Option<$crate::paths::NodePath> {
+                        self.find_component_path(|component| matches!(component, Component::$variant(data) if pred(data)))
+                    }
+                )*
             }
         }
     };
 }
+
+/// Declares a node tree inline, producing the root `Node` of the tree.  This must be used where the `Node` generated by
+/// `define_world!` is in scope.
+/// 
+/// Supported keys, all of which are optional:
+/// * component - The component of the node.
+/// * position - The position of the node as an (x, y, z) tuple or a Vector3.
+/// * rotation - The rotation of the node as an (x, y, z) tuple of euler angles in degrees or a Quaternion.
+/// * scale - The scale of the node as an (x, y, z) tuple or a single uniform scale.
+/// * rel_min_dimensions - The Dimensions of the node.
+/// * transform_mode - The TransformMode of the node.
+/// * confine_to_parent - True if the node should be kept inside its parents rel_min_dimensions.
+/// * name - The name of the node, used by `Node::find_by_path`.
+/// * enabled - False if the node and its children should not be updated or drawn.
+/// * visible - The same as enabled, for trees written as what is shown.  Nodes are visible unless this is given.
+/// * tags - A list of tags of the node, used by `Node::find_all_with_tag`.
+/// * children - A list of child nodes, each a braced list of these same keys.
+/// 
+/// Values can be any expression, so components can be created in place.  No ADDED callbacks are fired while the tree
+/// is built, they are fired once for every node in it when the returned node is attached with `add_child`.
+/// 
+/// Example:
+/// ```rust
+/// # use forte_world::{define_world, scene};
+/// # pub struct TestApp;
+/// # #[derive(Debug)]
+/// # pub struct Marker(u32);
+/// # define_world!(TestApp, [
+/// #     Marker => {
+/// #         DATA => Marker,
+/// #         ADDED => |_: &mut Node| {},
+/// #         UPDATE => |_: &mut Node| {},
+/// #         RENDER => |_: &mut wgpu::RenderPass<'a>, _: &'b TestApp, _: &'b Marker| {},
+/// #         REMOVED => |_: &mut Node| {}
+/// #     }
+/// # ]);
+/// let id = 5;
+/// let root = scene! {
+///     children: [
+///         {
+///             component: Component::Marker(Marker(id)),
+///             position: (0.0, 1.0, 0.0),
+///             rotation: (0.0, 90.0, 0.0),
+///             scale: 2.0,
+///             children: [
+///                 { scale: (1.0, 2.0, 1.0) },
+///                 { name: "hidden", visible: false }
+///             ]
+///         }
+///     ]
+/// };
+/// assert_eq!(root.children()[0].children()[0].transform.scale.y, 2.0);
+/// assert!(root.children()[0].enabled && !root.children()[0].children()[1].enabled);
+/// ```
+#[macro_export]
+macro_rules! scene {
+    ($($body:tt)*) => {{
+        #[allow(unused_mut)]
+        let mut node = Node::default();
+        $crate::__scene_fields!(node; $($body)*);
+        node
+    }};
+}
+
+/// Defines a world like `define_world!` from the apps own variants and the variants of component sets, so libraries
+/// built on forte_world can ship their own component variants.
+/// 
+/// A component set is a macro exported by the library, given the next macro to call, the app and the variants so far,
+/// each followed by a comma, and then the rest of the input.  It calls the next macro with its own variants added after
+/// the others, each also followed by a comma, and passes the rest of the input on unchanged.  Its DATA types should be
+/// given with `$crate` paths.  The sets are called in the order they are given, followed by an optional SCRIPT,
+/// DERIVE, ATTRS and PASS like `define_world!`.
+/// 
+/// Example:
+/// ```rust
+/// # use forte_world::{compose_world, scene};
+/// # pub struct TestApp;
+/// # #[derive(Debug)]
+/// # pub struct Skybox;
+/// # #[derive(Debug)]
+/// # pub struct Sprite;
+/// // in a library, with `#[macro_export]`
+/// macro_rules! sprite_components {
+///     ($($next:ident)::+; $app:ident, [$($variants:tt)*] $($rest:tt)*) => {
+///         $($next)::+!($app, [$($variants)* Sprite => { DATA => Sprite },] $($rest)*);
+///     };
+/// }
+/// 
+/// compose_world!(
+///     TestApp,
+///     [
+///         Skybox => { DATA => Skybox }
+///     ],
+///     sprite_components
+/// );
+/// 
+/// assert_eq!(Component::VARIANT_NAMES, &["Skybox", "Sprite"]);
+/// ```
+#[macro_export]
+macro_rules! compose_world {
+    // every set has added its variants
+    (
+        $app:ident, [$($variant:ident => { $($body:tt)* }),* $(,)?]
+        $(, SCRIPT => $script:ident)?
+        $(, DERIVE => [$($derive:path),* $(,)?])?
+        $(, ATTRS => [$(#[$attr:meta]),* $(,)?])?
+        $(, PASS => $pass:ty)?
+    ) => {
+        $crate::define_world!(
+            $app, [$($variant => { $($body)* }),*]
+            $(, SCRIPT => $script)?
+            $(, DERIVE => [$($derive),*])?
+            $(, ATTRS => [$(#[$attr]),*])?
+            $(, PASS => $pass)?
+        );
+    };
+
+    // hand the variants so far to the next set, each followed by a comma so the set can add its own after them
+    ($app:ident, [$($variant:ident => { $($body:tt)* }),* $(,)?], $($set:ident)::+ $(, $($rest:tt)*)?) => {
+        $($set)::+!($crate::compose_world; $app, [$($variant => { $($body)* },)*] $(, $($rest)*)?);
+    };
+}
+
+// the pass type given to define_world!, or a render pass if none is given
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __pass_type {
+    () => { $crate::wgpu::RenderPass<'a> };
+    ($pass:ty) => { $pass };
+}
+
+// the items given to define_world! that need the serde feature, as cfg attributes in the macro would check the features
+// of the crate calling it instead
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __serde_items {
+    ($($item:tt)*) => { $($item)* };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __serde_items {
+    ($($item:tt)*) => {};
+}
+
+// fails with a named error when SAVE and LOAD codecs are given without the serde feature
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __serde_codec {
+    ($variant:ident $save:expr) => {};
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __serde_codec {
+    ($variant:ident $save:expr) => {
+        compile_error!(concat!("the SAVE and LOAD codecs of `", stringify!($variant), "` need forte_world's serde feature"));
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __scene_fields {
+    ($node:ident; $(,)?) => {};
+    ($node:ident; component: $value:expr $(, $($rest:tt)*)?) => {
+        $node.component = $value;
+        $crate::__scene_fields!($node; $($($rest)*)?);
+    };
+    ($node:ident; position: $value:expr $(, $($rest:tt)*)?) => {
+        $node.transform.position = ($value).into();
+        $crate::__scene_fields!($node; $($($rest)*)?);
+    };
+    ($node:ident; rotation: ($x:expr, $y:expr, $z:expr) $(, $($rest:tt)*)?) => {
+        $node.transform.rotation = $crate::cgmath::Euler::new($crate::cgmath::Deg($x), $crate::cgmath::Deg($y), $crate::cgmath::Deg($z)).into();
+        $crate::__scene_fields!($node; $($($rest)*)?);
+    };
+    ($node:ident; rotation: $value:expr $(, $($rest:tt)*)?) => {
+        $node.transform.rotation = $value;
+        $crate::__scene_fields!($node; $($($rest)*)?);
+    };
+    ($node:ident; scale: ($x:expr, $y:expr, $z:expr) $(, $($rest:tt)*)?) => {
+        $node.transform.scale = $crate::cgmath::Vector3::new($x, $y, $z);
+        $crate::__scene_fields!($node; $($($rest)*)?);
+    };
+    ($node:ident; scale: $value:expr $(, $($rest:tt)*)?) => {
+        let scale = $value;
+        $node.transform.scale = $crate::cgmath::Vector3::new(scale, scale, scale);
+        $crate::__scene_fields!($node; $($($rest)*)?);
+    };
+    ($node:ident; rel_min_dimensions: $value:expr $(, $($rest:tt)*)?) => {
+        $node.rel_min_dimensions = $value;
+        $crate::__scene_fields!($node; $($($rest)*)?);
+    };
+    ($node:ident; transform_mode: $value:expr $(, $($rest:tt)*)?) => {
+        $node.transform_mode = $value;
+        $crate::__scene_fields!($node; $($($rest)*)?);
+    };
+    ($node:ident; confine_to_parent: $value:expr $(, $($rest:tt)*)?) => {
+        $node.confine_to_parent = $value;
+        $crate::__scene_fields!($node; $($($rest)*)?);
+    };
+    ($node:ident; enabled: $value:expr $(, $($rest:tt)*)?) => {
+        $node.enabled = $value;
+        $crate::__scene_fields!($node; $($($rest)*)?);
+    };
+    ($node:ident; visible: $value:expr $(, $($rest:tt)*)?) => {
+        $node.enabled = $value;
+        $crate::__scene_fields!($node; $($($rest)*)?);
+    };
+    ($node:ident; tags: [$($tag:expr),* $(,)?] $(, $($rest:tt)*)?) => {
+        $($node.add_tag($tag);)*
+        $crate::__scene_fields!($node; $($($rest)*)?);
+    };
+    ($node:ident; name: $value:expr $(, $($rest:tt)*)?) => {
+        $node.name = Some(($value).into());
+        $crate::__scene_fields!($node; $($($rest)*)?);
+    };
+    ($node:ident; children: [$({ $($child:tt)* }),* $(,)?] $(, $($rest:tt)*)?) => {
+        $($node.push_detached_child($crate::scene!($($child)*));)*
+        $crate::__scene_fields!($node; $($($rest)*)?);
+    };
+    ($node:ident; $key:ident : $($rest:tt)*) => {
+        compile_error!(concat!(
+            "unknown or malformed scene! key `", stringify!($key), "`, expected one of: ",
+            "component, position, rotation, scale, rel_min_dimensions, transform_mode, confine_to_parent, name, enabled, visible, tags, children"
+        ));
+    };
+    ($node:ident; $($rest:tt)*) => {
+        compile_error!(concat!("malformed scene! entry `", stringify!($($rest)*), "`, expected `key: value`"));
+    };
+}
@@ -0,0 +1,206 @@
+use cgmath::{InnerSpace, Quaternion, Rotation, Vector3, VectorSpace};
+use forte_engine::math::transforms::Transform;
+
+/// How a nodes local transform is interpreted when it is composed with its parents.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransformMode {
+    /// The full position, rotation and scale of the transform are used.
+    #[default]
+    ThreeD,
+
+    /// The transform is treated as a sprite.  The X and Y positions are used as is, the Z position is used as a draw
+    /// layer, only the rotation around the Z axis is used, and the Z scale is ignored.
+    TwoD
+}
+
+/// Calculates the rotation around the Z axis of the given quaternion.
+/// 
+/// Arguments:
+/// * rotation: Quaternion<f32> - The rotation to measure, this is assumed to only rotate around the Z axis.
+/// 
+/// Returns the angle of the rotation in radians.
+pub fn z_angle(rotation: Quaternion<f32>) -> f32 {
+    2.0 * rotation.v.z.atan2(rotation.s)
+}
+
+/// A trait to add interpolation and direction vectors to forte_engine's Transform.
+/// 
+/// Directions follow the right handed convention used by wgpu, -Z is forward, +X is right and +Y is up.  They only
+/// depend on the rotation, so they stay unit length under any scale.
+/// 
+/// Example of a mover using forward:
+/// ```rust
+/// # use forte_engine::math::transforms::Transform;
+/// use forte_world::math::TransformExt;
+/// 
+/// let mut transform = Transform::default();
+/// let speed = 2.0;
+/// transform.position += transform.forward() * speed;
+/// assert_eq!(transform.position, cgmath::Vector3::new(0.0, 0.0, -2.0));
+/// ```
+pub trait TransformExt {
+    /// Interpolates between two transforms.  Positions and scales are linearly interpolated, while rotations are
+    /// spherically interpolated along the shortest path.
+    /// 
+    /// Arguments:
+    /// * a: &Transform - The transform at t = 0.
+    /// * b: &Transform - The transform at t = 1.
+    /// * t: f32 - How far to interpolate from a to b, this is clamped between 0 and 1.
+    /// 
+    /// Returns the interpolated transform, which is exactly a at t <= 0 and exactly b at t >= 1.
+    fn lerp(a: &Transform, b: &Transform, t: f32) -> Transform;
+
+    /// The direction this transform faces, -Z rotated by its rotation.
+    fn forward(&self) -> Vector3<f32>;
+
+    /// The direction to the right of this transform, +X rotated by its rotation.
+    fn right(&self) -> Vector3<f32>;
+
+    /// The direction above this transform, +Y rotated by its rotation.
+    fn up(&self) -> Vector3<f32>;
+}
+
+/// An implementation of TransformExt.  See documentation for more info.
+impl TransformExt for Transform {
+    fn lerp(a: &Transform, b: &Transform, t: f32) -> Transform {
+        if t <= 0.0 || t.is_nan() { return *a }
+        if t >= 1.0 { return *b }
+
+        Transform {
+            position: a.position.lerp(b.position, t),
+            rotation: slerp(a.rotation, b.rotation, t),
+            scale: a.scale.lerp(b.scale, t)
+        }
+    }
+
+    fn forward(&self) -> Vector3<f32> { rotate_direction(self.rotation, -Vector3::unit_z()) }
+    fn right(&self) -> Vector3<f32> { rotate_direction(self.rotation, Vector3::unit_x()) }
+    fn up(&self) -> Vector3<f32> { rotate_direction(self.rotation, Vector3::unit_y()) }
+}
+
+/// Rotates a unit direction by the given rotation.
+/// 
+/// Arguments:
+/// * rotation: Quaternion<f32> - The rotation, this is normalized before use so it may have drifted.
+/// * direction: Vector3<f32> - The unit direction to rotate.
+/// 
+/// Returns the rotated direction, which is unit length.
+pub fn rotate_direction(rotation: Quaternion<f32>, direction: Vector3<f32>) -> Vector3<f32> {
+    let magnitude = rotation.magnitude();
+    if magnitude == 0.0 || !magnitude.is_finite() { return direction }
+    (rotation / magnitude).rotate_vector(direction).normalize()
+}
+
+/// Spherically interpolates between two rotations along the shortest path.
+/// 
+/// Arguments:
+/// * a: Quaternion<f32> - The rotation at t = 0.
+/// * b: Quaternion<f32> - The rotation at t = 1.
+/// * t: f32 - How far to interpolate from a to b.
+/// 
+/// Returns the interpolated rotation.  As q and -q are the same rotation, b is flipped when needed so that the
+/// interpolation never takes the long way around.
+pub fn slerp(a: Quaternion<f32>, b: Quaternion<f32>, t: f32) -> Quaternion<f32> {
+    let mut dot = a.dot(b);
+    let b = if dot < 0.0 { dot = -dot; -b } else { b };
+
+    // nearly identical rotations are normalized linearly to avoid dividing by a tiny sine
+    if dot > 0.9995 { return (a * (1.0 - t) + b * t).normalize() }
+
+    let theta = dot.min(1.0).acos();
+    let sin = theta.sin();
+    a * (((1.0 - t) * theta).sin() / sin) + b * ((t * theta).sin() / sin)
+}
+
+/// The serde form of forte_engine's `Transform`, for fields with `#[serde(with = "forte_world::math::TransformDef")]`.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(remote = "Transform")]
+pub struct TransformDef {
+    pub position: Vector3<f32>,
+    pub rotation: Quaternion<f32>,
+    pub scale: Vector3<f32>
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Deg, InnerSpace, Quaternion, Rotation3, Vector3};
+    use forte_engine::math::transforms::Transform;
+
+    use super::{rotate_direction, slerp, TransformExt};
+
+    fn near(a: Vector3<f32>, b: Vector3<f32>) -> bool { (a - b).magnitude() < 1e-5 }
+
+    // the angle between two rotations in degrees
+    fn angle(a: Quaternion<f32>, b: Quaternion<f32>) -> f32 { (2.0 * a.dot(b).abs().min(1.0).acos()).to_degrees() }
+
+    #[test]
+    fn lerp_interpolates_and_clamps() {
+        let a = Transform { position: Vector3::new(0.0, 0.0, 0.0), rotation: Quaternion::from_angle_y(Deg(0.0)), scale: Vector3::new(1.0, 1.0, 1.0) };
+        let b = Transform { position: Vector3::new(10.0, 0.0, 0.0), rotation: Quaternion::from_angle_y(Deg(90.0)), scale: Vector3::new(3.0, 3.0, 3.0) };
+
+        let half = Transform::lerp(&a, &b, 0.5);
+        assert!(near(half.position, Vector3::new(5.0, 0.0, 0.0)));
+        assert!(near(half.scale, Vector3::new(2.0, 2.0, 2.0)));
+        assert!((angle(half.rotation, Quaternion::from_angle_y(Deg(45.0)))).abs() < 1e-2);
+
+        // the ends are exact, and t outside of 0 to 1 or NaN does not extrapolate
+        let parts = |transform: Transform| (transform.position, transform.rotation, transform.scale);
+        for t in [0.0, -1.0, f32::NAN] { assert_eq!(parts(Transform::lerp(&a, &b, t)), parts(a)); }
+        for t in [1.0, 2.0] { assert_eq!(parts(Transform::lerp(&a, &b, t)), parts(b)); }
+    }
+
+    #[test]
+    fn slerp_takes_the_short_way() {
+        let a = Quaternion::from_angle_z(Deg(10.0));
+        let b = Quaternion::from_angle_z(Deg(350.0));
+
+        // -b is the same rotation as b, so both halfway points are the same
+        for b in [b, -b] {
+            let half = slerp(a, b, 0.5);
+            assert!(angle(half, Quaternion::from_angle_z(Deg(0.0))) < 1e-2);
+            assert!((half.magnitude() - 1.0).abs() < 1e-5);
+        }
+
+        // the rotation turns at a constant rate
+        let b = Quaternion::from_angle_z(Deg(120.0));
+        for step in 0..=4 {
+            let t = step as f32 / 4.0;
+            assert!((angle(slerp(a, b, t), a) - 110.0 * t).abs() < 1e-2, "{}", t);
+        }
+
+        // nearly identical rotations are still normalized
+        let close = Quaternion::from_angle_z(Deg(10.01));
+        assert!((slerp(a, close, 0.5).magnitude() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn directions_follow_the_rotation() {
+        let mut transform = Transform { position: Vector3::new(0.0, 0.0, 0.0), rotation: Quaternion::from_angle_y(Deg(0.0)), scale: Vector3::new(1.0, 1.0, 1.0) };
+        assert_eq!((transform.forward(), transform.right(), transform.up()), (-Vector3::unit_z(), Vector3::unit_x(), Vector3::unit_y()));
+
+        // turning left by 90 degrees faces -x, with right now facing -z
+        transform.rotation = Quaternion::from_angle_y(Deg(90.0));
+        assert!(near(transform.forward(), -Vector3::unit_x()));
+        assert!(near(transform.right(), -Vector3::unit_z()));
+        assert!(near(transform.up(), Vector3::unit_y()));
+
+        // pitching up points forward at the sky, and scale never changes the length
+        transform.rotation = Quaternion::from_angle_x(Deg(90.0));
+        transform.scale = Vector3::new(5.0, 0.0, -2.0);
+        assert!(near(transform.forward(), Vector3::unit_y()));
+        assert!(near(transform.up(), Vector3::unit_z()));
+        assert!((transform.right().magnitude() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rotate_direction_normalizes_drifted_rotations() {
+        let drifted = Quaternion::from_angle_y(Deg(90.0)) * 3.0;
+        assert!(near(rotate_direction(drifted, -Vector3::unit_z()), -Vector3::unit_x()));
+
+        // a rotation that can't be normalized leaves the direction alone
+        assert_eq!(rotate_direction(Quaternion::new(0.0, 0.0, 0.0, 0.0), Vector3::unit_x()), Vector3::unit_x());
+        assert_eq!(rotate_direction(Quaternion::new(f32::NAN, 0.0, 0.0, 0.0), Vector3::unit_x()), Vector3::unit_x());
+    }
+}
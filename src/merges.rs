@@ -0,0 +1,242 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    callbacks::RemovalReason,
+    errors::WorldError,
+    ids::NodeId,
+    nodes::{ComponentDef, Insertion, Node, World}
+};
+
+/// A source of time for `PendingMerge::step_with`, kept this small so tests and replays can step merges with their own
+/// clock.
+pub trait MergeClock {
+    /// The time passed since any fixed point, which must never go backwards.
+    fn now(&mut self) -> Duration;
+}
+
+// the clock used by `PendingMerge::step`
+struct SystemClock(Instant);
+
+impl MergeClock for SystemClock {
+    fn now(&mut self) -> Duration { self.0.elapsed() }
+}
+
+/// How far a `PendingMerge` has come after a step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeProgress {
+    /// Some nodes are still waiting for their added functions, with the number added so far and the number left.
+    Pending { added: usize, remaining: usize },
+
+    /// Every node of the subtree has been added.
+    Done
+}
+
+/// A subtree attached by `World::merge_subtree_incremental` whose added functions are fired a few at a time by `step`.
+/// Each node stays disabled, so it is neither updated nor drawn, until its added function has fired.
+#[derive(Debug)]
+pub struct PendingMerge {
+    root: NodeId,
+    // every node of the subtree with an id in pre-order, along with its enabled flag from before it was hidden
+    nodes: Vec<(NodeId, bool)>,
+    added: usize
+}
+
+impl PendingMerge {
+    /// The id of the root of the subtree.
+    pub fn root(&self) -> NodeId { self.root }
+
+    /// How far this merge has come.
+    pub fn progress(&self) -> MergeProgress {
+        match self.nodes.len() - self.added {
+            0 => MergeProgress::Done,
+            remaining => MergeProgress::Pending { added: self.added, remaining }
+        }
+    }
+
+    /// Fires the added functions of the next nodes of the subtree in pre-order until the given budget of time is used
+    /// up, enabling each node again right after its added function.  At least one node is added by each step, so a
+    /// merge always finishes.  The app is not used by the added functions, it is taken so stepping a merge looks like
+    /// the rest of a frame.
+    ///
+    /// Nodes despawned from the subtree before their turn are skipped.  A node whose enabled flag was changed while it
+    /// was waiting is given back the flag it had when the subtree was merged.
+    pub fn step<C: ComponentDef<A>, A>(&mut self, world: &mut World<C, A>, app: &mut A, budget: Duration) -> MergeProgress {
+        self.step_with(world, app, budget, &mut SystemClock(Instant::now()))
+    }
+
+    /// Steps this merge like `step`, measuring the budget with the given clock.
+    pub fn step_with<C: ComponentDef<A>, A>(&mut self, world: &mut World<C, A>, _: &mut A, budget: Duration, clock: &mut impl MergeClock) -> MergeProgress {
+        let start = clock.now();
+        world.observed(|world| {
+            while let Some(&(id, enabled)) = self.nodes.get(self.added) {
+                self.added += 1;
+                let Some(node) = world.get_mut(id) else { continue };
+                node.call_add();
+                node.enabled = enabled;
+                if clock.now().saturating_sub(start) >= budget { break }
+            }
+        });
+        self.progress()
+    }
+
+    /// Stops this merge, taking the subtree back out of the world, see `World::take`.  The remove functions fire only
+    /// for the nodes whose added functions had already fired, in pre-order, and every node is given back its enabled
+    /// flag so the subtree can be merged again.
+    ///
+    /// Returns the subtree, or None if its root is no longer in the world.
+    pub fn cancel<C: ComponentDef<A>, A>(self, world: &mut World<C, A>) -> Option<Node<C, A>> {
+        if !world.is_alive(self.root) { return None }
+
+        world.observed(|world| {
+            for (idx, &(id, _)) in self.nodes[..self.added].iter().enumerate() {
+                let reason = if idx == 0 { RemovalReason::Despawned } else { RemovalReason::ParentDespawned };
+                if let Some(node) = world.get_mut(id) { node.call_remove(reason); }
+            }
+        });
+        for &(id, enabled) in &self.nodes[self.added..] {
+            if let Some(node) = world.get_mut(id) { node.enabled = enabled; }
+        }
+
+        // the nodes were already counted as removed by their remove functions, or never counted as added
+        let node = world.take(self.root)?;
+        #[cfg(feature = "lifecycle-audit")]
+        crate::audit::count_tree(&node, &mut world.audit, 1);
+        Some(node)
+    }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Attaches the given subtree as the last child of the parent right away, giving its nodes ids, but leaves their
+    /// added functions for the returned `PendingMerge` to fire a few at a time, IE so a huge streamed chunk does not
+    /// stall a frame.  Every node of the subtree is disabled until its added function fires, without firing its enable
+    /// or disable functions.  The subtree may be renamed, see `set_name_collision_policy`.
+    ///
+    /// Returns `NotFound` if the parent does not exist, `TooManyChildren` if it is full, `NameTaken` if it has a child
+    /// with the same name under the `Reject` policy, or `TooManyNodes` or `TooDeep` if the subtree does not fit within
+    /// the limits of the config.
+    pub fn merge_subtree_incremental(&mut self, parent: NodeId, subtree: Node<C, A>) -> Result<PendingMerge, WorldError> {
+        let root = self.insert_inner(parent, subtree, Insertion { added: false, ..Insertion::new(&self.config) })?;
+        let nodes: Vec<(NodeId, bool)> = self.get(root).unwrap().iter().filter_map(|node| Some((node.id()?, node.enabled))).collect();
+        self.get_mut(root).unwrap().iter_mut().for_each(|node| *node.enabled = false);
+        Ok(PendingMerge { root, nodes, added: 0 })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{MergeClock, MergeProgress};
+    use self::world::*;
+
+    #[allow(dead_code)]
+    mod world {
+        use std::cell::{Cell, RefCell};
+
+        pub struct TestApp;
+
+        #[derive(Debug)]
+        pub struct Marker(pub u32);
+
+        thread_local! {
+            pub static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+            pub static UPDATES: RefCell<Vec<u32>> = const { RefCell::new(Vec::new()) };
+
+            // each added function takes a millisecond of fake time
+            pub static MILLIS: Cell<u64> = const { Cell::new(0) };
+        }
+
+        fn marker(node: &Node) -> u32 { node.get::<Marker>().unwrap().0 }
+
+        crate::define_world!(TestApp, [
+            Marker => {
+                DATA => Marker,
+                ADDED => |node: &mut Node| {
+                    MILLIS.with(|millis| millis.set(millis.get() + 1));
+                    LOG.with(|log| log.borrow_mut().push(format!("added {}", marker(node))));
+                },
+                UPDATE => |node: &mut Node| UPDATES.with(|updates| updates.borrow_mut().push(marker(node))),
+                REMOVED => |node: &mut Node| LOG.with(|log| log.borrow_mut().push(format!("removed {}", marker(node))))
+            }
+        ]);
+    }
+
+    struct FakeClock;
+
+    impl MergeClock for FakeClock {
+        fn now(&mut self) -> Duration { Duration::from_millis(MILLIS.with(|millis| millis.get())) }
+    }
+
+    fn take_log() -> Vec<String> { LOG.with(|log| std::mem::take(&mut *log.borrow_mut())) }
+
+    fn updated(world: &mut World) -> Vec<u32> {
+        UPDATES.with(|updates| updates.borrow_mut().clear());
+        world.update(&mut TestApp, 0.1);
+        UPDATES.with(|updates| std::mem::take(&mut *updates.borrow_mut()))
+    }
+
+    // 1 with children 2 and 5, 2 with children 3 and 4
+    fn chunk() -> Node {
+        let mut two = Node::with_marker(Marker(2));
+        two.push_detached_child(Node::with_marker(Marker(3)));
+        two.push_detached_child(Node::with_marker(Marker(4)));
+        let mut one = Node::with_marker(Marker(1));
+        one.push_detached_child(two);
+        one.push_detached_child(Node::with_marker(Marker(5)));
+        one
+    }
+
+    #[test]
+    fn merges_within_the_budget_in_pre_order() {
+        let mut world = World::new();
+        let mut merge = world.merge_subtree_incremental(world.root_id(), chunk()).unwrap();
+        assert_eq!(world.root().count_nodes(), 6);
+        assert!(take_log().is_empty());
+        assert!(updated(&mut world).is_empty());
+
+        let budget = Duration::from_millis(2);
+        assert_eq!(merge.step_with(&mut world, &mut TestApp, budget, &mut FakeClock), MergeProgress::Pending { added: 2, remaining: 3 });
+        assert_eq!(take_log(), ["added 1", "added 2"]);
+
+        // only the added nodes are updated, children after their parents, or drawn
+        assert_eq!(updated(&mut world), [2, 1]);
+        let mut drawn = Vec::new();
+        world.root().build_draw_list(&mut drawn);
+        assert_eq!(drawn.iter().filter_map(|node| node.get::<Marker>()).map(|marker| marker.0).collect::<Vec<_>>(), [1, 2]);
+
+        assert_eq!(merge.step_with(&mut world, &mut TestApp, budget, &mut FakeClock), MergeProgress::Pending { added: 4, remaining: 1 });
+        assert_eq!(updated(&mut world), [3, 4, 2, 1]);
+
+        // a step always adds at least one node
+        assert_eq!(merge.step_with(&mut world, &mut TestApp, Duration::ZERO, &mut FakeClock), MergeProgress::Done);
+        assert_eq!(take_log(), ["added 3", "added 4", "added 5"]);
+        assert_eq!(updated(&mut world), [3, 4, 2, 5, 1]);
+        assert_eq!(merge.step_with(&mut world, &mut TestApp, budget, &mut FakeClock), MergeProgress::Done);
+        assert!(take_log().is_empty());
+    }
+
+    #[test]
+    fn cancel_removes_only_what_was_added() {
+        let mut world = World::new();
+        let mut disabled = chunk();
+        disabled.set_enabled(false);
+        let mut merge = world.merge_subtree_incremental(world.root_id(), disabled).unwrap();
+        merge.step_with(&mut world, &mut TestApp, Duration::from_millis(3), &mut FakeClock);
+        assert_eq!(take_log(), ["added 1", "added 2", "added 3"]);
+
+        let root = merge.root();
+        let subtree = merge.cancel(&mut world).unwrap();
+        assert_eq!(take_log(), ["removed 1", "removed 2", "removed 3"]);
+        assert!(!world.is_alive(root));
+        assert_eq!(world.root().count_nodes(), 1);
+
+        // the flags are back as they were, so merging again adds everything once
+        assert!(!subtree.enabled);
+        assert!(subtree.iter().skip(1).all(|node| node.enabled));
+        let mut merge = world.merge_subtree_incremental(world.root_id(), subtree).unwrap();
+        assert_eq!(merge.step(&mut world, &mut TestApp, Duration::from_secs(60)), MergeProgress::Done);
+        assert_eq!(take_log(), ["added 1", "added 2", "added 3", "added 4", "added 5"]);
+        assert!(updated(&mut world).is_empty());
+        assert!(!world.get(merge.root()).unwrap().enabled);
+    }
+}
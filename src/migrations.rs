@@ -0,0 +1,61 @@
+use crate::scenes::{SceneError, SceneFile, SceneNode};
+
+/// A migration of a scene from one schema version to the next.
+pub type SceneMigration = Box<dyn Fn(&mut SceneFile)>;
+
+/// The migrations of an apps scenes between the versions of its component set, so old scene files keep loading as
+/// components are renamed or their saved data changes.
+///
+/// The app picks a schema version for its component set, bumps it whenever the saved form of its components changes
+/// and registers a migration from the old version to the new one.  Scenes are migrated one version at a time from the
+/// schema version they were saved with, see `SceneFile::read_migrated`.
+#[derive(Default)]
+pub struct SceneMigrations {
+    version: u32,
+    steps: Vec<Option<SceneMigration>>
+}
+
+impl SceneMigrations {
+    /// Creates new migrations with no steps for the given current schema version.
+    pub fn new(version: u32) -> Self { Self { version, steps: Vec::new() } }
+
+    /// The current schema version scenes are migrated to.
+    pub fn version(&self) -> u32 { self.version }
+
+    /// Registers the migration of scenes from the given schema version to the next, replacing any migration registered
+    /// from that version before.
+    pub fn register(mut self, from: u32, migration: impl Fn(&mut SceneFile) + 'static) -> Self {
+        let idx = from as usize;
+        if self.steps.len() <= idx { self.steps.resize_with(idx + 1, || None); }
+        self.steps[idx] = Some(Box::new(migration));
+        self
+    }
+
+    /// Registers a migration from the given schema version that is applied to every node of the scene, IE to rename a
+    /// variant or change the shape of its data.
+    pub fn register_nodes(self, from: u32, migration: impl Fn(&mut SceneNode) + 'static) -> Self {
+        self.register(from, move |scene| migrate_nodes_recr(&mut scene.root, &migration))
+    }
+
+    /// Migrates the given scene to the current schema version, setting its schema version.
+    ///
+    /// Returns an error if the scene was saved with a newer schema version, or if a migration is missing, in which case
+    /// the scene is left at the schema version that could not be migrated.
+    pub fn migrate(&self, scene: &mut SceneFile) -> Result<(), SceneError> {
+        if scene.schema_version > self.version { return Err(SceneError::UnsupportedSchemaVersion(scene.schema_version)) }
+
+        while scene.schema_version < self.version {
+            let Some(Some(migration)) = self.steps.get(scene.schema_version as usize) else {
+                return Err(SceneError::MissingMigration(scene.schema_version))
+            };
+            migration(scene);
+            scene.schema_version += 1;
+        }
+        Ok(())
+    }
+}
+
+fn migrate_nodes_recr(node: &mut SceneNode, migration: &impl Fn(&mut SceneNode)) {
+    migration(node);
+    node.children.iter_mut().for_each(|child| migrate_nodes_recr(child, migration));
+}
@@ -0,0 +1,240 @@
+use std::collections::{btree_map::Entry, BTreeMap, HashMap};
+
+use crate::{
+    errors::WorldError,
+    ids::NodeId,
+    nodes::{ComponentDef, Node, World}
+};
+
+/// What a world does when a node is spawned next to a sibling with the same name, see
+/// `World::set_name_collision_policy`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NameCollisionPolicy {
+    /// Siblings can share a name, so finding a node by its path gives the first of them.
+    #[default]
+    Allow,
+
+    /// The new node is given the smallest numeric suffix not used by its siblings, IE `warrior`, `warrior.001` then
+    /// `warrior.002`.
+    Rename,
+
+    /// The new node is not spawned, and `WorldError::NameTaken` is returned.
+    Reject
+}
+
+// splits a name into the part before its numeric suffix and the suffix, 0 if it has none.  Only suffixes written the way
+// `with_suffix` writes them count, so `a.01` and `a.000` are names without a suffix.
+fn split_suffix(name: &str) -> (&str, u32) {
+    let Some((base, digits)) = name.rsplit_once('.') else { return (name, 0) };
+    match digits.parse::<u32>() {
+        Ok(suffix) if suffix > 0 && format!("{suffix:03}") == digits => (base, suffix),
+        _ => (name, 0)
+    }
+}
+
+fn with_suffix(base: &str, suffix: u32) -> String { if suffix == 0 { base.to_owned() } else { format!("{base}.{suffix:03}") } }
+
+// the suffixes of one name used by the children of a node, with how many children use each
+#[derive(Default)]
+struct Suffixes {
+    used: BTreeMap<u32, usize>,
+    // no suffix below this is free, so bulk spawns do not scan the same suffixes again
+    lowest_free: u32
+}
+
+impl Suffixes {
+    fn add(&mut self, suffix: u32) { *self.used.entry(suffix).or_default() += 1; }
+
+    fn remove(&mut self, suffix: u32) {
+        let Entry::Occupied(mut entry) = self.used.entry(suffix) else { return };
+        *entry.get_mut() -= 1;
+        if *entry.get() == 0 {
+            entry.remove();
+            self.lowest_free = self.lowest_free.min(suffix);
+        }
+    }
+
+    fn lowest_free(&mut self) -> u32 {
+        while self.used.contains_key(&self.lowest_free) { self.lowest_free += 1; }
+        self.lowest_free
+    }
+}
+
+// the names of the children of one node by the part before their suffix, and the number of children they were built
+// from, so changes made without going through the world are noticed when children were added or removed
+#[derive(Default)]
+struct SiblingNames {
+    names: HashMap<String, Suffixes>,
+    children: usize
+}
+
+impl SiblingNames {
+    fn add(&mut self, name: Option<&str>) {
+        self.children += 1;
+        let Some((base, suffix)) = name.map(split_suffix) else { return };
+        self.names.entry(base.to_owned()).or_default().add(suffix);
+    }
+
+    fn remove(&mut self, name: Option<&str>) {
+        self.children = self.children.saturating_sub(1);
+        let Some((base, suffix)) = name.map(split_suffix) else { return };
+        if let Some(suffixes) = self.names.get_mut(base) { suffixes.remove(suffix); }
+    }
+}
+
+// while the policy of a world is not `Allow`, the names of the children of every node a name was checked under
+#[derive(Default)]
+pub(crate) struct NameIndex {
+    parents: HashMap<NodeId, SiblingNames>
+}
+
+impl NameIndex {
+    // the names of the children of the given node, built again if they were not kept or have fallen behind
+    fn siblings<C: ComponentDef<A>, A>(&mut self, parent: &Node<C, A>) -> &mut SiblingNames {
+        let id = parent.id().expect("names are only kept for nodes with ids");
+        let siblings = self.parents.entry(id).or_default();
+        if siblings.children != parent.children().len() {
+            *siblings = SiblingNames::default();
+            parent.children().iter().for_each(|child| siblings.add(child.name.as_deref()));
+        }
+        siblings
+    }
+
+    // the name a node should be given to be spawned under the given parent with the given policy
+    pub(crate) fn claim<C: ComponentDef<A>, A>(&mut self, parent: &Node<C, A>, name: Option<String>, policy: NameCollisionPolicy) -> Result<Option<String>, WorldError> {
+        let Some(name) = name else { return Ok(None) };
+        if policy == NameCollisionPolicy::Allow { return Ok(Some(name)) }
+
+        let (base, suffix) = split_suffix(&name);
+        let Some(suffixes) = self.siblings(parent).names.get_mut(base).filter(|suffixes| suffixes.used.contains_key(&suffix)) else { return Ok(Some(name)) };
+        match policy {
+            NameCollisionPolicy::Rename => Ok(Some(with_suffix(base, suffixes.lowest_free()))),
+            _ => Err(WorldError::NameTaken(parent.id().unwrap(), name))
+        }
+    }
+
+    // checks if a child of the given parent has the given name
+    pub(crate) fn is_taken<C: ComponentDef<A>, A>(&mut self, parent: &Node<C, A>, name: &str) -> bool {
+        let (base, suffix) = split_suffix(name);
+        self.siblings(parent).names.get(base).is_some_and(|suffixes| suffixes.used.contains_key(&suffix))
+    }
+
+    // records a child with the given name being added to the node with the given id or removed from it
+    pub(crate) fn added(&mut self, parent: Option<NodeId>, name: Option<&str>) {
+        if let Some(siblings) = parent.and_then(|parent| self.parents.get_mut(&parent)) { siblings.add(name); }
+    }
+
+    pub(crate) fn removed(&mut self, parent: Option<NodeId>, name: Option<&str>) {
+        if let Some(siblings) = parent.and_then(|parent| self.parents.get_mut(&parent)) { siblings.remove(name); }
+    }
+
+    // drops the names kept for the children of a node that is no longer in the world
+    pub(crate) fn forget(&mut self, id: NodeId) { self.parents.remove(&id); }
+
+    // drops every kept name, after the tree was changed in ways they could not follow
+    pub(crate) fn clear(&mut self) { self.parents.clear(); }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// What this world does when a node is spawned next to a sibling with the same name.
+    pub fn name_collision_policy(&self) -> NameCollisionPolicy { self.config.name_collision_policy }
+
+    /// Sets what this world does when a node is spawned with `spawn`, `try_spawn`, `insert_child_at` or a transaction
+    /// next to a sibling with the same name, IE `Rename` so every node can be found by its path, see
+    /// `NameCollisionPolicy` and `WorldConfig::name_collision_policy`.  Nodes already in the tree keep their names, and
+    /// nodes moved with `reparent` are never renamed.
+    ///
+    /// While the policy is not `Allow`, the world keeps the names of the children of every node a name was checked
+    /// under, so spawning many nodes with the same name stays fast.  Renaming a node directly, IE through `get_mut`, is
+    /// not seen until `refresh_ids` runs.
+    pub fn set_name_collision_policy(&mut self, policy: NameCollisionPolicy) {
+        if policy == NameCollisionPolicy::Allow { self.names.clear(); }
+        self.config.name_collision_policy = policy;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::WorldError;
+
+    use super::{split_suffix, NameCollisionPolicy};
+    use crate::test_support::*;
+
+    fn named(name: &str) -> Node { Node::builder().name(name).build() }
+
+    fn names(world: &World) -> Vec<&str> { world.root().children().iter().filter_map(|child| child.name.as_deref()).collect() }
+
+    #[test]
+    fn suffixes_are_parsed_exactly() {
+        assert_eq!(split_suffix("warrior"), ("warrior", 0));
+        assert_eq!(split_suffix("warrior.002"), ("warrior", 2));
+        assert_eq!(split_suffix("warrior.1000"), ("warrior", 1000));
+        assert_eq!(split_suffix("v1.2.010"), ("v1.2", 10));
+        assert_eq!(split_suffix("warrior.01"), ("warrior.01", 0));
+        assert_eq!(split_suffix("warrior.000"), ("warrior.000", 0));
+    }
+
+    #[test]
+    fn renames_with_the_smallest_free_suffix() {
+        let mut world = World::new();
+        world.set_name_collision_policy(NameCollisionPolicy::Rename);
+        let root = world.root_id();
+        let ids: Vec<_> = (0..4).map(|_| world.try_spawn(root, named("warrior")).unwrap()).collect();
+        assert_eq!(ids.iter().map(|spawned| spawned.name.as_deref().unwrap()).collect::<Vec<_>>(), ["warrior", "warrior.001", "warrior.002", "warrior.003"]);
+
+        // gaps are filled lowest first, including the bare name
+        assert!(world.despawn(ids[2].id));
+        assert!(world.despawn(ids[0].id));
+        assert_eq!(world.try_spawn(root, named("warrior")).unwrap().name.as_deref(), Some("warrior"));
+        assert_eq!(world.try_spawn(root, named("warrior")).unwrap().name.as_deref(), Some("warrior.002"));
+        assert_eq!(world.try_spawn(root, named("warrior")).unwrap().name.as_deref(), Some("warrior.004"));
+
+        // a suffixed name collides with its own suffix only
+        assert_eq!(world.try_spawn(root, named("warrior.001")).unwrap().name.as_deref(), Some("warrior.005"));
+        assert_eq!(world.try_spawn(root, named("warrior.009")).unwrap().name.as_deref(), Some("warrior.009"));
+        assert_eq!(world.try_spawn(root, named("mage")).unwrap().name.as_deref(), Some("mage"));
+
+        // every path finds exactly its own node
+        for child in world.root().children().iter() {
+            let found = world.find_by_path(child.name.as_deref().unwrap(), false).unwrap();
+            assert_eq!(found.id(), child.id());
+        }
+    }
+
+    #[test]
+    fn names_follow_moves_and_policy_changes() {
+        let mut world = World::new();
+        let root = world.root_id();
+        world.spawn(root, named("a"));
+        world.spawn(root, named("a"));
+        let other = world.spawn(root, named("other")).unwrap();
+        assert_eq!(names(&world), ["a", "a", "other"]);
+
+        // the duplicates from before are seen once the policy changes
+        world.set_name_collision_policy(NameCollisionPolicy::Reject);
+        let rejected = world.try_spawn(root, named("a")).err();
+        assert_eq!(rejected, Some(WorldError::NameTaken(root, "a".to_owned())));
+        assert!(world.spawn(root, named("a")).is_none());
+        assert_eq!(world.transaction(|tx| tx.spawn(root, named("other")).map(|_| ())), Err(WorldError::NameTaken(root, "other".to_owned())));
+        assert_eq!(world.transaction(|tx| { tx.spawn(root, named("b"))?; tx.spawn(root, named("b")).map(|_| ()) }), Err(WorldError::NameTaken(root, "b".to_owned())));
+
+        // moving a node away frees its name, and one of the duplicates still holds the other
+        let moved = world.spawn(other, named("a")).unwrap();
+        assert!(world.reparent(world.root().children()[0].id().unwrap(), other));
+        assert!(world.try_spawn(root, named("a")).is_err());
+        assert!(world.reparent(world.root().children()[0].id().unwrap(), other));
+        assert_eq!(world.try_spawn(root, named("a")).unwrap().name.as_deref(), Some("a"));
+        assert!(world.try_spawn(root, named("a")).is_err());
+
+        // under other, the moved nodes kept their names beside the one spawned there
+        world.set_name_collision_policy(NameCollisionPolicy::Rename);
+        assert_eq!(world.try_spawn(other, named("a")).unwrap().name.as_deref(), Some("a.001"));
+        assert!(world.despawn(moved));
+        assert_eq!(world.insert_child_at(root, 0, named("a")).map(|id| world.get(id).unwrap().name.clone().unwrap()).as_deref(), Some("a.001"));
+
+        world.set_name_collision_policy(NameCollisionPolicy::Allow);
+        world.spawn(root, named("a"));
+        assert_eq!(names(&world), ["a.001", "other", "a", "a"]);
+    }
+}
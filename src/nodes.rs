@@ -0,0 +1,3742 @@
+use std::{collections::{HashMap, HashSet, VecDeque}, fmt, iter::Skip, marker::PhantomData, sync::{atomic::{AtomicU64, Ordering}, Mutex, PoisonError}, time::Instant};
+
+use cgmath::{Quaternion, Vector3};
+use forte_engine::{math::transforms::Transform, render::{input::EngineInput, render_engine::RenderEngine}};
+use winit::dpi::PhysicalSize;
+
+use crate::{
+    assets::RemapReport,
+    callbacks::{self, CallbackPanic, PanicObserver, RemovalReason},
+    caps::VariantCaps,
+    commands::CommandQueue,
+    config::{ScratchCapacities, WorldConfig},
+    culling::{CullVolume, DrawCulling},
+    debug::DebugLines,
+    dimensions::{Dimensions, OverlapMode},
+    draw_data::FrameSpares,
+    easing::Easing,
+    errors::WorldError,
+    events::HierarchyEvent,
+    frames::FrameConversion,
+    ids::{IdAllocator, NodeId, NodeUuid},
+    inspector::Inspector,
+    names::{NameCollisionPolicy, NameIndex},
+    math::{z_angle, TransformExt, TransformMode},
+    paths::{NodePath, NodePathError},
+    queries::{AttachReport, KNearest},
+    reports::FrameCounters,
+    stats::{StatCounters, TreeStats},
+    tweens::{Tween, TweenHandle, TweenTarget},
+    validation::{ValidationKind, ValidationWarning}
+};
+
+#[cfg(feature = "serde")]
+use crate::{hot_reload::SceneWatch, scenes::{SceneError, SceneValue}, sources::SceneSource};
+
+/// The functions a component enum gives to the nodes holding it.  This is implemented for the `Component` enum
+/// generated by `define_world!`, see its documentation for what each of these does.
+pub trait ComponentDef<A>: Default + Sized {
+    /// The pass components are rendered into, `wgpu::RenderPass<'a>` unless another is given with PASS.
+    type Pass<'a> where Self: 'a, A: 'a;
+
+    /// The names of all variants, indexed by their variant index.
+    const VARIANT_NAMES: &'static [&'static str];
+
+    /// True if any variant overrides the default priority of 0.
+    const PRIORITIZED: bool;
+
+    /// True if any variant gives a sort key.
+    const SORTED: bool;
+
+    /// True for each variant, indexed by variant index, that gives a sort key.
+    const SORT_KEYED: &'static [bool];
+
+    /// True if any variant gives a LATE_UPDATE callback.
+    const LATE_UPDATES: bool;
+
+    /// True if any variant gives a FIXED_UPDATE callback.
+    const FIXED_UPDATES: bool;
+
+    /// True if any variant gives an INPUT callback.
+    const INPUTS: bool;
+
+    /// True if any variant gives a RESIZE callback.
+    const RESIZES: bool;
+
+    /// True if any variant gives a PREPARE callback.
+    const PREPARES: bool;
+
+    /// True for each variant, indexed by variant index, that gives a RENDER callback.
+    const RENDERED: &'static [bool];
+
+    /// The index of this components variant in the order they were defined, None for empty.
+    fn variant_index(&self) -> Option<usize>;
+
+    /// The render priority of this component, lower priorities are drawn first.
+    fn priority(&self) -> i32;
+
+    /// True if this component should be collected for shadow passes.
+    fn casts_shadow(&self) -> bool;
+
+    /// The draw sort key of this component, None if its variant does not give one.
+    fn sort_key(&self) -> Option<u64>;
+
+    /// Adds this components debug shapes to the given lines, returns false if this component has no debug shapes.
+    fn debug_draw(&self, transform: &Transform, out: &mut DebugLines) -> bool;
+
+    /// Passes this components fields to the given inspector, returns false if this component has nothing to inspect.
+    fn inspect(&mut self, ui: &mut dyn Inspector) -> bool;
+
+    /// Passes this components asset paths to the given remap function, returns how many paths were rewritten.
+    fn remap_assets(&mut self, remap: &dyn Fn(&str) -> Option<String>) -> usize;
+
+    /// The data of this component given by the SAVE codec of its variant, None if it is empty or has no codec.
+    #[cfg(feature = "serde")]
+    fn save_data(&self) -> Option<SceneValue>;
+
+    /// Creates a component of the variant with the given name from saved data with the LOAD codec of the variant.
+    #[cfg(feature = "serde")]
+    fn load_data(variant: &str, data: &SceneValue) -> Result<Self, SceneError>;
+
+    /// Calls the ADDED callback of the component of the given node.
+    fn added(node: &mut Node<Self, A>);
+
+    /// Calls the UPDATE callback of the component of the given node with the given app and seconds since the last
+    /// update.
+    fn update(node: &mut Node<Self, A>, app: &mut A, dt: f32);
+
+    /// Calls the START callback of the component of the given node.
+    fn start(node: &mut Node<Self, A>);
+
+    /// Calls the LATE_UPDATE callback of the component of the given node.
+    fn late_update(node: &mut Node<Self, A>);
+
+    /// Calls the FIXED_UPDATE callback of the component of the given node.
+    fn fixed_update(node: &mut Node<Self, A>);
+
+    /// Calls the INPUT callback of the component of the given node, returns true if it consumed the input.
+    fn input(node: &mut Node<Self, A>, input: &EngineInput) -> bool;
+
+    /// Calls the RESIZE callback of the component of the given node with the new size of the window.
+    fn resize(node: &mut Node<Self, A>, size: PhysicalSize<u32>);
+
+    /// Calls the PREPARE callback of the component of the given node with the render engine.
+    fn prepare(node: &mut Node<Self, A>, engine: &mut RenderEngine);
+
+    /// Calls the ON_ENABLE callback of the component of the given node.
+    fn on_enable(node: &mut Node<Self, A>);
+
+    /// Calls the ON_DISABLE callback of the component of the given node.
+    fn on_disable(node: &mut Node<Self, A>);
+
+    /// Calls the REMOVED callback of the component of the given node.
+    fn removed(node: &mut Node<Self, A>, reason: &RemovalReason);
+
+    /// Calls the RENDER callback of this component.
+    fn render<'a, 'b>(&'b self, pass: &mut Self::Pass<'a>, app: &'b A) where 'b: 'a;
+}
+
+/// The data type held by one variant of the component enum `C`, implemented for each DATA type by `define_world!`.
+/// This is what lets component data be looked up by type, so each variant needs a distinct DATA type.
+pub trait ComponentData<C>: Sized {
+    /// The data of the given component, None if it is another variant.
+    fn from_component(component: &C) -> Option<&Self>;
+
+    fn from_component_mut(component: &mut C) -> Option<&mut Self>;
+
+    /// The data of the given component, or the component back if it is another variant.
+    fn try_from_component(component: C) -> Result<Self, C>;
+
+    /// Wraps this data in its variant of the component enum.
+    fn into_component(self) -> C;
+}
+
+/// A node in the tree, holding a transform, a component and its children.  A node can hold a few more components beside
+/// its own with `add_component`, one of each variant, whose callbacks run after those of its own component.
+///
+/// The type parameters are the component enum and the app generated by `define_world!`, which also creates a `Node`
+/// type alias for them.
+///
+/// With the `serde` feature, nodes can be serialized with their children, leaving out the ids, tweens, poison and
+/// everything calculated by updates.  No added functions are fired when a node is deserialized.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "C: serde::Serialize", deserialize = "C: serde::Deserialize<'de> + Default")))]
+pub struct Node<C, A> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::math::TransformDef"))]
+    pub transform: Transform,
+    pub component: C,
+    #[cfg_attr(feature = "serde", serde(default))]
+    added_components: Vec<AddedComponent<C>>,
+    pub rel_min_dimensions: Dimensions,
+    pub transform_mode: TransformMode,
+    pub confine_to_parent: bool,
+    pub name: Option<String>,
+    pub enabled: bool,
+    pub metadata: HashMap<String, String>,
+    pub tags: Vec<String>,
+    pub uuid: Option<NodeUuid>,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) id: Option<NodeId>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    parent: Option<NodeId>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    poisoned: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    changes: Changes,
+    lifetime: Option<f32>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    tweens: Vec<Tween<Node<C, A>>>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "never_rendered"))]
+    last_rendered_frame: AtomicU64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    blend_override: Option<(Transform, f32)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    global_transform: Transform,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    previous_global_transform: Transform,
+    #[cfg_attr(feature = "serde", serde(skip, default = "still"))]
+    velocity: Vector3<f32>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "teleport_pending"))]
+    teleport_pending: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    teleported: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dimensions: Dimensions,
+    children: Vec<Node<C, A>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    app: PhantomData<fn() -> A>
+}
+
+// checks if any part of the transform moved by more than the epsilon, so an epsilon of 0 counts every change
+fn transform_moved(old: &Transform, new: &Transform, epsilon: f32) -> bool {
+    let moved = |old: f32, new: f32| old != new && (old - new).abs() > epsilon;
+    let (old_rot, new_rot) = (old.rotation, new.rotation);
+    moved(old.position.x, new.position.x) || moved(old.position.y, new.position.y) || moved(old.position.z, new.position.z)
+        || moved(old_rot.s, new_rot.s) || moved(old_rot.v.x, new_rot.v.x) || moved(old_rot.v.y, new_rot.v.y) || moved(old_rot.v.z, new_rot.v.z)
+        || moved(old.scale.x, new.scale.x) || moved(old.scale.y, new.scale.y) || moved(old.scale.z, new.scale.z)
+}
+
+// the frame stored for nodes that have never been rendered
+fn never_rendered() -> AtomicU64 { AtomicU64::new(u64::MAX) }
+
+// the velocity of nodes that have not been updated yet
+fn still() -> Vector3<f32> { Vector3::new(0.0, 0.0, 0.0) }
+
+// new nodes start out teleported, so their first update has no history to move from
+#[cfg(feature = "serde")]
+fn teleport_pending() -> bool { true }
+
+impl<C: Default, A> Default for Node<C, A> {
+    fn default() -> Self {
+        Self {
+            transform: Transform::default(),
+            global_transform: Transform::default(),
+            rel_min_dimensions: Dimensions::default(),
+            transform_mode: TransformMode::default(),
+            confine_to_parent: false,
+            name: None,
+            enabled: true,
+            metadata: HashMap::new(),
+            tags: Vec::new(),
+            uuid: None,
+            id: None,
+            parent: None,
+            poisoned: false,
+            changes: Changes::default(),
+            lifetime: None,
+            tweens: Vec::new(),
+            last_rendered_frame: never_rendered(),
+            blend_override: None,
+            previous_global_transform: Transform::default(),
+            velocity: still(),
+            teleport_pending: true,
+            teleported: false,
+            dimensions: Dimensions::default(),
+            component: C::default(),
+            added_components: Vec::new(),
+            children: Vec::new(),
+            app: PhantomData
+        }
+    }
+}
+
+impl<C: fmt::Debug, A> fmt::Debug for Node<C, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Node")
+            .field("transform", &self.transform)
+            .field("component", &self.component)
+            .field("added_components", &self.added_components)
+            .field("rel_min_dimensions", &self.rel_min_dimensions)
+            .field("transform_mode", &self.transform_mode)
+            .field("confine_to_parent", &self.confine_to_parent)
+            .field("name", &self.name)
+            .field("enabled", &self.enabled)
+            .field("metadata", &self.metadata)
+            .field("tags", &self.tags)
+            .field("uuid", &self.uuid)
+            .field("id", &self.id)
+            .field("poisoned", &self.poisoned)
+            .field("changes", &self.changes)
+            .field("lifetime", &self.lifetime)
+            .field("tweens", &self.tweens)
+            .field("last_rendered_frame", &self.last_rendered_frame)
+            .field("blend_override", &self.blend_override)
+            .field("global_transform", &self.global_transform)
+            .field("previous_global_transform", &self.previous_global_transform)
+            .field("velocity", &self.velocity)
+            .field("teleport_pending", &self.teleport_pending)
+            .field("teleported", &self.teleported)
+            .field("dimensions", &self.dimensions)
+            .field("children", &self.children)
+            .finish()
+    }
+}
+
+/// Everything of a node other than its component, given to callbacks along with the data of the component so both can
+/// be changed at once, see `Node::split_component`.
+pub struct NodeContext<'n, C, A> {
+    pub transform: &'n mut Transform,
+    pub rel_min_dimensions: &'n mut Dimensions,
+    pub transform_mode: &'n mut TransformMode,
+    pub confine_to_parent: &'n mut bool,
+    pub name: &'n mut Option<String>,
+    pub enabled: &'n mut bool,
+    pub metadata: &'n mut HashMap<String, String>,
+    pub tags: &'n mut Vec<String>,
+    pub uuid: &'n mut Option<NodeUuid>,
+    id: Option<NodeId>,
+    parent: Option<NodeId>,
+    lifetime: &'n mut Option<f32>,
+    tweens: &'n mut Vec<Tween<Node<C, A>>>,
+    teleport_pending: &'n mut bool,
+    global_transform: &'n Transform,
+    dimensions: &'n Dimensions,
+    children: &'n mut Vec<Node<C, A>>
+}
+
+impl<C: ComponentDef<A>, A> NodeContext<'_, C, A> {
+    /// The id of the node in the `World` it belongs to, None if it has not been given one.
+    pub fn id(&self) -> Option<NodeId> { self.id }
+
+    /// The id of the parent of the node, see `Node::parent_id`.
+    pub fn parent_id(&self) -> Option<NodeId> { self.parent }
+
+    /// The global transform of the node as of its last update.
+    pub fn global_transform(&self) -> &Transform { self.global_transform }
+
+    /// The dimensions of the node as of its last update.
+    pub fn dimensions(&self) -> &Dimensions { self.dimensions }
+
+    pub fn children(&self) -> &Vec<Node<C, A>> { self.children }
+
+    // the children of the node, for the functions of this crate that keep the shape of the tree
+    pub(crate) fn children_mut(&mut self) -> &mut Vec<Node<C, A>> { self.children }
+
+    /// Adds a child to the end of the children of the node, see `Node::add_child`.
+    pub fn add_child(&mut self, child: Node<C, A>) {
+        self.children.push(child);
+        self.children.last_mut().unwrap().call_add_recr();
+    }
+
+    /// Moves the node to the given local transform without any motion in between, see `Node::teleport`.
+    pub fn teleport(&mut self, new_local: Transform) {
+        *self.transform = new_local;
+        *self.teleport_pending = true;
+    }
+
+    /// Despawns the node once the given number of seconds have passed, see `Node::despawn_after`.
+    pub fn despawn_after(&mut self, seconds: f32) { *self.lifetime = Some(seconds); }
+
+    /// The seconds left until the node is despawned, None if it is not set to despawn.
+    pub fn lifetime(&self) -> Option<f32> { *self.lifetime }
+
+    /// Starts animating the node towards the given target, see `Node::tween`.
+    pub fn tween(&mut self, target: TweenTarget<Node<C, A>>, duration: f32, easing: Easing) -> TweenHandle {
+        let tween = Tween::new(target, duration, easing);
+        let handle = tween.handle();
+        self.tweens.push(tween);
+        handle
+    }
+}
+
+impl<C: ComponentDef<A>, A> Node<C, A> {
+    /// The global transform of this node as of the last update.
+    pub fn global_transform(&self) -> &Transform { &self.global_transform }
+    pub fn global_rotation(&self) -> Quaternion<f32> { self.global_transform.rotation }
+    pub fn global_scale(&self) -> Vector3<f32> { self.global_transform.scale }
+
+    /// The unit directions this node faces as of the last update, see `TransformExt` for the convention.
+    pub fn forward(&self) -> Vector3<f32> { self.global_transform.forward() }
+    pub fn right(&self) -> Vector3<f32> { self.global_transform.right() }
+    pub fn up(&self) -> Vector3<f32> { self.global_transform.up() }
+
+    /// The global transform of this node before its last update, the same as `global_transform` if it was teleported
+    /// in that update.
+    pub fn previous_global_transform(&self) -> &Transform { &self.previous_global_transform }
+
+    /// How far this nodes global position moved per second in its last update, zero if it was teleported.
+    pub fn velocity(&self) -> Vector3<f32> { self.velocity }
+
+    /// The global transform of this node the given fraction of the way from before its last update to after it, IE
+    /// with `World::interpolation_alpha` to draw between fixed steps.  A node teleported in its last update is always
+    /// at its new pose, as if the alpha were 1.
+    pub fn interpolated_transform(&self, alpha: f32) -> Transform {
+        if self.teleported { return self.global_transform }
+        Transform::lerp(&self.previous_global_transform, &self.global_transform, alpha)
+    }
+
+    /// Moves this node to the given local transform without any motion in between, so the next update gives it and its
+    /// whole subtree no history to move from, no velocity and no interpolation.  Setting `transform` directly moves the
+    /// node as usual.
+    pub fn teleport(&mut self, new_local: Transform) {
+        self.transform = new_local;
+        self.teleport_pending = true;
+    }
+
+    /// True if this node or one of its ancestors was teleported in the last update, see `teleport`.  This clears by
+    /// itself with the update after.  New nodes count as teleported in their first update, which has nothing to move
+    /// from.
+    pub fn teleported_this_frame(&self) -> bool { self.teleported }
+
+    pub fn rel_min_dimensions(&self) -> &Dimensions { &self.rel_min_dimensions }
+
+    /// The dimensions of this node merged with all of its children as of the last update.
+    pub fn dimensions(&self) -> &Dimensions { &self.dimensions }
+    pub fn children(&self) -> &Vec<Node<C, A>> { &self.children }
+
+    /// The id of this node in the `World` it belongs to, None if it has not been given one.
+    pub fn id(&self) -> Option<NodeId> { self.id }
+
+    /// The id of the parent of this node in the `World` it belongs to, kept by the world as nodes are spawned, moved
+    /// and taken out.  None for the root of the world and for nodes that have not been given an id.
+    ///
+    /// A node cannot borrow the nodes above it, so walking up the tree goes through the world, see `parent`,
+    /// `ancestors` and `root`.  Components in callbacks, which have the world borrowed, can hold on to this id and
+    /// look the parent up from outside the update, or target it with `World::queue_command`.
+    pub fn parent_id(&self) -> Option<NodeId> { self.parent }
+
+    /// The parent of this node in the given world, see `World::parent`.
+    ///
+    /// Returns None if this node is the root or does not belong to the world.
+    pub fn parent<'w>(&self, world: &'w World<C, A>) -> Option<&'w Node<C, A>> { world.parent(self.id?) }
+
+    /// Iterates over the ancestors of this node in the given world, from its parent up to the root of the world, see
+    /// `World::ancestors`.  This is empty if this node is the root or does not belong to the world.
+    pub fn ancestors<'w>(&self, world: &'w World<C, A>) -> impl Iterator<Item = &'w Node<C, A>> + 'w {
+        self.id.into_iter().flat_map(move |id| world.ancestors(id))
+    }
+
+    /// The root of the given world if this node belongs to it, IE the last of its `ancestors` or this node itself.
+    pub fn root<'w>(&self, world: &'w World<C, A>) -> Option<&'w Node<C, A>> {
+        world.locate(self.id?).map(|_| world.root())
+    }
+
+    /// The data of the component of this node of the given type, IE `node.get::<CubeModel>()`, looking at its own
+    /// component and then the components added with `add_component`.
+    pub fn get<T: ComponentData<C>>(&self) -> Option<&T> {
+        T::from_component(&self.component).or_else(|| self.added_components.iter().find_map(|added| T::from_component(&added.component)))
+    }
+
+    /// The data of the component of this node of the given type like `get`, marking the component as changed if there
+    /// is one.
+    pub fn get_mut<T: ComponentData<C>>(&mut self) -> Option<&mut T> {
+        let Node { component, added_components, changes, .. } = self;
+        let data = T::from_component_mut(component).or_else(|| added_components.iter_mut().find_map(|added| T::from_component_mut(&mut added.component)))?;
+        changes.component_pending = true;
+        Some(data)
+    }
+
+    /// Splits this node into the data of its component of the given type, see `get`, and everything else, so both can
+    /// be changed at once.  Unlike `get_mut` this does not mark the component as changed.
+    pub fn split_component<T: ComponentData<C>>(&mut self) -> Option<(&mut T, NodeContext<'_, C, A>)> {
+        let Node {
+            transform, component, added_components, rel_min_dimensions, transform_mode, confine_to_parent, name, enabled,
+            metadata, tags, uuid, id, parent, lifetime, tweens, teleport_pending, global_transform, dimensions, children, ..
+        } = self;
+        let data = T::from_component_mut(component).or_else(|| added_components.iter_mut().find_map(|added| T::from_component_mut(&mut added.component)))?;
+        Some((data, NodeContext {
+            transform, rel_min_dimensions, transform_mode, confine_to_parent, name, enabled, metadata, tags, uuid,
+            id: *id, parent: *parent, lifetime, tweens, teleport_pending, global_transform, dimensions, children
+        }))
+    }
+
+    /// The component of this node for mutation, marking it changed for the next update, see `is_component_changed`.
+    pub fn component_mut(&mut self) -> &mut C {
+        self.changes.component_pending = true;
+        &mut self.component
+    }
+
+    /// Every component of this node, its own component first unless it is empty, then the components added with
+    /// `add_component` in the order they were added.
+    pub fn components(&self) -> impl Iterator<Item = &C> {
+        std::iter::once(&self.component).filter(|component| component.variant_index().is_some())
+            .chain(self.added_components.iter().map(|added| &added.component))
+    }
+
+    // checks if any component of this node is of the given variant
+    pub(crate) fn holds_variant(&self, variant: usize) -> bool { self.components().any(|component| component.variant_index() == Some(variant)) }
+
+    /// Gives this node a component holding the given data, replacing its component of the same type if it has one,
+    /// which fires the remove function of the old one with `Replaced`, then fires the add function of the new one.  A
+    /// node with an empty component is given the new one as its own component, otherwise it is added beside it.  Every
+    /// component of a node is rendered, but its draw priority, sort key and shadow come from its own component alone.
+    ///
+    /// The components of a node are moved around while their callbacks run, so the callbacks of a node should not add
+    /// or remove its components, they can queue a `WorldCommand` instead.
+    ///
+    /// Returns the data of the replaced component, if any.
+    pub fn add_component<T: ComponentData<C>>(&mut self, data: T) -> Option<T> {
+        let component = data.into_component();
+        let variant = component.variant_index();
+        if self.component.variant_index().is_none_or(|own| Some(own) == variant) {
+            return T::try_from_component(self.set_component(component)).ok()
+        }
+
+        let replaced = self.added_components.iter().position(|added| added.component.variant_index() == variant);
+        let idx = match replaced {
+            Some(idx) => {
+                self.with_added(idx, |node| node.call_remove_single(RemovalReason::Replaced));
+                idx
+            },
+            None => {
+                self.added_components.push(AddedComponent { component: C::default(), started: false });
+                self.added_components.len() - 1
+            }
+        };
+        let old = std::mem::replace(&mut self.added_components[idx], AddedComponent { component, started: false });
+        self.changes.component_pending = true;
+        self.with_added(idx, Self::call_add_single);
+        replaced.and_then(|_| T::try_from_component(old.component).ok())
+    }
+
+    /// Removes the component of the given type from this node, firing its remove function with `Replaced`.  If it was
+    /// the own component of this node, the first added component takes its place, and otherwise it is left empty.
+    ///
+    /// Returns the data of the removed component, None if this node has no component of the given type.
+    pub fn remove_component<T: ComponentData<C>>(&mut self) -> Option<T> {
+        if T::from_component(&self.component).is_some() {
+            self.call_remove_single(RemovalReason::Replaced);
+            let next = match self.added_components.is_empty() {
+                true => AddedComponent { component: C::default(), started: false },
+                false => self.added_components.remove(0)
+            };
+            let old = std::mem::replace(&mut self.component, next.component);
+            self.changes.start_pending = !next.started;
+            self.changes.component_pending = true;
+            return T::try_from_component(old).ok()
+        }
+
+        let idx = self.added_components.iter().position(|added| T::from_component(&added.component).is_some())?;
+        self.with_added(idx, |node| node.call_remove_single(RemovalReason::Replaced));
+        self.changes.component_pending = true;
+        T::try_from_component(self.added_components.remove(idx).component).ok()
+    }
+
+    /// True if the global transform of this node changed in its last update, including from its parent moving, or if
+    /// that was its first update.
+    pub fn is_transform_changed(&self) -> bool { self.changes.transform }
+
+    /// True if the component of this node was changed before its last update through `set_component`,
+    /// `clear_component`, `attach_where`, `component_mut` or `get_mut`, or if that was its first update.  Assigning the
+    /// `component` field directly is not seen.
+    pub fn is_component_changed(&self) -> bool { self.changes.component }
+
+    // checks if an update of this node or one of its descendants saw an edit since they were last saved
+    #[cfg(feature = "serde")]
+    pub(crate) fn is_edited(&self) -> bool { self.iter().any(|node| node.changes.edited) }
+
+    // forgets the edits seen by updates of this node and its descendants, once they have been saved
+    #[cfg(feature = "serde")]
+    pub(crate) fn clear_edits(&mut self) {
+        self.changes.edited = false;
+        self.for_each_descendant_mut(|node| node.changes.edited = false);
+    }
+
+    /// The persistent uuid of this node, giving it a new random one if it does not have one yet.
+    pub fn ensure_uuid(&mut self) -> NodeUuid { *self.uuid.get_or_insert_with(NodeUuid::new_v4) }
+
+    /// Gives every node in this tree without a uuid a new one, IE before the tree is saved.
+    pub fn assign_uuids(&mut self) {
+        self.ensure_uuid();
+        self.for_each_descendant_mut(|node| { node.ensure_uuid(); });
+    }
+
+    /// Finds the node in this tree, including this one, with the given uuid.
+    pub fn find_by_uuid(&self, uuid: NodeUuid) -> Option<&Node<C, A>> { self.iter().find(|node| node.uuid == Some(uuid)) }
+
+    /// The metadata value of this node under the given key, IE an editor note or gameplay flag.
+    pub fn meta(&self, key: &str) -> Option<&str> { self.metadata.get(key).map(String::as_str) }
+
+    /// Sets the metadata value of this node under the given key.
+    ///
+    /// Returns the value it replaced, if any.
+    pub fn set_meta(&mut self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        self.metadata.insert(key.into(), value.into())
+    }
+
+    /// True if this node has the given tag.
+    pub fn has_tag(&self, tag: &str) -> bool { self.tags.iter().any(|own| own == tag) }
+
+    /// Adds the given tag to this node, returns false if it already had it.
+    pub fn add_tag(&mut self, tag: impl Into<String>) -> bool {
+        let tag = tag.into();
+        if self.has_tag(&tag) { return false }
+        self.tags.push(tag);
+        true
+    }
+
+    /// Removes the given tag from this node, returns false if it did not have it.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let count = self.tags.len();
+        self.tags.retain(|own| own != tag);
+        self.tags.len() != count
+    }
+
+    /// Finds every node in this tree, including this one, with the given tag in pre-order.
+    pub fn find_all_with_tag(&self, tag: &str) -> Vec<&Node<C, A>> { self.iter().filter(|node| node.has_tag(tag)).collect() }
+
+    /// The dimensions of this node alone, without its children, as of the last update.
+    pub fn own_dimensions(&self) -> Dimensions {
+        let position = self.global_transform.position;
+        let mut dimensions = Dimensions { from: position + self.rel_min_dimensions.from, to: position + self.rel_min_dimensions.to };
+
+        // two dimensional nodes are flat rects on their layer, as in `update`
+        if self.transform_mode == TransformMode::TwoD {
+            dimensions.from.z = position.z;
+            dimensions.to.z = position.z;
+        }
+        dimensions
+    }
+
+    /// Passes this nodes transform, name, enabled flag, tags and `confine_to_parent` to the given inspector.  An empty
+    /// name clears the name, the tags are given as one comma separated string, and a change to the enabled flag is made
+    /// with `set_enabled` so the enable and disable functions fire.
+    pub fn inspect_common(&mut self, ui: &mut dyn Inspector) {
+        ui.field_vec3("position", &mut self.transform.position);
+        ui.field_quat("rotation", &mut self.transform.rotation);
+        ui.field_vec3("scale", &mut self.transform.scale);
+
+        let mut name = self.name.clone().unwrap_or_default();
+        ui.field_string("name", &mut name);
+        self.name = (!name.is_empty()).then_some(name);
+
+        let mut enabled = self.enabled;
+        ui.field_bool("enabled", &mut enabled);
+        if enabled != self.enabled { self.set_enabled(enabled); }
+
+        let mut tags = self.tags.join(",");
+        ui.field_string("tags", &mut tags);
+        self.tags = tags.split(',').map(str::trim).filter(|tag| !tag.is_empty()).map(str::to_string).collect();
+
+        ui.field_bool("confine_to_parent", &mut self.confine_to_parent);
+    }
+
+    /// Adds a child to the end of this nodes children, firing the added functions of it and all of its children.
+    pub fn add_child(&mut self, child: Node<C, A>) {
+        self.children.push(child);
+        self.children.last_mut().as_mut().unwrap().call_add_recr();
+    }
+
+    /// Inserts a child at the given index, shifting the children after it along, and fires the added functions of it
+    /// and all of its children.
+    ///
+    /// Panics if the index is greater than the number of children.
+    pub fn insert_child_at(&mut self, idx: usize, child: Node<C, A>) {
+        self.children.insert(idx, child);
+        self.children[idx].call_add_recr();
+    }
+
+    /// Moves the child at the given index to the other index, shifting the children between them.  No callbacks fire.
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn move_child(&mut self, from: usize, to: usize) {
+        let child = self.children.remove(from);
+        self.children.insert(to, child);
+    }
+
+    /// Sorts the children of this node by the given key, keeping the order of children with equal keys.  As children
+    /// are drawn in tree order within each priority, this also reorders how they are drawn.
+    pub fn sort_children_by_key<K: Ord>(&mut self, key: impl FnMut(&Node<C, A>) -> K) { self.children.sort_by_key(key); }
+
+    /// Adds a child without firing any added functions, used by `scene!` which fires them all once the tree is attached.
+    #[doc(hidden)]
+    pub fn push_detached_child(&mut self, child: Node<C, A>) { self.children.push(child); }
+
+    /// Removes the child at the given index, firing the remove functions of it and all of its children.
+    pub fn remove_child(&mut self, idx: usize) {
+        self.children[idx].call_remove_recr(RemovalReason::Despawned);
+        self.children.remove(idx);
+    }
+
+    /// Detaches the child at the given index without firing any remove functions, so it can be added somewhere else or
+    /// kept for later.  Adding it again with `add_child` fires its added functions again.
+    ///
+    /// Returns the detached child.
+    pub fn take_child(&mut self, idx: usize) -> Node<C, A> { self.children.remove(idx) }
+
+    /// Removes all children at once, their remove functions are given `WorldCleared`.
+    pub fn clear_children(&mut self) {
+        self.children.iter_mut().for_each(|child| child.call_remove_recr(RemovalReason::WorldCleared));
+        self.children.clear();
+    }
+
+    /// Replaces the component of this node, firing the remove function of the old one with `Replaced` and the add
+    /// function of the new one.  Setting the `component` field directly fires neither.  The components added with
+    /// `add_component` are kept.
+    ///
+    /// Returns the old component.
+    pub fn set_component(&mut self, component: C) -> C {
+        self.call_remove_single(RemovalReason::Replaced);
+        let old = std::mem::replace(&mut self.component, component);
+        self.changes.component_pending = true;
+        self.changes.start_pending = true;
+        self.call_add_single();
+        old
+    }
+
+    /// Empties the component of this node, firing the remove function of the old one with `Replaced`.
+    ///
+    /// Returns the old component.
+    pub fn clear_component(&mut self) -> C { self.set_component(C::default()) }
+
+    /// Removes every descendant that is in the region by the given mode and passes the filter, firing their remove
+    /// functions.
+    ///
+    /// Returns the number of nodes removed, including the children of the removed nodes.
+    pub fn despawn_in_region(&mut self, region: &Dimensions, mode: OverlapMode, filter: impl Fn(&Node<C, A>) -> bool) -> usize {
+        self.despawn_in_region_recr(region, mode, &filter)
+    }
+
+    fn despawn_in_region_recr(&mut self, region: &Dimensions, mode: OverlapMode, filter: &impl Fn(&Node<C, A>) -> bool) -> usize {
+        let mut removed = 0;
+        let mut idx = 0;
+        while idx < self.children.len() {
+            let child = &self.children[idx];
+            match child.in_region(region, mode) {
+                None => idx += 1,
+                Some(true) if filter(child) => {
+                    removed += child.count_nodes();
+                    self.remove_child(idx);
+                },
+                Some(_) => {
+                    removed += self.children[idx].despawn_in_region_recr(region, mode, filter);
+                    idx += 1;
+                }
+            }
+        }
+        removed
+    }
+
+    // true if this node is in the region by the given mode, or None if nothing under it can be in the region either
+    fn in_region(&self, region: &Dimensions, mode: OverlapMode) -> Option<bool> {
+        if !region.overlap(&self.dimensions) && !region.contains(&self.dimensions) { return None }
+
+        Some(match mode {
+            OverlapMode::Contained => region.contains(&self.dimensions),
+            OverlapMode::Touching => {
+                let own = self.own_dimensions();
+                region.overlap(&own) || region.contains(&own)
+            }
+        })
+    }
+
+    // finds the paths to the descendants `despawn_in_region` would remove, without going below the removed nodes
+    fn region_paths_recr(&self, region: &Dimensions, mode: OverlapMode, filter: &impl Fn(&Node<C, A>) -> bool, path: &mut NodePath, out: &mut Vec<NodePath>) {
+        for (idx, child) in self.children.iter().take(u16::MAX as usize + 1).enumerate() {
+            let Some(picked) = child.in_region(region, mode) else { continue };
+            path.push(idx as u16);
+            if picked && filter(child) { out.push(path.clone()); } else { child.region_paths_recr(region, mode, filter, path, out); }
+            path.pop();
+        }
+    }
+
+    /// The number of nodes in this tree, including this one.
+    pub fn count_nodes(&self) -> usize { 1 + self.children.iter().map(|child| child.count_nodes()).sum::<usize>() }
+
+    /// Calls the given function on this node and its descendants depth first, in pre-order, with what it returns
+    /// deciding if the children of each node are visited and if the visit should stop.  The function is called before
+    /// the children are visited, so it may add or remove children of the node it is given.
+    ///
+    /// Returns true if the visit was stopped early.
+    pub fn visit(&mut self, mut f: impl FnMut(&mut Node<C, A>) -> VisitControl) -> bool { self.visit_recr(&mut f) }
+
+    fn visit_recr(&mut self, f: &mut impl FnMut(&mut Node<C, A>) -> VisitControl) -> bool {
+        match f(self) {
+            VisitControl::Continue => self.children.iter_mut().any(|child| child.visit_recr(f)),
+            VisitControl::SkipChildren => false,
+            VisitControl::Stop => true
+        }
+    }
+
+    // the number of levels below this node of its deepest descendant, 0 without children
+    pub(crate) fn height(&self) -> usize { self.children.iter().map(|child| child.height() + 1).max().unwrap_or(0) }
+
+    /// Counts the nodes, depth, components and children capacity of this tree.  This walks the whole tree, for just the
+    /// number of nodes in a world `World::id_count` is O(1).
+    pub fn stats(&self) -> TreeStats {
+        let mut stats = TreeStats::new(C::VARIANT_NAMES);
+        self.stats_recr(0, &mut stats);
+        stats
+    }
+
+    fn stats_recr(&self, depth: usize, stats: &mut TreeStats) {
+        stats.nodes += 1;
+        stats.max_depth = stats.max_depth.max(depth);
+        stats.children_capacity += self.children.capacity();
+        match self.component.variant_index() {
+            Some(variant) => stats.components[variant].1 += 1,
+            None => stats.empty += 1
+        }
+
+        self.children.iter().for_each(|child| child.stats_recr(depth + 1, stats));
+    }
+
+    /// Calls the given function on every descendant of this node, not including this one, in pre-order.  Each node is
+    /// given before its children, so the function may change its children.
+    pub fn for_each_descendant_mut(&mut self, mut f: impl FnMut(&mut Node<C, A>)) { self.for_each_descendant_mut_recr(&mut f); }
+
+    fn for_each_descendant_mut_recr(&mut self, f: &mut impl FnMut(&mut Node<C, A>)) {
+        self.children.iter_mut().for_each(|child| {
+            f(child);
+            child.for_each_descendant_mut_recr(f);
+        });
+    }
+
+    /// Moves this node and everything under it by the given delta.  As child positions are relative to their parents,
+    /// only the local position of this node changes.
+    pub fn translate_subtree(&mut self, delta: Vector3<f32>) { self.transform.position += delta; }
+
+    /// Sets the enabled flag of this node, see `enabled`, firing the enable or disable functions of this node and of its
+    /// descendants that are toggled with it, IE those not under another disabled node.  Nothing fires if the flag does
+    /// not change, and setting the `enabled` field directly fires nothing.
+    ///
+    /// This node is expected to be under enabled nodes, see `World::set_enabled` for nodes that may not be.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if self.enabled == enabled { return }
+        self.enabled = enabled;
+        self.call_toggle_recr(enabled);
+    }
+
+    /// Sets the enabled flag of this node and all of its descendants, see `enabled`, firing the enable or disable
+    /// functions of every node whose flag, or the flag of whose ancestors, changes whether it is updated.
+    pub fn set_enabled_recursive(&mut self, enabled: bool) { self.set_enabled_recursive_recr(enabled, true); }
+
+    // sets the enabled flag of this node and its descendants, parent active is whether the parent was updated before
+    fn set_enabled_recursive_recr(&mut self, enabled: bool, parent_active: bool) {
+        let active = parent_active && self.enabled;
+        self.enabled = enabled;
+        if active != enabled { self.call_toggle(enabled); }
+        self.children.iter_mut().for_each(|child| child.set_enabled_recursive_recr(enabled, active));
+    }
+
+    // calls the enable or disable functions of this node and its enabled descendants
+    fn call_toggle_recr(&mut self, enabled: bool) {
+        self.call_toggle(enabled);
+        self.children.iter_mut().filter(|child| child.enabled).for_each(|child| child.call_toggle_recr(enabled));
+    }
+
+    // calls the enable or disable functions of this nodes components only
+    fn call_toggle(&mut self, enabled: bool) {
+        if enabled { self.run_components(C::on_enable); } else { self.run_components(C::on_disable); }
+    }
+
+    /// Iterates over this node and all of its descendants depth first, in pre-order.
+    pub fn iter(&self) -> Iter<'_, C, A> { Iter::new(self, false) }
+
+    /// Iterates over this node and all of its descendants breadth first, level by level.
+    pub fn iter_bfs(&self) -> Iter<'_, C, A> { Iter::new(self, true) }
+
+    /// Iterates mutably over this node and all of its descendants depth first, in pre-order.  As a node contains its
+    /// children, each node is given as a `NodeMut` that can change everything but its children.
+    pub fn iter_mut(&mut self) -> IterMut<'_, C, A> { IterMut::new(self, false) }
+
+    /// Iterates mutably over this node and all of its descendants breadth first, see `iter_mut`.
+    pub fn iter_mut_bfs(&mut self) -> IterMut<'_, C, A> { IterMut::new(self, true) }
+
+    /// Updates the global transforms and dimensions of this node and its children, calling their update functions,
+    /// and then their late update functions once every node has been updated.  Components that were added since the
+    /// last update have their start function called before their first update.  Disabled nodes and their children are
+    /// skipped, keeping the transforms and dimensions of their last update, and are left out of the dimensions of their
+    /// parents.
+    ///
+    /// Arguments:
+    /// * previous: &Transform - The global transform of this nodes parent, or the default transform for a root.
+    /// * app: &mut A - The app handed to the update functions.
+    /// * dt: f32 - The seconds since the last update, handed to the update functions.
+    pub fn update(&mut self, previous: &Transform, app: &mut A, dt: f32) { self.update_with(previous, app, dt, &WorldConfig::default()); }
+
+    // updates like `update`, tracking changes as the given config says, returns the number of nodes updated
+    pub(crate) fn update_with(&mut self, previous: &Transform, app: &mut A, dt: f32, config: &WorldConfig) -> usize {
+        if !self.enabled { return 0 }
+        let updated = self.update_recr(previous, None, app, dt, config);
+        if C::LATE_UPDATES { self.late_update_recr(); }
+        updated
+    }
+
+    // calls the late update functions of this node and its enabled children, children first like updates
+    fn late_update_recr(&mut self) {
+        self.children.iter_mut().filter(|child| child.enabled).for_each(|child| child.late_update_recr());
+        self.run_components(C::late_update);
+    }
+
+    /// Passes the given input to the input functions of this node and its children top down, IE parents before their
+    /// children and children in order, until one consumes it.  Disabled nodes and their children are skipped.
+    ///
+    /// Returns true if the input was consumed.
+    pub fn input(&mut self, input: &EngineInput) -> bool {
+        if !C::INPUTS || !self.enabled { return false }
+
+        let mut consumed = false;
+        self.run_components(|node| if !consumed { consumed = C::input(node, input) });
+        if consumed { return true }
+        self.children.iter_mut().any(|child| child.input(input))
+    }
+
+    /// Passes the new size of the window to the resize functions of this node and all of its children, including
+    /// disabled ones so they are ready when they are enabled again.
+    pub fn resize(&mut self, size: PhysicalSize<u32>) {
+        if !C::RESIZES { return }
+        self.run_components(|node| C::resize(node, size));
+        self.children.iter_mut().for_each(|child| child.resize(size));
+    }
+
+    /// Passes the render engine to the prepare functions of this node and its children top down, so they can upload
+    /// buffers or update bind groups before the render pass begins.  Disabled nodes and their children are skipped, as
+    /// they are not drawn.
+    pub fn prepare(&mut self, engine: &mut RenderEngine) {
+        if !C::PREPARES || !self.enabled { return }
+        self.run_components(|node| C::prepare(node, engine));
+        self.children.iter_mut().for_each(|child| child.prepare(engine));
+    }
+
+    // calls the fixed update functions of this node and its enabled children, children first like updates
+    fn fixed_update_recr(&mut self) {
+        self.children.iter_mut().filter(|child| child.enabled).for_each(|child| child.fixed_update_recr());
+        self.run_components(C::fixed_update);
+    }
+
+    // updates this node and its children, parent angle is the parents global z angle if the parent is two dimensional,
+    // returns the number of nodes updated
+    fn update_recr(&mut self, previous: &Transform, parent_angle: Option<f32>, app: &mut A, dt: f32, config: &WorldConfig) -> usize {
+        // blend the local transform towards the override if one is set
+        let local = match &self.blend_override {
+            Some((target, weight)) => Transform::lerp(&self.transform, target, *weight),
+            None => self.transform
+        };
+
+        // calculate new global transform, two dimensional nodes under two dimensional parents skip the quaternion multiply
+        let (global_transform, global_angle) = match (self.transform_mode, parent_angle) {
+            (TransformMode::TwoD, Some(parent_angle)) => {
+                let angle = parent_angle + z_angle(local.rotation);
+                let transform = Transform {
+                    position: local.position + previous.position,
+                    rotation: cgmath::Rotation3::from_angle_z(cgmath::Rad(angle)),
+                    scale: Vector3 {
+                        x: local.scale.x * previous.scale.x,
+                        y: local.scale.y * previous.scale.y,
+                        z: previous.scale.z
+                    }
+                };
+                (transform, Some(angle))
+            },
+            (mode, _) => {
+                let transform = Transform {
+                    position: local.position + previous.position,
+                    rotation: previous.rotation * local.rotation,
+                    scale: cgmath::ElementWise::mul_element_wise(local.scale, previous.scale)
+                };
+                (transform, (mode == TransformMode::TwoD).then(|| z_angle(transform.rotation)))
+            }
+        };
+
+        // calculate starting dimensions
+        let mut dimensions = Dimensions {
+            from: Vector3 {
+                x: global_transform.position.x + self.rel_min_dimensions.from.x,
+                y: global_transform.position.y + self.rel_min_dimensions.from.y,
+                z: global_transform.position.z + self.rel_min_dimensions.from.z,
+            },
+            to: Vector3 {
+                x: global_transform.position.x + self.rel_min_dimensions.to.x,
+                y: global_transform.position.y + self.rel_min_dimensions.to.y,
+                z: global_transform.position.z + self.rel_min_dimensions.to.z,
+            }
+        };
+
+        // two dimensional nodes are flat rects on their layer
+        if self.transform_mode == TransformMode::TwoD {
+            dimensions.from.z = global_transform.position.z;
+            dimensions.to.z = global_transform.position.z;
+        }
+
+        // a teleport of this node takes its whole subtree with it
+        self.teleported = std::mem::take(&mut self.teleport_pending);
+        let teleported = self.teleported;
+
+        // update children first, and update dimensions if/when necessary
+        let mut updated = 1;
+        self.children.iter_mut().filter(|child| child.enabled).for_each(|child| {
+            if child.confine_to_parent { child.confine_to(&self.rel_min_dimensions); }
+            child.teleport_pending |= teleported;
+            updated += child.update_recr(&global_transform, global_angle, app, dt, config);
+
+            // check for dimension updates
+            if child.dimensions.from.x < dimensions.from.x { dimensions.from.x = child.dimensions.from.x; }
+            if child.dimensions.from.y < dimensions.from.y { dimensions.from.y = child.dimensions.from.y; }
+            if child.dimensions.from.z < dimensions.from.z { dimensions.from.z = child.dimensions.from.z; }
+            if child.dimensions.to.x > dimensions.to.x { dimensions.to.x = child.dimensions.to.x; }
+            if child.dimensions.to.y > dimensions.to.y { dimensions.to.y = child.dimensions.to.y; }
+            if child.dimensions.to.z > dimensions.to.z { dimensions.to.z = child.dimensions.to.z; }
+        });
+
+        // track what changed since the last update
+        if config.track_changes {
+            let moved = transform_moved(&self.global_transform, &global_transform, config.transform_changed_epsilon);
+            let first_update = std::mem::take(&mut self.changes.first_update);
+            self.changes.transform = moved || first_update;
+            self.changes.component = std::mem::take(&mut self.changes.component_pending);
+
+            // what a scene file holds of this node, see `World::save_dirty`
+            let saved_changed = self.changes.component || self.children.len() != self.changes.child_count
+                || transform_moved(&self.changes.local, &self.transform, 0.0);
+            self.changes.edited |= !first_update && saved_changed;
+            (self.changes.local, self.changes.child_count) = (self.transform, self.children.len());
+        } else {
+            (self.changes.transform, self.changes.component) = (false, false);
+        }
+
+        // update global transform, its history and dimensions
+        self.previous_global_transform = if teleported { global_transform } else { self.global_transform };
+        self.velocity = match teleported || dt <= 0.0 {
+            true => still(),
+            false => (global_transform.position - self.previous_global_transform.position) / dt
+        };
+        self.global_transform = global_transform;
+        self.dimensions = dimensions;
+
+        // call the start of each component if it has not been updated yet, then its update
+        self.for_each_component(|node| {
+            if std::mem::take(&mut node.changes.start_pending) { node.run_callback(C::start); }
+            node.run_callback(|node| C::update(node, app, dt));
+        });
+        updated
+    }
+
+    /// Gives every matching node in this tree with an empty component the component made for it, firing its added
+    /// function.
+    pub fn attach_where(&mut self, pred: impl Fn(&Node<C, A>) -> bool, make: impl Fn(&Node<C, A>) -> C) -> AttachReport {
+        let mut report = AttachReport::default();
+        self.attach_where_recr(&pred, &make, &mut report);
+        report
+    }
+
+    // attaches components to the matching nodes
+    fn attach_where_recr(&mut self, pred: &impl Fn(&Node<C, A>) -> bool, make: &impl Fn(&Node<C, A>) -> C, report: &mut AttachReport) {
+        if pred(self) {
+            if self.component.variant_index().is_none() {
+                self.component = make(self);
+                self.changes.component_pending = true;
+                self.call_add();
+                report.attached += 1;
+            } else {
+                report.skipped += 1;
+            }
+        }
+
+        self.children.iter_mut().for_each(|child| child.attach_where_recr(pred, make, report));
+    }
+
+    /// Blends this nodes local transform towards the given target by the given weight during update, until cleared.
+    pub fn blend_override(&mut self, target: Transform, weight: f32) { self.blend_override = Some((target, weight)); }
+    pub fn clear_blend_override(&mut self) { self.blend_override = None; }
+
+    /// Converts this subtree from the frame it was authored in, converting every position in it and adding the
+    /// conversion to the rotation and scale of this node.  Mirrored conversions are only exact when this node has no
+    /// rotation of its own.
+    pub fn apply_frame_conversion(&mut self, conversion: &FrameConversion) {
+        let (rotation, scale) = (conversion.rotation(), conversion.scale());
+        self.transform.rotation = rotation * self.transform.rotation;
+        self.transform.scale = cgmath::ElementWise::mul_element_wise(scale, self.transform.scale);
+        self.convert_positions_recr(conversion);
+    }
+
+    fn convert_positions_recr(&mut self, conversion: &FrameConversion) {
+        self.transform.position = conversion.convert_vector(self.transform.position);
+        self.children.iter_mut().for_each(|child| child.convert_positions_recr(conversion));
+    }
+
+    /// True if a RENDER callback of a component of this node was called by a stamped draw in the given frame, by any pass
+    /// or camera.  Nodes whose components have no RENDER callback are never rendered.
+    pub fn was_rendered(&self, current_frame: u64) -> bool { self.last_rendered_frame.load(Ordering::Relaxed) == current_frame }
+
+    /// Counts the nodes in this tree, including this one, that were rendered by a stamped draw in the given frame.
+    pub fn rendered_count(&self, current_frame: u64) -> usize {
+        self.was_rendered(current_frame) as usize + self.children.iter().map(|child| child.rendered_count(current_frame)).sum::<usize>()
+    }
+
+    /// Passes the asset paths of every component in this tree to the given remap function, see REMAP_ASSETS.
+    pub fn remap_assets(&mut self, remap: impl Fn(&str) -> Option<String>) -> RemapReport {
+        let mut report = RemapReport::new(C::VARIANT_NAMES);
+        self.remap_assets_recr(&remap, &mut report);
+        report
+    }
+
+    fn remap_assets_recr(&mut self, remap: &dyn Fn(&str) -> Option<String>, report: &mut RemapReport) {
+        if let Some(variant) = self.component.variant_index() {
+            let rewritten = self.component.remap_assets(remap);
+            report.add(variant, rewritten);
+        }
+
+        self.children.iter_mut().for_each(|child| child.remap_assets_recr(remap, report));
+    }
+
+    /// Starts animating this node towards the given target, the tween is dropped with the node if it is removed first.
+    pub fn tween(&mut self, target: TweenTarget<Node<C, A>>, duration: f32, easing: Easing) -> TweenHandle {
+        self.add_tween(Tween::new(target, duration, easing))
+    }
+
+    pub fn add_tween(&mut self, tween: Tween<Node<C, A>>) -> TweenHandle {
+        let handle = tween.handle();
+        self.tweens.push(tween);
+        handle
+    }
+
+    /// Stops the tween with the given handle on this node where it is, returns false if no such tween is running.
+    pub fn cancel_tween(&mut self, handle: TweenHandle) -> bool {
+        let count = self.tweens.len();
+        self.tweens.retain(|tween| tween.handle() != handle);
+        self.tweens.len() != count
+    }
+
+    /// Advances the tweens of this node and all its children.  This should be called before update so the tweened
+    /// transforms are propagated in the same frame.
+    pub fn advance_tweens(&mut self, dt: f32) {
+        if !self.tweens.is_empty() {
+            // take the tweens out while they step so they can borrow this node, keeping any added while stepping
+            let mut tweens = std::mem::take(&mut self.tweens);
+            tweens.retain_mut(|tween| !tween.step(self, |node| &mut node.transform, dt));
+            tweens.append(&mut self.tweens);
+            self.tweens = tweens;
+        }
+
+        self.children.iter_mut().for_each(|child| child.advance_tweens(dt));
+    }
+
+    // clamps this nodes local position so its own dimensions stay inside the given parent relative bounds,
+    // centering it on any axis where it is larger than the bounds
+    fn confine_to(&mut self, bounds: &Dimensions) {
+        let own = &self.rel_min_dimensions;
+        let position = &mut self.transform.position;
+        for axis in 0..3 {
+            let (min, max) = (bounds.from[axis] - own.from[axis], bounds.to[axis] - own.to[axis]);
+            position[axis] = if min > max { (min + max) * 0.5 } else { position[axis].max(min).min(max) };
+        }
+    }
+
+    /// Despawns this node and its children once the given number of seconds have passed in `World::tick`, replacing
+    /// any earlier lifetime.  The root of a world is never despawned.
+    pub fn despawn_after(&mut self, seconds: f32) { self.lifetime = Some(seconds); }
+
+    /// Stops this node from being despawned by its lifetime.
+    pub fn cancel_despawn(&mut self) { self.lifetime = None; }
+
+    /// The seconds left before this node is despawned, None if it has no lifetime.
+    pub fn lifetime(&self) -> Option<f32> { self.lifetime }
+
+    // counts down the lifetimes of this tree, pushing the paths of nodes whose time is up
+    fn advance_lifetimes_recr(&mut self, dt: f32, path: &mut NodePath, expired: &mut Vec<NodePath>) {
+        if let Some(lifetime) = &mut self.lifetime {
+            *lifetime -= dt;
+            if *lifetime <= 0.0 {
+                expired.push(path.clone());
+                return
+            }
+        }
+
+        for (idx, child) in self.children.iter_mut().take(u16::MAX as usize + 1).enumerate() {
+            path.push(idx as u16);
+            child.advance_lifetimes_recr(dt, path, expired);
+            path.pop();
+        }
+    }
+
+    /// True if a callback of this node panicked, see catch-callbacks.  Its callbacks are skipped until the poison is
+    /// cleared.
+    pub fn is_poisoned(&self) -> bool { self.poisoned }
+    pub fn clear_poison(&mut self) { self.poisoned = false; }
+
+    // runs a component callback on this node unless it is poisoned, poisoning it if the callback panics
+    fn run_callback(&mut self, callback: impl FnOnce(&mut Node<C, A>)) {
+        if self.poisoned { return }
+        if let Err(message) = callbacks::run_callback(|| callback(self)) {
+            self.poisoned = true;
+            let variant = self.component.variant_index().map_or("Empty", |idx| C::VARIANT_NAMES[idx]);
+            callbacks::report_panic(self.id, variant, &message);
+        }
+    }
+
+    // runs a component callback for every component of this node, see `for_each_component`
+    fn run_components(&mut self, mut callback: impl FnMut(&mut Node<C, A>)) {
+        self.for_each_component(|node| node.run_callback(&mut callback));
+    }
+
+    // calls the given function for every component of this node, its own component first unless it is empty, with each
+    // added component swapped in as the component of the node so callbacks find their data the same way for all of them
+    fn for_each_component(&mut self, mut f: impl FnMut(&mut Node<C, A>)) {
+        if self.component.variant_index().is_some() { f(self); }
+        let mut idx = 0;
+        while idx < self.added_components.len() {
+            self.with_added(idx, &mut f);
+            idx += 1;
+        }
+    }
+
+    // calls the given function with the added component at the given index swapped in as the component of this node,
+    // along with whether its start function is still to be called
+    fn with_added(&mut self, idx: usize, f: impl FnOnce(&mut Node<C, A>)) {
+        self.swap_added(idx);
+        f(self);
+        self.swap_added(idx);
+    }
+
+    fn swap_added(&mut self, idx: usize) {
+        let added = &mut self.added_components[idx];
+        std::mem::swap(&mut self.component, &mut added.component);
+        let pending = !added.started;
+        added.started = !self.changes.start_pending;
+        self.changes.start_pending = pending;
+    }
+
+    // calls the add functions recursively for this node and all its children
+    pub(crate) fn call_add_recr(&mut self) {
+        self.call_add();
+        self.children.iter_mut().for_each(|child| child.call_add_recr());
+    }
+
+    // calls the add functions of this nodes components only
+    pub(crate) fn call_add(&mut self) { self.for_each_component(Self::call_add_single); }
+
+    // calls the add function of the component swapped in as this nodes component
+    fn call_add_single(&mut self) {
+        let Some(_variant) = self.component.variant_index() else { return };
+        #[cfg(feature = "lifecycle-audit")]
+        if !self.poisoned { crate::audit::record(_variant, 1); }
+        self.run_callback(C::added);
+    }
+
+    // calls the remove functions recursively for this node and all its children, children are given the cascaded reason
+    pub(crate) fn call_remove_recr(&mut self, reason: RemovalReason) {
+        self.call_remove(reason);
+        self.children.iter_mut().for_each(|child| child.call_remove_recr(reason.cascaded()));
+    }
+
+    // calls the remove functions of this nodes components only
+    pub(crate) fn call_remove(&mut self, reason: RemovalReason) { self.for_each_component(|node| node.call_remove_single(reason)); }
+
+    // calls the remove function of the component swapped in as this nodes component
+    fn call_remove_single(&mut self, reason: RemovalReason) {
+        let Some(_variant) = self.component.variant_index() else { return };
+        #[cfg(feature = "lifecycle-audit")]
+        if !self.poisoned { crate::audit::record(_variant, -1); }
+        self.run_callback(|node| C::removed(node, &reason));
+    }
+
+    /// Collects all shadow casting components whose dimensions intersect the given volume, skipping subtrees that are
+    /// entirely outside of it.
+    pub fn collect_in_volume<'a>(&'a self, volume: &CullVolume, out: &mut Vec<(&'a C, &'a Transform)>) {
+        if !self.enabled || !volume.intersects(&self.dimensions) { return }
+
+        if self.component.casts_shadow() && volume.intersects(&self.own_dimensions()) {
+            out.push((&self.component, &self.global_transform));
+        }
+
+        self.children.iter().for_each(|child| child.collect_in_volume(volume, out));
+    }
+
+    /// Collects the debug shapes of this node and its children, only from the given variant indices if some are given.
+    pub fn collect_debug_variant(&self, out: &mut DebugLines, only_variants: Option<&[usize]>) {
+        let included = match (only_variants, self.component.variant_index()) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(only), Some(idx)) => only.contains(&idx)
+        };
+        if included { self.component.debug_draw(&self.global_transform, out); }
+
+        self.children.iter().for_each(|child| child.collect_debug_variant(out, only_variants));
+    }
+
+    /// Checks this node and its children for common authoring mistakes, this never panics on bad data.
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        let mut out = Vec::new();
+        self.validate_recr(&mut NodePath::new(), &mut out);
+        out
+    }
+
+    fn validate_recr(&self, path: &mut NodePath, out: &mut Vec<ValidationWarning>) {
+        let finite = |v: Vector3<f32>| v.x.is_finite() && v.y.is_finite() && v.z.is_finite();
+        let mut push = |kind: ValidationKind| out.push(ValidationWarning::new(path.clone(), kind));
+
+        // check transform
+        let transform = &self.transform;
+        if !finite(transform.position) { push(ValidationKind::NonFinitePosition); }
+        if !(finite(transform.rotation.v) && transform.rotation.s.is_finite()) { push(ValidationKind::NonFiniteRotation); }
+        if !finite(transform.scale) { push(ValidationKind::NonFiniteScale); }
+        else {
+            let scale = transform.scale;
+            if scale.x == 0.0 || scale.y == 0.0 || scale.z == 0.0 { push(ValidationKind::ZeroScale); }
+            if scale.x < 0.0 || scale.y < 0.0 || scale.z < 0.0 { push(ValidationKind::NegativeScale); }
+        }
+
+        // check dimensions
+        let dimensions = &self.rel_min_dimensions;
+        if dimensions.from.x > dimensions.to.x || dimensions.from.y > dimensions.to.y || dimensions.from.z > dimensions.to.z {
+            push(ValidationKind::InvertedDimensions);
+        } else if self.component.variant_index().is_some() && dimensions.from == dimensions.to {
+            push(ValidationKind::ZeroDimensions);
+        }
+
+        let mut names = HashSet::new();
+        for (idx, child) in self.children.iter().take(u16::MAX as usize + 1).enumerate() {
+            path.push(idx as u16);
+            if child.name.as_deref().is_some_and(|name| !names.insert(name)) {
+                out.push(ValidationWarning::new(path.clone(), ValidationKind::DuplicateName));
+            }
+            child.validate_recr(path, out);
+            path.pop();
+        }
+    }
+
+    /// Finds the first node in pre-order, including this one, whose component matches the given predicate.
+    pub fn find_component(&self, pred: impl Fn(&C) -> bool) -> Option<&Node<C, A>> { self.find_component_recr(&pred) }
+
+    fn find_component_recr(&self, pred: &impl Fn(&C) -> bool) -> Option<&Node<C, A>> {
+        if pred(&self.component) { return Some(self) }
+        self.children.iter().find_map(|child| child.find_component_recr(pred))
+    }
+
+    /// Finds the path to the first node in pre-order, including this one, whose component matches the given predicate.
+    pub fn find_component_path(&self, pred: impl Fn(&C) -> bool) -> Option<NodePath> {
+        self.find_path(|node| pred(&node.component))
+    }
+
+    /// Finds a descendant by the names of the nodes leading to it, separated by slashes, IE `"arm/hand"` finds a child
+    /// named `hand` of a child named `arm` of this node.  If siblings share a name, the first match in pre-order is
+    /// found.  Empty segments are ignored, so an empty path finds this node.
+    pub fn find_by_path(&self, path: &str) -> Option<&Node<C, A>> {
+        let names: Vec<&str> = path.split('/').filter(|name| !name.is_empty()).collect();
+        let path = self.find_by_names(&names, &mut NodePath::new())?;
+        self.resolve_path(&path)
+    }
+
+    pub fn find_by_path_mut(&mut self, path: &str) -> Option<&mut Node<C, A>> {
+        let names: Vec<&str> = path.split('/').filter(|name| !name.is_empty()).collect();
+        let path = self.find_by_names(&names, &mut NodePath::new())?;
+        self.resolve_path_mut(&path)
+    }
+
+    fn find_by_names(&self, names: &[&str], path: &mut NodePath) -> Option<NodePath> {
+        let Some((name, rest)) = names.split_first() else { return Some(path.clone()) };
+
+        for (idx, child) in self.children.iter().take(u16::MAX as usize + 1).enumerate() {
+            if child.name.as_deref() != Some(*name) { continue }
+            path.push(idx as u16);
+            if let Some(found) = child.find_by_names(rest, path) { return Some(found) }
+            path.pop();
+        }
+
+        None
+    }
+
+    /// Finds the node at the given path from this node in O(depth).
+    pub fn resolve_path(&self, path: &NodePath) -> Option<&Node<C, A>> {
+        path.indices().iter().try_fold(self, |node, idx| node.children.get(*idx as usize))
+    }
+
+    pub fn resolve_path_mut(&mut self, path: &NodePath) -> Option<&mut Node<C, A>> {
+        path.indices().iter().try_fold(self, |node, idx| node.children.get_mut(*idx as usize))
+    }
+
+    /// Borrows the two nodes at the given paths mutably at once.  The paths must not be the same or an ancestor of one
+    /// another, as the ancestor contains the descendant.
+    pub fn get_two_mut(&mut self, a: &NodePath, b: &NodePath) -> Result<(&mut Self, &mut Self), NodePathError> {
+        // the shorter path must not be a prefix of the longer one
+        let common = a.indices().iter().zip(b.indices()).take_while(|(a, b)| a == b).count();
+        if common == a.depth() { return Err(NodePathError::AliasedNodes(a.clone(), b.clone())) }
+        if common == b.depth() { return Err(NodePathError::AliasedNodes(b.clone(), a.clone())) }
+
+        // split the children of the last shared ancestor so each side can be borrowed separately
+        let ancestor = self.resolve_path_mut(&NodePath::from(&a.indices()[..common])).ok_or_else(|| NodePathError::NotFound(a.clone()))?;
+        let (a_idx, b_idx) = (a.indices()[common] as usize, b.indices()[common] as usize);
+        let (first, second) = (a_idx.min(b_idx), a_idx.max(b_idx));
+        if second >= ancestor.children.len() {
+            let missing = if a_idx == second { a } else { b };
+            return Err(NodePathError::NotFound(missing.clone()));
+        }
+        let (low, high) = ancestor.children.split_at_mut(second);
+        let (first, second) = (&mut low[first], &mut high[0]);
+        let (a_child, b_child) = if a_idx < b_idx { (first, second) } else { (second, first) };
+
+        // resolve the rest of each path within its own side
+        let a_node = a_child.resolve_path_mut(&NodePath::from(&a.indices()[common + 1..])).ok_or_else(|| NodePathError::NotFound(a.clone()))?;
+        let b_node = b_child.resolve_path_mut(&NodePath::from(&b.indices()[common + 1..])).ok_or_else(|| NodePathError::NotFound(b.clone()))?;
+        Ok((a_node, b_node))
+    }
+
+    /// Finds the path to the first node in pre-order, including this one, that matches the given predicate.
+    pub fn find_path(&self, pred: impl Fn(&Node<C, A>) -> bool) -> Option<NodePath> {
+        let mut path = NodePath::new();
+        if self.find_path_recr(&pred, &mut path) { Some(path) } else { None }
+    }
+
+    fn find_path_recr(&self, pred: &impl Fn(&Node<C, A>) -> bool, path: &mut NodePath) -> bool {
+        if pred(self) { return true }
+
+        for (idx, child) in self.children.iter().take(u16::MAX as usize + 1).enumerate() {
+            path.push(idx as u16);
+            if child.find_path_recr(pred, path) { return true }
+            path.pop();
+        }
+
+        false
+    }
+
+    /// Finds the paths to all non empty nodes whose own dimensions overlap the given dimensions in pre-order.
+    pub fn query_overlapping_paths(&self, dimensions: &Dimensions) -> Vec<NodePath> {
+        let mut out = Vec::new();
+        self.query_overlapping_paths_recr(dimensions, &mut NodePath::new(), &mut out);
+        out
+    }
+
+    fn query_overlapping_paths_recr(&self, dimensions: &Dimensions, path: &mut NodePath, out: &mut Vec<NodePath>) {
+        if !self.dimensions.overlap(dimensions) { return }
+        if self.component.variant_index().is_some() && self.own_dimensions().overlap(dimensions) { out.push(path.clone()); }
+
+        for (idx, child) in self.children.iter().take(u16::MAX as usize + 1).enumerate() {
+            path.push(idx as u16);
+            child.query_overlapping_paths_recr(dimensions, path, out);
+            path.pop();
+        }
+    }
+
+    /// Finds the path to and distance of the closest non empty node whose own dimensions are hit by the given ray
+    /// within max_dist.
+    pub fn raycast_path(&self, origin: Vector3<f32>, direction: Vector3<f32>, max_dist: f32) -> Option<(NodePath, f32)> {
+        let mut best = None;
+        self.raycast_path_recr(origin, direction, max_dist, &mut NodePath::new(), &mut best);
+        best
+    }
+
+    fn raycast_path_recr(&self, origin: Vector3<f32>, direction: Vector3<f32>, max_dist: f32, path: &mut NodePath, best: &mut Option<(NodePath, f32)>) {
+        // skip this subtree if it is missed or cannot beat the current best hit
+        let bound = best.as_ref().map_or(max_dist, |best| best.1);
+        match self.dimensions.ray_intersection(origin, direction) {
+            Some(dist) if dist <= bound => {},
+            _ => return
+        }
+
+        if self.component.variant_index().is_some() {
+            if let Some(dist) = self.own_dimensions().ray_intersection(origin, direction) {
+                if dist <= max_dist && best.as_ref().is_none_or(|best| dist < best.1) { *best = Some((path.clone(), dist)); }
+            }
+        }
+
+        for (idx, child) in self.children.iter().take(u16::MAX as usize + 1).enumerate() {
+            path.push(idx as u16);
+            child.raycast_path_recr(origin, direction, max_dist, path, best);
+            path.pop();
+        }
+    }
+
+    /// Finds up to k nodes, including this one, that pass the filter and are within max_dist of the point, sorted
+    /// nearest first.  Distances are measured to the closest point of each nodes own dimensions.
+    pub fn k_nearest(&self, point: Vector3<f32>, k: usize, max_dist: f32, filter: impl Fn(&Node<C, A>) -> bool) -> Vec<(&Node<C, A>, f32)> {
+        let mut nearest = KNearest::new(k, max_dist);
+        self.k_nearest_recr(point, &filter, &mut nearest);
+        nearest.into_sorted()
+    }
+
+    fn k_nearest_recr<'a>(&'a self, point: Vector3<f32>, filter: &impl Fn(&Node<C, A>) -> bool, nearest: &mut KNearest<&'a Node<C, A>>) {
+        // skip this subtree if no node in it can beat the current bound
+        if self.dimensions.distance_to(point) > nearest.bound() { return }
+
+        if filter(self) { nearest.offer(self, self.own_dimensions().distance_to(point)); }
+
+        self.children.iter().for_each(|child| child.k_nearest_recr(point, filter, nearest));
+    }
+
+    /// Pushes this node and all its children onto the given draw list in draw order, skipping disabled subtrees.
+    pub fn build_draw_list<'b>(&'b self, out: &mut Vec<&'b Node<C, A>>) { self.build_draw_list_with(None, out); }
+
+    /// Pushes this node and all its children that pass the given culling onto the given draw list in draw order.
+    pub fn build_draw_list_culled<'b>(&'b self, culling: &DrawCulling, out: &mut Vec<&'b Node<C, A>>) { self.build_draw_list_with(Some(culling), out); }
+
+    fn build_draw_list_with<'b>(&'b self, culling: Option<&DrawCulling>, out: &mut Vec<&'b Node<C, A>>) {
+        self.build_draw_list_counted(culling, None, out, &mut Vec::new());
+    }
+
+    // builds a draw list like `build_draw_list_culled`, counting the failed culling tests into the given counters and
+    // sorting with the given scratch buffer
+    fn build_draw_list_counted<'b>(
+        &'b self,
+        culling: Option<&DrawCulling>,
+        counters: Option<&FrameCounters>,
+        out: &mut Vec<&'b Node<C, A>>,
+        scratch: &mut Vec<(u64, &'b Node<C, A>)>
+    ) {
+        let start = out.len();
+        self.push_draw_list_recr(culling, counters, out);
+
+        // stable sort so that tree order is preserved within each priority
+        if C::PRIORITIZED {
+            out[start..].sort_by_key(|node| node.component.priority());
+        }
+
+        // reorder each keyed variant amongst its own slots, reusing one buffer for every variant
+        if C::SORTED {
+            C::SORT_KEYED.iter().enumerate()
+                .filter(|(_, keyed)| **keyed)
+                .for_each(|(variant, _)| Self::sort_variant_by_key(&mut out[start..], variant, scratch));
+        }
+    }
+
+    fn sort_variant_by_key<'b>(list: &mut [&'b Node<C, A>], variant: usize, scratch: &mut Vec<(u64, &'b Node<C, A>)>) {
+        scratch.clear();
+        scratch.extend(list.iter()
+            .filter(|node| node.component.variant_index() == Some(variant))
+            .filter_map(|node| Some((node.component.sort_key()?, *node))));
+        if scratch.len() < 2 { return }
+
+        // stable sort so that tree order is preserved within each key
+        scratch.sort_by_key(|(key, _)| *key);
+        let mut sorted = scratch.iter();
+        list.iter_mut()
+            .filter(|node| node.component.variant_index() == Some(variant))
+            .for_each(|slot| *slot = sorted.next().unwrap().1);
+    }
+
+    fn push_draw_list_recr<'b>(&'b self, culling: Option<&DrawCulling>, counters: Option<&FrameCounters>, out: &mut Vec<&'b Node<C, A>>) {
+        if !self.enabled { return }
+        if !subtree_visible(culling, counters, self) { return }
+        if node_visible(culling, counters, self) { out.push(self); }
+        self.for_each_child_in_draw_order(|child| child.push_draw_list_recr(culling, counters, out));
+    }
+
+    // calls the given function on each child in draw order, two dimensional siblings are drawn in order of their layers
+    fn for_each_child_in_draw_order<'b>(&'b self, f: impl FnMut(&'b Node<C, A>)) {
+        if !self.children.iter().any(|child| child.transform_mode == TransformMode::TwoD) {
+            self.children.iter().for_each(f);
+            return;
+        }
+
+        // reorder the two dimensional children amongst the slots they already take up
+        let mut order: Vec<&Node<C, A>> = self.children.iter().collect();
+        let mut sprites: Vec<&Node<C, A>> = self.children.iter().filter(|child| child.transform_mode == TransformMode::TwoD).collect();
+        sprites.sort_by(|a, b| a.global_transform.position.z.total_cmp(&b.global_transform.position.z));
+        let mut sprites = sprites.into_iter();
+        order.iter_mut()
+            .filter(|child| child.transform_mode == TransformMode::TwoD)
+            .for_each(|slot| *slot = sprites.next().unwrap());
+        order.into_iter().for_each(f);
+    }
+}
+
+impl<C: ComponentDef<A> + Clone, A> Node<C, A> {
+    /// Copies this node and all of its children.  No added functions are fired until the copy is added to a tree with
+    /// `add_child` or `World::spawn`.  The copy has no ids, uuids, tweens or poison, and has not been rendered.
+    pub fn duplicate(&self) -> Node<C, A> {
+        Node {
+            children: self.children.iter().map(|child| child.duplicate()).collect(),
+            ..self.duplicate_shallow()
+        }
+    }
+
+    // copies this node and all of its children exactly as they are, other than their tweens which cannot be copied
+    fn snapshot_recr(&self) -> Node<C, A> {
+        Node {
+            id: self.id,
+            parent: self.parent,
+            uuid: self.uuid,
+            poisoned: self.poisoned,
+            changes: self.changes,
+            lifetime: self.lifetime,
+            last_rendered_frame: AtomicU64::new(self.last_rendered_frame.load(Ordering::Relaxed)),
+            added_components: self.added_components.clone(),
+            previous_global_transform: self.previous_global_transform,
+            velocity: self.velocity,
+            teleport_pending: self.teleport_pending,
+            teleported: self.teleported,
+            children: self.children.iter().map(|child| child.snapshot_recr()).collect(),
+            ..self.duplicate_shallow()
+        }
+    }
+
+    // copies this node without its children, like `duplicate`
+    fn duplicate_shallow(&self) -> Node<C, A> {
+        Node {
+            transform: self.transform,
+            component: self.component.clone(),
+            added_components: self.added_components.iter().map(|added| AddedComponent { component: added.component.clone(), started: false }).collect(),
+            rel_min_dimensions: self.rel_min_dimensions,
+            transform_mode: self.transform_mode,
+            confine_to_parent: self.confine_to_parent,
+            name: self.name.clone(),
+            enabled: self.enabled,
+            metadata: self.metadata.clone(),
+            tags: self.tags.clone(),
+            blend_override: self.blend_override,
+            global_transform: self.global_transform,
+            dimensions: self.dimensions,
+            ..Node::default()
+        }
+    }
+}
+
+// what changed about a node in its last update, and what has changed since
+#[derive(Debug, Clone, Copy)]
+struct Changes {
+    transform: bool,
+    component: bool,
+    component_pending: bool,
+    start_pending: bool,
+    first_update: bool,
+    // the local transform and number of children as of the last update, and whether either or the component changed
+    // in an update since the scene source of the node was last saved
+    local: Transform,
+    child_count: usize,
+    edited: bool
+}
+
+impl Default for Changes {
+    fn default() -> Self {
+        Self {
+            transform: false, component: false, component_pending: true, start_pending: true, first_update: true,
+            local: Transform::default(), child_count: 0, edited: false
+        }
+    }
+}
+
+// a component added to a node beside its own with `Node::add_component`, and whether its start function was called
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+struct AddedComponent<C> {
+    component: C,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    started: bool
+}
+
+/// A trait to draw node trees with a render pass, or whichever pass the component renders into.  Disabled nodes and
+/// their children are not drawn.
+pub trait DrawNodes<'a, 'b, C, A> where 'b: 'a {
+    fn draw_node(
+        &mut self,
+        app: &'b A,
+        node: &'b Node<C, A>
+    );
+
+    fn draw_node_culled(
+        &mut self,
+        app: &'b A,
+        node: &'b Node<C, A>,
+        culling: &DrawCulling
+    );
+
+    /// Draws like `draw_node` or `draw_node_culled`, stamping each node whose component renders with the given frame.
+    fn draw_node_stamped(
+        &mut self,
+        app: &'b A,
+        node: &'b Node<C, A>,
+        culling: Option<&DrawCulling>,
+        frame: u64
+    );
+}
+
+// renders every component of a single node, ignoring children, stamping it with the frame if given and it has a
+// component to render
+fn draw_single_node<'a, 'b, C: ComponentDef<A>, A>(pass: &mut C::Pass<'a>, app: &'b A, node: &'b Node<C, A>, stamp: DrawStamp) where 'b: 'a {
+    // only the components whose variant gives a RENDER callback are rendered, stamped and counted
+    node.components().for_each(|component| {
+        let Some(variant) = component.variant_index().filter(|variant| C::RENDERED[*variant]) else { return };
+        if let Some(frame) = stamp.frame { node.last_rendered_frame.store(frame, Ordering::Relaxed); }
+        if let Some(counters) = stamp.counters { counters.drawn(variant); }
+        component.render(pass, app);
+    });
+}
+
+// checks the merged dimensions of the subtree of the node against the culling if given, counting a failed test
+fn subtree_visible<C: ComponentDef<A>, A>(culling: Option<&DrawCulling>, counters: Option<&FrameCounters>, node: &Node<C, A>) -> bool {
+    let visible = culling.is_none_or(|culling| culling.subtree_visible(&node.dimensions));
+    if !visible { counters.inspect(|counters| counters.culled()); }
+    visible
+}
+
+// checks the own dimensions of the node against the culling if given, counting a failed test
+fn node_visible<C: ComponentDef<A>, A>(culling: Option<&DrawCulling>, counters: Option<&FrameCounters>, node: &Node<C, A>) -> bool {
+    let visible = culling.is_none_or(|culling| culling.node_visible(&node.own_dimensions()));
+    if !visible { counters.inspect(|counters| counters.culled()); }
+    visible
+}
+
+// renders a node and its children in tree order, skipping any that fail the culling if given
+fn draw_node_recr<'a, 'b, C: ComponentDef<A>, A>(pass: &mut C::Pass<'a>, app: &'b A, node: &'b Node<C, A>, culling: Option<&DrawCulling>, stamp: DrawStamp, own: bool) where 'b: 'a {
+    if !node.enabled { return }
+    if !subtree_visible(culling, stamp.counters, node) { return }
+    if own && node_visible(culling, stamp.counters, node) { draw_single_node(pass, app, node, stamp); }
+    node.for_each_child_in_draw_order(|child| draw_node_recr(pass, app, child, culling, stamp, true));
+}
+
+// renders a node and its children, using a draw list when priorities or sort keys have to be sorted
+fn draw_node_with<'a, 'b, C: ComponentDef<A>, A>(pass: &mut C::Pass<'a>, app: &'b A, node: &'b Node<C, A>, culling: Option<&DrawCulling>, frame: Option<u64>) where 'b: 'a {
+    draw_tree(pass, app, node, culling, DrawStamp { frame, counters: None, buffers: None }, true);
+}
+
+// renders the children of a node, and the node itself when own is true
+fn draw_tree<'a, 'b, C: ComponentDef<A>, A>(pass: &mut C::Pass<'a>, app: &'b A, node: &'b Node<C, A>, culling: Option<&DrawCulling>, stamp: DrawStamp, own: bool) where 'b: 'a {
+    // without priorities or sort keys, tree order is draw order so no list is needed
+    if !C::PRIORITIZED && !C::SORTED {
+        draw_node_recr(pass, app, node, culling, stamp, own);
+        return;
+    }
+
+    // the buffers of the world are taken for the draw, so a draw from inside a RENDER callback gets fresh ones
+    let buffers = stamp.buffers.and_then(|buffers| buffers.try_lock().ok().map(|mut buffers| std::mem::take(&mut *buffers)));
+    let DrawBuffers { list, scratch } = buffers.unwrap_or_default();
+    let (mut list, mut scratch) = (recycle(list), recycle(scratch));
+    node.build_draw_list_counted(culling, stamp.counters, &mut list, &mut scratch);
+    list.iter().filter(|drawn| own || !std::ptr::eq(**drawn, node)).for_each(|node| draw_single_node(pass, app, node, stamp));
+
+    if let Some(mut buffers) = stamp.buffers.and_then(|buffers| buffers.try_lock().ok()) {
+        *buffers = DrawBuffers { list: recycle(list), scratch: recycle(scratch) };
+    }
+}
+
+// the frame a draw stamps the nodes it renders with, the counters of the world it counts them into and the buffers of
+// the world it sorts with, none of which plain `DrawNodes` draws have
+#[derive(Clone, Copy)]
+struct DrawStamp<'s> {
+    frame: Option<u64>,
+    counters: Option<&'s FrameCounters>,
+    buffers: Option<&'s Mutex<DrawBuffers>>
+}
+
+// the draw list and sort scratch of `World::draw`, kept empty between draws so each draw reuses their allocations
+#[derive(Debug, Default)]
+struct DrawBuffers {
+    list: Vec<&'static ()>,
+    scratch: Vec<(u64, &'static ())>
+}
+
+// empties the given buffer and hands its allocation over to a buffer of references with another lifetime, which has the
+// same layout so the allocation is reused in place
+fn recycle<T, U>(mut buffer: Vec<T>) -> Vec<U> {
+    buffer.clear();
+    buffer.into_iter().map(|_| unreachable!()).collect()
+}
+
+/// An implementation of DrawNodes for the pass of the component, a render pass unless another PASS is given.  See
+/// documentation for more info.
+impl<'a, 'b, C: ComponentDef<A, Pass<'a> = P> + 'a, A: 'a, P> DrawNodes<'a, 'b, C, A> for P where 'b: 'a {
+    fn draw_node(
+        &mut self,
+        app: &'b A,
+        node: &'b Node<C, A>
+    ) { draw_node_with(self, app, node, None, None); }
+
+    fn draw_node_culled(
+        &mut self,
+        app: &'b A,
+        node: &'b Node<C, A>,
+        culling: &DrawCulling
+    ) { draw_node_with(self, app, node, Some(culling), None); }
+
+    fn draw_node_stamped(
+        &mut self,
+        app: &'b A,
+        node: &'b Node<C, A>,
+        culling: Option<&DrawCulling>,
+        frame: u64
+    ) { draw_node_with(self, app, node, culling, Some(frame)); }
+}
+
+/// The default number of seconds between fixed updates, see `World::set_fixed_timestep`.
+pub const DEFAULT_FIXED_TIMESTEP: f32 = 1.0 / 60.0;
+
+/// The most fixed updates run by one `World::tick`.
+pub const MAX_FIXED_STEPS: u32 = 8;
+
+// two nodes of a world borrowed mutably at once, see `World::get_two_mut`
+type NodePairMut<'n, C, A> = (&'n mut Node<C, A>, &'n mut Node<C, A>);
+
+// how `World::insert_inner` adds a node, at the given index or at the end, with the reserved id if there is one, named
+// with the policy, firing the added functions of the subtree unless they are left for later, and within the limits of
+// the config unless they were already checked
+#[derive(Clone, Copy)]
+pub(crate) struct Insertion {
+    pub(crate) idx: Option<usize>,
+    pub(crate) reserved: Option<NodeId>,
+    pub(crate) policy: NameCollisionPolicy,
+    pub(crate) added: bool,
+    pub(crate) limited: bool
+}
+
+impl Insertion {
+    // spawning at the end with every callback and check
+    pub(crate) fn new(config: &WorldConfig) -> Self {
+        Self { idx: None, reserved: None, policy: config.name_collision_policy, added: true, limited: true }
+    }
+}
+
+/// A node spawned by `World::try_spawn`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpawnedNode {
+    /// The id the node was given.
+    pub id: NodeId,
+
+    /// The name the node was given, which may have been changed to not collide with its siblings.
+    pub name: Option<String>
+}
+
+/// A copy of the whole state of a world, see `World::snapshot`.
+pub struct WorldSnapshot<C, A> {
+    root: Node<C, A>,
+    ids: IdAllocator,
+    paths: Vec<Option<NodePath>>,
+    layers: Vec<(String, NodeId)>
+}
+
+/// Owns the root node of a tree and gives its nodes stable `NodeId`s.  Nodes are added and removed with `spawn` and
+/// `despawn`, and the whole tree is updated with `update`.
+///
+/// Ids are found through a cache of each nodes path which is checked against the id stored on the node, so the tree
+/// can still be edited directly through `root_mut` and `Node::add_child`.  When a cached path no longer leads to its
+/// node, the tree is searched again.  Nodes added without going through the world are given ids the next time
+/// `refresh_ids` runs.
+///
+/// Each node owns its children, so nodes are laid out in tree order and spatial queries such as
+/// `Node::query_overlapping_paths` and `Node::k_nearest` walk memory in tree order rather than by position.  They make
+/// up for it by skipping every subtree whose merged dimensions miss, so trees grouped by region query fastest.  There
+/// is no pass that reorders the storage by position, as the storage order is the order of siblings, which update and
+/// draw follow.
+///
+/// The tree is not kept in a separate arena, as `root`, `root_mut`, `Node::children` and `scene!` hand out the nodes
+/// themselves.  A node moves in memory as its siblings are added and removed, so hold on to its `NodeId` rather than
+/// a reference, and use `Node::parent_id` with `parent` and `ancestors` to walk up from it.
+pub struct World<C, A> {
+    root: Node<C, A>,
+    pub(crate) ids: IdAllocator,
+    paths: Vec<Option<NodePath>>,
+    events: Vec<HierarchyEvent>,
+    layers: Vec<(String, NodeId)>,
+    pub(crate) config: WorldConfig,
+    fixed_accumulator: f32,
+    frame: u64,
+    panic_observer: Option<PanicObserver>,
+    pub(crate) counters: StatCounters,
+    pub(crate) commands: CommandQueue<C, A>,
+    pub(crate) names: NameIndex,
+    pub(crate) caps: VariantCaps,
+    pub(crate) report: FrameCounters,
+    draw_buffers: Mutex<DrawBuffers>,
+    pub(crate) frame_spares: FrameSpares,
+    #[cfg(feature = "serde")]
+    pub(crate) watch: Option<SceneWatch>,
+    #[cfg(feature = "serde")]
+    pub(crate) sources: Vec<SceneSource>,
+    #[cfg(feature = "lifecycle-audit")]
+    pub(crate) audit: Vec<i64>
+}
+
+impl<C: ComponentDef<A> + Clone, A> World<C, A> {
+    /// Copies the whole state of this world, IE for save games, undo or rollback.  Every node is copied with its
+    /// component, id, lifetime and the state of its last update, so ids given out before the snapshot resolve to the
+    /// same nodes after it is restored.  Tweens cannot be copied, so they are left out.
+    ///
+    /// The component enum must derive `Clone`, see the DERIVE option of `define_world!`.
+    pub fn snapshot(&self) -> WorldSnapshot<C, A> {
+        WorldSnapshot { root: self.root.snapshot_recr(), ids: self.ids.clone(), paths: self.paths.clone(), layers: self.layers.clone() }
+    }
+
+    /// Puts this world back into the state of the given snapshot, which can be restored again later.  No callbacks
+    /// are fired, every running tween is stopped and the events that have not been drained are dropped.
+    pub fn restore(&mut self, snapshot: &WorldSnapshot<C, A>) {
+        self.root = snapshot.root.snapshot_recr();
+        self.ids = snapshot.ids.clone();
+        self.paths = snapshot.paths.clone();
+        self.layers = snapshot.layers.clone();
+        self.events.clear();
+        self.names.clear();
+        self.caps.forget_order();
+        self.recount();
+        #[cfg(feature = "lifecycle-audit")]
+        {
+            self.audit.clear();
+            crate::audit::count_tree(&self.root, &mut self.audit, 1);
+        }
+    }
+}
+
+impl<C: ComponentDef<A>, A> Default for World<C, A> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Creates a new world with a reserved root, an empty node named `root` with the id `NodeId::ROOT`.  The root is
+    /// never despawned, moved or drawn, and is left out of `iter`, `find_by_path` and the other queries of the world
+    /// unless they are asked to include it, so new nodes should be spawned under it rather than stored in it.
+    pub fn new() -> Self { Self::from_root(Node::builder().name("root").build()) }
+
+    /// Creates a new world around the given root, giving every node in it an id.  The root is treated like the
+    /// reserved root of `new`, so anything it holds is never drawn.  Prefer `new` and spawning the tree under its root,
+    /// which keeps the root free of components.
+    pub fn from_root(root: Node<C, A>) -> Self {
+        let mut world = Self {
+            root, ids: IdAllocator::new(), paths: Vec::new(), events: Vec::new(), layers: Vec::new(),
+            config: WorldConfig::default(), fixed_accumulator: 0.0, frame: 0, panic_observer: None, counters: StatCounters::default(),
+            commands: CommandQueue::default(), names: NameIndex::default(), caps: VariantCaps::default(), report: FrameCounters::default(),
+            draw_buffers: Mutex::default(),
+            frame_spares: FrameSpares::default(),
+            #[cfg(feature = "serde")]
+            watch: None,
+            #[cfg(feature = "serde")]
+            sources: Vec::new(),
+            #[cfg(feature = "lifecycle-audit")]
+            audit: Vec::new()
+        };
+        world.refresh_ids();
+        world.report.reset(C::VARIANT_NAMES.len());
+        #[cfg(feature = "lifecycle-audit")]
+        crate::audit::count_tree(&world.root, &mut world.audit, 1);
+        world
+    }
+
+    pub fn root(&self) -> &Node<C, A> { &self.root }
+
+    /// Borrows the root for any change the world does not have a function for.  The world keeps the path to every
+    /// node cached so finding a node by id only walks down to it, changes to the shape of the tree made through this
+    /// are not seen by that cache, so finding a moved node searches the whole tree until `refresh_ids` is called.
+    /// Prefer `spawn`, `despawn`, `reparent`, `take`, `insert_child_at`, `move_child` and `sort_children_by_key`.
+    pub fn root_mut(&mut self) -> &mut Node<C, A> { &mut self.root }
+
+    /// The id of the root node.
+    pub fn root_id(&self) -> NodeId { self.root.id.expect("the world root always has an id") }
+
+    /// Consumes this world, giving back its root.
+    pub fn into_root(self) -> Node<C, A> { self.root }
+
+    /// The number of nodes with live ids, this may be behind if nodes were added or removed through `root_mut`.
+    pub fn id_count(&self) -> usize { self.ids.len() }
+
+    /// Finds the node with the given id.
+    ///
+    /// Returns None if the node was despawned or removed from the tree.
+    pub fn get(&self, id: NodeId) -> Option<&Node<C, A>> {
+        let path = self.locate(id)?;
+        self.root.resolve_path(&path)
+    }
+
+    /// Finds a node by the names leading to it from the root, see `Node::find_by_path`.  The empty path leads to the
+    /// root, which is only found when include_root is true.
+    pub fn find_by_path(&self, path: &str, include_root: bool) -> Option<&Node<C, A>> {
+        self.root.find_by_path(path).filter(|node| include_root || !std::ptr::eq(*node, &self.root))
+    }
+
+    /// Finds every node in the tree with the given tag in pre-order, see `Node::find_all_with_tag`.  The root is only
+    /// checked when include_root is true.
+    pub fn find_all_with_tag(&self, tag: &str, include_root: bool) -> Vec<&Node<C, A>> { self.iter(include_root).filter(|node| node.has_tag(tag)).collect() }
+
+    /// Finds the node in the tree with the given uuid, see `Node::find_by_uuid`.  The root is only checked when
+    /// include_root is true.
+    pub fn find_by_uuid(&self, uuid: NodeUuid, include_root: bool) -> Option<&Node<C, A>> { self.iter(include_root).find(|node| node.uuid == Some(uuid)) }
+
+    /// Finds the parent of the node with the given id.
+    ///
+    /// Returns None if the node does not exist or is the root.
+    pub fn parent(&self, id: NodeId) -> Option<&Node<C, A>> {
+        let path = self.locate(id)?.parent()?;
+        self.root.resolve_path(&path)
+    }
+
+    /// Iterates over the ancestors of the node with the given id, from its parent up to the root.  This is empty if the
+    /// node does not exist or is the root.
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = &Node<C, A>> + '_ {
+        let path = self.locate(id).unwrap_or_default();
+        (0..path.depth()).rev().filter_map(move |depth| self.root.resolve_path(&NodePath::from(&path.indices()[..depth])))
+    }
+
+    // finds the path to the node with the given id without updating the cache, trying the cached path first and
+    // falling back on searching the tree if the node was moved through `root_mut`
+    fn locate(&self, id: NodeId) -> Option<NodePath> {
+        if !self.ids.is_live(id) { return None }
+
+        let cached = self.paths[id.index() as usize].as_ref();
+        if let Some(path) = cached.filter(|path| self.root.resolve_path(path).is_some_and(|node| node.id == Some(id))) { return Some(path.clone()) }
+        self.root.find_path(|node| node.id == Some(id))
+    }
+
+    /// Checks if the node with the given id is still in this world.  Ids are generational, so once a node is despawned
+    /// its id stays dead even after its slot is reused by a new node.
+    pub fn is_alive(&self, id: NodeId) -> bool { self.get(id).is_some() }
+
+    /// Finds the node with the given id for mutation, updating the cached path to it if it has moved.
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut Node<C, A>> {
+        let path = self.path_of(id)?;
+        self.root.resolve_path_mut(&path)
+    }
+
+    /// Finds the current path from the root to the node with the given id, updating the cached path if it has moved.
+    pub fn path_of(&mut self, id: NodeId) -> Option<NodePath> {
+        if !self.ids.is_live(id) { return None }
+
+        let index = id.index() as usize;
+        let cached = self.paths[index].as_ref().filter(|path| self.root.resolve_path(path).is_some_and(|node| node.id == Some(id)));
+        if let Some(path) = cached { return Some(path.clone()) }
+
+        // the node moved or was removed without the world knowing
+        self.refresh_ids();
+        if self.ids.is_live(id) { self.paths[index].clone() } else { None }
+    }
+
+    /// Borrows the two nodes with the given ids mutably at once, IE to copy data between them, see `Node::get_two_mut`.
+    ///
+    /// Returns `NotFound` if either node does not exist, or `AliasedNodes` if the ids are the same node or one is an
+    /// ancestor of the other, with the ancestor first.
+    pub fn get_two_mut(&mut self, a: NodeId, b: NodeId) -> Result<NodePairMut<'_, C, A>, WorldError> {
+        let a_path = self.path_of(a).ok_or(WorldError::NotFound(a))?;
+        let b_path = self.path_of(b).ok_or(WorldError::NotFound(b))?;
+        self.root.get_two_mut(&a_path, &b_path).map_err(|error| match error {
+            NodePathError::AliasedNodes(first, _) if first == a_path => WorldError::AliasedNodes(a, b),
+            NodePathError::AliasedNodes(..) => WorldError::AliasedNodes(b, a),
+            NodePathError::NotFound(path) => WorldError::NotFound(if path == a_path { a } else { b })
+        })
+    }
+
+    /// Adds the given node as the last child of the node with the given id, firing the added functions of it and all of
+    /// its children and giving each of them a new id.  The node may be renamed first, see `set_name_collision_policy`.
+    ///
+    /// Returns the id of the spawned node, or None if the parent does not exist, already has 65536 children or has a
+    /// child with the same name under the `Reject` policy, in which case the node is dropped without any callbacks
+    /// firing.  `try_spawn` gives the reason instead.
+    pub fn spawn(&mut self, parent: NodeId, node: Node<C, A>) -> Option<NodeId> { self.observed(|world| world.spawn_inner(parent, node)) }
+
+    fn spawn_inner(&mut self, parent: NodeId, node: Node<C, A>) -> Option<NodeId> {
+        self.insert_inner(parent, node, Insertion::new(&self.config)).ok()
+    }
+
+    /// Spawns the given node like `spawn`, giving back its id along with the name it was given.
+    ///
+    /// Returns `NotFound` if the parent does not exist, `TooManyChildren` if it is full, `NameTaken` if it has a child
+    /// with the same name under the `Reject` policy, or `TooManyNodes` or `TooDeep` if the node does not fit within the
+    /// limits of the config, see `WorldConfig`.
+    pub fn try_spawn(&mut self, parent: NodeId, node: Node<C, A>) -> Result<SpawnedNode, WorldError> {
+        let id = self.observed(|world| world.insert_inner(parent, node, Insertion::new(&world.config)))?;
+        Ok(SpawnedNode { id, name: self.get(id).and_then(|node| node.name.clone()) })
+    }
+
+    /// Inserts the given node as a child of the node with the given id at the given index, shifting the children after
+    /// it along, see `spawn` and `Node::insert_child_at`.
+    ///
+    /// Returns the id of the inserted node, or None if the parent does not exist, the index is greater than its number
+    /// of children, it already has 65536 children or has a child with the same name under the `Reject` policy, in
+    /// which case the node is dropped without any callbacks firing.
+    pub fn insert_child_at(&mut self, parent: NodeId, idx: usize, node: Node<C, A>) -> Option<NodeId> {
+        if idx > self.get(parent)?.children.len() { return None }
+        self.observed(|world| world.insert_inner(parent, node, Insertion { idx: Some(idx), ..Insertion::new(&world.config) }).ok())
+    }
+
+    // inserts the node under the parent as the given insertion describes
+    pub(crate) fn insert_inner(&mut self, parent: NodeId, mut node: Node<C, A>, how: Insertion) -> Result<NodeId, WorldError> {
+        let mut parent_path = self.path_of(parent).ok_or(WorldError::NotFound(parent))?;
+        if how.limited { self.check_limits(parent, parent_path.depth(), &node, 0)?; }
+        let parent_id = parent;
+        let parent = self.root.resolve_path(&parent_path).unwrap();
+        if parent.children.len() > u16::MAX as usize { return Err(WorldError::TooManyChildren(parent_id)) }
+        node.name = self.names.claim(parent, node.name.take(), how.policy)?;
+
+        // replacing the oldest nodes of a capped variant may move the parent, or take siblings from before the index
+        if how.limited {
+            self.enforce_caps(parent_id, &node)?;
+            parent_path = self.path_of(parent_id).ok_or(WorldError::NotFound(parent_id))?;
+        }
+        let parent = self.root.resolve_path_mut(&parent_path).unwrap();
+        let idx = how.idx.map_or(parent.children.len(), |idx| idx.min(parent.children.len()));
+
+        let mut path = parent_path.child(idx as u16);
+        let capacity = parent.children.capacity();
+        parent.children.insert(idx, node);
+        if how.added { parent.children[idx].call_add_recr(); }
+        self.counters.resize_children(capacity, parent.children.capacity());
+        let child = &mut parent.children[idx];
+        self.names.added(Some(parent_id), child.name.as_deref());
+        Self::index_new_recr(child, &mut path, &mut self.ids, &mut self.paths, how.reserved, Some(parent_id));
+        Self::count_recr(child, path.depth(), &mut self.counters, true);
+
+        // the siblings after the new node moved up one index
+        let child = child.id.unwrap();
+        self.recache_children_from(&parent_path, idx + 1);
+        self.record_event(HierarchyEvent::ChildAdded { parent: parent_id, child });
+        self.record_created(child);
+        Ok(child)
+    }
+
+    // checks that the given subtree can be added under the parent at the given depth within the limits of the config,
+    // along with the given number of nodes waiting to be added that do not have ids yet
+    pub(crate) fn check_limits(&self, parent: NodeId, parent_depth: usize, node: &Node<C, A>, pending: usize) -> Result<(), WorldError> {
+        if self.config.max_nodes.is_some_and(|max| self.ids.len() + pending + node.count_nodes() > max) { return Err(WorldError::TooManyNodes) }
+        if self.config.max_depth.is_some_and(|max| parent_depth + 1 + node.height() > max) { return Err(WorldError::TooDeep(parent)) }
+        Ok(())
+    }
+
+    // allocates an id for a node that is not in the tree yet, which `insert_inner` gives it once it is
+    pub(crate) fn reserve_id(&mut self) -> NodeId {
+        let id = self.ids.alloc();
+        let index = id.index() as usize;
+        if self.paths.len() <= index { self.paths.resize(index + 1, None); }
+        id
+    }
+
+    // reserves room for the buffers of this world to reach the given capacities
+    pub(crate) fn reserve_scratch(&mut self, capacities: &ScratchCapacities) {
+        self.events.reserve(capacities.events.saturating_sub(self.events.len()));
+        self.commands.reserve(capacities.commands);
+    }
+
+    // checks if the node with the given id has a child with the given name, through the names kept for the policy
+    pub(crate) fn is_name_taken(&mut self, parent: NodeId, name: &str) -> bool {
+        let Some(path) = self.path_of(parent) else { return false };
+        self.names.is_taken(self.root.resolve_path(&path).unwrap(), name)
+    }
+
+    /// Moves the child at the given index of the node with the given id to the other index, see `Node::move_child`,
+    /// keeping the ids of every child.
+    ///
+    /// Returns false if the node does not exist or either index is out of bounds.
+    pub fn move_child(&mut self, parent: NodeId, from: usize, to: usize) -> bool {
+        let Some(path) = self.path_of(parent) else { return false };
+        let node = self.root.resolve_path_mut(&path).unwrap();
+        if from >= node.children.len() || to >= node.children.len() { return false }
+
+        node.move_child(from, to);
+        self.recache_children_from(&path, from.min(to));
+        #[cfg(feature = "serde")]
+        self.mark_source_dirty(parent);
+        true
+    }
+
+    /// Sorts the children of the node with the given id by the given key, see `Node::sort_children_by_key`, keeping the
+    /// ids of every child.
+    ///
+    /// Returns false if the node does not exist.
+    pub fn sort_children_by_key<K: Ord>(&mut self, parent: NodeId, key: impl FnMut(&Node<C, A>) -> K) -> bool {
+        let Some(path) = self.path_of(parent) else { return false };
+        self.root.resolve_path_mut(&path).unwrap().sort_children_by_key(key);
+        self.recache_children_from(&path, 0);
+        #[cfg(feature = "serde")]
+        self.mark_source_dirty(parent);
+        true
+    }
+
+    /// Removes the node with the given id and all of its children, firing their remove functions and freeing their ids.
+    ///
+    /// Returns false if the node does not exist or is the root, which cannot be despawned.  `try_despawn` gives the
+    /// reason instead.
+    pub fn despawn(&mut self, id: NodeId) -> bool { self.observed(|world| world.despawn_inner(id)) }
+
+    /// Despawns the node like `despawn`.
+    ///
+    /// Returns `RootImmutable` for the root, or `NotFound` if the node does not exist.
+    pub fn try_despawn(&mut self, id: NodeId) -> Result<(), WorldError> {
+        if id == self.root_id() { return Err(WorldError::RootImmutable) }
+        if self.despawn(id) { Ok(()) } else { Err(WorldError::NotFound(id)) }
+    }
+
+    pub(crate) fn despawn_inner(&mut self, id: NodeId) -> bool {
+        let Some(path) = self.path_of(id) else { return false };
+        let Some(parent_path) = path.parent() else { return false };
+        let idx = *path.indices().last().unwrap() as usize;
+        let parent = self.root.resolve_path_mut(&parent_path).unwrap();
+
+        // free the ids of the whole subtree before it is dropped
+        let mut removed = Vec::new();
+        Self::collect_ids_recr(&parent.children[idx], &mut removed);
+        Self::count_recr(&parent.children[idx], path.depth(), &mut self.counters, false);
+        let parent_id = parent.id;
+        self.names.removed(parent_id, parent.children[idx].name.as_deref());
+        parent.remove_child(idx);
+        for id in removed {
+            if self.ids.free(id) { self.paths[id.index() as usize] = None; }
+            self.names.forget(id);
+        }
+        if let Some(parent) = parent_id { self.record_event(HierarchyEvent::ChildRemoved { parent, child: id }); }
+
+        // the siblings after the removed node moved down one index
+        self.recache_children_from(&parent_path, idx);
+        true
+    }
+
+    /// Moves the node with the given id and its children to the end of the children of the new parent, keeping their
+    /// ids.  The local transform of the node is recalculated so that its global transform as of the last update stays
+    /// the same, on any axis where the new parent has a scale of 0 the local scale is kept.  No added or remove
+    /// functions are fired.
+    ///
+    /// Returns false if either node does not exist, the node is the root, the new parent is the node or one of its
+    /// descendants, the new parent already has 65536 children, or the node or one of its descendants would be deeper
+    /// than the `max_depth` of the config.  `try_reparent` gives the reason instead.
+    pub fn reparent(&mut self, child: NodeId, new_parent: NodeId) -> bool { self.try_reparent(child, new_parent).is_ok() }
+
+    /// Moves the node like `reparent`.
+    ///
+    /// Returns `NotFound` if either node does not exist, `RootImmutable` for the root, `WouldCycle` if the new parent
+    /// is the node or one of its descendants, `TooManyChildren` if the new parent is full, or `TooDeep` if the node
+    /// would end up deeper than the `max_depth` of the config.
+    pub fn try_reparent(&mut self, child: NodeId, new_parent: NodeId) -> Result<(), WorldError> {
+        let child_path = self.path_of(child).ok_or(WorldError::NotFound(child))?;
+        let parent_path = self.path_of(new_parent).ok_or(WorldError::NotFound(new_parent))?;
+        if child_path.depth() == 0 { return Err(WorldError::RootImmutable) }
+        if parent_path.starts_with(&child_path) { return Err(WorldError::WouldCycle(child, new_parent)) }
+        if self.root.resolve_path(&parent_path).unwrap().children.len() > u16::MAX as usize { return Err(WorldError::TooManyChildren(new_parent)) }
+        let height = self.root.resolve_path(&child_path).unwrap().height();
+        if self.config.max_depth.is_some_and(|max| parent_path.depth() + 1 + height > max) { return Err(WorldError::TooDeep(new_parent)) }
+
+        // work out the local transform that keeps the node where it is under its new parent
+        let global = self.root.resolve_path(&child_path).unwrap().global_transform;
+        let parent_global = self.root.resolve_path(&parent_path).unwrap().global_transform;
+        let unscale = |global: f32, parent: f32, local: f32| if parent == 0.0 { local } else { global / parent };
+
+        // detach the node, the new parent may have shifted down if it came after the node
+        let old_parent = self.root.resolve_path(&child_path.parent().unwrap()).unwrap().id;
+        let mut node = self.detach(&child_path);
+        Self::count_recr(&node, child_path.depth(), &mut self.counters, false);
+        self.names.removed(old_parent, node.name.as_deref());
+        let parent_path = self.path_of(new_parent).unwrap();
+
+        node.transform = Transform {
+            position: global.position - parent_global.position,
+            rotation: cgmath::Rotation::invert(&parent_global.rotation) * global.rotation,
+            scale: Vector3 {
+                x: unscale(global.scale.x, parent_global.scale.x, node.transform.scale.x),
+                y: unscale(global.scale.y, parent_global.scale.y, node.transform.scale.y),
+                z: unscale(global.scale.z, parent_global.scale.z, node.transform.scale.z)
+            }
+        };
+
+        // attach it under the new parent
+        let parent = self.root.resolve_path_mut(&parent_path).unwrap();
+        let idx = parent.children.len();
+        let capacity = parent.children.capacity();
+        self.names.added(Some(new_parent), node.name.as_deref());
+        node.parent = Some(new_parent);
+        parent.children.push(node);
+        self.counters.resize_children(capacity, parent.children.capacity());
+        Self::recache_paths_recr(&parent.children[idx], &mut parent_path.child(idx as u16), &self.ids, &mut self.paths);
+        Self::count_recr(&parent.children[idx], parent_path.depth() + 1, &mut self.counters, true);
+        if let Some(old_parent) = old_parent { self.record_event(HierarchyEvent::Reparented { child, old_parent, new_parent }); }
+        Ok(())
+    }
+
+    /// Detaches the node with the given id and its children without firing any remove functions, freeing their ids,
+    /// see `Node::take_child`.
+    ///
+    /// Returns the detached node, or None if the node does not exist or is the root.
+    pub fn take(&mut self, id: NodeId) -> Option<Node<C, A>> {
+        let path = self.path_of(id)?;
+        if path.depth() == 0 { return None }
+
+        let parent = self.root.resolve_path(&path.parent().unwrap()).unwrap().id;
+        let mut node = self.detach(&path);
+        Self::count_recr(&node, path.depth(), &mut self.counters, false);
+        self.names.removed(parent, node.name.as_deref());
+        #[cfg(feature = "lifecycle-audit")]
+        crate::audit::count_tree(&node, &mut self.audit, -1);
+        Self::forget_ids_recr(&mut node, &mut self.ids, &mut self.paths);
+        if let Some(parent) = parent { self.record_event(HierarchyEvent::ChildRemoved { parent, child: id }); }
+        Some(node)
+    }
+
+    // takes the node at the given non empty path out of the tree, recaching the paths of the siblings after it
+    fn detach(&mut self, path: &NodePath) -> Node<C, A> {
+        let parent_path = path.parent().unwrap();
+        let idx = *path.indices().last().unwrap() as usize;
+        let node = self.root.resolve_path_mut(&parent_path).unwrap().take_child(idx);
+        self.recache_children_from(&parent_path, idx);
+        node
+    }
+
+    // frees the ids of the given subtree and clears them from its nodes
+    fn forget_ids_recr(node: &mut Node<C, A>, ids: &mut IdAllocator, paths: &mut [Option<NodePath>]) {
+        if let Some(id) = node.id.take() {
+            if ids.free(id) { paths[id.index() as usize] = None; }
+        }
+        node.parent = None;
+        node.children.iter_mut().for_each(|child| Self::forget_ids_recr(child, ids, paths));
+    }
+
+    // recaches the paths of the children of the node at the given path from the given index on, after they have shifted
+    fn recache_children_from(&mut self, parent_path: &NodePath, from: usize) {
+        let Some(parent) = self.root.resolve_path(parent_path) else { return };
+        for (idx, child) in parent.children.iter().enumerate().take(u16::MAX as usize + 1).skip(from) {
+            Self::recache_paths_recr(child, &mut parent_path.child(idx as u16), &self.ids, &mut self.paths);
+        }
+    }
+
+    /// Gives every matching node in the tree with an empty component the component made for it, firing its added
+    /// function, see `Node::attach_where`.  The reserved root is never given a component, and nodes added through
+    /// `root_mut` are given ids first.  No hierarchy event is recorded as the tree keeps its shape, the nodes given a
+    /// component report it through `Node::is_component_changed` after the next update.
+    pub fn attach_where(&mut self, pred: impl Fn(&Node<C, A>) -> bool, make: impl Fn(&Node<C, A>) -> C) -> AttachReport {
+        self.refresh_ids();
+        let mut report = AttachReport::default();
+        self.observed(|world| world.root.children.iter_mut().for_each(|child| child.attach_where_recr(&pred, &make, &mut report)));
+        self.recount();
+        report
+    }
+
+    /// Starts animating the node with the given id towards the given target, see `Node::tween`.  The tweens of the world
+    /// are advanced by `update`, before the transforms they change are propagated.
+    ///
+    /// Returns the handle of the tween, or None if there is no such node.
+    pub fn tween(&mut self, id: NodeId, target: TweenTarget<Node<C, A>>, duration: f32, easing: Easing) -> Option<TweenHandle> {
+        Some(self.get_mut(id)?.tween(target, duration, easing))
+    }
+
+    /// Stops the tween with the given handle on the node with the given id where it is, see `Node::cancel_tween`.
+    ///
+    /// Returns false if there is no such node or tween.
+    pub fn cancel_tween(&mut self, id: NodeId, handle: TweenHandle) -> bool {
+        self.get_mut(id).is_some_and(|node| node.cancel_tween(handle))
+    }
+
+    /// Passes the asset paths of every component in the tree to the given remap function, see `Node::remap_assets`.
+    pub fn remap_assets(&mut self, remap: impl Fn(&str) -> Option<String>) -> RemapReport { self.root.remap_assets(remap) }
+
+    /// Despawns every node below the root that is in the region by the given mode and passes the filter, firing their
+    /// remove functions, freeing their ids and recording a `ChildRemoved` event for each, see `Node::despawn_in_region`.
+    ///
+    /// Returns the number of nodes removed, including the children of the removed nodes.
+    pub fn despawn_in_region(&mut self, region: &Dimensions, mode: OverlapMode, filter: impl Fn(&Node<C, A>) -> bool) -> usize {
+        let mut paths = Vec::new();
+        self.root.region_paths_recr(region, mode, &filter, &mut NodePath::new(), &mut paths);
+
+        let mut removed = 0;
+        let ids: Vec<NodeId> = paths.iter().filter_map(|path| self.id_at(path)).collect();
+        for id in ids {
+            let count = self.get(id).map_or(0, Node::count_nodes);
+            if self.despawn(id) { removed += count; }
+        }
+        removed
+    }
+
+    /// The number of nodes, depth, components and children capacity of the tree, see `Node::stats`.  These are kept up
+    /// to date by the functions of the world that change the tree, so this does not walk the tree.  Like `id_count`,
+    /// they may be behind if the tree was changed through `root_mut`, `get_mut` or a callback, until `refresh_ids`.
+    pub fn stats(&self) -> TreeStats { self.counters.to_stats(C::VARIANT_NAMES) }
+
+    /// Replaces the component of the node with the given id, see `Node::set_component`, keeping the counts of
+    /// `stats` up to date.
+    ///
+    /// Returns the old component, or None if the node does not exist.
+    pub fn set_component(&mut self, id: NodeId, component: C) -> Option<C> {
+        let new = component.variant_index();
+        let old = self.observed(|world| Some(world.get_mut(id)?.set_component(component)))?;
+        self.counters.set_variant(old.variant_index(), new);
+        self.caps.created(new, id);
+        Some(old)
+    }
+
+    /// Gives the node with the given id a component holding the given data, see `Node::add_component`, keeping the
+    /// counts of `stats` up to date.  Variant caps are not checked.
+    ///
+    /// Returns the data of the replaced component, if any, or `NotFound` if the node does not exist.
+    pub fn add_component<T: ComponentData<C>>(&mut self, id: NodeId, data: T) -> Result<Option<T>, WorldError> {
+        let old = self.variants_of(id).ok_or(WorldError::NotFound(id))?;
+        let replaced = self.observed(|world| world.get_mut(id).unwrap().add_component(data));
+        let new = self.variants_of(id).unwrap_or_default();
+        self.counters.set_variants(&old, &new);
+        if let Some(&variant) = new.iter().find(|variant| !old.contains(variant)) { self.caps.created(Some(variant), id); }
+        Ok(replaced)
+    }
+
+    /// Removes the component of the given type from the node with the given id, see `Node::remove_component`, keeping
+    /// the counts of `stats` up to date.
+    ///
+    /// Returns the data of the removed component, None if the node does not exist or has no component of the type.
+    pub fn remove_component<T: ComponentData<C>>(&mut self, id: NodeId) -> Option<T> {
+        let old = self.variants_of(id)?;
+        let removed = self.observed(|world| world.get_mut(id)?.remove_component::<T>())?;
+        let new = self.variants_of(id).unwrap_or_default();
+        self.counters.set_variants(&old, &new);
+        Some(removed)
+    }
+
+    // the variants of every component of the node with the given id
+    fn variants_of(&self, id: NodeId) -> Option<Vec<usize>> {
+        Some(self.get(id)?.components().filter_map(|component| component.variant_index()).collect())
+    }
+
+    /// Takes all hierarchy events recorded since the last drain, oldest first.  Events are kept until drained, so this
+    /// should be called every frame by anything that records them.
+    pub fn drain_events(&mut self) -> std::vec::Drain<'_, HierarchyEvent> { self.events.drain(..) }
+
+    // records an event for `drain_events`, marking the scene sources it changed as dirty
+    fn record_event(&mut self, event: HierarchyEvent) {
+        self.events.push(event);
+        #[cfg(feature = "serde")]
+        match event {
+            HierarchyEvent::ChildAdded { parent, .. } | HierarchyEvent::ChildRemoved { parent, .. } => self.mark_source_dirty(parent),
+            HierarchyEvent::Reparented { old_parent, new_parent, .. } => {
+                self.mark_source_dirty(old_parent);
+                self.mark_source_dirty(new_parent);
+            }
+        }
+    }
+
+    /// Iterates over every node in the tree in pre-order, starting with the root if include_root is true and with its
+    /// first child otherwise.
+    pub fn iter(&self, include_root: bool) -> Skip<Iter<'_, C, A>> { self.root.iter().skip(usize::from(!include_root)) }
+
+    /// Iterates mutably over every node in the tree in pre-order like `iter`, see `Node::iter_mut`.
+    pub fn iter_mut(&mut self, include_root: bool) -> Skip<IterMut<'_, C, A>> { self.root.iter_mut().skip(usize::from(!include_root)) }
+
+    /// Iterates over the data of every component of the given type in the tree in pre-order, with the id of its node.
+    /// Nodes that have not been given an id yet are skipped, see `refresh_ids`, and so is the root unless include_root
+    /// is true.
+    pub fn iter_components<'a, T: ComponentData<C> + 'a>(&'a self, include_root: bool) -> impl Iterator<Item = (NodeId, &'a T)> + 'a {
+        self.iter(include_root).filter_map(|node| Some((node.id?, T::from_component(&node.component)?)))
+    }
+
+    /// Advances every tween of the world by the given number of seconds, then updates the global transforms and
+    /// dimensions of every node in the tree, calling their update functions, see `Node::update`.  Tweened transforms
+    /// are propagated by the same update.
+    pub fn update(&mut self, app: &mut A, dt: f32) {
+        let start = Instant::now();
+        self.root.advance_tweens(dt);
+        let updated = self.observed(|world| world.root.update_with(&Transform::default(), app, dt, &world.config));
+        self.report.updated += updated;
+        self.report.update_time += start.elapsed();
+    }
+
+    /// Teleports the node with the given id to the given local transform, see `Node::teleport`.
+    ///
+    /// Returns false if the node does not exist.
+    pub fn teleport(&mut self, id: NodeId, new_local: Transform) -> bool {
+        let Some(node) = self.get_mut(id) else { return false };
+        node.teleport(new_local);
+        true
+    }
+
+    /// How far this world is between its last fixed step and the next one, from 0 to 1, to give
+    /// `Node::interpolated_transform` when drawing between fixed steps.
+    pub fn interpolation_alpha(&self) -> f32 { (self.fixed_accumulator / self.config.fixed_timestep).clamp(0.0, 1.0) }
+
+    /// Adds a layer with the given name, IE `"world"`, `"ui"` or `"debug"`, as a new child of the root.  Each layer is
+    /// an independent tree that can be updated, enabled and drawn on its own, with `draw_node` in its own pass and
+    /// camera.
+    ///
+    /// Returns the id of the layers node, the existing one if there is already a layer with the name, or None if the
+    /// root has no room for another child.
+    pub fn add_layer(&mut self, name: &str) -> Option<NodeId> {
+        if let Some(id) = self.layer(name) { return Some(id) }
+
+        let node = Node { name: Some(name.to_string()), ..Node::default() };
+        let id = self.spawn(self.root_id(), node)?;
+        self.layers.retain(|(layer, _)| layer != name);
+        self.layers.push((name.to_string(), id));
+        Some(id)
+    }
+
+    /// The id of the node of the layer with the given name, None if there is no such layer or it was despawned.
+    pub fn layer(&self, name: &str) -> Option<NodeId> {
+        self.layers.iter().find(|(layer, id)| layer == name && self.is_alive(*id)).map(|(_, id)| *id)
+    }
+
+    /// Iterates over the names and node ids of every layer that is still alive, in the order they were added.
+    pub fn layers(&self) -> impl Iterator<Item = (&str, NodeId)> + '_ {
+        self.layers.iter().filter(|(_, id)| self.is_alive(*id)).map(|(name, id)| (name.as_str(), *id))
+    }
+
+    /// Updates only the layer with the given name, advancing only its tweens, see `update`.  Returns false if there is no
+    /// such layer.
+    pub fn update_layer(&mut self, name: &str, app: &mut A, dt: f32) -> bool {
+        let Some(id) = self.layer(name) else { return false };
+        let parent = self.root.global_transform;
+        let path = self.path_of(id).unwrap();
+        let start = Instant::now();
+        self.root.resolve_path_mut(&path).unwrap().advance_tweens(dt);
+        let updated = self.observed(|world| world.root.resolve_path_mut(&path).unwrap().update_with(&parent, app, dt, &world.config));
+        self.report.updated += updated;
+        self.report.update_time += start.elapsed();
+        true
+    }
+
+    /// Enables or disables the layer with the given name, see `set_enabled`.  Returns false if there is no such layer.
+    pub fn set_layer_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        let Some(id) = self.layer(name) else { return false };
+        self.set_enabled(id, enabled)
+    }
+
+    /// Sets the enabled flag of the node with the given id, see `Node::set_enabled`.  The enable or disable functions
+    /// only fire if every ancestor of the node is enabled, as otherwise the node is not updated either way.
+    ///
+    /// Returns false if there is no such node.
+    pub fn set_enabled(&mut self, id: NodeId, enabled: bool) -> bool {
+        let active = self.ancestors(id).all(|ancestor| ancestor.enabled);
+        self.observed(|world| {
+            let Some(node) = world.get_mut(id) else { return false };
+            if active { node.set_enabled(enabled); } else { node.enabled = enabled; }
+            true
+        })
+    }
+
+    /// The number of seconds between fixed updates, see `set_fixed_timestep`.
+    pub fn fixed_timestep(&self) -> f32 { self.config.fixed_timestep }
+
+    /// Sets the number of seconds between the fixed updates run by `tick`, defaults to 1/60th of a second, see
+    /// `WorldConfig::fixed_timestep`.  Time left over from earlier ticks is kept, so fixed updates run at this rate on
+    /// average whatever the frame rate.
+    ///
+    /// Panics if the timestep is not above 0.
+    pub fn set_fixed_timestep(&mut self, seconds: f32) {
+        assert!(seconds > 0.0, "the fixed timestep must be above 0, was {seconds}");
+        self.config.fixed_timestep = seconds;
+    }
+
+    /// Passes the given input to the input functions of the tree top down until one consumes it, see `Node::input`.  This
+    /// is meant to be called from `EngineApp::input`.
+    ///
+    /// Returns true if the input was consumed.
+    pub fn input(&mut self, input: &EngineInput) -> bool { self.observed(|world| world.root.input(input)) }
+
+    /// Passes the new size of the window to the resize functions of every node in the tree, see `Node::resize`.  This is
+    /// meant to be called from `EngineApp::resize`.
+    pub fn resize(&mut self, size: PhysicalSize<u32>) { self.observed(|world| world.root.resize(size)); }
+
+    /// Passes the render engine to the prepare functions of every enabled node in the tree, see `Node::prepare`.  This
+    /// is meant to be called from `EngineApp::render` before the render pass is begun.
+    pub fn prepare(&mut self, engine: &mut RenderEngine) { self.observed(|world| world.root.prepare(engine)); }
+
+    /// Calls the fixed update functions of every enabled node in the tree once, see `tick`.
+    pub fn fixed_update(&mut self) {
+        if self.root.enabled { self.observed(|world| world.root.fixed_update_recr()); }
+    }
+
+    /// Sets the function given each callback panic caught with the `catch-callbacks` feature during the functions of
+    /// this world, after the function that ran the callback returns, IE to show the error in an editor.  Panics are
+    /// still logged, and without the feature the observer is never called as panics are not caught.
+    pub fn set_panic_observer(&mut self, observer: impl FnMut(&CallbackPanic) + Send + Sync + 'static) {
+        self.panic_observer = Some(Box::new(observer));
+    }
+
+    /// Removes the function given to `set_panic_observer`.
+    pub fn clear_panic_observer(&mut self) { self.panic_observer = None; }
+
+    /// Clears the poison of the node with the given id, so its callbacks run again, see `Node::clear_poison`.
+    ///
+    /// Returns false if there is no such node.
+    pub fn clear_poison(&mut self, id: NodeId) -> bool {
+        let Some(node) = self.get_mut(id) else { return false };
+        node.clear_poison();
+        true
+    }
+
+    // runs the given function, then passes the callback panics caught while it ran to the panic observer
+    pub(crate) fn observed<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        // the callbacks fired by the function are counted for this world
+        #[cfg(feature = "lifecycle-audit")]
+        let f = |world: &mut Self| {
+            let counts = std::mem::take(&mut world.audit);
+            let (result, counts) = crate::audit::collect(counts, || f(world));
+            world.audit = counts;
+            result
+        };
+
+        if !cfg!(feature = "catch-callbacks") { return f(self) }
+
+        let (result, panics) = callbacks::collect_panics(|| f(self));
+        if let Some(observer) = self.panic_observer.as_mut() { panics.iter().for_each(observer); }
+        result
+    }
+
+    /// The number of the current frame, which nodes drawn with `draw` are stamped with.  This starts at 0 and is
+    /// advanced by each `tick`.
+    pub fn frame(&self) -> u64 { self.frame }
+
+    /// Starts a new frame for the stamps of `draw`, for apps that update the world without `tick`.
+    ///
+    /// Returns the number of the new frame.
+    pub fn advance_frame(&mut self) -> u64 {
+        self.frame = self.frame.wrapping_add(1) % u64::MAX;
+        self.frame
+    }
+
+    /// Draws the tree below the root into the given pass like `DrawNodes::draw_node`, or `draw_node_culled` when given
+    /// a culling, stamping each drawn node with the current frame, see `frame`, unless `render_stamps` is off in the
+    /// config.  The root itself is never drawn.  Every pass drawn in the same frame counts towards `rendered_count`.
+    pub fn draw<'a, 'b>(&'b self, pass: &mut C::Pass<'a>, app: &'b A, culling: Option<&DrawCulling>) where 'b: 'a {
+        let start = Instant::now();
+        let stamp = DrawStamp { frame: self.config.render_stamps.then_some(self.frame), counters: Some(&self.report), buffers: Some(&self.draw_buffers) };
+        draw_tree(pass, app, &self.root, culling, stamp, false);
+        self.report.add_draw_time(start.elapsed());
+    }
+
+    // calls the given function with the draw list of the tree below the root, in the order `draw` renders it, built
+    // with the buffers of `draw` so the allocations are reused
+    pub(crate) fn with_draw_list<R>(&mut self, culling: Option<&DrawCulling>, f: impl FnOnce(&[&Node<C, A>]) -> R) -> R {
+        let World { root, draw_buffers, .. } = self;
+        let buffers = draw_buffers.get_mut().unwrap_or_else(PoisonError::into_inner);
+        let DrawBuffers { list, scratch } = std::mem::take(buffers);
+        let (mut list, mut scratch) = (recycle(list), recycle(scratch));
+        root.build_draw_list_counted(culling, None, &mut list, &mut scratch);
+        list.retain(|node| !std::ptr::eq(*node, &*root));
+        let result = f(&list);
+        *buffers = DrawBuffers { list: recycle(list), scratch: recycle(scratch) };
+        result
+    }
+
+    /// The number of nodes drawn by `draw` in the current frame, see `Node::rendered_count`.
+    pub fn rendered_count(&self) -> usize { self.root.rendered_count(self.frame) }
+
+    /// True if the node with the given id was drawn by `draw` in the current frame.
+    pub fn was_rendered(&self, id: NodeId) -> bool { self.get(id).is_some_and(|node| node.was_rendered(self.frame)) }
+
+    /// Steps the world forward by the given number of seconds, starting a new frame, despawning every node whose lifetime
+    /// has run out, see `Node::despawn_after`, running a fixed update for each fixed timestep that has passed, see
+    /// `set_fixed_timestep`, and then updating the tree, which advances every tween.
+    ///
+    /// At most `MAX_FIXED_STEPS` fixed updates are run in one tick, dropping the rest of the time, so a long frame does
+    /// not make the next frame even longer.
+    pub fn tick(&mut self, app: &mut A, dt: f32) {
+        self.begin_frame();
+
+        // nodes below an expired node are not counted down, they are despawned with it, and the root never expires
+        self.root.lifetime = None;
+        let mut expired = Vec::new();
+        self.root.advance_lifetimes_recr(dt, &mut NodePath::new(), &mut expired);
+        if !expired.is_empty() {
+            // despawn from the last path first so removing a node does not shift the paths still to be despawned
+            let ids: Vec<NodeId> = expired.iter().rev().filter_map(|path| self.id_at(path)).collect();
+            ids.into_iter().for_each(|id| { self.despawn(id); });
+        }
+
+        if C::FIXED_UPDATES {
+            self.fixed_accumulator += dt;
+            let mut steps = 0;
+            while self.fixed_accumulator >= self.config.fixed_timestep {
+                if steps == MAX_FIXED_STEPS { self.fixed_accumulator = 0.0; break }
+                self.fixed_accumulator -= self.config.fixed_timestep;
+                self.fixed_update();
+                steps += 1;
+            }
+        }
+
+        self.update(app, dt);
+    }
+
+    /// Finds the id of the node at the given path, giving it one if it does not have one yet.
+    pub fn id_at(&mut self, path: &NodePath) -> Option<NodeId> {
+        if self.root.resolve_path(path)?.id.is_none() { self.refresh_ids(); }
+        self.root.resolve_path(path)?.id
+    }
+
+    /// Walks the whole tree, giving nodes without an id a new one, recaching the path of every node, and freeing the ids
+    /// of nodes that are no longer in the tree.  Only the first 65536 children of a node can be given ids, as paths
+    /// can't reach any further.
+    pub fn refresh_ids(&mut self) {
+        let mut seen = vec![false; self.ids.slots()];
+        Self::refresh_ids_recr(&mut self.root, None, &mut NodePath::new(), &mut self.ids, &mut self.paths, &mut seen);
+
+        // anything not seen has been removed from the tree
+        let stale: Vec<NodeId> = self.ids.iter().filter(|id| !seen.get(id.index() as usize).copied().unwrap_or(false)).collect();
+        for id in stale {
+            self.ids.free(id);
+            self.paths[id.index() as usize] = None;
+        }
+        self.names.clear();
+        self.recount();
+    }
+
+    // counts the whole tree again, after it was changed in ways the counters could not follow
+    fn recount(&mut self) {
+        self.counters = StatCounters::default();
+        Self::count_recr(&self.root, 0, &mut self.counters, true);
+    }
+
+    // adds the given subtree at the given depth to the counters or removes it, skipping nodes the world has not seen
+    fn count_recr(node: &Node<C, A>, depth: usize, counters: &mut StatCounters, add: bool) {
+        if node.id.is_some() {
+            let (variant, capacity) = (node.component.variant_index(), node.children.capacity());
+            if add { counters.add(variant, depth, capacity) } else { counters.remove(variant, depth, capacity) }
+            node.added_components.iter().filter_map(|added| added.component.variant_index()).for_each(|variant| counters.count_added(variant, add));
+        }
+        node.children.iter().for_each(|child| Self::count_recr(child, depth + 1, counters, add));
+    }
+
+    fn refresh_ids_recr(node: &mut Node<C, A>, parent: Option<NodeId>, path: &mut NodePath, ids: &mut IdAllocator, paths: &mut Vec<Option<NodePath>>, seen: &mut Vec<bool>) {
+        // keep the nodes id unless it is stale or another node already claimed it, IE the node was duplicated
+        let kept = node.id.filter(|id| ids.is_live(*id) && !seen.get(id.index() as usize).copied().unwrap_or(false));
+        let id = kept.unwrap_or_else(|| ids.alloc());
+        node.id = Some(id);
+        node.parent = parent;
+
+        let index = id.index() as usize;
+        if seen.len() <= index { seen.resize(index + 1, false); }
+        if paths.len() <= index { paths.resize(index + 1, None); }
+        seen[index] = true;
+        paths[index] = Some(path.clone());
+
+        for (idx, child) in node.children.iter_mut().take(u16::MAX as usize + 1).enumerate() {
+            path.push(idx as u16);
+            Self::refresh_ids_recr(child, Some(id), path, ids, paths, seen);
+            path.pop();
+        }
+    }
+
+    fn index_new_recr(node: &mut Node<C, A>, path: &mut NodePath, ids: &mut IdAllocator, paths: &mut Vec<Option<NodePath>>, reserved: Option<NodeId>, parent: Option<NodeId>) {
+        let id = reserved.unwrap_or_else(|| ids.alloc());
+        node.id = Some(id);
+        node.parent = parent;
+
+        let index = id.index() as usize;
+        if paths.len() <= index { paths.resize(index + 1, None); }
+        paths[index] = Some(path.clone());
+
+        for (idx, child) in node.children.iter_mut().take(u16::MAX as usize + 1).enumerate() {
+            path.push(idx as u16);
+            Self::index_new_recr(child, path, ids, paths, None, Some(id));
+            path.pop();
+        }
+    }
+
+    fn collect_ids_recr(node: &Node<C, A>, out: &mut Vec<NodeId>) {
+        out.extend(node.id);
+        node.children.iter().for_each(|child| Self::collect_ids_recr(child, out));
+    }
+
+    fn recache_paths_recr(node: &Node<C, A>, path: &mut NodePath, ids: &IdAllocator, paths: &mut [Option<NodePath>]) {
+        if let Some(id) = node.id.filter(|id| ids.is_live(*id)) { paths[id.index() as usize] = Some(path.clone()); }
+
+        for (idx, child) in node.children.iter().take(u16::MAX as usize + 1).enumerate() {
+            path.push(idx as u16);
+            Self::recache_paths_recr(child, path, ids, paths);
+            path.pop();
+        }
+    }
+}
+
+/// What `Node::visit` should do after visiting a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisitControl {
+    /// Visit the children of the node, then carry on.
+    Continue,
+
+    /// Carry on without visiting the children of the node.
+    SkipChildren,
+
+    /// Stop visiting any more nodes.
+    Stop
+}
+
+/// An iterator over a node and its descendants, see `Node::iter` and `Node::iter_bfs`.
+pub struct Iter<'a, C, A> {
+    queue: VecDeque<&'a Node<C, A>>,
+    breadth_first: bool
+}
+
+impl<'a, C, A> Iter<'a, C, A> {
+    fn new(node: &'a Node<C, A>, breadth_first: bool) -> Self { Self { queue: VecDeque::from([node]), breadth_first } }
+}
+
+impl<'a, C, A> Iterator for Iter<'a, C, A> {
+    type Item = &'a Node<C, A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // depth first takes from the back with children pushed in reverse, so the first child comes out next
+        if self.breadth_first {
+            let node = self.queue.pop_front()?;
+            self.queue.extend(node.children.iter());
+            Some(node)
+        } else {
+            let node = self.queue.pop_back()?;
+            self.queue.extend(node.children.iter().rev());
+            Some(node)
+        }
+    }
+}
+
+/// A mutable view of a node given by `IterMut`, borrowing every public field but the children.
+pub struct NodeMut<'a, C, A> {
+    pub transform: &'a mut Transform,
+    pub component: &'a mut C,
+    pub rel_min_dimensions: &'a mut Dimensions,
+    pub transform_mode: &'a mut TransformMode,
+    pub confine_to_parent: &'a mut bool,
+    pub name: &'a mut Option<String>,
+    pub enabled: &'a mut bool,
+    pub metadata: &'a mut HashMap<String, String>,
+    pub tags: &'a mut Vec<String>,
+
+    id: Option<NodeId>,
+    global_transform: &'a Transform,
+    dimensions: &'a Dimensions,
+    app: PhantomData<fn() -> A>
+}
+
+impl<C, A> NodeMut<'_, C, A> {
+    /// The global transform of the node as of the last update.
+    pub fn global_transform(&self) -> &Transform { self.global_transform }
+
+    /// The dimensions of the node merged with all of its children as of the last update.
+    pub fn dimensions(&self) -> &Dimensions { self.dimensions }
+
+    /// The id of the node in the `World` it belongs to, None if it has not been given one.
+    pub fn id(&self) -> Option<NodeId> { self.id }
+}
+
+/// A mutable iterator over a node and its descendants, see `Node::iter_mut` and `Node::iter_mut_bfs`.
+pub struct IterMut<'a, C, A> {
+    queue: VecDeque<&'a mut Node<C, A>>,
+    breadth_first: bool
+}
+
+impl<'a, C, A> IterMut<'a, C, A> {
+    fn new(node: &'a mut Node<C, A>, breadth_first: bool) -> Self { Self { queue: VecDeque::from([node]), breadth_first } }
+}
+
+impl<'a, C, A> Iterator for IterMut<'a, C, A> {
+    type Item = NodeMut<'a, C, A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = if self.breadth_first { self.queue.pop_front()? } else { self.queue.pop_back()? };
+
+        // split the node so its children can be queued while the rest of it is given out
+        let Node { transform, component, rel_min_dimensions, transform_mode, confine_to_parent, name, enabled, metadata, tags, id, global_transform, dimensions, children, .. } = node;
+        if self.breadth_first { self.queue.extend(children.iter_mut()); } else { self.queue.extend(children.iter_mut().rev()); }
+
+        Some(NodeMut {
+            transform,
+            component,
+            rel_min_dimensions,
+            transform_mode,
+            confine_to_parent,
+            name,
+            enabled,
+            metadata,
+            tags,
+            id: *id,
+            global_transform,
+            dimensions,
+            app: PhantomData
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Quaternion, Vector3};
+    use forte_engine::math::transforms::Transform;
+
+    use crate::{
+        culling::CullVolume, dimensions::{Dimensions, OverlapMode}, easing::Easing, errors::WorldError, tweens::TweenTarget, events::HierarchyEvent, ids::{NodeId, NodeUuid}, inspector::Inspector, math::TransformMode, paths::NodePath,
+        queries::AttachReport, test_support::*
+    };
+
+    // a world whose variants are drawn in front of or behind each other, each recording its id when drawn
+    #[allow(dead_code)]
+    mod ordered {
+        pub struct TestApp;
+
+        #[derive(Debug)]
+        pub struct Back(pub u32);
+
+        #[derive(Debug)]
+        pub struct Middle(pub u32);
+
+        #[derive(Debug)]
+        pub struct Front(pub u32);
+
+        // a sort key and an id
+        #[derive(Debug)]
+        pub struct Keyed(pub u64, pub u32);
+
+        #[derive(Default)]
+        pub struct TestPass<'a>(pub Vec<u32>, pub std::marker::PhantomData<&'a ()>);
+
+        crate::define_world!(TestApp, [
+            Back => { DATA => Back, RENDER => |pass: &mut TestPass<'a>, _: &'b TestApp, data: &'b Back| pass.0.push(data.0), PRIORITY => -1 },
+            Middle => { DATA => Middle, RENDER => |pass: &mut TestPass<'a>, _: &'b TestApp, data: &'b Middle| pass.0.push(data.0) },
+            Front => { DATA => Front, RENDER => |pass: &mut TestPass<'a>, _: &'b TestApp, data: &'b Front| pass.0.push(data.0), PRIORITY => 1 },
+            Keyed => {
+                DATA => Keyed,
+                RENDER => |pass: &mut TestPass<'a>, _: &'b TestApp, data: &'b Keyed| pass.0.push(data.1),
+                SORT_KEY => |data: &Keyed| data.0
+            }
+        ], PASS => TestPass<'a>);
+    }
+
+    // a world without priorities, with two sort keyed materials and an unkeyed variant, each recording its id when drawn
+    #[allow(dead_code)]
+    mod sorted {
+        pub struct TestApp;
+
+        // a material and an id
+        #[derive(Debug)]
+        pub struct Textured(pub u64, pub u32);
+
+        #[derive(Debug)]
+        pub struct Lit(pub u64, pub u32);
+
+        #[derive(Debug)]
+        pub struct Plain(pub u32);
+
+        #[derive(Default)]
+        pub struct TestPass<'a>(pub Vec<u32>, pub std::marker::PhantomData<&'a ()>);
+
+        crate::define_world!(TestApp, [
+            Textured => { DATA => Textured, RENDER => |pass: &mut TestPass<'a>, _: &'b TestApp, data: &'b Textured| pass.0.push(data.1), SORT_KEY => |data: &Textured| data.0 },
+            Lit => { DATA => Lit, RENDER => |pass: &mut TestPass<'a>, _: &'b TestApp, data: &'b Lit| pass.0.push(data.1), SORT_KEY => |data: &Lit| data.0 },
+            Plain => { DATA => Plain, RENDER => |pass: &mut TestPass<'a>, _: &'b TestApp, data: &'b Plain| pass.0.push(data.0) }
+        ], PASS => TestPass<'a>);
+    }
+
+    // a world whose nodes hold several components, each logging its callbacks
+    #[allow(dead_code)]
+    mod multi {
+        use std::cell::RefCell;
+
+        pub struct TestApp;
+
+        #[derive(Debug, PartialEq)]
+        pub struct Health(pub u32);
+
+        #[derive(Debug, PartialEq)]
+        pub struct Armor(pub u32);
+
+        #[derive(Default)]
+        pub struct TestPass<'a>(pub Vec<String>, pub std::marker::PhantomData<&'a ()>);
+
+        thread_local! {
+            pub static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+        }
+
+        fn log(entry: String) { LOG.with(|log| log.borrow_mut().push(entry)); }
+
+        crate::define_world!(TestApp, [
+            Health => {
+                DATA => Health,
+                ADDED => |data: &mut Health, _: &mut NodeContext| log(format!("added health {}", data.0)),
+                UPDATE => |node: &mut Node| log(format!("update health, armor {:?}", node.get::<Armor>().map(|armor| armor.0))),
+                RENDER => |pass: &mut TestPass<'a>, _: &'b TestApp, data: &'b Health| pass.0.push(format!("health {}", data.0)),
+                REMOVED => |data: &mut Health, _: &mut NodeContext, reason: &crate::callbacks::RemovalReason| log(format!("removed health {} {reason:?}", data.0)),
+                START => |node: &mut Node| log(format!("start health {}", node.get::<Health>().unwrap().0))
+            },
+            Armor => {
+                DATA => Armor,
+                ADDED => |data: &mut Armor, _: &mut NodeContext| log(format!("added armor {}", data.0)),
+                UPDATE => |node: &mut Node| log(format!("update armor, health {:?}", node.get::<Health>().map(|health| health.0))),
+                RENDER => |pass: &mut TestPass<'a>, _: &'b TestApp, data: &'b Armor| pass.0.push(format!("armor {}", data.0)),
+                REMOVED => |data: &mut Armor, _: &mut NodeContext, reason: &crate::callbacks::RemovalReason| log(format!("removed armor {} {reason:?}", data.0)),
+                START => |node: &mut Node| log(format!("start armor {}", node.get::<Armor>().unwrap().0))
+            }
+        ], PASS => TestPass<'a>);
+
+        pub fn take_log() -> Vec<String> { LOG.with(|log| std::mem::take(&mut *log.borrow_mut())) }
+    }
+
+    // a world whose components can be serialized
+    #[cfg(feature = "serde")]
+    #[allow(dead_code)]
+    mod serialized {
+        pub struct TestApp;
+
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        pub struct Marker(pub u32);
+
+        crate::define_world!(TestApp, [
+            Marker => { DATA => Marker }
+        ], DERIVE => [serde::Serialize, serde::Deserialize]);
+    }
+
+    // records the name of every field and overwrites the strings and bools it is given
+    #[derive(Default)]
+    struct Recorder { fields: Vec<String>, string: Option<String>, flag: Option<bool> }
+
+    impl Inspector for Recorder {
+        fn field_f32(&mut self, name: &str, _: &mut f32) { self.fields.push(name.to_string()); }
+        fn field_vec3(&mut self, name: &str, _: &mut Vector3<f32>) { self.fields.push(name.to_string()); }
+        fn field_quat(&mut self, name: &str, _: &mut Quaternion<f32>) { self.fields.push(name.to_string()); }
+
+        fn field_bool(&mut self, name: &str, value: &mut bool) {
+            self.fields.push(format!("{name}={value}"));
+            if let Some(flag) = self.flag { *value = flag; }
+        }
+
+        fn field_string(&mut self, name: &str, value: &mut String) {
+            self.fields.push(format!("{name}={value}"));
+            if let Some(string) = &self.string { *value = string.clone(); }
+        }
+    }
+
+    #[test]
+    fn inspect_common_exposes_node_fields() {
+        let mut node = marker(0);
+        node.name = Some("player".to_string());
+        node.add_tag("a");
+        node.add_tag("b");
+
+        let mut ui = Recorder::default();
+        node.inspect_common(&mut ui);
+        assert_eq!(ui.fields, ["position", "rotation", "scale", "name=player", "enabled=true", "tags=a,b", "confine_to_parent=false"]);
+
+        let mut ui = Recorder { string: Some("c, d".to_string()), flag: Some(false), ..Recorder::default() };
+        node.inspect_common(&mut ui);
+        assert_eq!(node.name.as_deref(), Some("c, d"));
+        assert!(!node.enabled);
+        assert_eq!(node.tags, ["c", "d"]);
+        assert!(!node.confine_to_parent);
+
+        let mut ui = Recorder { string: Some(String::new()), flag: Some(true), ..Recorder::default() };
+        node.inspect_common(&mut ui);
+        assert_eq!(node.name, None);
+        assert!(node.tags.is_empty());
+        assert!(node.enabled && node.confine_to_parent);
+    }
+
+    #[test]
+    fn two_dimensional_nodes_overlap() {
+        let flat = |x: f32| {
+            let mut node = marker(0);
+            node.transform.position.x = x;
+            node.transform_mode = TransformMode::TwoD;
+            node.rel_min_dimensions = Dimensions { from: Vector3::new(-1.0, -1.0, -1.0), to: Vector3::new(1.0, 1.0, 1.0) };
+            node
+        };
+        let mut root = Node::default();
+        root.add_child(flat(0.0));
+        root.add_child(flat(1.5));
+        root.add_child(flat(5.0));
+        root.update(&Transform::default(), &mut TestApp, 0.0);
+
+        let [a, b, c] = [0, 1, 2].map(|idx| root.children()[idx].dimensions);
+        assert_eq!((a.from.z, a.to.z), (0.0, 0.0));
+        assert!(a.overlap(&b));
+        assert!(!a.overlap(&c));
+        assert_eq!(root.query_overlapping_paths(&b), [NodePath::from(&[0][..]), NodePath::from(&[1][..])]);
+    }
+
+    // a two dimensional marker at the given position, turned by the given degrees around z and with the given scale
+    fn sprite(id: u32, position: Vector3<f32>, degrees: f32, scale: Vector3<f32>) -> Node {
+        let mut node = marker(id);
+        node.transform_mode = TransformMode::TwoD;
+        node.transform = Transform { position, rotation: cgmath::Rotation3::from_angle_z(cgmath::Deg(degrees)), scale };
+        node
+    }
+
+    fn assert_close(a: f32, b: f32) { assert!((a - b).abs() < 1e-4, "{a} != {b}"); }
+
+    #[test]
+    fn two_dimensional_children_compose_with_their_parents() {
+        let mut parent = sprite(1, Vector3::new(1.0, 2.0, 0.0), 30.0, Vector3::new(2.0, 3.0, 5.0));
+        parent.add_child(sprite(2, Vector3::new(1.0, 1.0, 1.0), 15.0, Vector3::new(2.0, 2.0, 7.0)));
+        parent.update(&Transform::default(), &mut TestApp, 0.0);
+
+        // positions add, angles add, x and y scales multiply and the z scale of the parent is kept
+        let child = parent.children()[0].global_transform();
+        assert_eq!(child.position, Vector3::new(2.0, 3.0, 1.0));
+        assert_close(crate::math::z_angle(child.rotation).to_degrees(), 45.0);
+        assert_eq!(child.scale, Vector3::new(4.0, 6.0, 5.0));
+    }
+
+    #[test]
+    fn two_dimensional_subtrees_follow_a_turned_three_dimensional_parent() {
+        let mut parent = marker(0);
+        parent.transform = Transform {
+            position: Vector3::new(5.0, 0.0, 0.0),
+            rotation: cgmath::Rotation3::from_angle_z(cgmath::Deg(90.0)),
+            scale: Vector3::new(2.0, 2.0, 2.0)
+        };
+        let mut child = sprite(1, Vector3::new(0.0, 1.0, 2.0), 10.0, Vector3::new(1.0, 1.0, 1.0));
+        child.add_child(sprite(2, Vector3::new(1.0, 0.0, 1.0), 20.0, Vector3::new(3.0, 1.0, 1.0)));
+        parent.add_child(child);
+        parent.update(&Transform::default(), &mut TestApp, 0.0);
+
+        // the first sprite takes the full rotation of its parent, and its own child turns on from there
+        let child = parent.children()[0].global_transform();
+        assert_eq!(child.position, Vector3::new(5.0, 1.0, 2.0));
+        assert_close(crate::math::z_angle(child.rotation).to_degrees(), 100.0);
+        assert_eq!(child.scale, Vector3::new(2.0, 2.0, 2.0));
+        let grandchild = parent.children()[0].children()[0].global_transform();
+        assert_eq!(grandchild.position, Vector3::new(6.0, 1.0, 3.0));
+        assert_close(crate::math::z_angle(grandchild.rotation).to_degrees(), 120.0);
+        assert_eq!(grandchild.scale, Vector3::new(6.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn teleports_skip_velocity_and_interpolation_for_the_subtree() {
+        let mut world = World::new();
+        let parent = world.spawn(world.root_id(), marker(1)).unwrap();
+        let mut child = marker(2);
+        child.transform.position.y = 1.0;
+        let child = world.spawn(parent, child).unwrap();
+
+        // the first update has nothing to move from
+        world.update(&mut TestApp, 0.5);
+        assert!(world.get(child).unwrap().teleported_this_frame());
+        assert_eq!(world.get(child).unwrap().velocity(), Vector3::new(0.0, 0.0, 0.0));
+
+        // moving the transform as usual gives a velocity and interpolates between the poses
+        world.get_mut(parent).unwrap().transform.position.x = 2.0;
+        world.update(&mut TestApp, 0.5);
+        let node = world.get(child).unwrap();
+        assert!(!node.teleported_this_frame());
+        assert_eq!(node.velocity(), Vector3::new(4.0, 0.0, 0.0));
+        assert_eq!(node.previous_global_transform().position, Vector3::new(0.0, 1.0, 0.0));
+        assert_eq!(node.interpolated_transform(0.5).position, Vector3::new(1.0, 1.0, 0.0));
+
+        // a teleport takes the whole subtree straight to the new pose
+        let mut far = Transform::default();
+        far.position.x = 100.0;
+        assert!(world.teleport(parent, far));
+        world.update(&mut TestApp, 0.5);
+        for id in [parent, child] {
+            let node = world.get(id).unwrap();
+            assert!(node.teleported_this_frame());
+            assert_eq!(node.velocity(), Vector3::new(0.0, 0.0, 0.0));
+            assert_eq!(node.previous_global_transform().position, node.global_transform().position);
+            assert_eq!(node.interpolated_transform(0.25).position, node.global_transform().position);
+        }
+        assert_eq!(world.get(child).unwrap().global_transform().position, Vector3::new(100.0, 1.0, 0.0));
+
+        // and clears with the next update
+        world.update(&mut TestApp, 0.5);
+        assert!(!world.get(child).unwrap().teleported_this_frame());
+        let gone = world.spawn(world.root_id(), marker(3)).unwrap();
+        assert!(world.despawn(gone));
+        assert!(!world.teleport(gone, far));
+    }
+
+    #[test]
+    fn two_dimensional_siblings_draw_in_layer_order() {
+        let at_layer = |id: u32, z: f32| sprite(id, Vector3::new(0.0, 0.0, z), 0.0, Vector3::new(1.0, 1.0, 1.0));
+        let mut world = World::new();
+        let root = world.root_id();
+        let layers = world.spawn(root, at_layer(10, 0.0)).unwrap();
+        world.spawn(layers, at_layer(3, 3.0));
+        world.spawn(layers, marker(100));
+        world.spawn(layers, at_layer(1, 1.0));
+        world.spawn(layers, at_layer(2, -0.5));
+        world.update(&mut TestApp, 0.0);
+
+        // the layers compose with the parent, and the three dimensional sibling keeps its slot
+        let mut drawn = Vec::new();
+        world.get(layers).unwrap().for_each_child_in_draw_order(|child| drawn.push(child.get::<Marker>().unwrap().0));
+        assert_eq!(drawn, [2, 100, 1, 3]);
+        let mut pass = TestPass::default();
+        world.draw(&mut pass, &TestApp, None);
+        assert_eq!(pass.0, [10, 2, 100, 1, 3]);
+    }
+
+    #[cfg(feature = "catch-callbacks")]
+    #[test]
+    fn panicking_update_poisons_only_its_node() {
+        use std::sync::{Arc, Mutex};
+
+        use crate::callbacks::CallbackPanic;
+
+        let mut world = World::new();
+        let root = world.root_id();
+        let mut node = marker(0);
+        node.name = Some("panic".to_string());
+        let bad = world.spawn(root, node).unwrap();
+        let child = world.spawn(bad, marker(1)).unwrap();
+        let good = world.spawn(root, marker(2)).unwrap();
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let observed = seen.clone();
+        world.set_panic_observer(move |panic| observed.lock().unwrap().push(panic.clone()));
+
+        world.update(&mut TestApp, 0.0);
+        world.update(&mut TestApp, 0.0);
+        assert!(world.get(bad).unwrap().is_poisoned());
+        assert!(!world.get(child).unwrap().is_poisoned() && !world.get(good).unwrap().is_poisoned());
+        assert_eq!(*seen.lock().unwrap(), [CallbackPanic { node: Some(bad), variant: "Marker", message: "boom".to_string() }]);
+
+        world.get_mut(bad).unwrap().name = None;
+        assert!(world.clear_poison(bad));
+        world.update(&mut TestApp, 0.0);
+        assert!(!world.get(bad).unwrap().is_poisoned());
+        assert_eq!(seen.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn world_tween() {
+        let mut world = World::new();
+        let id = world.spawn(world.root_id(), marker(0)).unwrap();
+        let handle = world.tween(id, TweenTarget::Position(Vector3::new(4.0, 0.0, 0.0)), 1.0, Easing::Linear).unwrap();
+
+        // a plain update advances the tween before propagating, so the global transform follows in the same frame
+        world.update(&mut TestApp, 0.25);
+        assert_eq!(world.get(id).unwrap().transform.position.x, 1.0);
+        assert_eq!(world.get(id).unwrap().global_transform().position.x, 1.0);
+
+        // a tick advances it once, through its update
+        world.tick(&mut TestApp, 0.25);
+        assert_eq!(world.get(id).unwrap().transform.position.x, 2.0);
+        assert_eq!(world.get(id).unwrap().global_transform().position.x, 2.0);
+
+        assert!(world.cancel_tween(id, handle));
+        assert!(!world.cancel_tween(id, handle));
+        world.tick(&mut TestApp, 0.5);
+        assert_eq!(world.get(id).unwrap().transform.position.x, 2.0);
+
+        world.despawn(id);
+        assert!(world.tween(id, TweenTarget::Scale(Vector3::new(2.0, 2.0, 2.0)), 1.0, Easing::Linear).is_none());
+    }
+
+    #[test]
+    fn world_frames_stamp_drawn_nodes() {
+        let mut world = World::new();
+        let root = world.root_id();
+        let shown = world.spawn(root, marker(0)).unwrap();
+        let hidden = world.spawn(root, marker(1)).unwrap();
+        world.spawn(root, Node::default()).unwrap();
+        let unrendered = world.spawn(root, Node::with_other(Other)).unwrap();
+        world.set_enabled(hidden, false);
+        assert_eq!(world.frame(), 0);
+
+        world.tick(&mut TestApp, 0.0);
+        assert_eq!(world.frame(), 1);
+        assert_eq!(world.rendered_count(), 0);
+
+        // both passes of a frame count, and neither the empty node nor the variant without RENDER is stamped
+        let mut pass = TestPass::default();
+        world.draw(&mut pass, &TestApp, None);
+        world.draw(&mut pass, &TestApp, None);
+        assert_eq!(pass.0, [0, 0]);
+        assert_eq!(world.rendered_count(), 1);
+        assert!(world.was_rendered(shown) && !world.was_rendered(hidden) && !world.was_rendered(unrendered));
+
+        world.tick(&mut TestApp, 0.0);
+        assert_eq!(world.rendered_count(), 0);
+        assert_eq!(world.advance_frame(), 3);
+    }
+
+    #[test]
+    fn nodes_hold_several_components() {
+        use self::multi::{take_log, Armor, Health, TestApp, TestPass, WithData, World};
+
+        let mut world = World::new();
+        let id = world.spawn(world.root_id(), multi::Node::with_health(Health(10))).unwrap();
+        assert_eq!(world.add_component(id, Armor(3)), Ok(None));
+        assert_eq!(take_log(), ["added health 10", "added armor 3"]);
+        let node = world.get(id).unwrap();
+        assert_eq!((node.get::<Health>(), node.get::<Armor>()), (Some(&Health(10)), Some(&Armor(3))));
+        assert_eq!(node.components().count(), 2);
+        assert_eq!(world.stats().components, [("Health", 1), ("Armor", 1)]);
+
+        // every component starts once and is updated each frame, seeing the other components of its node
+        world.update(&mut TestApp, 0.1);
+        world.update(&mut TestApp, 0.1);
+        assert_eq!(take_log(), [
+            "start health 10", "update health, armor Some(3)", "start armor 3", "update armor, health Some(10)",
+            "update health, armor Some(3)", "update armor, health Some(10)"
+        ]);
+        let mut pass = TestPass::default();
+        world.draw(&mut pass, &TestApp, None);
+        assert_eq!(pass.0, ["health 10", "armor 3"]);
+
+        // adding a component of a type the node holds replaces it, and only the new one starts
+        assert_eq!(world.add_component(id, Armor(5)), Ok(Some(Armor(3))));
+        assert_eq!(take_log(), ["removed armor 3 Replaced", "added armor 5"]);
+        world.update(&mut TestApp, 0.1);
+        assert_eq!(take_log(), ["update health, armor Some(5)", "start armor 5", "update armor, health Some(10)"]);
+
+        // removing the own component leaves the added one in its place, already started
+        assert_eq!(world.remove_component::<Health>(id), Some(Health(10)));
+        assert_eq!(world.remove_component::<Health>(id), None);
+        world.update(&mut TestApp, 0.1);
+        assert_eq!(take_log(), ["removed health 10 Replaced", "update armor, health None"]);
+        assert!(world.get(id).unwrap().component.is_armor());
+        assert_eq!(world.stats().components, [("Health", 0), ("Armor", 1)]);
+
+        // despawning removes every component, and the counts match a fresh count
+        assert_eq!(world.add_component(id, Health(1)), Ok(None));
+        assert_eq!(world.stats().empty, 1);
+        world.refresh_ids();
+        assert_eq!(world.stats().components, [("Health", 1), ("Armor", 1)]);
+        assert!(world.despawn(id));
+        assert_eq!(take_log(), ["added health 1", "removed armor 5 Despawned", "removed health 1 Despawned"]);
+        assert_eq!(world.stats().components, [("Health", 0), ("Armor", 0)]);
+        assert_eq!(world.add_component(id, Health(1)), Err(WorldError::NotFound(id)));
+    }
+
+    #[test]
+    fn world_root_is_reserved() {
+        let mut world = World::new();
+        let root = world.root_id();
+        assert_eq!(root, NodeId::ROOT);
+        assert_eq!(world.root().name.as_deref(), Some("root"));
+
+        let uuid = NodeUuid::new_v4();
+        world.root_mut().tags.push("shared".to_owned());
+        world.root_mut().uuid = Some(uuid);
+        let mut tagged = Node::builder().name("a").build();
+        tagged.tags.push("shared".to_owned());
+        let a = world.spawn(root, tagged).unwrap();
+
+        // the root is only found when asked for
+        assert_eq!(world.iter(false).filter_map(|node| node.id()).collect::<Vec<_>>(), [a]);
+        assert_eq!(world.iter(true).next().unwrap().id(), Some(root));
+        assert_eq!(world.iter_mut(false).count(), 1);
+        assert!(world.find_by_path("", false).is_none());
+        assert_eq!(world.find_by_path("", true).unwrap().id(), Some(root));
+        assert_eq!(world.find_by_path("a", false).unwrap().id(), Some(a));
+        assert_eq!(world.find_all_with_tag("shared", false).len(), 1);
+        assert_eq!(world.find_all_with_tag("shared", true).len(), 2);
+        assert!(world.find_by_uuid(uuid, false).is_none());
+        assert_eq!(world.find_by_uuid(uuid, true).unwrap().id(), Some(root));
+
+        // the root can neither be despawned nor moved
+        assert_eq!(world.try_despawn(root), Err(WorldError::RootImmutable));
+        assert_eq!(world.try_reparent(root, a), Err(WorldError::RootImmutable));
+        assert_eq!(world.try_reparent(a, a), Err(WorldError::WouldCycle(a, a)));
+        assert_eq!(world.try_despawn(a), Ok(()));
+        assert_eq!(world.try_despawn(a), Err(WorldError::NotFound(a)));
+        assert_eq!(world.try_reparent(a, root), Err(WorldError::NotFound(a)));
+        assert!(world.is_alive(root));
+
+        // a root holding a component is still never drawn
+        let mut world = World::from_root(marker(9));
+        world.spawn(world.root_id(), marker(1));
+        let mut pass = TestPass::default();
+        world.draw(&mut pass, &TestApp, None);
+        assert_eq!(pass.0, [1]);
+    }
+
+    #[test]
+    fn world_despawn_in_region() {
+        let boxed = |x: f32, id: u32| {
+            let mut node = marker(id);
+            node.transform.position.x = x;
+            node.rel_min_dimensions = Dimensions { from: Vector3::new(-0.5, -0.5, -0.5), to: Vector3::new(0.5, 0.5, 0.5) };
+            node
+        };
+        let mut world = World::new();
+        let root = world.root_id();
+        let inside = world.spawn(root, boxed(0.0, 0)).unwrap();
+        let child = world.spawn(inside, boxed(0.0, 1)).unwrap();
+        let kept = world.spawn(root, boxed(0.0, 2)).unwrap();
+        let outside = world.spawn(root, boxed(10.0, 3)).unwrap();
+        world.update(&mut TestApp, 0.0);
+        world.drain_events().for_each(drop);
+
+        let region = Dimensions { from: Vector3::new(-1.0, -1.0, -1.0), to: Vector3::new(1.0, 1.0, 1.0) };
+        let removed = world.despawn_in_region(&region, OverlapMode::Contained, |node| node.get::<Marker>() != Some(&Marker(2)));
+        assert_eq!(removed, 2);
+        assert!(!world.is_alive(inside) && !world.is_alive(child));
+        assert!(world.is_alive(kept) && world.is_alive(outside));
+        assert_eq!(world.id_count(), 3);
+        assert_eq!(world.drain_events().collect::<Vec<_>>(), [HierarchyEvent::ChildRemoved { parent: root, child: inside }]);
+    }
+
+    #[test]
+    fn world_attach_where() {
+        let mut world = World::new();
+        let root = world.root_id();
+        let empty = world.spawn(root, Node::default()).unwrap();
+        let full = world.spawn(root, marker(1)).unwrap();
+        world.get_mut(full).unwrap().add_child(Node::default());
+        world.drain_events().for_each(drop);
+        take_log();
+
+        // the root matches too, but is never given a component
+        let next = std::cell::Cell::new(5);
+        let report = world.attach_where(|_| true, |_| {
+            next.set(next.get() + 1);
+            Component::Marker(Marker(next.get()))
+        });
+        assert_eq!(report, AttachReport { attached: 2, skipped: 1 });
+        assert_eq!(world.root().component.variant_index(), None);
+
+        // the added function ran once for each attached node, and the tree kept its shape so no event was recorded
+        assert_eq!(take_log(), ["added 6", "added 7"]);
+        assert_eq!(world.drain_events().count(), 0);
+
+        // the node added without the world was given an id before being attached
+        let added = world.get(full).unwrap().children()[0].id().unwrap();
+        assert_eq!(world.get(empty).unwrap().get::<Marker>(), Some(&Marker(6)));
+        assert_eq!(world.get(added).unwrap().get::<Marker>(), Some(&Marker(7)));
+        assert_eq!(world.get(full).unwrap().get::<Marker>(), Some(&Marker(1)));
+    }
+
+    #[test]
+    fn world_get_two_mut() {
+        let mut world = World::new();
+        let root = world.root_id();
+        let [a, b] = [0, 1].map(|id| world.spawn(root, marker(id)).unwrap());
+        let [a_child, b_child] = [(a, 2), (b, 3)].map(|(parent, id)| world.spawn(parent, marker(id)).unwrap());
+        let mut ids = |x, y| world.get_two_mut(x, y).map(|(x, y)| (x.get::<Marker>().unwrap().0, y.get::<Marker>().unwrap().0));
+
+        // siblings and cousins in either order
+        assert_eq!(ids(a, b), Ok((0, 1)));
+        assert_eq!(ids(b, a), Ok((1, 0)));
+        assert_eq!(ids(a_child, b_child), Ok((2, 3)));
+        assert_eq!(ids(b_child, a), Ok((3, 0)));
+
+        // the ancestor is always given first
+        assert_eq!(ids(a, a_child), Err(WorldError::AliasedNodes(a, a_child)));
+        assert_eq!(ids(a_child, a), Err(WorldError::AliasedNodes(a, a_child)));
+        assert_eq!(ids(root, b_child), Err(WorldError::AliasedNodes(root, b_child)));
+        assert_eq!(ids(b, b), Err(WorldError::AliasedNodes(b, b)));
+
+        let (x, y) = world.get_two_mut(a, b_child).unwrap();
+        std::mem::swap(x.get_mut::<Marker>().unwrap(), y.get_mut::<Marker>().unwrap());
+        assert_eq!(world.get(a).unwrap().get::<Marker>(), Some(&Marker(3)));
+
+        world.despawn(b);
+        assert_eq!(world.get_two_mut(a, b_child).err(), Some(WorldError::NotFound(b_child)));
+    }
+
+    #[test]
+    fn get_mut_of_another_type_does_not_mark_changed() {
+        let mut node = marker(0);
+        node.update(&Transform::default(), &mut TestApp, 0.0);
+        node.update(&Transform::default(), &mut TestApp, 0.0);
+        assert!(!node.is_component_changed());
+
+        assert!(node.get_mut::<Other>().is_none());
+        node.update(&Transform::default(), &mut TestApp, 0.0);
+        assert!(!node.is_component_changed());
+
+        node.get_mut::<Marker>().unwrap().0 = 1;
+        node.update(&Transform::default(), &mut TestApp, 0.0);
+        assert!(node.is_component_changed());
+    }
+
+    #[test]
+    fn world_stats_follow_the_tree() {
+        let mut world = World::new();
+        let root = world.root_id();
+        let a = world.spawn(root, marker(0)).unwrap();
+        let b = world.spawn(a, marker(1)).unwrap();
+        let c = world.spawn(b, Node::default()).unwrap();
+        world.spawn(root, Node::with_other(Other)).unwrap();
+        assert_eq!(world.stats(), world.root().stats());
+        assert_eq!((world.stats().nodes, world.stats().max_depth), (5, 3));
+
+        assert!(world.reparent(c, root));
+        assert_eq!(world.stats(), world.root().stats());
+        assert_eq!(world.stats().max_depth, 2);
+
+        assert!(world.set_component(c, Component::Other(Other)).is_some());
+        assert_eq!(world.stats(), world.root().stats());
+        assert_eq!(world.stats().for_variant("Other"), 2);
+
+        world.take(b).unwrap();
+        assert_eq!(world.stats(), world.root().stats());
+        assert!(world.despawn(a));
+        assert_eq!(world.stats(), world.root().stats());
+        assert_eq!((world.stats().nodes, world.stats().empty, world.stats().max_depth), (3, 1, 1));
+
+        // changes made around the world are counted again once ids are refreshed
+        world.root_mut().add_child(marker(2));
+        world.refresh_ids();
+        assert_eq!(world.stats(), world.root().stats());
+    }
+
+    // checks that the cached path of every live id leads to its node
+    fn paths_are_cached(world: &World) -> bool {
+        world.ids.iter().all(|id| world.paths[id.index() as usize].as_ref().and_then(|path| world.root.resolve_path(path)).is_some_and(|node| node.id == Some(id)))
+    }
+
+    #[test]
+    fn world_keeps_paths_cached() {
+        let mut world = World::new();
+        let root = world.root_id();
+        let ids: Vec<_> = (0..4).map(|id| world.spawn(root, marker(id)).unwrap()).collect();
+        let child = world.spawn(ids[1], marker(10)).unwrap();
+        assert!(paths_are_cached(&world));
+
+        let first = world.insert_child_at(root, 0, marker(4)).unwrap();
+        assert!(paths_are_cached(&world));
+        assert_eq!(world.root().children()[0].id(), Some(first));
+        assert!(world.insert_child_at(root, 9, marker(5)).is_none());
+
+        assert!(world.move_child(root, 0, 4));
+        assert!(paths_are_cached(&world));
+        assert!(!world.move_child(root, 0, 5));
+
+        assert!(world.sort_children_by_key(root, |node| std::cmp::Reverse(node.get::<Marker>().map(|marker| marker.0))));
+        assert!(paths_are_cached(&world));
+        assert_eq!(world.root().children()[0].id(), Some(first));
+
+        assert!(world.reparent(child, ids[3]));
+        assert!(paths_are_cached(&world));
+        world.take(ids[0]).unwrap();
+        assert!(paths_are_cached(&world));
+        assert!(world.despawn(ids[2]));
+        assert!(paths_are_cached(&world));
+        assert_eq!(world.locate(child), world.root().find_path(|node| node.id() == Some(child)));
+    }
+
+    #[test]
+    fn nodes_walk_up_through_the_world() {
+        let mut world = World::new();
+        let root = world.root_id();
+        let mut node = marker(1);
+        node.add_child(marker(2));
+        let a = world.spawn(root, node).unwrap();
+        let b = world.root().children()[0].children()[0].id().unwrap();
+        let c = world.spawn(b, marker(3)).unwrap();
+        assert_eq!(world.root().parent_id(), None);
+        assert_eq!(world.get(b).unwrap().parent_id(), Some(a));
+
+        let node = world.get(c).unwrap();
+        assert_eq!(node.parent_id(), Some(b));
+        assert_eq!(node.parent(&world).and_then(Node::id), Some(b));
+        assert_eq!(node.ancestors(&world).map(|node| node.id().unwrap()).collect::<Vec<_>>(), [b, a, root]);
+        assert_eq!(node.root(&world).and_then(Node::id), Some(root));
+        let (_, context) = world.get_mut(c).unwrap().split_component::<Marker>().unwrap();
+        assert_eq!(context.parent_id(), Some(b));
+
+        // the links follow the node as it is moved and are cleared once it is taken out
+        assert!(world.reparent(c, a));
+        assert_eq!(world.get(c).unwrap().parent_id(), Some(a));
+        let taken = world.take(a).unwrap();
+        assert_eq!((taken.parent_id(), taken.children()[1].parent_id()), (None, None));
+        assert!(taken.parent(&world).is_none() && taken.root(&world).is_none());
+        assert_eq!(taken.ancestors(&world).count(), 0);
+
+        // trees built through `root_mut` are linked by `refresh_ids`
+        world.root_mut().add_child(taken);
+        world.refresh_ids();
+        let a = world.root().children()[0].id();
+        assert_eq!(world.root().children()[0].children()[0].parent_id(), a);
+    }
+
+    #[test]
+    fn draw_order_follows_priority_then_sort_key_then_tree_order() {
+        use self::ordered::{Back, Front, Keyed, Middle, TestApp, TestPass, WithData, World};
+
+        // in tree order: front 1, keyed 10, middle 2, back 3 holding keyed 11, keyed 12, back 4, middle 5
+        let mut world = World::new();
+        let root = world.root_id();
+        let spawn = |world: &mut World, parent, node| world.spawn(parent, node).unwrap();
+        spawn(&mut world, root, ordered::Node::with_front(Front(1)));
+        spawn(&mut world, root, ordered::Node::with_keyed(Keyed(2, 10)));
+        spawn(&mut world, root, ordered::Node::with_middle(Middle(2)));
+        let back = spawn(&mut world, root, ordered::Node::with_back(Back(3)));
+        spawn(&mut world, back, ordered::Node::with_keyed(Keyed(1, 11)));
+        spawn(&mut world, root, ordered::Node::with_keyed(Keyed(2, 12)));
+        spawn(&mut world, root, ordered::Node::with_back(Back(4)));
+        spawn(&mut world, root, ordered::Node::with_middle(Middle(5)));
+        world.update(&mut TestApp, 0.0);
+
+        // lower priorities first, the keyed nodes reordered amongst their own slots with equal keys kept in tree order
+        let mut pass = TestPass::default();
+        world.draw(&mut pass, &TestApp, None);
+        assert_eq!(pass.0, [3, 4, 11, 2, 10, 12, 5, 1]);
+
+        // the next draw sorts in the buffers the world kept from this one
+        let buffers = |world: &World| {
+            let buffers = world.draw_buffers.lock().unwrap();
+            assert!(buffers.list.is_empty() && buffers.list.capacity() >= 8 && buffers.scratch.capacity() >= 3);
+            (buffers.list.as_ptr(), buffers.scratch.as_ptr())
+        };
+        let kept = buffers(&world);
+        let mut pass = TestPass::default();
+        world.draw(&mut pass, &TestApp, None);
+        assert_eq!(pass.0, [3, 4, 11, 2, 10, 12, 5, 1]);
+        assert_eq!(buffers(&world), kept);
+
+        let mut list = Vec::new();
+        world.root().build_draw_list(&mut list);
+        assert_eq!(list.len(), 9);
+        assert!(list[0].get::<Back>().is_some() && list[8].get::<Front>().is_some());
+    }
+
+    // a small xorshift generator, so the random trees are the same on every run
+    fn xorshift(mut state: u64) -> impl FnMut(u64) -> u64 {
+        move |below| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % below
+        }
+    }
+
+    #[test]
+    fn k_nearest_matches_a_sorted_scan() {
+        let mut next = xorshift(0x2545_f491_4f6c_dd1d);
+        for _ in 0..50 {
+            // integer positions and boxes make many nodes the same distance away
+            let mut world = World::new();
+            let mut ids = vec![world.root_id()];
+            for id in 0..60 {
+                let mut node = marker(id);
+                node.transform.position = Vector3::new(next(7) as f32 - 3.0, next(7) as f32 - 3.0, next(3) as f32 - 1.0);
+                if next(2) == 0 { node.rel_min_dimensions = Dimensions { from: Vector3::new(-1.0, -1.0, -1.0), to: Vector3::new(1.0, 1.0, 1.0) }; }
+                let parent = ids[next(ids.len() as u64) as usize];
+                ids.push(world.spawn(parent, node).unwrap());
+            }
+            world.update(&mut TestApp, 0.0);
+
+            let point = Vector3::new(next(9) as f32 - 4.0, next(9) as f32 - 4.0, 0.0);
+            let k = next(8) as usize;
+            let max_dist = [f32::INFINITY, 3.0, 1.0, 0.0][next(4) as usize];
+            let filter = |node: &Node| node.get::<Marker>().is_some_and(|marker| marker.0 % 5 != 0);
+
+            // every passing node in pre-order, stably sorted so ties keep tree order
+            let mut expected: Vec<(u32, f32)> = world.iter(true)
+                .filter(|node| filter(node))
+                .map(|node| (node.get::<Marker>().unwrap().0, node.own_dimensions().distance_to(point)))
+                .filter(|(_, dist)| *dist <= max_dist)
+                .collect();
+            expected.sort_by(|a, b| a.1.total_cmp(&b.1));
+            expected.truncate(k);
+
+            let found: Vec<(u32, f32)> = world.root().k_nearest(point, k, max_dist, filter).into_iter()
+                .map(|(node, dist)| (node.get::<Marker>().unwrap().0, dist))
+                .collect();
+            assert_eq!(found, expected, "k {} within {} of {:?}", k, max_dist, point);
+        }
+    }
+
+    #[test]
+    fn collect_in_volume_skips_non_casters_and_outside_subtrees() {
+        let unit = Dimensions { from: Vector3::new(-0.5, -0.5, -0.5), to: Vector3::new(0.5, 0.5, 0.5) };
+        let placed = |mut node: Node, x: f32| {
+            node.transform.position = Vector3::new(x, 0.0, 0.0);
+            node.rel_min_dimensions = unit;
+            node
+        };
+
+        let mut world = World::new();
+        let root = world.root_id();
+        world.spawn(root, placed(marker(0), 0.0)).unwrap();
+        world.spawn(root, placed(Node::with_other(Other), 0.0)).unwrap();
+        let far = world.spawn(root, placed(marker(1), 10.0)).unwrap();
+        world.spawn(far, placed(marker(2), -10.0)).unwrap();
+        let hidden = world.spawn(root, placed(marker(3), 0.0)).unwrap();
+        world.set_enabled(hidden, false);
+        world.update(&mut TestApp, 0.0);
+
+        // the child of the far node is back at the origin, so the far subtree is still walked
+        let ids = |volume: &CullVolume| {
+            let mut out = Vec::new();
+            world.root().collect_in_volume(volume, &mut out);
+            out.into_iter().map(|(component, _)| match component { Component::Marker(marker) => marker.0, _ => panic!("{:?} does not cast shadows", component) }).collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&CullVolume::Sphere(Vector3::new(0.0, 0.0, 0.0), 1.0)), [0, 2]);
+        assert_eq!(ids(&CullVolume::Aabb(Dimensions { from: Vector3::new(9.0, -1.0, -1.0), to: Vector3::new(11.0, 1.0, 1.0) })), [1]);
+        assert_eq!(ids(&CullVolume::Sphere(Vector3::new(0.0, 50.0, 0.0), 1.0)), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn find_data_searches_in_pre_order() {
+        // root 0 holds 1 holding 2, then 2 again and an other
+        let mut inner = marker(1);
+        inner.add_child(marker(2));
+        let mut root = marker(0);
+        root.add_child(inner);
+        root.add_child(marker(2));
+        root.add_child(Node::with_other(Other));
+
+        // the root itself is searched first
+        let (found, data) = root.find_data_marker(|_| true).unwrap();
+        assert_eq!((found.get::<Marker>(), data), (Some(&Marker(0)), &Marker(0)));
+
+        // the first match in pre-order wins over a shallower one later on
+        assert_eq!(root.find_data_marker_path(|marker| marker.0 == 2), Some(NodePath::from(&[0, 0][..])));
+        assert_eq!(root.find_data_other_path(|_| true), Some(NodePath::from(&[2][..])));
+        assert!(root.find_data_marker(|marker| marker.0 == 3).is_none());
+        assert!(root.find_data_marker_path(|marker| marker.0 == 3).is_none());
+        assert!(marker(0).find_data_other(|_| true).is_none());
+    }
+
+    #[test]
+    fn confine_to_parent_clamps_the_local_position() {
+        let bounds = |from: [f32; 3], to: [f32; 3]| Dimensions { from: Vector3::from(from), to: Vector3::from(to) };
+        let child = |position: [f32; 3], own: Dimensions, confine: bool| {
+            let mut node = marker(0);
+            node.transform.position = Vector3::from(position);
+            node.rel_min_dimensions = own;
+            node.confine_to_parent = confine;
+            node
+        };
+
+        let mut parent = Node::default();
+        parent.transform.position = Vector3::new(100.0, 0.0, 0.0);
+        parent.rel_min_dimensions = bounds([-5.0, -5.0, -5.0], [5.0, 5.0, 5.0]);
+        let unit = bounds([-1.0, -1.0, -1.0], [1.0, 1.0, 1.0]);
+        parent.add_child(child([10.0, -10.0, 2.0], unit, true));
+        parent.add_child(child([10.0, 0.0, 0.0], unit, false));
+
+        // a child wider than the bounds on x is centered on x, and clamped as usual on the other axes
+        parent.add_child(child([3.0, 9.0, 0.0], bounds([-8.0, 0.0, -1.0], [6.0, 1.0, 1.0]), true));
+
+        parent.update(&Transform::default(), &mut TestApp, 0.0);
+        let positions: Vec<_> = parent.children().iter().map(|child| child.transform.position).collect();
+        assert_eq!(positions, [Vector3::new(4.0, -4.0, 2.0), Vector3::new(10.0, 0.0, 0.0), Vector3::new(1.0, 4.0, 0.0)]);
+
+        // the clamp is in the space of the parent, so the global position follows it
+        assert_eq!(parent.children()[0].global_transform().position, Vector3::new(104.0, -4.0, 2.0));
+    }
+
+    #[test]
+    fn sort_keys_group_materials_in_tree_order() {
+        use self::sorted::{Lit, Plain, Textured, TestApp, TestPass, WithData, World};
+        const { assert!(!sorted::Component::PRIORITIZED && sorted::Component::SORTED) };
+
+        // materials interleaved in tree order, with a plain node and a nested node between them
+        let mut world = World::new();
+        let root = world.root_id();
+        let nodes = [
+            sorted::Node::with_textured(Textured(2, 1)),
+            sorted::Node::with_lit(Lit(9, 2)),
+            sorted::Node::with_textured(Textured(1, 3)),
+            sorted::Node::with_plain(Plain(4)),
+            sorted::Node::with_textured(Textured(2, 5)),
+            sorted::Node::with_lit(Lit(3, 6)),
+            sorted::Node::with_textured(Textured(1, 7))
+        ];
+        let ids: Vec<_> = nodes.into_iter().map(|node| world.spawn(root, node).unwrap()).collect();
+        world.spawn(ids[3], sorted::Node::with_textured(Textured(2, 8))).unwrap();
+        world.update(&mut TestApp, 0.0);
+
+        // each variant keeps the slots it took in tree order, grouped by key with tree order kept within a key
+        let mut pass = TestPass::default();
+        world.draw(&mut pass, &TestApp, None);
+        assert_eq!(pass.0, [3, 6, 7, 4, 1, 8, 2, 5]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn nodes_round_trip_through_serde() {
+        use self::serialized::{Marker, Node as SavedNode, World as SavedWorld, Component as SavedComponent};
+
+        let mut root = SavedNode {
+            name: Some("root".to_string()),
+            enabled: false,
+            rel_min_dimensions: Dimensions { from: Vector3::new(-1.0, -1.0, -1.0), to: Vector3::new(1.0, 1.0, 1.0) },
+            ..Default::default()
+        };
+        root.transform.position = Vector3::new(1.0, 2.0, 3.0);
+        root.add_tag("saved");
+        let mut child = SavedNode { component: SavedComponent::Marker(Marker(7)), ..Default::default() };
+        child.transform.scale = Vector3::new(2.0, 2.0, 2.0);
+        root.push_detached_child(child);
+
+        // ids are left out, so nodes from a world load like nodes that were never spawned
+        let world = SavedWorld::from_root(root);
+        let json = serde_json::to_string(world.root()).unwrap();
+        let loaded: SavedNode = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.id(), None);
+        assert_eq!(loaded.name.as_deref(), Some("root"));
+        assert!(!loaded.enabled);
+        assert_eq!(loaded.transform.position, Vector3::new(1.0, 2.0, 3.0));
+        assert_eq!(loaded.rel_min_dimensions, world.root().rel_min_dimensions);
+        assert_eq!(loaded.tags, ["saved"]);
+        assert_eq!(loaded.children().len(), 1);
+        assert_eq!(loaded.children()[0].get::<Marker>(), Some(&Marker(7)));
+        assert_eq!(loaded.children()[0].transform.scale, Vector3::new(2.0, 2.0, 2.0));
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+    }
+}
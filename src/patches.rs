@@ -0,0 +1,309 @@
+use std::collections::BTreeMap;
+
+use cgmath::{Quaternion, Vector3};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dimensions::Dimensions,
+    ids::{NodeId, NodeUuid},
+    math::TransformMode,
+    nodes::{ComponentDef, Node, World},
+    scenes::{load_component, parse_transform_mode, parse_uuid, SceneError, SceneFile, SceneNode, SceneValue}
+};
+
+/// How a child is found amongst the children of its parent in a `ScenePatch`, so patches do not depend on the order
+/// of children.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PatchKey {
+    /// The child with the given uuid.
+    Uuid(String),
+
+    /// The nth child with the given name, starting at 0, for children without uuids.
+    Name(String, usize),
+
+    /// The nth child with no name, starting at 0, for children without names or uuids.
+    Unnamed(usize)
+}
+
+impl PatchKey {
+    /// The keys of each of the given saved children, by uuid, then by name, then by order amongst the unnamed children.
+    pub fn for_children(children: &[SceneNode]) -> Vec<PatchKey> {
+        children.iter().enumerate().map(|(idx, child)| {
+            let before = &children[..idx];
+            match (&child.uuid, &child.name) {
+                (Some(uuid), _) => PatchKey::Uuid(uuid.clone()),
+                (None, Some(name)) => PatchKey::Name(name.clone(), before.iter().filter(|other| other.name.as_ref() == Some(name)).count()),
+                (None, None) => PatchKey::Unnamed(before.iter().filter(|other| other.name.is_none()).count())
+            }
+        }).collect()
+    }
+
+    /// Finds the index of the child matching this key amongst the given children of a node in a tree.
+    pub fn find<C, A>(&self, children: &[Node<C, A>]) -> Option<usize> {
+        let mut matching = children.iter().enumerate().filter(|(_, child)| match self {
+            PatchKey::Uuid(uuid) => child.uuid.is_some_and(|own| own.to_string() == *uuid),
+            PatchKey::Name(name, _) => child.name.as_ref() == Some(name),
+            PatchKey::Unnamed(_) => child.name.is_none()
+        });
+
+        match self {
+            PatchKey::Uuid(_) => matching.next(),
+            PatchKey::Name(_, nth) | PatchKey::Unnamed(nth) => matching.nth(*nth)
+        }.map(|(idx, _)| idx)
+    }
+}
+
+/// A change to one node of a tree, which is found by the keys of the children leading to it from the root.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PatchOp {
+    /// Sets the given saved fields of the node, keyed by their names in `SceneNode`.
+    Set { path: Vec<PatchKey>, fields: BTreeMap<String, SceneValue> },
+
+    /// Spawns the given saved node and its children as the last child of the node.
+    Add { parent: Vec<PatchKey>, node: Box<SceneNode> },
+
+    /// Despawns the node and its children.
+    Remove { path: Vec<PatchKey> }
+}
+
+/// The changes between two versions of a scene, so editors and networks can send the changes to a tree rather than
+/// the whole scene.  Patches are serializable with serde.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenePatch {
+    pub ops: Vec<PatchOp>
+}
+
+impl ScenePatch {
+    /// Finds the changes that turn the first saved tree into the second.  Children are matched by their `PatchKey`,
+    /// with children only in the second tree added and children only in the first removed.
+    pub fn diff(a: &SceneNode, b: &SceneNode) -> Self {
+        let mut patch = Self::default();
+        Self::diff_recr(&mut Vec::new(), a, b, &mut patch.ops);
+        patch
+    }
+
+    fn diff_recr(path: &mut Vec<PatchKey>, a: &SceneNode, b: &SceneNode, ops: &mut Vec<PatchOp>) {
+        let (fields_a, fields_b) = (shallow_fields(a), shallow_fields(b));
+        let fields: BTreeMap<String, SceneValue> = fields_b.iter()
+            .filter(|(key, value)| fields_a.get(*key) != Some(value))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        if !fields.is_empty() { ops.push(PatchOp::Set { path: path.clone(), fields }); }
+
+        let keys_a = PatchKey::for_children(&a.children);
+        let keys_b = PatchKey::for_children(&b.children);
+        for key in keys_a.iter().filter(|key| !keys_b.contains(key)) {
+            let mut path = path.clone();
+            path.push(key.clone());
+            ops.push(PatchOp::Remove { path });
+        }
+
+        for (child, key) in b.children.iter().zip(keys_b) {
+            match keys_a.iter().position(|other| *other == key) {
+                Some(idx) => {
+                    path.push(key);
+                    Self::diff_recr(path, &a.children[idx], child, ops);
+                    path.pop();
+                },
+                None => ops.push(PatchOp::Add { parent: path.clone(), node: Box::new(child.clone()) })
+            }
+        }
+    }
+
+    /// Checks if this patch changes nothing.
+    pub fn is_empty(&self) -> bool { self.ops.is_empty() }
+}
+
+impl SceneFile {
+    /// Finds the changes that turn this scene into the other, see `ScenePatch::diff`.
+    pub fn diff(&self, other: &SceneFile) -> ScenePatch { ScenePatch::diff(&self.root, &other.root) }
+}
+
+// every field of the given saved node other than its children, with fields left out of its saved form given their
+// default so removed fields are also diffed
+fn shallow_fields(node: &SceneNode) -> BTreeMap<String, SceneValue> {
+    let children = SceneNode { children: Vec::new(), ..node.clone() };
+    let SceneValue::Object(defaults) = serde_json::to_value(SceneNode::default()).unwrap() else { unreachable!() };
+    let SceneValue::Object(fields) = serde_json::to_value(children).unwrap() else { unreachable!() };
+
+    let mut all: BTreeMap<String, SceneValue> = defaults.into_iter().collect();
+    all.extend(fields);
+    for (key, empty) in [("name", SceneValue::Null), ("uuid", SceneValue::Null), ("component", SceneValue::Null)] {
+        all.entry(key.to_string()).or_insert(empty);
+    }
+    for (key, empty) in [("tags", SceneValue::Array(Vec::new())), ("metadata", SceneValue::Object(Default::default()))] {
+        all.entry(key.to_string()).or_insert(empty);
+    }
+    all
+}
+
+// the saved fields of a set operation, with the fields that can fail to load already loaded
+struct PreparedFields<C> {
+    saved: SceneNode,
+    uuid: Option<NodeUuid>,
+    transform_mode: TransformMode,
+    component: Option<C>
+}
+
+// a patch operation that has been checked, so it can no longer fail to apply
+enum PreparedOp<C, A> {
+    Set(BTreeMap<String, SceneValue>, Box<PreparedFields<C>>),
+    Add(Box<Node<C, A>>),
+    Remove
+}
+
+// reads and loads the given fields, checking every one of them before anything is changed
+fn prepare_fields<C: ComponentDef<A>, A>(fields: &BTreeMap<String, SceneValue>) -> Result<PreparedFields<C>, SceneError> {
+    let saved = read_fields(fields)?;
+    let uuid = saved.uuid.as_deref().map(parse_uuid).transpose()?;
+    let transform_mode = parse_transform_mode(&saved.transform_mode)?;
+    let component = if fields.contains_key("component") { Some(load_component(&saved.component)?) } else { None };
+    Ok(PreparedFields { saved, uuid, transform_mode, component })
+}
+
+// sets the given saved fields of the node from their prepared form, other than its component which is set through the
+// world so its stats stay up to date
+fn apply_fields<C: ComponentDef<A>, A>(node: &mut Node<C, A>, fields: &BTreeMap<String, SceneValue>, prepared: PreparedFields<C>) {
+    let PreparedFields { saved, uuid, transform_mode, .. } = prepared;
+    for key in fields.keys() {
+        match key.as_str() {
+            "name" => node.name = saved.name.clone(),
+            "uuid" => node.uuid = uuid,
+            "position" => node.transform.position = Vector3::from(saved.position),
+            "rotation" => {
+                let [x, y, z, w] = saved.rotation;
+                node.transform.rotation = Quaternion::new(w, x, y, z);
+            },
+            "scale" => node.transform.scale = Vector3::from(saved.scale),
+            "rel_min_dimensions" => {
+                let [from, to] = saved.rel_min_dimensions;
+                node.rel_min_dimensions = Dimensions { from: Vector3::from(from), to: Vector3::from(to) };
+            },
+            "transform_mode" => node.transform_mode = transform_mode,
+            "confine_to_parent" => node.confine_to_parent = saved.confine_to_parent,
+            "enabled" => node.set_enabled(saved.enabled),
+            "tags" => node.tags = saved.tags.clone(),
+            "metadata" => node.metadata = saved.metadata.iter().map(|(key, value)| (key.clone(), value.clone())).collect(),
+            _ => {}
+        }
+    }
+}
+
+// reads the given fields into a saved node, checking that each is a field that can be patched
+fn read_fields(fields: &BTreeMap<String, SceneValue>) -> Result<SceneNode, SceneError> {
+    const PATCHABLE: &[&str] = &[
+        "name", "uuid", "position", "rotation", "scale", "rel_min_dimensions", "transform_mode", "confine_to_parent",
+        "enabled", "tags", "metadata", "component"
+    ];
+
+    if let Some(key) = fields.keys().find(|key| !PATCHABLE.contains(&key.as_str())) { return Err(SceneError::InvalidPatch(key.clone())) }
+    let value = SceneValue::Object(fields.iter().map(|(key, value)| (key.clone(), value.clone())).collect());
+    serde_json::from_value(value).map_err(|error| SceneError::InvalidPatch(error.to_string()))
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Applies a patch to the tree, see `ScenePatch::diff`.  All paths are found before anything is changed, so they
+    /// all refer to the tree as it was before the patch.  Operations on nodes that cannot be found are skipped, so
+    /// nodes removed from the tree stay removed.  Changed components are replaced with `World::set_component`.
+    ///
+    /// Every operation is checked and its components loaded before any of them are applied, so the patch is applied
+    /// in full or not at all.  Returns the first error found in the patch, in which case the tree is left unchanged.
+    pub fn apply_patch(&mut self, patch: &ScenePatch) -> Result<(), SceneError> {
+        let mut prepared = Vec::with_capacity(patch.ops.len());
+        for op in &patch.ops {
+            let (PatchOp::Set { path, .. } | PatchOp::Remove { path } | PatchOp::Add { parent: path, .. }) = op;
+            let target: Option<NodeId> = self.find_by_keys(path);
+            prepared.push((target, match op {
+                PatchOp::Set { fields, .. } => PreparedOp::Set(fields.clone(), Box::new(prepare_fields(fields)?)),
+                PatchOp::Add { node, .. } => PreparedOp::Add(Box::new(Node::from_scene(node)?)),
+                PatchOp::Remove { .. } => PreparedOp::Remove
+            }));
+        }
+
+        for (target, op) in prepared {
+            let Some(id) = target else { continue };
+            match op {
+                PreparedOp::Set(fields, mut prepared) => {
+                    let component = prepared.component.take();
+                    if let Some(node) = self.get_mut(id) { apply_fields(node, &fields, *prepared); }
+                    if let Some(component) = component { self.set_component(id, component); }
+                },
+                PreparedOp::Add(node) => { self.spawn(id, *node); },
+                PreparedOp::Remove => { self.despawn(id); }
+            }
+        }
+        Ok(())
+    }
+
+    /// Finds the node reached by the given keys of children from the root.
+    pub fn find_by_keys(&self, keys: &[PatchKey]) -> Option<NodeId> {
+        let mut node = self.root();
+        for key in keys { node = &node.children()[key.find(node.children())?]; }
+        node.id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::scenes::{SceneComponent, SceneError, SceneNode, SceneValue};
+
+    use super::{PatchKey, PatchOp, ScenePatch};
+    use self::world::*;
+
+    #[allow(dead_code)]
+    mod world {
+        pub struct TestApp;
+
+        #[derive(Debug)]
+        pub struct Marker(pub u64);
+
+        crate::define_world!(TestApp, [
+            Marker => {
+                DATA => Marker,
+                SAVE => |data: &Marker| crate::scenes::SceneValue::from(data.0),
+                LOAD => |value: &crate::scenes::SceneValue| Some(Marker(value.as_u64()?))
+            }
+        ]);
+    }
+
+    fn world() -> World {
+        let mut world = World::new();
+        let mut node = Node::default();
+        node.name = Some("a".to_string());
+        world.spawn(world.root_id(), node);
+        world
+    }
+
+    fn rename(name: &str) -> PatchOp {
+        PatchOp::Set { path: vec![PatchKey::Name("a".to_string(), 0)], fields: BTreeMap::from([("name".to_string(), SceneValue::from(name))]) }
+    }
+
+    fn add(variant: &str) -> PatchOp {
+        let component = SceneComponent { variant: variant.to_string(), data: SceneValue::from(7) };
+        PatchOp::Add { parent: Vec::new(), node: Box::new(SceneNode { component: Some(component), ..SceneNode::default() }) }
+    }
+
+    #[test]
+    fn failing_patch_changes_nothing() {
+        let mut world = world();
+        let patch = ScenePatch { ops: vec![rename("b"), add("Missing")] };
+        assert_eq!(world.apply_patch(&patch), Err(SceneError::UnknownVariant("Missing".to_string())));
+        assert_eq!(world.root().children().len(), 1);
+        assert_eq!(world.root().children()[0].name.as_deref(), Some("a"));
+
+        let patch = ScenePatch { ops: vec![add("Marker"), rename("b"), PatchOp::Set { path: Vec::new(), fields: BTreeMap::from([("bogus".to_string(), SceneValue::Null)]) }] };
+        assert_eq!(world.apply_patch(&patch), Err(SceneError::InvalidPatch("bogus".to_string())));
+        assert_eq!(world.root().children().len(), 1);
+    }
+
+    #[test]
+    fn patch_applies_every_op() {
+        let mut world = world();
+        let patch = ScenePatch { ops: vec![rename("b"), add("Marker")] };
+        assert_eq!(world.apply_patch(&patch), Ok(()));
+        assert_eq!(world.root().children()[0].name.as_deref(), Some("b"));
+        assert_eq!(world.root().children()[1].get::<Marker>().map(|marker| marker.0), Some(7));
+    }
+}
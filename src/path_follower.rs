@@ -0,0 +1,281 @@
+use cgmath::{InnerSpace, Quaternion, Rotation, Vector3};
+use forte_engine::math::transforms::Transform;
+
+use crate::nodes::{ComponentDef, NodeContext};
+
+/// What a `PathFollower` does when it reaches the end of its path.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PathMode {
+    /// Continue from the last waypoint back to the first, making the path a closed loop.
+    #[default]
+    Loop,
+
+    /// Turn around and follow the path backwards, then forwards again at the start.
+    PingPong,
+
+    /// Stop at the last waypoint.
+    Once
+}
+
+/// A component that moves a node along a path of waypoints at a constant speed.
+///
+/// The waypoints are not stored in the follower, instead they are given to `path_follower_update` each update so they
+/// can be read from other nodes, IE empty child nodes of a static parent, or read from the children of the following
+/// node with `path_follower_update_node`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PathFollower {
+    /// The speed to move at in units per second.
+    pub speed: f32,
+
+    /// What to do at the end of the path.
+    pub mode: PathMode,
+
+    /// The index of the segment being followed, segment i goes from waypoint i to waypoint i + 1.
+    pub current: usize,
+
+    /// How far along the current segment the follower is, from 0 at its start to 1 at its end.
+    pub t: f32,
+
+    /// True if the follower is moving backwards along the path.
+    pub reverse: bool,
+
+    /// True if the nodes rotation should face the direction of travel, using -Z as forward.
+    pub orient: bool
+}
+
+impl PathFollower {
+    /// Creates a new path follower at the start of its path.
+    ///
+    /// Arguments:
+    /// * speed: f32 - The speed to move at in units per second.
+    /// * mode: PathMode - What to do at the end of the path.
+    ///
+    /// Returns the new path follower.
+    pub fn new(speed: f32, mode: PathMode) -> Self {
+        Self { speed, mode, ..Default::default() }
+    }
+}
+
+/// Moves the given transform along the path of the given follower.
+///
+/// Distance is measured along the whole path, so the follower moves at a constant speed even when its segments are
+/// different lengths.
+///
+/// Arguments:
+/// * follower: &mut PathFollower - The follower whose progress will be advanced.
+/// * positions: &[Vector3<f32>] - The waypoints of the path, these must be in the same space as the transform.
+/// * transform: &mut Transform - The transform to move, and rotate if the follower orients.
+/// * dt: f32 - The time since the last update in seconds.
+pub fn path_follower_update(follower: &mut PathFollower, positions: &[Vector3<f32>], transform: &mut Transform, dt: f32) {
+    let count = positions.len();
+    if count == 0 { return }
+    if count == 1 {
+        transform.position = positions[0];
+        return;
+    }
+
+    let segments = if follower.mode == PathMode::Loop { count } else { count - 1 };
+    let segment = |idx: usize| (positions[idx], positions[(idx + 1) % count]);
+    let total: f32 = (0..segments).map(|idx| { let (a, b) = segment(idx); (b - a).magnitude() }).sum();
+
+    follower.current = follower.current.min(segments - 1);
+    follower.t = follower.t.clamp(0.0, 1.0);
+    let mut remaining = (follower.speed * dt).max(0.0);
+
+    // looping paths repeat, so skip any whole laps
+    match follower.mode {
+        PathMode::Loop => remaining %= total,
+        PathMode::PingPong => remaining %= total * 2.0,
+        PathMode::Once => {}
+    }
+
+    // a path with no length has nowhere to move too
+    if total > 0.0 { advance(follower, remaining, segments, segment); }
+
+    // apply position and rotation
+    let (a, b) = segment(follower.current);
+    transform.position = a + (b - a) * follower.t;
+    if follower.orient {
+        let direction = if follower.reverse { a - b } else { b - a };
+        if direction.magnitude2() > 0.0 {
+            transform.rotation = Quaternion::between_vectors(-Vector3::unit_z(), direction.normalize());
+        }
+    }
+}
+
+/// Moves the node of the given context along the path through its children, see `path_follower_update`, IE as the
+/// UPDATE of a variant holding a follower:
+/// `UPDATE => |_: &mut App, data: &mut PathFollower, ctx: &mut NodeContext, dt: f32| path_follower_update_node(data, ctx, dt)`.
+///
+/// Each child is a waypoint, in order, at its position in the space of the nodes parent, so empty children can be
+/// placed and drawn where the node should go.  Children move with their parent, so the children are moved back by
+/// however far the node moved, keeping every waypoint where it is.  A node without children does not move.
+///
+/// Arguments:
+/// * follower: &mut PathFollower - The follower whose progress will be advanced.
+/// * ctx: &mut NodeContext - The node to move, whose children are the waypoints.
+/// * dt: f32 - The time since the last update in seconds.
+pub fn path_follower_update_node<C: ComponentDef<A>, A>(follower: &mut PathFollower, ctx: &mut NodeContext<'_, C, A>, dt: f32) {
+    let origin = ctx.transform.position;
+    let positions: Vec<Vector3<f32>> = ctx.children().iter().map(|child| origin + child.transform.position).collect();
+    if positions.is_empty() { return }
+
+    path_follower_update(follower, &positions, ctx.transform, dt);
+    let moved = ctx.transform.position - origin;
+    ctx.children_mut().iter_mut().for_each(|child| child.transform.position -= moved);
+}
+
+// moves the follower the given distance along a path with a non zero length
+fn advance(follower: &mut PathFollower, mut remaining: f32, segments: usize, segment: impl Fn(usize) -> (Vector3<f32>, Vector3<f32>)) {
+    loop {
+        let (a, b) = segment(follower.current);
+        let length = (b - a).magnitude();
+        let left = if follower.reverse { follower.t * length } else { (1.0 - follower.t) * length };
+
+        // stop part way along this segment
+        if remaining < left {
+            let step = remaining / length;
+            follower.t += if follower.reverse { -step } else { step };
+            break;
+        }
+        remaining -= left;
+
+        // move onto the next segment, or handle the end of the path
+        let at_end = if follower.reverse { follower.current == 0 } else { follower.current + 1 == segments };
+        if !at_end {
+            if follower.reverse { follower.current -= 1; follower.t = 1.0; }
+            else { follower.current += 1; follower.t = 0.0; }
+            continue;
+        }
+
+        match follower.mode {
+            PathMode::Loop => {
+                if follower.reverse { follower.current = segments - 1; follower.t = 1.0; }
+                else { follower.current = 0; follower.t = 0.0; }
+            },
+            PathMode::PingPong => {
+                follower.t = if follower.reverse { 0.0 } else { 1.0 };
+                follower.reverse = !follower.reverse;
+            },
+            PathMode::Once => {
+                follower.t = if follower.reverse { 0.0 } else { 1.0 };
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3};
+    use forte_engine::math::transforms::Transform;
+
+    use super::{path_follower_update, PathFollower, PathMode};
+    use self::world::*;
+
+    #[allow(dead_code)]
+    mod world {
+        use crate::path_follower::{path_follower_update_node, PathFollower};
+
+        pub struct TestApp;
+
+        crate::define_world!(TestApp, [
+            Follower => {
+                DATA => PathFollower,
+                UPDATE => |_: &mut TestApp, data: &mut PathFollower, ctx: &mut NodeContext, dt: f32| path_follower_update_node(data, ctx, dt)
+            }
+        ]);
+    }
+
+    // an L shaped path with segments of length 1 and 3
+    fn waypoints() -> [Vector3<f32>; 3] { [Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0), Vector3::new(1.0, 3.0, 0.0)] }
+
+    // moves a follower with the given mode by one unit per step, giving where it is after each step
+    fn steps(mode: PathMode, count: usize) -> Vec<Vector3<f32>> {
+        let mut follower = PathFollower::new(1.0, mode);
+        let mut transform = Transform::default();
+        (0..count).map(|_| {
+            path_follower_update(&mut follower, &waypoints(), &mut transform, 1.0);
+            transform.position
+        }).collect()
+    }
+
+    fn assert_near(found: &[Vector3<f32>], expected: &[[f32; 3]]) {
+        assert_eq!(found.len(), expected.len());
+        for (found, expected) in found.iter().zip(expected) {
+            assert!((found - Vector3::from(*expected)).magnitude() < 1e-4, "expected {:?} but found {:?}", expected, found);
+        }
+    }
+
+    #[test]
+    fn constant_speed_across_unequal_segments() {
+        let mut follower = PathFollower::new(0.5, PathMode::Once);
+        let mut transform = Transform::default();
+        let mut last = transform.position;
+        for _ in 0..7 {
+            path_follower_update(&mut follower, &waypoints(), &mut transform, 1.0);
+            let step = transform.position - last;
+
+            // the step around the corner is shorter as a straight line, but covers the same distance along the path
+            if follower.current == 1 && follower.t > 0.2 { assert!((step.magnitude() - 0.5).abs() < 1e-4); }
+            last = transform.position;
+        }
+        assert_near(&[transform.position], &[[1.0, 2.5, 0.0]]);
+    }
+
+    #[test]
+    fn loop_wraps_back_to_the_first_waypoint() {
+        // the closing segment from the last waypoint to the first is about 3.16 long
+        let found = steps(PathMode::Loop, 8);
+        assert_near(&found[..4], &[[1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [1.0, 2.0, 0.0], [1.0, 3.0, 0.0]]);
+        let back = 3.0 / 10.0f32.sqrt();
+        assert_near(&found[4..5], &[[1.0 - back / 3.0, 3.0 - back, 0.0]]);
+        assert_near(&found[7..], &[[4.0 - 10.0f32.sqrt(), 0.0, 0.0]]);
+    }
+
+    #[test]
+    fn ping_pong_turns_around_at_each_end() {
+        let found = steps(PathMode::PingPong, 9);
+        assert_near(&found, &[
+            [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [1.0, 2.0, 0.0], [1.0, 3.0, 0.0],
+            [1.0, 2.0, 0.0], [1.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0]
+        ]);
+    }
+
+    #[test]
+    fn once_stops_at_the_last_waypoint() {
+        let found = steps(PathMode::Once, 6);
+        assert_near(&found, &[[1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [1.0, 2.0, 0.0], [1.0, 3.0, 0.0], [1.0, 3.0, 0.0], [1.0, 3.0, 0.0]]);
+    }
+
+    #[test]
+    fn node_follows_its_child_waypoints() {
+        let mut world = World::new();
+        let root = world.root_id();
+        let mut node = Node::with_follower(PathFollower::new(1.0, PathMode::PingPong));
+        node.transform.position = Vector3::new(5.0, 0.0, 0.0);
+        for waypoint in waypoints() {
+            let mut child = Node::default();
+            child.transform.position = waypoint;
+            node.add_child(child);
+        }
+        let id = world.spawn(root, node).unwrap();
+
+        // the waypoints are where the children were placed in the space of the nodes parent, and stay there
+        let mut positions = Vec::new();
+        for _ in 0..5 {
+            world.update(&mut TestApp, 1.0);
+            let node = world.get(id).unwrap();
+            positions.push(node.transform.position);
+            let children: Vec<_> = node.children().iter().map(|child| node.transform.position + child.transform.position).collect();
+            assert_near(&children, &[[5.0, 0.0, 0.0], [6.0, 0.0, 0.0], [6.0, 3.0, 0.0]]);
+        }
+        assert_near(&positions, &[[6.0, 0.0, 0.0], [6.0, 1.0, 0.0], [6.0, 2.0, 0.0], [6.0, 3.0, 0.0], [6.0, 2.0, 0.0]]);
+
+        // a follower without waypoints stays put
+        let lone = world.spawn(root, Node::with_follower(PathFollower::new(1.0, PathMode::Loop))).unwrap();
+        world.update(&mut TestApp, 1.0);
+        assert_eq!(world.get(lone).unwrap().transform.position, Vector3::new(0.0, 0.0, 0.0));
+    }
+}
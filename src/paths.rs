@@ -0,0 +1,153 @@
+use std::fmt;
+
+use smallvec::SmallVec;
+
+use crate::{ids::NodeId, nodes::{ComponentDef, Node}};
+
+/// A path to a node given as the child index taken at each level of the tree, starting from the node the path was
+/// created from.  An empty path points at that node itself.
+///
+/// Unlike references, a path does not borrow the tree, so it can be stored and resolved again later.  Resolving a path
+/// with `Node::resolve_path` is O(depth).  Paths are positional, so adding or removing a sibling before any index on
+/// the path will make the path point at a different node.
+///
+/// As indices are stored as u16s, only the first 65536 children of a node can be reached by a path.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct NodePath(pub SmallVec<[u16; 8]>);
+
+impl NodePath {
+    /// Creates a new empty path, pointing at the node it is resolved from.
+    pub fn new() -> Self { Self(SmallVec::new()) }
+
+    /// The child indices of this path from top to bottom.
+    pub fn indices(&self) -> &[u16] { &self.0 }
+
+    /// The number of levels this path descends.
+    pub fn depth(&self) -> usize { self.0.len() }
+
+    /// Appends a child index to the end of this path.
+    pub fn push(&mut self, idx: u16) { self.0.push(idx); }
+
+    /// Removes the last child index of this path.
+    ///
+    /// Returns the removed index or None if this path was empty.
+    pub fn pop(&mut self) -> Option<u16> { self.0.pop() }
+
+    /// Creates a path to the child at the given index of the node this path points too.
+    pub fn child(&self, idx: u16) -> Self {
+        let mut path = self.clone();
+        path.push(idx);
+        path
+    }
+
+    /// Creates a path to the parent of the node this path points too.
+    ///
+    /// Returns None if this path is empty.
+    pub fn parent(&self) -> Option<Self> {
+        if self.0.is_empty() { return None }
+        Some(Self(SmallVec::from_slice(&self.0[..self.0.len() - 1])))
+    }
+
+    /// Checks if this path points at the given path or one of its descendants.
+    pub fn starts_with(&self, other: &NodePath) -> bool { self.0.starts_with(&other.0) }
+
+    /// Checks if this path, resolved from the given root, still points at the node with the expected id, as a sibling
+    /// added or removed before it makes it point at another node.
+    pub fn is_still_valid<C: ComponentDef<A>, A>(&self, root: &Node<C, A>, expected: NodeId) -> bool {
+        root.resolve_path(self).is_some_and(|node| node.id() == Some(expected))
+    }
+}
+
+impl From<&[u16]> for NodePath {
+    fn from(value: &[u16]) -> Self { Self(SmallVec::from_slice(value)) }
+}
+
+/// The errors that can occur when resolving node paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodePathError {
+    /// No node exists at the given path.
+    NotFound(NodePath),
+
+    /// The two paths point at the same node, or one is an ancestor of the other, so both cannot be borrowed mutably at
+    /// once as the ancestor contains the descendant.
+    AliasedNodes(NodePath, NodePath)
+}
+
+impl fmt::Display for NodePathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodePathError::NotFound(path) => write!(f, "no node exists at path {:?}", path.indices()),
+            NodePathError::AliasedNodes(a, b) if a == b => write!(f, "paths {:?} and {:?} point at the same node", a.indices(), b.indices()),
+            NodePathError::AliasedNodes(a, b) => write!(f, "path {:?} is an ancestor of {:?}, so both cannot be borrowed mutably", a.indices(), b.indices())
+        }
+    }
+}
+
+impl std::error::Error for NodePathError {}
+
+#[cfg(test)]
+mod tests {
+    use super::NodePath;
+    use crate::test_support::*;
+
+    #[test]
+    fn path_is_invalid_after_an_earlier_sibling_is_removed() {
+        let mut world = World::new();
+        let ids: Vec<_> = (0..3).map(|_| world.spawn(world.root_id(), Node::default()).unwrap()).collect();
+        let path = world.path_of(ids[1]).unwrap();
+        assert_eq!(path, NodePath::from(&[1][..]));
+        assert!(path.is_still_valid(world.root(), ids[1]));
+        assert!(!path.is_still_valid(world.root(), ids[2]));
+
+        world.despawn(ids[0]);
+        assert!(!path.is_still_valid(world.root(), ids[1]));
+        assert!(path.is_still_valid(world.root(), ids[2]));
+        assert!(!NodePath::from(&[5][..]).is_still_valid(world.root(), ids[2]));
+    }
+
+    // a world with three markers numbered 0 to 2 under the root, and the path to the middle one
+    fn three() -> (World, Vec<crate::ids::NodeId>, NodePath) {
+        let mut world = World::new();
+        let ids: Vec<_> = (0..3).map(|idx| world.spawn(world.root_id(), marker(idx)).unwrap()).collect();
+        let path = world.path_of(ids[1]).unwrap();
+        (world, ids, path)
+    }
+
+    #[test]
+    fn path_is_invalid_after_an_earlier_sibling_is_inserted() {
+        let (mut world, ids, path) = three();
+        let inserted = world.insert_child_at(world.root_id(), 0, marker(3)).unwrap();
+        assert!(!path.is_still_valid(world.root(), ids[1]));
+        assert!(path.is_still_valid(world.root(), ids[0]));
+        assert_eq!(world.path_of(ids[1]).unwrap(), NodePath::from(&[2][..]));
+        assert!(world.path_of(inserted).unwrap().is_still_valid(world.root(), inserted));
+    }
+
+    #[test]
+    fn path_stays_valid_after_later_siblings_change() {
+        let (mut world, ids, path) = three();
+        world.spawn(world.root_id(), marker(3));
+        assert!(path.is_still_valid(world.root(), ids[1]));
+        world.insert_child_at(world.root_id(), 2, marker(4));
+        assert!(path.is_still_valid(world.root(), ids[1]));
+
+        world.despawn(ids[2]);
+        assert!(path.is_still_valid(world.root(), ids[1]));
+        assert_eq!(world.root().resolve_path(&path).unwrap().get::<Marker>(), Some(&Marker(1)));
+    }
+
+    #[test]
+    fn mutable_resolution_follows_the_same_positions() {
+        let (mut world, ids, path) = three();
+        world.root_mut().resolve_path_mut(&path).unwrap().get_mut::<Marker>().unwrap().0 = 10;
+        assert_eq!(world.get(ids[1]).unwrap().get::<Marker>(), Some(&Marker(10)));
+
+        // once an earlier sibling is gone the same path reaches the next node along
+        world.despawn(ids[0]);
+        let node = world.root_mut().resolve_path_mut(&path).unwrap();
+        assert_eq!(node.id(), Some(ids[2]));
+        node.get_mut::<Marker>().unwrap().0 = 20;
+        assert_eq!(world.get(ids[2]).unwrap().get::<Marker>(), Some(&Marker(20)));
+        assert!(world.root_mut().resolve_path_mut(&NodePath::from(&[2][..])).is_none());
+    }
+}
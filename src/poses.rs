@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use forte_engine::math::transforms::Transform;
+
+use crate::{
+    ids::NodeId,
+    nodes::{ComponentDef, World}
+};
+
+/// The pose of one node in a `PoseFile`.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodePose {
+    /// The id of the node in the world the pose was saved from.
+    pub id: NodeId,
+
+    /// The local transform of the node.
+    #[cfg_attr(feature = "serde", serde(with = "crate::math::TransformDef"))]
+    pub transform: Transform,
+
+    /// The enabled flag of the node.
+    pub enabled: bool
+}
+
+/// The local transforms and enabled flags of every node of a world, from `World::save_pose`.  Unlike a scene this holds
+/// no components or names, so it is small enough to keep many of, IE for photo modes or scrubbing through a replay.
+/// With the `serde` feature, pose files can be written in any serde format.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoseFile {
+    /// The pose of each node, in the pre-order of the tree it was saved from.
+    pub poses: Vec<NodePose>
+}
+
+impl PoseFile {
+    /// The pose of the node with the given id, None if the file has no pose for it.
+    pub fn get(&self, id: NodeId) -> Option<&NodePose> { self.poses.iter().find(|pose| pose.id == id) }
+}
+
+/// The result of `World::load_pose`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PoseLoadReport {
+    /// The number of nodes given their pose from the file.
+    pub applied: usize,
+
+    /// The ids in the file that no node of the world has, in the order of the file.
+    pub missing_in_world: Vec<NodeId>,
+
+    /// The ids of the nodes of the world that the file has no pose for, in pre-order.
+    pub missing_in_file: Vec<NodeId>
+}
+
+impl PoseLoadReport {
+    /// True if every pose in the file was applied and every node of the world had a pose.
+    pub fn is_exact(&self) -> bool { self.missing_in_world.is_empty() && self.missing_in_file.is_empty() }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Saves the local transform and enabled flag of every node with an id, see `PoseFile`.
+    pub fn save_pose(&self) -> PoseFile {
+        let poses = self.iter(true)
+            .filter_map(|node| Some(NodePose { id: node.id()?, transform: node.transform, enabled: node.enabled }))
+            .collect();
+        PoseFile { poses }
+    }
+
+    /// Gives each node the local transform and enabled flag saved for its id in the given pose file.  Ids are not
+    /// required to match, nodes without a pose are left as they are, and poses without a node are skipped, with both
+    /// listed in the returned report.
+    ///
+    /// Only the transforms and flags are changed, so no components are touched and no callbacks are fired, not even
+    /// the enable and disable functions.  Global transforms are brought up to date by the next update.
+    pub fn load_pose(&mut self, pose: &PoseFile) -> PoseLoadReport {
+        let mut pending: HashMap<NodeId, &NodePose> = pose.poses.iter().map(|pose| (pose.id, pose)).collect();
+        let mut report = PoseLoadReport::default();
+        for node in self.iter_mut(true) {
+            let Some(id) = node.id() else { continue };
+            match pending.remove(&id) {
+                Some(pose) => {
+                    *node.transform = pose.transform;
+                    *node.enabled = pose.enabled;
+                    report.applied += 1;
+                },
+                None => report.missing_in_file.push(id)
+            }
+        }
+
+        report.missing_in_world = pose.poses.iter().map(|pose| pose.id).filter(|id| pending.contains_key(id)).collect();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Quaternion, Vector3};
+
+    use super::PoseFile;
+    use self::world::*;
+
+    #[allow(dead_code)]
+    mod world {
+        use std::cell::Cell;
+
+        pub struct TestApp;
+
+        #[derive(Debug)]
+        pub struct Marker(pub u32);
+
+        thread_local! {
+            pub static CALLBACKS: Cell<usize> = const { Cell::new(0) };
+        }
+
+        fn bump() { CALLBACKS.with(|count| count.set(count.get() + 1)); }
+
+        crate::define_world!(TestApp, [
+            Marker => {
+                DATA => Marker,
+                ADDED => |_: &mut Node| bump(),
+                REMOVED => |_: &mut Node| bump(),
+                ON_ENABLE => |_: &mut Node| bump(),
+                ON_DISABLE => |_: &mut Node| bump()
+            }
+        ]);
+    }
+
+    fn callbacks() -> usize { CALLBACKS.with(|count| count.get()) }
+
+    fn positions(world: &World) -> Vec<(f32, f32, f32)> { world.iter(true).map(|node| node.transform.position.into()).collect() }
+
+    #[test]
+    fn poses_round_trip() {
+        let mut world = World::new();
+        let a = world.spawn(world.root_id(), Node::with_marker(Marker(1))).unwrap();
+        let b = world.spawn(a, Node::with_marker(Marker(2))).unwrap();
+        world.get_mut(a).unwrap().transform.position = Vector3::new(1.0, 2.0, 3.0);
+        world.get_mut(b).unwrap().transform.rotation = Quaternion::new(0.0, 1.0, 0.0, 0.0);
+        let pose = world.save_pose();
+        let saved = positions(&world);
+
+        // move and toggle everything, then put it back
+        world.get_mut(a).unwrap().transform.position = Vector3::new(9.0, 9.0, 9.0);
+        world.get_mut(b).unwrap().transform.rotation = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+        world.set_enabled(b, false);
+        world.update(&mut TestApp, 0.1);
+        let before = callbacks();
+        let report = world.load_pose(&pose);
+        assert_eq!(report.applied, 3);
+        assert!(report.is_exact());
+        assert_eq!(callbacks(), before);
+        assert_eq!(positions(&world), saved);
+        assert_eq!(world.get(b).unwrap().transform.rotation, Quaternion::new(0.0, 1.0, 0.0, 0.0));
+        assert!(world.get(b).unwrap().enabled);
+        assert_eq!(world.get(b).unwrap().get::<Marker>().unwrap().0, 2);
+
+        // globals catch up in a single update
+        world.update(&mut TestApp, 0.1);
+        assert_eq!(world.get(b).unwrap().global_transform().position, Vector3::new(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn mismatched_ids_are_reported() {
+        let mut world = World::new();
+        let kept = world.spawn(world.root_id(), Node::with_marker(Marker(1))).unwrap();
+        let gone = world.spawn(world.root_id(), Node::with_marker(Marker(2))).unwrap();
+        world.get_mut(kept).unwrap().transform.position = Vector3::new(1.0, 0.0, 0.0);
+        let pose = world.save_pose();
+
+        assert!(world.despawn(gone));
+        let new = world.spawn(kept, Node::default()).unwrap();
+        world.get_mut(kept).unwrap().transform.position = Vector3::new(5.0, 0.0, 0.0);
+
+        let report = world.load_pose(&pose);
+        assert_eq!(report.applied, 2);
+        assert_eq!(report.missing_in_world, [gone]);
+        assert_eq!(report.missing_in_file, [new]);
+        assert!(!report.is_exact());
+        assert_eq!(world.get(kept).unwrap().transform.position, Vector3::new(1.0, 0.0, 0.0));
+
+        // an empty pose changes nothing and is missing every node
+        let report = world.load_pose(&PoseFile::default());
+        assert_eq!(report.applied, 0);
+        assert_eq!(report.missing_in_file, [world.root_id(), kept, new]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn poses_serialize() {
+        let mut world = World::new();
+        let a = world.spawn(world.root_id(), Node::with_marker(Marker(1))).unwrap();
+        world.get_mut(a).unwrap().transform.scale = Vector3::new(2.0, 3.0, 4.0);
+        world.get_mut(a).unwrap().enabled = false;
+        let json = serde_json::to_string(&world.save_pose()).unwrap();
+        let pose: PoseFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(pose.poses.len(), 2);
+        assert_eq!(pose.get(a).unwrap().transform.scale, Vector3::new(2.0, 3.0, 4.0));
+        assert!(!pose.get(a).unwrap().enabled);
+
+        world.get_mut(a).unwrap().enabled = true;
+        assert!(world.load_pose(&pose).is_exact());
+        assert!(!world.get(a).unwrap().enabled);
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use crate::{
+    ids::NodeId,
+    nodes::{ComponentDef, Node, World},
+    scenes::{SceneError, SceneFile}
+};
+
+/// A template of a node subtree that can be instantiated any number of times, IE for enemies or props.
+///
+/// Instances are made with `Node::duplicate`, so the component enum must derive `Clone`, see the DERIVE option of
+/// `define_world!`.  The added functions of an instance fire when it is attached, not when the prefab is made.
+pub struct Prefab<C, A> {
+    template: Node<C, A>
+}
+
+impl<C: ComponentDef<A> + Clone, A> Prefab<C, A> {
+    /// Creates a new prefab from the given subtree, dropping any ids and tweens it has.
+    pub fn new(template: Node<C, A>) -> Self { Self { template: template.duplicate() } }
+
+    /// Loads a prefab from a scene file on disk, see `SceneFile::read`.  Scene files may also use prefab files
+    /// directly, see `SceneNode::prefab`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SceneError> { Ok(Self { template: Node::from_scene(&SceneFile::read(path)?.root)? }) }
+
+    /// The subtree this prefab instantiates.
+    pub fn template(&self) -> &Node<C, A> { &self.template }
+
+    /// Creates a new copy of the subtree.
+    pub fn instantiate(&self) -> Node<C, A> { self.template.duplicate() }
+
+    /// Creates a new copy of the subtree, passing it to the given function to override anything for this instance
+    /// before it is returned.
+    pub fn instantiate_with(&self, overrides: impl FnOnce(&mut Node<C, A>)) -> Node<C, A> {
+        let mut node = self.instantiate();
+        overrides(&mut node);
+        node
+    }
+
+    /// Spawns a new copy of the subtree under the given parent, after passing it to the given function to override
+    /// anything for this instance, see `World::spawn`.
+    ///
+    /// Returns the id of the spawned node, or None if it could not be spawned.
+    pub fn spawn(&self, world: &mut World<C, A>, parent: NodeId, overrides: impl FnOnce(&mut Node<C, A>)) -> Option<NodeId> {
+        world.spawn(parent, self.instantiate_with(overrides))
+    }
+}
@@ -0,0 +1,92 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+/// The result of `Node::attach_where`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AttachReport {
+    /// The number of matching nodes that were given a component.
+    pub attached: usize,
+
+    /// The number of matching nodes that were skipped because they already had a component.
+    pub skipped: usize
+}
+
+/// A candidate kept by `KNearest`, ordered by distance and then by the order it was offered in.
+struct Candidate<T> {
+    dist: f32,
+    order: usize,
+    item: T
+}
+
+impl<T> PartialEq for Candidate<T> {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+
+impl<T> Eq for Candidate<T> {}
+
+impl<T> PartialOrd for Candidate<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl<T> Ord for Candidate<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.dist.total_cmp(&other.dist).then(self.order.cmp(&other.order))
+    }
+}
+
+/// A bounded max heap that keeps the k closest items offered to it within a maximum distance.
+///
+/// When two items are the same distance away, the item that was offered first wins.
+pub struct KNearest<T> {
+    k: usize,
+    max_dist: f32,
+    offered: usize,
+    heap: BinaryHeap<Candidate<T>>
+}
+
+impl<T> KNearest<T> {
+    /// Creates a new empty KNearest.
+    ///
+    /// Arguments:
+    /// * k: usize - The maximum number of items to keep.
+    /// * max_dist: f32 - The maximum distance (inclusive) an item can be to be kept.
+    ///
+    /// Returns the new KNearest.
+    pub fn new(k: usize, max_dist: f32) -> Self {
+        Self { k, max_dist, offered: 0, heap: BinaryHeap::with_capacity(k) }
+    }
+
+    /// The furthest distance an item can be and still be kept.  This is the max distance until k items have been found,
+    /// after which it is the distance of the furthest kept item.
+    pub fn bound(&self) -> f32 {
+        match self.heap.peek() {
+            Some(furthest) if self.heap.len() >= self.k => furthest.dist.min(self.max_dist),
+            _ => self.max_dist
+        }
+    }
+
+    /// Offers an item to be kept.
+    ///
+    /// Arguments:
+    /// * &mut self - The KNearest to offer too.
+    /// * item: T - The item being offered.
+    /// * dist: f32 - The distance of the item.
+    pub fn offer(&mut self, item: T, dist: f32) {
+        let order = self.offered;
+        self.offered += 1;
+        if self.k == 0 || dist.is_nan() || dist > self.max_dist { return }
+
+        if self.heap.len() < self.k {
+            self.heap.push(Candidate { dist, order, item });
+        } else if self.heap.peek().is_some_and(|furthest| dist < furthest.dist) {
+            self.heap.pop();
+            self.heap.push(Candidate { dist, order, item });
+        }
+    }
+
+    /// Consumes this KNearest.
+    ///
+    /// Returns all kept items sorted by ascending distance.
+    pub fn into_sorted(self) -> Vec<(T, f32)> {
+        self.heap.into_sorted_vec().into_iter().map(|candidate| (candidate.item, candidate.dist)).collect()
+    }
+}
@@ -0,0 +1,224 @@
+use std::{fmt, sync::atomic::{AtomicU64, AtomicUsize, Ordering}, time::Duration};
+
+use crate::nodes::{ComponentDef, World};
+
+/// What a world did in its current frame, see `World::frame_report`, for an overlay showing the health of the world.
+/// The counts start over with each `World::begin_frame`, which `World::tick` calls.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameReport {
+    /// The frame of the world, see `World::frame`.
+    pub frame: u64,
+
+    /// The number of nodes with ids, including the root.
+    pub nodes: usize,
+
+    /// The number of nodes updated in the frame, a node updated twice, IE by `update` and `update_layer`, counts twice.
+    pub updated: usize,
+
+    /// The number of nodes not updated in the frame, IE disabled nodes and their children or the layers left out by
+    /// `update_layer`.
+    pub skipped: usize,
+
+    /// The name of each variant and the number of times its RENDER callback was called in the frame, in the order the
+    /// variants were defined.
+    pub draw_calls: Vec<(String, usize)>,
+
+    /// The number of culling tests failed by the draws of the frame, a culled subtree counting once.
+    pub culled: usize,
+
+    /// The number of commands queued with `World::queue_command`, commands sent from other threads are not counted
+    /// until they are applied.
+    pub queued_commands: usize,
+
+    /// The time spent in the updates of the frame.
+    pub update_time: Duration,
+
+    /// The time spent in the draws of the frame.
+    pub draw_time: Duration
+}
+
+impl FrameReport {
+    /// The total number of components rendered in the frame.
+    pub fn total_draw_calls(&self) -> usize { self.draw_calls.iter().map(|(_, count)| count).sum() }
+}
+
+impl fmt::Display for FrameReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let millis = |time: Duration| format!("{:.3} ms", time.as_secs_f64() * 1000.0);
+        writeln!(f, "{:<16}{:>12}", "frame", self.frame)?;
+        writeln!(f, "{:<16}{:>12}", "nodes", self.nodes)?;
+        writeln!(f, "{:<16}{:>12}", "updated", self.updated)?;
+        writeln!(f, "{:<16}{:>12}", "skipped", self.skipped)?;
+        writeln!(f, "{:<16}{:>12}", "culled", self.culled)?;
+        writeln!(f, "{:<16}{:>12}", "commands", self.queued_commands)?;
+        writeln!(f, "{:<16}{:>12}", "update time", millis(self.update_time))?;
+        writeln!(f, "{:<16}{:>12}", "draw time", millis(self.draw_time))?;
+        write!(f, "{:<16}{:>12}", "draw calls", self.total_draw_calls())?;
+        self.draw_calls.iter().try_for_each(|(name, count)| write!(f, "\n  {:<14}{:>12}", name, count))
+    }
+}
+
+// the counts behind `World::frame_report`, the draw counts are atomic as worlds are drawn through a shared reference
+#[derive(Debug, Default)]
+pub(crate) struct FrameCounters {
+    pub(crate) updated: usize,
+    pub(crate) update_time: Duration,
+    draw_calls: Vec<AtomicUsize>,
+    culled: AtomicUsize,
+    draw_nanos: AtomicU64
+}
+
+impl FrameCounters {
+    // starts the counts of a new frame for the given number of variants
+    pub(crate) fn reset(&mut self, variants: usize) {
+        self.updated = 0;
+        self.update_time = Duration::ZERO;
+        self.draw_calls.resize_with(variants, AtomicUsize::default);
+        self.draw_calls.iter_mut().for_each(|count| *count.get_mut() = 0);
+        *self.culled.get_mut() = 0;
+        *self.draw_nanos.get_mut() = 0;
+    }
+
+    // counts a component of the variant with the given index rendered
+    pub(crate) fn drawn(&self, variant: usize) {
+        if let Some(count) = self.draw_calls.get(variant) { count.fetch_add(1, Ordering::Relaxed); }
+    }
+
+    // counts a failed culling test
+    pub(crate) fn culled(&self) { self.culled.fetch_add(1, Ordering::Relaxed); }
+
+    // adds the time taken by a draw
+    pub(crate) fn add_draw_time(&self, time: Duration) {
+        self.draw_nanos.fetch_add(time.as_nanos().try_into().unwrap_or(u64::MAX), Ordering::Relaxed);
+    }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Starts a new frame like `advance_frame`, and starts the counts of `frame_report` over.  `tick` calls this, so
+    /// apps that update the world without `tick` should call it once at the start of each frame.
+    ///
+    /// Returns the number of the new frame.
+    pub fn begin_frame(&mut self) -> u64 {
+        self.report.reset(C::VARIANT_NAMES.len());
+        self.advance_frame()
+    }
+
+    /// What this world did since the last `begin_frame`, counted by `update`, `update_layer` and `draw` as they run.
+    pub fn frame_report(&self) -> FrameReport {
+        let nodes = self.id_count();
+        let draw_calls = C::VARIANT_NAMES.iter().enumerate()
+            .map(|(idx, name)| (name.to_string(), self.report.draw_calls.get(idx).map_or(0, |count| count.load(Ordering::Relaxed))))
+            .collect();
+        FrameReport {
+            frame: self.frame(),
+            nodes,
+            updated: self.report.updated,
+            skipped: nodes.saturating_sub(self.report.updated),
+            draw_calls,
+            culled: self.report.culled.load(Ordering::Relaxed),
+            queued_commands: self.commands.len(),
+            update_time: self.report.update_time,
+            draw_time: Duration::from_nanos(self.report.draw_nanos.load(Ordering::Relaxed))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{marker::PhantomData, time::{Duration, Instant}};
+
+    use cgmath::{Matrix4, SquareMatrix, Vector3};
+
+    use crate::{commands::WorldCommand, culling::{DrawCulling, Frustum}};
+
+    use super::FrameReport;
+    use crate::test_support::*;
+
+    // a marker in front of the identity frustum at the given x
+    fn placed(id: u32, x: f32) -> Node {
+        let mut node = marker(id);
+        node.transform.position = Vector3::new(x, 0.0, 0.5);
+        node
+    }
+
+    #[test]
+    fn a_scripted_frame_is_reported() {
+        let mut world = World::new();
+        let root = world.root_id();
+        world.spawn(root, placed(1, 0.0)).unwrap();
+        let mut parent = placed(2, 0.0);
+        parent.add_child(placed(3, 0.0));
+        let hidden = world.spawn(root, parent).unwrap();
+        let mut other = Node::with_other(Other);
+        other.transform.position = Vector3::new(0.0, 0.0, 0.5);
+        world.spawn(root, other).unwrap();
+        let far = world.spawn(root, placed(4, 100.0)).unwrap();
+        world.update(&mut TestApp, 0.1);
+
+        // a frame that skips a disabled subtree, culls one node and leaves a command queued
+        world.begin_frame();
+        world.set_enabled(hidden, false);
+        world.queue_command(WorldCommand::Despawn(far));
+        let start = Instant::now();
+        world.update(&mut TestApp, 0.1);
+        let updating = start.elapsed();
+        let frustum = Frustum::from_matrix(Matrix4::identity());
+        let culling = DrawCulling { frustum, eye: Vector3::new(0.0, 0.0, 0.0), fov_y: 1.0, viewport_height: 100, min_screen_size: None };
+        let mut pass = TestPass(Vec::new(), PhantomData);
+        let start = Instant::now();
+        world.draw(&mut pass, &TestApp, Some(&culling));
+        let drawing = start.elapsed();
+        assert_eq!(pass.0, [1]);
+
+        let report = world.frame_report();
+        assert!(report.update_time <= updating && report.draw_time <= drawing);
+        let expected = FrameReport {
+            frame: 1,
+            nodes: 6,
+            updated: 4,
+            skipped: 2,
+            draw_calls: vec![("Marker".to_string(), 1), ("Other".to_string(), 0)],
+            culled: 1,
+            queued_commands: 1,
+            update_time: report.update_time,
+            draw_time: report.draw_time
+        };
+        assert_eq!(report, expected);
+
+        // the next frame starts the counts over
+        world.begin_frame();
+        let report = world.frame_report();
+        assert_eq!((report.frame, report.updated, report.culled, report.total_draw_calls()), (2, 0, 0, 0));
+        assert_eq!((report.update_time, report.draw_time), (Duration::ZERO, Duration::ZERO));
+    }
+
+    #[test]
+    fn reports_display_as_aligned_lines() {
+        let report = FrameReport {
+            frame: 7,
+            nodes: 12,
+            updated: 10,
+            skipped: 2,
+            draw_calls: vec![("Marker".to_string(), 3), ("Other".to_string(), 0)],
+            culled: 1,
+            queued_commands: 4,
+            update_time: Duration::from_micros(1250),
+            draw_time: Duration::from_micros(500)
+        };
+        let expected = [
+            "frame                      7",
+            "nodes                     12",
+            "updated                   10",
+            "skipped                    2",
+            "culled                     1",
+            "commands                   4",
+            "update time         1.250 ms",
+            "draw time           0.500 ms",
+            "draw calls                 3",
+            "  Marker                   3",
+            "  Other                    0"
+        ];
+        assert_eq!(report.to_string(), expected.join("\n"));
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use crate::nodes::{ComponentDef, Node, World};
+
+/// A source of random numbers for `World::sample_nodes`, kept this small so any generator can be used through a short
+/// wrapper, IE around rand's `RngCore`.
+pub trait NodeRng {
+    /// The next random number, with every bit equally likely to be set.
+    fn next_u64(&mut self) -> u64;
+
+    /// A random number below the given bound, which must not be 0.
+    fn below(&mut self, bound: usize) -> usize { ((self.next_u64() as u128 * bound as u128) >> 64) as usize }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Calls the given function on up to count nodes picked at random from every node with an id, the same node is
+    /// never picked twice.  Ids are picked straight from the table of live ids, so this takes time in the number of
+    /// nodes picked rather than the size of the tree.  Ids whose node has been taken out of the tree without going
+    /// through the world are skipped, and nodes added without going through the world cannot be picked until
+    /// `refresh_ids` runs.
+    ///
+    /// Returns the number of nodes the function was called on, which is less than count only when there are fewer
+    /// nodes than that.
+    pub fn sample_nodes(&mut self, count: usize, rng: &mut impl NodeRng, mut f: impl FnMut(&mut Node<C, A>)) -> usize {
+        // a partial shuffle of the live ids, only the swapped positions are stored
+        let live = self.ids.len();
+        let mut swapped: HashMap<usize, usize> = HashMap::new();
+        let mut visited = 0;
+        for drawn in 0..live {
+            if visited == count { break }
+
+            let pick = drawn + rng.below(live - drawn);
+            let position = swapped.get(&pick).copied().unwrap_or(pick);
+            swapped.insert(pick, swapped.get(&drawn).copied().unwrap_or(drawn));
+
+            let Some(node) = self.ids.live_at(position).and_then(|id| self.get_mut(id)) else { continue };
+            f(node);
+            visited += 1;
+        }
+        visited
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::NodeRng;
+    use crate::test_support::*;
+
+    struct XorShift(u64);
+
+    impl NodeRng for XorShift {
+        fn next_u64(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+    }
+
+    // a world of markers numbered 1 to count below the root, which is marker 0 so it can be picked too
+    fn markers(count: usize) -> World {
+        let mut world = World::from_root(marker(0));
+        (1..=count).for_each(|idx| { world.spawn(world.root_id(), marker(idx as u32)); });
+        world
+    }
+
+    fn sample(world: &mut World, count: usize, rng: &mut XorShift) -> Vec<usize> {
+        let mut picked = Vec::new();
+        world.sample_nodes(count, rng, |node| picked.push(node.get::<Marker>().unwrap().0 as usize));
+        picked
+    }
+
+    #[test]
+    fn samples_without_replacement() {
+        let mut world = markers(9);
+        let mut rng = XorShift(7);
+        for count in 0..=10 {
+            let picked = sample(&mut world, count, &mut rng);
+            assert_eq!(picked.len(), count.min(10));
+            assert_eq!(picked.iter().collect::<HashSet<_>>().len(), picked.len());
+        }
+
+        // asking for more than there are visits every node once
+        let mut all = sample(&mut world, 100, &mut rng);
+        all.sort();
+        assert_eq!(all, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn samples_uniformly() {
+        let mut world = markers(9);
+        let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+        let mut hits = [0usize; 10];
+        for _ in 0..10_000 {
+            sample(&mut world, 3, &mut rng).into_iter().for_each(|marker| hits[marker] += 1);
+        }
+
+        // each node is expected 3000 times
+        assert!(hits.iter().all(|hits| (2700..3300).contains(hits)), "{hits:?}");
+    }
+
+    #[test]
+    fn skips_removed_nodes() {
+        let mut world = markers(9);
+        let removed: Vec<_> = world.iter(true).filter(|node| node.get::<Marker>().unwrap().0 % 2 == 1).filter_map(|node| node.id()).collect();
+        removed.into_iter().for_each(|id| assert!(world.despawn(id)));
+
+        // a node taken out behind the worlds back still has a live id until ids are refreshed
+        let taken = world.root_mut().take_child(0);
+        assert_eq!(taken.get::<Marker>().unwrap().0, 2);
+
+        let mut rng = XorShift(11);
+        for _ in 0..50 {
+            let mut picked = sample(&mut world, 10, &mut rng);
+            picked.sort();
+            assert_eq!(picked, [0, 4, 6, 8]);
+        }
+    }
+}
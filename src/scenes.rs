@@ -0,0 +1,850 @@
+use std::{collections::BTreeMap, fmt, fs, path::{Path, PathBuf}};
+
+use cgmath::{Quaternion, Vector3};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::{RemapReport, RemapTable},
+    dimensions::Dimensions,
+    ids::{NodeId, NodeUuid},
+    math::TransformMode,
+    migrations::SceneMigrations,
+    nodes::{ComponentDef, Node, World},
+    validation::ValidationWarning
+};
+
+/// The data of a component as stored in a scene, given by the SAVE codec of its variant and read back by LOAD, see
+/// `define_world!`.
+pub type SceneValue = serde_json::Value;
+
+/// A SAVE codec for data that implements serde's `Serialize`, IE `SAVE => save_serde, LOAD => load_serde` in
+/// `define_world!`.  Data that cannot be written as a scene value, IE maps with keys that are not strings, is saved as
+/// null.
+pub fn save_serde<T: Serialize>(data: &T) -> SceneValue { serde_json::to_value(data).unwrap_or_default() }
+
+/// A LOAD codec for data that implements serde's `Deserialize`, see `save_serde`.  Gives None if the value does not
+/// hold the data.
+pub fn load_serde<T: for<'de> Deserialize<'de>>(value: &SceneValue) -> Option<T> { T::deserialize(value).ok() }
+
+/// The version of the scene format written by this version of the crate.  Scenes of older versions are still read,
+/// version 1 scenes have no schema version and are read with a schema version of 0, and binary scenes before version
+/// 3 have no sub-scenes.
+pub const SCENE_VERSION: u32 = 3;
+
+/// The metadata key of a node holding the path of the lazy sub-scene it loads, see `World::load_sub_scene`.
+pub const SUB_SCENE_META: &str = "sub_scene";
+
+/// The magic bytes at the start of every binary scene.
+pub const SCENE_MAGIC: [u8; 4] = *b"FWSC";
+
+/// The deepest nodes and component data values can be nested in a binary scene, so corrupt scenes cannot overflow the
+/// stack while being read.
+pub const MAX_BINARY_DEPTH: usize = 128;
+
+/// A saved scene, a version and the tree of nodes it holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SceneFile {
+    /// The version of the scene format, written by the crate.
+    pub version: u32,
+
+    /// The version of the apps component set the scene was saved with, which the app bumps as its components change
+    /// so old scenes can be migrated, see `SceneMigrations`.
+    #[serde(default)]
+    pub schema_version: u32,
+    pub root: SceneNode
+}
+
+/// A saved node with everything needed to rebuild it, but none of its runtime state like ids, tweens or its global
+/// transform.  Every field may be left out of a scene, giving the same value as a default node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SceneNode {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+
+    pub position: [f32; 3],
+
+    /// The rotation quaternion as `[x, y, z, w]`.
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+
+    /// The from and to points of the nodes minimum dimensions.
+    pub rel_min_dimensions: [[f32; 3]; 2],
+
+    /// Either `ThreeD` or `TwoD`.
+    pub transform_mode: String,
+    pub confine_to_parent: bool,
+    pub enabled: bool,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component: Option<SceneComponent>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<SceneNode>,
+
+    /// The path of a prefab scene file this node is an instance of, relative to the file holding this node.  When set,
+    /// this node is replaced by the root of the prefab with `overrides` applied and `children` added after the
+    /// children of the prefab, its other fields other than its sub-scene are ignored, see `SceneFile::resolve_prefabs`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefab: Option<String>,
+
+    /// The fields of the prefab to override for this instance, keyed by their dot separated path in the saved node,
+    /// IE `position`, `component.data.speed` or `children.0.name`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, SceneValue>,
+
+    /// The path of another scene file whose root is added as the last child of this node, relative to the file holding
+    /// this node, so large worlds can be split into several files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_scene: Option<String>,
+
+    /// If true the sub-scene is not loaded with this scene.  The node is given the path of the sub-scene in its
+    /// metadata under `SUB_SCENE_META` instead, to be loaded later with `World::load_sub_scene`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub lazy: bool
+}
+
+impl Default for SceneNode {
+    fn default() -> Self {
+        let dimensions = Dimensions::default();
+        Self {
+            name: None,
+            uuid: None,
+            position: [0.0; 3],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0; 3],
+            rel_min_dimensions: [dimensions.from.into(), dimensions.to.into()],
+            transform_mode: transform_mode_name(TransformMode::default()).to_string(),
+            confine_to_parent: false,
+            enabled: true,
+            tags: Vec::new(),
+            metadata: BTreeMap::new(),
+            component: None,
+            children: Vec::new(),
+            prefab: None,
+            overrides: BTreeMap::new(),
+            sub_scene: None,
+            lazy: false
+        }
+    }
+}
+
+/// A saved component, the name of its variant and the data its SAVE codec gave, null if the variant has no codec.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SceneComponent {
+    pub variant: String,
+
+    #[serde(default)]
+    pub data: SceneValue
+}
+
+/// The errors that can occur when loading a scene.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SceneError {
+    /// The text is not a valid scene, with the error given by the parser.
+    Parse(String),
+
+    /// The scene was written by a newer version of the format than this crate can read.
+    UnsupportedVersion(u32),
+
+    /// No variant of the component has the given name.
+    UnknownVariant(String),
+
+    /// The variant with the given name has no LOAD codec.
+    NoCodec(String),
+
+    /// The LOAD codec of the variant with the given name could not read its data.
+    InvalidData(String),
+
+    /// The given uuid is not a valid uuid.
+    InvalidUuid(String),
+
+    /// The scene was saved with the given schema version, which is newer than the schema version of the migrations.
+    UnsupportedSchemaVersion(u32),
+
+    /// No migration was registered from the given schema version.
+    MissingMigration(u32),
+
+    /// The given transform mode is not `ThreeD` or `TwoD`.
+    UnknownTransformMode(String),
+
+    /// The bytes do not start with `SCENE_MAGIC`, so they are not a binary scene.
+    NotBinaryScene,
+
+    /// The binary scene ended before the given byte offset could be read.
+    Truncated(usize),
+
+    /// The binary scene nests nodes or values deeper than `MAX_BINARY_DEPTH` at the given byte offset.
+    TooDeep(usize),
+
+    /// The scene file could not be read, with the error given by the file system.
+    Io(String),
+
+    /// A node is an instance of the prefab or uses the sub-scene at the given path, which has not been resolved.
+    UnresolvedPrefab(String),
+
+    /// The prefab or sub-scene at the given path uses itself, directly or through other prefabs or sub-scenes.
+    PrefabCycle(String),
+
+    /// The override with the given path does not lead to a field of the prefab, or gives it an invalid value.
+    InvalidOverride(String),
+
+    /// A patch sets the given field, which is not a field of a saved node, or sets a field to an invalid value.
+    InvalidPatch(String)
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Parse(error) => write!(f, "invalid scene: {}", error),
+            SceneError::UnsupportedVersion(version) => write!(f, "scene version {} is newer than the supported version {}", version, SCENE_VERSION),
+            SceneError::UnknownVariant(variant) => write!(f, "no component variant is named {:?}", variant),
+            SceneError::NoCodec(variant) => write!(f, "component variant {:?} has no LOAD codec", variant),
+            SceneError::InvalidData(variant) => write!(f, "the data of component variant {:?} could not be loaded", variant),
+            SceneError::InvalidUuid(uuid) => write!(f, "{:?} is not a valid uuid", uuid),
+            SceneError::UnsupportedSchemaVersion(version) => write!(f, "scene schema version {} is newer than the apps schema version", version),
+            SceneError::MissingMigration(version) => write!(f, "no migration is registered from scene schema version {}", version),
+            SceneError::UnknownTransformMode(mode) => write!(f, "{:?} is not a transform mode", mode),
+            SceneError::NotBinaryScene => write!(f, "the bytes are not a binary scene"),
+            SceneError::Truncated(offset) => write!(f, "the binary scene ends before byte {}", offset),
+            SceneError::TooDeep(offset) => write!(f, "the binary scene nests deeper than {} at byte {}", MAX_BINARY_DEPTH, offset),
+            SceneError::Io(error) => write!(f, "the scene file could not be read: {}", error),
+            SceneError::UnresolvedPrefab(path) => write!(f, "the prefab or sub-scene {:?} has not been resolved", path),
+            SceneError::PrefabCycle(path) => write!(f, "the prefab or sub-scene {:?} uses itself", path),
+            SceneError::InvalidOverride(path) => write!(f, "the override {:?} does not fit the prefab", path),
+            SceneError::InvalidPatch(field) => write!(f, "the patch of {:?} does not fit a saved node", field)
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl SceneFile {
+    /// Reads a scene file, in the binary format if it starts with `SCENE_MAGIC` and as JSON otherwise, and resolves
+    /// the prefabs and sub-scenes it uses relative to the directory of the file.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, SceneError> { Self::read_inner(path.as_ref(), None) }
+
+    /// Reads a scene file like `read`, migrating the scene and each prefab and sub-scene it uses to the current schema
+    /// version of the given migrations before they are resolved.
+    pub fn read_migrated(path: impl AsRef<Path>, migrations: &SceneMigrations) -> Result<Self, SceneError> {
+        Self::read_inner(path.as_ref(), Some(migrations))
+    }
+
+    fn read_inner(path: &Path, migrations: Option<&SceneMigrations>) -> Result<Self, SceneError> {
+        let mut scene = Self::read_unresolved(path)?;
+        if let Some(migrations) = migrations { migrations.migrate(&mut scene)?; }
+        let root = std::mem::take(&mut scene.root);
+        scene.root = resolve_references_recr(root, path.parent().unwrap_or(Path::new("")), migrations, &mut vec![path.to_path_buf()])?;
+        Ok(scene)
+    }
+
+    /// Reads a scene file like `read`, but leaves the prefabs and sub-scenes it uses unresolved.
+    pub fn read_unresolved(path: impl AsRef<Path>) -> Result<Self, SceneError> {
+        let bytes = fs::read(path).map_err(|error| SceneError::Io(error.to_string()))?;
+        if bytes.starts_with(&SCENE_MAGIC) { return Self::from_binary(&bytes) }
+        Self::from_json(std::str::from_utf8(&bytes).map_err(|error| SceneError::Parse(error.to_string()))?)
+    }
+
+    /// Replaces every prefab instance in this scene with the root of its prefab file, read relative to the given
+    /// directory, with the overrides of the instance applied.  Prefabs may use other prefabs, which are read relative
+    /// to the directory of the prefab using them.  Sub-scenes are resolved the same way, eager sub-scenes are added as
+    /// children and lazy sub-scenes are given their full path in `SUB_SCENE_META`.
+    ///
+    /// As prefabs are read each time a scene is resolved, edits to a prefab are picked up by every scene using it.
+    pub fn resolve_prefabs(&mut self, dir: impl AsRef<Path>) -> Result<(), SceneError> {
+        let root = std::mem::take(&mut self.root);
+        self.root = resolve_references_recr(root, dir.as_ref(), None, &mut Vec::new())?;
+        Ok(())
+    }
+
+    /// Parses a scene from JSON, checking that its version can be read.
+    pub fn from_json(text: &str) -> Result<Self, SceneError> {
+        let scene: SceneFile = serde_json::from_str(text).map_err(|error| SceneError::Parse(error.to_string()))?;
+        if scene.version > SCENE_VERSION { return Err(SceneError::UnsupportedVersion(scene.version)) }
+        Ok(scene)
+    }
+
+    /// Writes this scene as pretty printed JSON.
+    pub fn to_json(&self) -> String { serde_json::to_string_pretty(self).expect("scenes always serialize") }
+
+    /// Reads a scene from the binary format written by `to_binary`, checking its magic header and that its version
+    /// can be read.
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, SceneError> {
+        if !bytes.starts_with(&SCENE_MAGIC) { return Err(SceneError::NotBinaryScene) }
+
+        let mut reader = BinaryReader { bytes, offset: SCENE_MAGIC.len(), version: 0, depth: 0 };
+        let version = reader.u32()?;
+        if version > SCENE_VERSION { return Err(SceneError::UnsupportedVersion(version)) }
+        reader.version = version;
+        let schema_version = if version >= 2 { reader.u32()? } else { 0 };
+        Ok(Self { version, schema_version, root: reader.node()? })
+    }
+
+    /// Writes this scene in the compact binary format, for shipping large scenes.
+    ///
+    /// The bytes are `SCENE_MAGIC` followed by the version, the schema version and the root node.  All numbers are little endian, strings
+    /// and lists are prefixed with their u32 length, and optional fields with a 0 or 1 byte.
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut out = SCENE_MAGIC.to_vec();
+        out.extend_from_slice(&self.version.to_le_bytes());
+        out.extend_from_slice(&self.schema_version.to_le_bytes());
+        write_node(&mut out, &self.root);
+        out
+    }
+}
+
+// the tags of each kind of binary component data value
+const VALUE_NULL: u8 = 0;
+const VALUE_FALSE: u8 = 1;
+const VALUE_TRUE: u8 = 2;
+const VALUE_U64: u8 = 3;
+const VALUE_I64: u8 = 4;
+const VALUE_F64: u8 = 5;
+const VALUE_STRING: u8 = 6;
+const VALUE_ARRAY: u8 = 7;
+const VALUE_OBJECT: u8 = 8;
+
+fn write_len(out: &mut Vec<u8>, len: usize) { out.extend_from_slice(&(len as u32).to_le_bytes()); }
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    write_len(out, s.len());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn write_floats(out: &mut Vec<u8>, floats: &[f32]) { floats.iter().for_each(|float| out.extend_from_slice(&float.to_le_bytes())); }
+
+fn write_option_str(out: &mut Vec<u8>, s: &Option<String>) {
+    match s {
+        Some(s) => { out.push(1); write_str(out, s); },
+        None => out.push(0)
+    }
+}
+
+fn write_node(out: &mut Vec<u8>, node: &SceneNode) {
+    write_option_str(out, &node.name);
+    write_option_str(out, &node.uuid);
+    write_floats(out, &node.position);
+    write_floats(out, &node.rotation);
+    write_floats(out, &node.scale);
+    write_floats(out, &node.rel_min_dimensions[0]);
+    write_floats(out, &node.rel_min_dimensions[1]);
+    write_str(out, &node.transform_mode);
+    out.push(node.confine_to_parent as u8 | (node.enabled as u8) << 1);
+
+    write_len(out, node.tags.len());
+    node.tags.iter().for_each(|tag| write_str(out, tag));
+    write_len(out, node.metadata.len());
+    node.metadata.iter().for_each(|(key, value)| { write_str(out, key); write_str(out, value); });
+
+    match &node.component {
+        Some(component) => {
+            out.push(1);
+            write_str(out, &component.variant);
+            write_value(out, &component.data);
+        },
+        None => out.push(0)
+    }
+
+    write_len(out, node.children.len());
+    node.children.iter().for_each(|child| write_node(out, child));
+
+    write_option_str(out, &node.prefab);
+    write_len(out, node.overrides.len());
+    node.overrides.iter().for_each(|(field, value)| { write_str(out, field); write_value(out, value); });
+    write_option_str(out, &node.sub_scene);
+    out.push(node.lazy as u8);
+}
+
+fn write_value(out: &mut Vec<u8>, value: &SceneValue) {
+    match value {
+        SceneValue::Null => out.push(VALUE_NULL),
+        SceneValue::Bool(false) => out.push(VALUE_FALSE),
+        SceneValue::Bool(true) => out.push(VALUE_TRUE),
+        SceneValue::Number(number) => {
+            if let Some(number) = number.as_u64() {
+                out.push(VALUE_U64);
+                out.extend_from_slice(&number.to_le_bytes());
+            } else if let Some(number) = number.as_i64() {
+                out.push(VALUE_I64);
+                out.extend_from_slice(&number.to_le_bytes());
+            } else {
+                out.push(VALUE_F64);
+                out.extend_from_slice(&number.as_f64().unwrap_or_default().to_le_bytes());
+            }
+        },
+        SceneValue::String(s) => { out.push(VALUE_STRING); write_str(out, s); },
+        SceneValue::Array(values) => {
+            out.push(VALUE_ARRAY);
+            write_len(out, values.len());
+            values.iter().for_each(|value| write_value(out, value));
+        },
+        SceneValue::Object(fields) => {
+            out.push(VALUE_OBJECT);
+            write_len(out, fields.len());
+            fields.iter().for_each(|(key, value)| { write_str(out, key); write_value(out, value); });
+        }
+    }
+}
+
+// reads values from a binary scene, tracking the offset so truncated scenes say where they ended
+struct BinaryReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    version: u32,
+    depth: usize
+}
+
+impl<'a> BinaryReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SceneError> {
+        let end = self.offset.checked_add(len).filter(|end| *end <= self.bytes.len()).ok_or(SceneError::Truncated(self.offset.saturating_add(len)))?;
+        let bytes = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], SceneError> { Ok(self.take(N)?.try_into().unwrap()) }
+
+    fn u8(&mut self) -> Result<u8, SceneError> { Ok(self.array::<1>()?[0]) }
+    fn u32(&mut self) -> Result<u32, SceneError> { Ok(u32::from_le_bytes(self.array()?)) }
+    fn len(&mut self) -> Result<usize, SceneError> { Ok(self.u32()? as usize) }
+
+    fn floats<const N: usize>(&mut self) -> Result<[f32; N], SceneError> {
+        let mut floats = [0.0; N];
+        for float in floats.iter_mut() { *float = f32::from_le_bytes(self.array()?); }
+        Ok(floats)
+    }
+
+    fn string(&mut self) -> Result<String, SceneError> {
+        let offset = self.offset;
+        let len = self.len()?;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| SceneError::Parse(format!("invalid utf-8 in the string at byte {}", offset)))
+    }
+
+    fn option_string(&mut self) -> Result<Option<String>, SceneError> {
+        Ok(if self.u8()? != 0 { Some(self.string()?) } else { None })
+    }
+
+    // reads a nested node or value, failing once they are nested too deep
+    fn nested<T>(&mut self, read: impl FnOnce(&mut Self) -> Result<T, SceneError>) -> Result<T, SceneError> {
+        if self.depth == MAX_BINARY_DEPTH { return Err(SceneError::TooDeep(self.offset)) }
+        self.depth += 1;
+        let result = read(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn node(&mut self) -> Result<SceneNode, SceneError> { self.nested(Self::node_inner) }
+
+    fn node_inner(&mut self) -> Result<SceneNode, SceneError> {
+        let name = self.option_string()?;
+        let uuid = self.option_string()?;
+        let position = self.floats()?;
+        let rotation = self.floats()?;
+        let scale = self.floats()?;
+        let rel_min_dimensions = [self.floats()?, self.floats()?];
+        let transform_mode = self.string()?;
+        let flags = self.u8()?;
+
+        let tags = (0..self.len()?).map(|_| self.string()).collect::<Result<_, _>>()?;
+        let metadata = (0..self.len()?).map(|_| Ok((self.string()?, self.string()?))).collect::<Result<_, _>>()?;
+        let component = if self.u8()? != 0 { Some(SceneComponent { variant: self.string()?, data: self.value()? }) } else { None };
+        let children = (0..self.len()?).map(|_| self.node()).collect::<Result<_, _>>()?;
+        let prefab = self.option_string()?;
+        let overrides = (0..self.len()?).map(|_| Ok((self.string()?, self.value()?))).collect::<Result<_, _>>()?;
+        let (sub_scene, lazy) = if self.version >= 3 { (self.option_string()?, self.u8()? != 0) } else { (None, false) };
+
+        Ok(SceneNode {
+            name, uuid, position, rotation, scale, rel_min_dimensions, transform_mode,
+            confine_to_parent: flags & 1 != 0,
+            enabled: flags & 2 != 0,
+            tags, metadata, component, children, prefab, overrides, sub_scene, lazy
+        })
+    }
+
+    fn value(&mut self) -> Result<SceneValue, SceneError> { self.nested(Self::value_inner) }
+
+    fn value_inner(&mut self) -> Result<SceneValue, SceneError> {
+        let offset = self.offset;
+        Ok(match self.u8()? {
+            VALUE_NULL => SceneValue::Null,
+            VALUE_FALSE => SceneValue::Bool(false),
+            VALUE_TRUE => SceneValue::Bool(true),
+            VALUE_U64 => SceneValue::from(u64::from_le_bytes(self.array()?)),
+            VALUE_I64 => SceneValue::from(i64::from_le_bytes(self.array()?)),
+            VALUE_F64 => SceneValue::from(f64::from_le_bytes(self.array()?)),
+            VALUE_STRING => SceneValue::String(self.string()?),
+            VALUE_ARRAY => SceneValue::Array((0..self.len()?).map(|_| self.value()).collect::<Result<_, _>>()?),
+            VALUE_OBJECT => SceneValue::Object((0..self.len()?).map(|_| Ok((self.string()?, self.value()?))).collect::<Result<_, _>>()?),
+            tag => return Err(SceneError::Parse(format!("unknown value tag {} at byte {}", tag, offset)))
+        })
+    }
+}
+
+fn transform_mode_name(mode: TransformMode) -> &'static str {
+    match mode {
+        TransformMode::ThreeD => "ThreeD",
+        TransformMode::TwoD => "TwoD"
+    }
+}
+
+// resolves the prefabs and sub-scenes of this node and its children, keeping the stack of files being read to catch
+// cycles
+fn resolve_references_recr(
+    mut node: SceneNode,
+    dir: &Path,
+    migrations: Option<&SceneMigrations>,
+    stack: &mut Vec<PathBuf>
+) -> Result<SceneNode, SceneError> {
+    let children = std::mem::take(&mut node.children).into_iter()
+        .map(|child| resolve_references_recr(child, dir, migrations, stack))
+        .collect::<Result<Vec<_>, _>>()?;
+    let sub_scene = node.sub_scene.take();
+    let lazy = node.lazy;
+
+    let mut node = match node.prefab.take() {
+        Some(prefab) => {
+            let base = read_reference(dir, &prefab, migrations, stack)?;
+
+            // apply the overrides to the saved form of the prefab so any field can be overridden by its path
+            let mut value = serde_json::to_value(&base).expect("scenes always serialize");
+            for (field, override_value) in &node.overrides {
+                if !set_field(&mut value, field, override_value.clone()) { return Err(SceneError::InvalidOverride(field.clone())) }
+            }
+            let invalid = node.overrides.keys().cloned().collect::<Vec<_>>().join(", ");
+            let mut instance: SceneNode = serde_json::from_value(value).map_err(|_| SceneError::InvalidOverride(invalid))?;
+            instance.children.extend(children);
+            instance
+        },
+        None => {
+            node.children = children;
+            node
+        }
+    };
+
+    node.lazy = false;
+    if let Some(sub_scene) = sub_scene {
+        if lazy { node.metadata.insert(SUB_SCENE_META.to_string(), dir.join(&sub_scene).to_string_lossy().into_owned()); }
+        else { node.children.push(read_reference(dir, &sub_scene, migrations, stack)?); }
+    }
+    Ok(node)
+}
+
+// reads the root of the prefab or sub-scene at the given path relative to the given directory, resolving its own
+// references relative to its directory
+fn read_reference(dir: &Path, reference: &str, migrations: Option<&SceneMigrations>, stack: &mut Vec<PathBuf>) -> Result<SceneNode, SceneError> {
+    let path = dir.join(reference);
+    if stack.contains(&path) { return Err(SceneError::PrefabCycle(reference.to_string())) }
+
+    let mut scene = SceneFile::read_unresolved(&path)?;
+    if let Some(migrations) = migrations { migrations.migrate(&mut scene)?; }
+    stack.push(path.clone());
+    let root = resolve_references_recr(scene.root, path.parent().unwrap_or(Path::new("")), migrations, stack);
+    stack.pop();
+    root
+}
+
+// sets the field at the given dot separated path, only the last field may be missing
+fn set_field(value: &mut SceneValue, path: &str, new: SceneValue) -> bool {
+    let (field, rest) = match path.split_once('.') {
+        Some((field, rest)) => (field, Some(rest)),
+        None => (path, None)
+    };
+
+    let slot = match value {
+        SceneValue::Object(fields) => match rest {
+            Some(_) => fields.get_mut(field),
+            None => Some(fields.entry(field.to_string()).or_insert(SceneValue::Null))
+        },
+        SceneValue::Array(values) => field.parse::<usize>().ok().and_then(|idx| values.get_mut(idx)),
+        _ => None
+    };
+
+    match (slot, rest) {
+        (Some(slot), Some(rest)) => set_field(slot, rest, new),
+        (Some(slot), None) => { *slot = new; true },
+        (None, _) => false
+    }
+}
+
+pub(crate) fn parse_transform_mode(mode: &str) -> Result<TransformMode, SceneError> {
+    match mode {
+        "ThreeD" => Ok(TransformMode::ThreeD),
+        "TwoD" => Ok(TransformMode::TwoD),
+        mode => Err(SceneError::UnknownTransformMode(mode.to_string()))
+    }
+}
+
+pub(crate) fn parse_uuid(uuid: &str) -> Result<NodeUuid, SceneError> { uuid.parse().map_err(|_| SceneError::InvalidUuid(uuid.to_string())) }
+
+pub(crate) fn load_component<C: ComponentDef<A>, A>(component: &Option<SceneComponent>) -> Result<C, SceneError> {
+    match component {
+        Some(component) => C::load_data(&component.variant, &component.data),
+        None => Ok(C::default())
+    }
+}
+
+impl<C: ComponentDef<A>, A> Node<C, A> {
+    /// Saves this node and all of its children, see `SceneNode`.
+    pub fn to_scene(&self) -> SceneNode {
+        let rotation = self.transform.rotation;
+        SceneNode {
+            name: self.name.clone(),
+            uuid: self.uuid.map(|uuid| uuid.to_string()),
+            position: self.transform.position.into(),
+            rotation: [rotation.v.x, rotation.v.y, rotation.v.z, rotation.s],
+            scale: self.transform.scale.into(),
+            rel_min_dimensions: [self.rel_min_dimensions.from.into(), self.rel_min_dimensions.to.into()],
+            transform_mode: transform_mode_name(self.transform_mode).to_string(),
+            confine_to_parent: self.confine_to_parent,
+            enabled: self.enabled,
+            tags: self.tags.clone(),
+            metadata: self.metadata.iter().map(|(key, value)| (key.clone(), value.clone())).collect(),
+            component: self.component.variant_index().map(|idx| SceneComponent {
+                variant: C::VARIANT_NAMES[idx].to_string(),
+                data: self.component.save_data().unwrap_or_default()
+            }),
+            children: self.children().iter().map(|child| child.to_scene()).collect(),
+            prefab: None,
+            overrides: BTreeMap::new(),
+            sub_scene: None,
+            lazy: false
+        }
+    }
+
+    /// Rebuilds a node and all of its children from a saved node.  Like `Node::builder`, no added functions are fired
+    /// until the node is attached with `add_child` or `World::spawn`.
+    ///
+    /// Returns the node, or the first error found in the saved tree, which must have its prefabs and sub-scenes resolved.
+    pub fn from_scene(scene: &SceneNode) -> Result<Self, SceneError> {
+        if let Some(prefab) = scene.prefab.as_ref().or(scene.sub_scene.as_ref()) { return Err(SceneError::UnresolvedPrefab(prefab.clone())) }
+
+        let [x, y, z, w] = scene.rotation;
+        let [from, to] = scene.rel_min_dimensions;
+        let mut builder = Node::builder()
+            .position(Vector3::from(scene.position))
+            .rotation(Quaternion::new(w, x, y, z))
+            .scale(Vector3::from(scene.scale))
+            .rel_min_dimensions(Dimensions { from: Vector3::from(from), to: Vector3::from(to) })
+            .transform_mode(parse_transform_mode(&scene.transform_mode)?)
+            .confine_to_parent(scene.confine_to_parent)
+            .enabled(scene.enabled);
+
+        if let Some(name) = &scene.name { builder = builder.name(name.clone()); }
+        if let Some(uuid) = &scene.uuid { builder = builder.uuid(parse_uuid(uuid)?); }
+        for tag in &scene.tags { builder = builder.tag(tag.clone()); }
+        for (key, value) in &scene.metadata { builder = builder.meta(key.clone(), value.clone()); }
+        if scene.component.is_some() { builder = builder.component(load_component(&scene.component)?); }
+        for child in &scene.children { builder = builder.child(Node::from_scene(child)?); }
+
+        Ok(builder.build())
+    }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Saves the whole tree as a scene with a schema version of 0, see `Node::to_scene`.
+    pub fn save_scene(&self) -> SceneFile { self.save_scene_versioned(0) }
+
+    /// Saves the whole tree as a scene with the given schema version, IE `SceneMigrations::version`.
+    pub fn save_scene_versioned(&self, schema_version: u32) -> SceneFile {
+        SceneFile { version: SCENE_VERSION, schema_version, root: self.root().to_scene() }
+    }
+
+    /// Creates a new world from a saved scene, firing the added functions of every node in it.
+    pub fn load_scene(scene: &SceneFile) -> Result<Self, SceneError> {
+        let mut root = Node::from_scene(&scene.root)?;
+        root.call_add_recr();
+        Ok(World::from_root(root))
+    }
+
+    /// Creates a new world from a saved scene like `load_scene`, then checks its tree for authoring mistakes, see
+    /// `Node::validate`.
+    ///
+    /// Returns the world with every warning found in it, or the first error found in the scene.
+    pub fn load_scene_validated(scene: &SceneFile) -> Result<(Self, Vec<ValidationWarning>), SceneError> {
+        let world = Self::load_scene(scene)?;
+        let warnings = world.root().validate();
+        Ok((world, warnings))
+    }
+
+    /// Creates a new world from a saved scene like `load_scene`, passing the asset paths of every component to the
+    /// given table before the added functions fire, see `Node::remap_assets`, so scenes saved before assets moved
+    /// still load them.
+    ///
+    /// Returns the world with the paths each variant rewrote, or the first error found in the scene.
+    pub fn load_scene_remapped(scene: &SceneFile, table: &RemapTable) -> Result<(Self, RemapReport), SceneError> {
+        let mut root = Node::from_scene(&scene.root)?;
+        let report = root.remap_assets(|path| table.remap(path));
+        root.call_add_recr();
+        Ok((World::from_root(root), report))
+    }
+
+    /// Loads the lazy sub-scene of the node with the given id, see `SceneNode::lazy`, spawning its root as the last child
+    /// of the node and removing `SUB_SCENE_META` from the node so it is not loaded again.
+    ///
+    /// Returns the id of the spawned root, None if the node does not exist or has no sub-scene to load, or the error
+    /// given when reading the sub-scene, in which case the node keeps its sub-scene.
+    pub fn load_sub_scene(&mut self, id: NodeId) -> Result<Option<NodeId>, SceneError> {
+        let Some(path) = self.get(id).and_then(|node| node.meta(SUB_SCENE_META)).map(str::to_string) else { return Ok(None) };
+        let scene = SceneFile::read(&path)?;
+        self.get_mut(id).unwrap().metadata.remove(SUB_SCENE_META);
+        self.spawn_scene(id, &scene)
+    }
+
+    /// The ids of every node with a lazy sub-scene that has not been loaded yet, see `load_sub_scene`.
+    pub fn pending_sub_scenes(&self) -> Vec<NodeId> {
+        self.iter(true).filter(|node| node.meta(SUB_SCENE_META).is_some()).filter_map(|node| node.id()).collect()
+    }
+
+    /// Spawns the root of a saved scene and all of its children under the given parent, see `World::spawn`.
+    ///
+    /// Returns the id of the spawned node, None if it could not be spawned, or the first error found in the scene.
+    pub fn spawn_scene(&mut self, parent: NodeId, scene: &SceneFile) -> Result<Option<NodeId>, SceneError> {
+        Ok(self.spawn(parent, Node::from_scene(&scene.root)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::ValidationKind;
+
+    fn scene() -> SceneFile {
+        let child = SceneNode { name: Some("child".to_string()), tags: vec!["a".to_string()], ..SceneNode::default() };
+        let data = SceneValue::from(vec![SceneValue::from(1.5), SceneValue::from("texture.png")]);
+        let root = SceneNode {
+            name: Some("root".to_string()),
+            component: Some(SceneComponent { variant: "Skybox".to_string(), data }),
+            children: vec![child],
+            ..SceneNode::default()
+        };
+        SceneFile { version: SCENE_VERSION, schema_version: 4, root }
+    }
+
+    #[allow(dead_code)]
+    mod world {
+        pub struct TestApp;
+
+        #[derive(Debug)]
+        pub struct Sprite(pub String);
+
+        #[derive(Debug)]
+        pub struct Sound(pub String);
+
+        crate::define_world!(TestApp, [
+            Sprite => {
+                DATA => Sprite,
+                ADDED => |data: &mut Sprite, _: &mut NodeContext| assert!(!data.0.starts_with("old/")),
+                REMAP_ASSETS => |data: &mut Sprite, remap: &dyn Fn(&str) -> Option<String>| {
+                    let Some(path) = remap(&data.0) else { return 0 };
+                    data.0 = path;
+                    1
+                },
+                SAVE => |data: &Sprite| crate::scenes::SceneValue::from(data.0.clone()),
+                LOAD => |value: &crate::scenes::SceneValue| Some(Sprite(value.as_str()?.to_string()))
+            },
+            Sound => {
+                DATA => Sound,
+                SAVE => |data: &Sound| crate::scenes::SceneValue::from(data.0.clone()),
+                LOAD => |value: &crate::scenes::SceneValue| Some(Sound(value.as_str()?.to_string()))
+            }
+        ]);
+    }
+
+    #[test]
+    fn load_scene_remapped_reports_rewritten_paths() {
+        let node = |variant: &str, path: &str| SceneNode {
+            component: Some(SceneComponent { variant: variant.to_string(), data: SceneValue::from(path) }),
+            ..SceneNode::default()
+        };
+        let root = SceneNode { children: vec![node("Sprite", "old/a.png"), node("Sprite", "b.png"), node("Sprite", "old/c.png"), node("Sound", "old/a.png")], ..SceneNode::default() };
+        let scene = SceneFile { version: SCENE_VERSION, schema_version: 0, root };
+        let table = RemapTable::parse("old/a.png = new/a.png\nold/c.png = new/c.png").unwrap();
+
+        let (world, report) = world::World::load_scene_remapped(&scene, &table).unwrap();
+        assert_eq!(report.counts, [("Sprite", 2), ("Sound", 0)]);
+        assert_eq!(report.total(), 2);
+        let paths: Vec<_> = world.iter_components::<world::Sprite>(false).map(|(_, sprite)| sprite.0.as_str()).collect();
+        assert_eq!(paths, ["new/a.png", "b.png", "new/c.png"]);
+        assert_eq!(world.iter_components::<world::Sound>(false).next().unwrap().1.0, "old/a.png");
+    }
+
+    #[test]
+    fn load_scene_validated_gives_warnings_and_errors() {
+        let named = |name: &str| SceneNode { name: Some(name.to_string()), ..SceneNode::default() };
+        let root = SceneNode { children: vec![named("a"), named("a"), SceneNode { scale: [0.0, 1.0, 1.0], ..named("b") }], ..SceneNode::default() };
+        let scene = SceneFile { version: SCENE_VERSION, schema_version: 0, root };
+
+        let (world, warnings) = world::World::load_scene_validated(&scene).unwrap();
+        assert_eq!(world.root().children().len(), 3);
+        let kinds: Vec<_> = warnings.iter().map(|warning| (warning.path.indices().to_vec(), warning.kind)).collect();
+        assert_eq!(kinds, [(vec![1], ValidationKind::DuplicateName), (vec![2], ValidationKind::ZeroScale)]);
+
+        let component = Some(SceneComponent { variant: "Missing".to_string(), data: SceneValue::Null });
+        let scene = SceneFile { root: SceneNode { component, ..SceneNode::default() }, ..scene };
+        assert!(matches!(world::World::load_scene_validated(&scene), Err(SceneError::UnknownVariant(_))));
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let scene = scene();
+        assert_eq!(SceneFile::from_binary(&scene.to_binary()).unwrap(), scene);
+    }
+
+    #[test]
+    fn truncated_scene_reports_the_end_of_the_read() {
+        let bytes = scene().to_binary();
+        for len in SCENE_MAGIC.len()..bytes.len() {
+            match SceneFile::from_binary(&bytes[..len]) {
+                Err(SceneError::Truncated(offset)) => assert!(offset > len, "truncated at {} but reported {}", len, offset),
+                result => panic!("truncated at {} but read {:?}", len, result)
+            }
+        }
+
+        // the version is the 4 bytes after the magic
+        assert_eq!(SceneFile::from_binary(&bytes[..6]), Err(SceneError::Truncated(8)));
+    }
+
+    #[test]
+    fn corrupt_scene_is_an_error() {
+        assert_eq!(SceneFile::from_binary(b"NOPE"), Err(SceneError::NotBinaryScene));
+
+        // an unknown value tag
+        let mut bytes = scene().to_binary();
+        let tag = bytes.iter().rposition(|byte| *byte == VALUE_ARRAY).unwrap();
+        bytes[tag] = 200;
+        assert!(matches!(SceneFile::from_binary(&bytes), Err(SceneError::Parse(_))));
+
+        // a list length far past the end of the scene
+        let mut bytes = scene().to_binary();
+        let tags = bytes.len() - 1 - bytes.iter().rev().position(|byte| *byte == b'a').unwrap() - 8;
+        bytes[tags..tags + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(SceneFile::from_binary(&bytes), Err(SceneError::Truncated(_))));
+    }
+
+    #[test]
+    fn deeply_nested_scene_is_an_error() {
+        let mut data = SceneValue::Null;
+        for _ in 0..MAX_BINARY_DEPTH { data = SceneValue::from(vec![data]); }
+        let mut deep = scene();
+        deep.root.component.as_mut().unwrap().data = data;
+        assert!(matches!(SceneFile::from_binary(&deep.to_binary()), Err(SceneError::TooDeep(_))));
+
+        // the root is the first level, so the deepest readable chain has one fewer child than the depth
+        let mut node = SceneNode::default();
+        for _ in 1..MAX_BINARY_DEPTH { node = SceneNode { children: vec![node], ..SceneNode::default() }; }
+        let deepest = SceneFile { root: node.clone(), ..scene() };
+        assert_eq!(SceneFile::from_binary(&deepest.to_binary()).unwrap(), deepest);
+
+        let deep = SceneFile { root: SceneNode { children: vec![node], ..SceneNode::default() }, ..scene() };
+        assert!(matches!(SceneFile::from_binary(&deep.to_binary()), Err(SceneError::TooDeep(_))));
+    }
+}
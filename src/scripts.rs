@@ -0,0 +1,79 @@
+use std::fmt;
+
+use crate::{
+    callbacks::RemovalReason,
+    components::WorldComponent,
+    nodes::{ComponentDef, NodeContext}
+};
+
+/// A one-off behavior of a node, held by a `Script` component, so small behaviors do not each need their own component
+/// variant.  Each function matches the callback of the same name in `define_world!`, taking the rest of the node as a
+/// `NodeContext`, and does nothing by default.
+pub trait NodeBehavior<C: ComponentDef<A>, A> {
+    fn added(&mut self, _: &mut NodeContext<'_, C, A>) {}
+    fn update(&mut self, _: &mut A, _: &mut NodeContext<'_, C, A>, _: f32) {}
+    fn removed(&mut self, _: &mut NodeContext<'_, C, A>, _: &RemovalReason) {}
+
+    /// The name of this behavior, used when the script is debug printed.
+    fn name(&self) -> &str { std::any::type_name::<Self>() }
+}
+
+/// The data of a script component, a boxed `NodeBehavior` whose functions are called as the components callbacks.
+/// This is added as a variant by `SCRIPT => Script` in `define_world!`, or can be held by a variant of an enum from
+/// `#[world]`, IE `Script(Script<Component, App>)`.
+///
+/// Behaviors do not have to be `Send` or `Sync`, so they can hold `Rc`s or other handles local to the thread.  Scripts
+/// cannot be cloned or serialized, so component enums holding them cannot derive `Clone` or serde's traits.
+pub struct Script<C, A>(pub Box<dyn NodeBehavior<C, A>>);
+
+impl<C: ComponentDef<A>, A> Script<C, A> {
+    /// Creates a script running the given behavior.
+    pub fn new(behavior: impl NodeBehavior<C, A> + 'static) -> Self { Self(Box::new(behavior)) }
+}
+
+impl<C: ComponentDef<A>, A> fmt::Debug for Script<C, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.debug_tuple("Script").field(&self.0.name()).finish() }
+}
+
+impl<C: ComponentDef<A>, A> WorldComponent<C, A> for Script<C, A> {
+    fn added(&mut self, ctx: &mut NodeContext<'_, C, A>) { self.0.added(ctx) }
+    fn update(&mut self, app: &mut A, ctx: &mut NodeContext<'_, C, A>, dt: f32) { self.0.update(app, ctx, dt) }
+    fn removed(&mut self, ctx: &mut NodeContext<'_, C, A>, reason: &RemovalReason) { self.0.removed(ctx, reason) }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::callbacks::RemovalReason;
+
+    use super::NodeBehavior;
+    use self::world::*;
+
+    #[allow(dead_code)]
+    mod world {
+        pub struct TestApp;
+
+        crate::define_world!(TestApp, [], SCRIPT => Script);
+    }
+
+    // records each call into a log shared with the test, which is not Send
+    struct Recorder(Rc<RefCell<Vec<&'static str>>>);
+
+    impl NodeBehavior<Component, TestApp> for Recorder {
+        fn added(&mut self, _: &mut NodeContext) { self.0.borrow_mut().push("added"); }
+        fn update(&mut self, _: &mut TestApp, _: &mut NodeContext, _: f32) { self.0.borrow_mut().push("update"); }
+        fn removed(&mut self, _: &mut NodeContext, _: &RemovalReason) { self.0.borrow_mut().push("removed"); }
+    }
+
+    #[test]
+    fn script_callbacks_all_fire() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut world = World::new();
+        let node = Node::with_script(super::Script::new(Recorder(log.clone())));
+        let id = world.spawn(world.root_id(), node).unwrap();
+        world.update(&mut TestApp, 0.1);
+        assert!(world.despawn(id));
+        assert_eq!(*log.borrow(), ["added", "update", "removed"]);
+    }
+}
@@ -0,0 +1,272 @@
+use std::{fmt, fs, path::{Path, PathBuf}};
+
+use crate::{
+    ids::NodeId,
+    nodes::{ComponentDef, World},
+    paths::NodePath,
+    scenes::{SceneError, SceneFile, SCENE_VERSION}
+};
+
+/// A subtree of a world loaded from a scene file, which `World::save_dirty` writes back to the file once it changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneSource {
+    root: NodeId,
+    path: PathBuf,
+    schema_version: u32,
+    dirty: bool
+}
+
+impl SceneSource {
+    /// The id of the root of the subtree, which is saved as the root of the scene.
+    pub fn root(&self) -> NodeId { self.root }
+
+    /// The path of the scene file the subtree is saved to.
+    pub fn path(&self) -> &Path { &self.path }
+}
+
+/// The scene files `World::save_dirty` could not write, along with those it did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaveError {
+    /// The paths of the files that were written, whose sources are no longer dirty.
+    pub saved: Vec<PathBuf>,
+
+    /// The paths of the files that could not be written with the reason each failed, whose sources are still dirty.
+    pub failed: Vec<(PathBuf, SceneError)>
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} scene files could not be saved", self.failed.len(), self.failed.len() + self.saved.len())?;
+        self.failed.iter().try_for_each(|(path, error)| write!(f, "\n{}: {}", path.display(), error))
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Reads a scene file and spawns it under the given parent like `spawn_scene`, keeping the file as the source of
+    /// the spawned subtree for `save_dirty`.  If the parent is inside another source, the scene becomes part of that
+    /// source instead.
+    ///
+    /// Returns the id of the spawned node, None if it could not be spawned, or the error given when reading the file.
+    pub fn spawn_scene_file(&mut self, parent: NodeId, path: impl AsRef<Path>) -> Result<Option<NodeId>, SceneError> {
+        let scene = SceneFile::read(path.as_ref())?;
+        let Some(id) = self.spawn_scene(parent, &scene)? else { return Ok(None) };
+        if self.set_scene_source(id, path) { self.sources.last_mut().unwrap().schema_version = scene.schema_version; }
+        Ok(Some(id))
+    }
+
+    /// Keeps the given scene file as the source of the subtree of the node with the given id, IE one loaded with
+    /// `spawn_scene`, replacing any source it had.  The subtree starts out clean, so it is only written once it
+    /// changes.  Nodes spawned into the subtree later belong to the same file.
+    ///
+    /// Returns false if the node does not exist, or is inside or holds the subtree of another source, as each node can
+    /// only be saved to one file.
+    pub fn set_scene_source(&mut self, id: NodeId, path: impl AsRef<Path>) -> bool {
+        let Some(node_path) = self.path_of(id) else { return false };
+        self.sources.retain(|source| source.root != id);
+        let overlaps = self.source_paths().into_iter().flatten().any(|other| other.starts_with(&node_path) || node_path.starts_with(&other));
+        if overlaps { return false }
+
+        self.get_mut(id).unwrap().clear_edits();
+        self.sources.push(SceneSource { root: id, path: path.as_ref().to_path_buf(), schema_version: 0, dirty: false });
+        true
+    }
+
+    /// Stops saving the subtree of the node with the given id to its scene file.
+    ///
+    /// Returns false if the node was not the root of a source.
+    pub fn clear_scene_source(&mut self, id: NodeId) -> bool {
+        let count = self.sources.len();
+        self.sources.retain(|source| source.root != id);
+        self.sources.len() != count
+    }
+
+    /// The scene file sources of this world, in the order they were added.
+    pub fn scene_sources(&self) -> &[SceneSource] { &self.sources }
+
+    /// Checks if the subtree of the source with the given root changed since it was last saved, through the functions
+    /// of the world that change its tree or in an update, as change tracking sees it.  Changes to the local
+    /// transform, component or children of a node are seen by its next update, assuming `WorldConfig::track_changes`
+    /// is on, changes to anything else through `mark_scene_dirty`.
+    ///
+    /// Returns false if the node is not the root of a source, or no longer exists.
+    pub fn is_scene_dirty(&self, root: NodeId) -> bool {
+        let Some(source) = self.sources.iter().find(|source| source.root == root) else { return false };
+        source.dirty || self.get(root).is_some_and(|node| node.is_edited())
+    }
+
+    /// Marks the source holding the node with the given id as changed, for the changes `is_scene_dirty` does not see,
+    /// IE to the name, tags or metadata of a node.
+    ///
+    /// Returns false if the node is not inside any source.
+    pub fn mark_scene_dirty(&mut self, id: NodeId) -> bool {
+        let Some(source) = self.source_of(id) else { return false };
+        self.sources[source].dirty = true;
+        true
+    }
+
+    /// The ids of the topmost nodes that are not saved by any source, as they are neither inside a source nor hold
+    /// one, IE nodes spawned while the app runs outside the sourced subtrees.  The root is never reported.
+    pub fn unsaved_nodes(&mut self) -> Vec<NodeId> {
+        let sources: Vec<NodePath> = self.source_paths().into_iter().flatten().collect();
+        let mut unsaved = Vec::new();
+        let mut path = NodePath::new();
+        let mut stack = vec![(self.root(), 0)];
+        while let Some((node, idx)) = stack.pop() {
+            let Some(child) = node.children().get(idx) else {
+                path.pop();
+                continue
+            };
+            stack.push((node, idx + 1));
+
+            path.push(idx as u16);
+            if sources.iter().any(|source| source.starts_with(&path) && *source != path) {
+                stack.push((child, 0));
+                continue
+            }
+            if !sources.contains(&path) { unsaved.extend(child.id()); }
+            path.pop();
+        }
+        unsaved
+    }
+
+    /// Writes the subtree of every dirty source back to its scene file as JSON, see `is_scene_dirty`, leaving the
+    /// files of clean sources untouched.  Sources whose root was despawned are dropped.
+    ///
+    /// Returns the paths of the written files, or every file that could not be written along with those that were, in
+    /// which case the sources that failed are still dirty and are written again by the next save.
+    pub fn save_dirty(&mut self) -> Result<Vec<PathBuf>, SaveError> {
+        let paths = self.source_paths();
+        let mut sources = std::mem::take(&mut self.sources);
+        let (mut saved, mut failed) = (Vec::new(), Vec::new());
+        for (source, path) in sources.iter_mut().zip(&paths) {
+            let Some(path) = path else { continue };
+            let node = self.root().resolve_path(path).unwrap();
+            if !source.dirty && !node.is_edited() { continue }
+
+            let scene = SceneFile { version: SCENE_VERSION, schema_version: source.schema_version, root: node.to_scene() };
+            match fs::write(&source.path, scene.to_json()) {
+                Ok(()) => {
+                    source.dirty = false;
+                    self.root_mut().resolve_path_mut(path).unwrap().clear_edits();
+                    saved.push(source.path.clone());
+                },
+                Err(error) => failed.push((source.path.clone(), SceneError::Io(error.to_string())))
+            }
+        }
+
+        self.sources = sources.into_iter().zip(paths).filter(|(_, path)| path.is_some()).map(|(source, _)| source).collect();
+        if failed.is_empty() { Ok(saved) } else { Err(SaveError { saved, failed }) }
+    }
+
+    // marks the source holding the node with the given id as dirty, if there is one
+    pub(crate) fn mark_source_dirty(&mut self, id: NodeId) {
+        if let Some(source) = self.source_of(id) { self.sources[source].dirty = true; }
+    }
+
+    // the index of the source holding the node with the given id
+    fn source_of(&mut self, id: NodeId) -> Option<usize> {
+        if self.sources.is_empty() { return None }
+        let path = self.path_of(id)?;
+        self.source_paths().iter().position(|root| root.as_ref().is_some_and(|root| path.starts_with(root)))
+    }
+
+    // the path of the root of each source, None for sources whose root no longer exists
+    fn source_paths(&mut self) -> Vec<Option<NodePath>> {
+        (0..self.sources.len()).map(|idx| self.path_of(self.sources[idx].root)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::{Path, PathBuf}, time::{Duration, SystemTime}};
+
+    use cgmath::Vector3;
+
+    use crate::scenes::{SceneError, SceneFile, SceneNode, SCENE_VERSION};
+
+    use crate::test_support::*;
+
+    // writes a scene with the given child names to a file in a fresh temp directory, dated long ago
+    fn write(dir: &Path, file: &str, names: &[&str]) -> PathBuf {
+        let children = names.iter().map(|name| SceneNode { name: Some(name.to_string()), ..SceneNode::default() }).collect();
+        let scene = SceneFile { version: SCENE_VERSION, schema_version: 2, root: SceneNode { children, ..SceneNode::default() } };
+        let path = dir.join(file);
+        fs::write(&path, scene.to_json()).unwrap();
+        fs::File::options().write(true).open(&path).unwrap().set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1)).unwrap();
+        path
+    }
+
+    fn modified(path: &Path) -> SystemTime { fs::metadata(path).unwrap().modified().unwrap() }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("forte_world_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn only_touched_files_are_saved() {
+        let dir = temp_dir("sources");
+        let (a, b) = (write(&dir, "a.json", &["door"]), write(&dir, "b.json", &["tree"]));
+        let mut world = World::new();
+        let root = world.root_id();
+        let chunk_a = world.spawn_scene_file(root, &a).unwrap().unwrap();
+        let chunk_b = world.spawn_scene_file(root, &b).unwrap().unwrap();
+        world.update(&mut TestApp, 0.1);
+
+        // nothing changed yet
+        assert_eq!(world.save_dirty(), Ok(Vec::new()));
+        let old = modified(&a);
+        assert_eq!(modified(&b), old);
+
+        // a moved door only touches its own file
+        let door = world.get(chunk_a).unwrap().children()[0].id().unwrap();
+        world.get_mut(door).unwrap().transform.position = Vector3::new(1.0, 0.0, 0.0);
+        world.update(&mut TestApp, 0.1);
+        assert!(world.is_scene_dirty(chunk_a) && !world.is_scene_dirty(chunk_b));
+        assert_eq!(world.save_dirty(), Ok(vec![a.clone()]));
+        assert_ne!(modified(&a), old);
+        assert_eq!(modified(&b), old);
+        let saved = SceneFile::read(&a).unwrap();
+        assert_eq!(saved.schema_version, 2);
+        assert_eq!(saved.root.children[0].position, [1.0, 0.0, 0.0]);
+        assert!(!world.is_scene_dirty(chunk_a));
+
+        // a node spawned under a chunk belongs to its file, one spawned elsewhere to no file
+        world.spawn(chunk_b, marker(1)).unwrap();
+        let loose = world.spawn(root, marker(2)).unwrap();
+        assert_eq!(world.save_dirty(), Ok(vec![b.clone()]));
+        assert_eq!(SceneFile::read(&b).unwrap().root.children.len(), 2);
+        assert_eq!(world.unsaved_nodes(), [loose]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failed_writes_stay_dirty() {
+        let dir = temp_dir("sources_failed");
+        let (a, b) = (write(&dir, "a.json", &[]), write(&dir, "b.json", &[]));
+        let mut world = World::new();
+        let root = world.root_id();
+        let chunk_a = world.spawn_scene_file(root, &a).unwrap().unwrap();
+        let chunk_b = world.spawn_scene_file(root, &b).unwrap().unwrap();
+        let missing = dir.join("missing").join("b.json");
+        assert!(world.set_scene_source(chunk_b, &missing));
+        world.spawn(chunk_a, marker(1)).unwrap();
+        world.spawn(chunk_b, marker(2)).unwrap();
+
+        let error = world.save_dirty().unwrap_err();
+        assert_eq!(error.saved, [a]);
+        assert_eq!(error.failed.len(), 1);
+        assert_eq!(error.failed[0].0, missing);
+        assert!(matches!(error.failed[0].1, SceneError::Io(_)));
+        assert!(!world.is_scene_dirty(chunk_a) && world.is_scene_dirty(chunk_b));
+
+        // the failed file is written once it can be
+        assert!(world.set_scene_source(chunk_b, &b));
+        assert!(world.mark_scene_dirty(chunk_b));
+        assert_eq!(world.save_dirty(), Ok(vec![b]));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,108 @@
+/// The result of `Node::stats` and `World::stats`, describing the size and shape of a tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeStats {
+    /// The number of nodes in the tree, including the root.
+    pub nodes: usize,
+
+    /// The number of levels below the root of the deepest node, 0 for a lone root.
+    pub max_depth: usize,
+
+    /// The number of nodes with an empty component.
+    pub empty: usize,
+
+    /// The name of each variant and the number of nodes holding it, in the order the variants were defined.
+    pub components: Vec<(&'static str, usize)>,
+
+    /// The total capacity of the children lists of every node, which may be larger than the number of children.
+    pub children_capacity: usize
+}
+
+impl TreeStats {
+    /// Creates new stats of an empty tree with a count of 0 for every given variant.
+    pub fn new(variant_names: &[&'static str]) -> Self {
+        Self { nodes: 0, max_depth: 0, empty: 0, components: variant_names.iter().map(|name| (*name, 0)).collect(), children_capacity: 0 }
+    }
+
+    /// The number of nodes holding the variant with the given name, 0 if there is no such variant.
+    pub fn for_variant(&self, name: &str) -> usize {
+        self.components.iter().find(|(variant, _)| *variant == name).map_or(0, |(_, count)| *count)
+    }
+}
+
+// the running totals behind `World::stats`, kept up to date by the functions of the world that change its tree
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StatCounters {
+    nodes: usize,
+    empty: usize,
+    components: Vec<usize>,
+    // the number of nodes at each depth, so the deepest level is still known once the deepest node is removed
+    depths: Vec<usize>,
+    children_capacity: usize
+}
+
+impl StatCounters {
+    // counts a node with the given variant at the given depth, whose children list has the given capacity
+    pub(crate) fn add(&mut self, variant: Option<usize>, depth: usize, children_capacity: usize) {
+        self.nodes += 1;
+        self.children_capacity += children_capacity;
+        *self.variant_mut(variant) += 1;
+        if self.depths.len() <= depth { self.depths.resize(depth + 1, 0); }
+        self.depths[depth] += 1;
+    }
+
+    // stops counting a node given to `add` with the same arguments, the counts stay at 0 rather than underflowing if
+    // the tree was changed without the world knowing, until it is counted again
+    pub(crate) fn remove(&mut self, variant: Option<usize>, depth: usize, children_capacity: usize) {
+        self.nodes = self.nodes.saturating_sub(1);
+        self.children_capacity = self.children_capacity.saturating_sub(children_capacity);
+        let count = self.variant_mut(variant);
+        *count = count.saturating_sub(1);
+        if let Some(count) = self.depths.get_mut(depth) { *count = count.saturating_sub(1); }
+        while self.depths.last() == Some(&0) { self.depths.pop(); }
+    }
+
+    // moves a node from one variant to another
+    pub(crate) fn set_variant(&mut self, old: Option<usize>, new: Option<usize>) {
+        let count = self.variant_mut(old);
+        *count = count.saturating_sub(1);
+        *self.variant_mut(new) += 1;
+    }
+
+    // moves a node from holding the first variants to holding the second, holding none being an empty node
+    pub(crate) fn set_variants(&mut self, old: &[usize], new: &[usize]) {
+        if old.is_empty() { self.empty = self.empty.saturating_sub(1) }
+        old.iter().for_each(|variant| self.count_added(*variant, false));
+        if new.is_empty() { self.empty += 1 }
+        new.iter().for_each(|variant| self.count_added(*variant, true));
+    }
+
+    // counts a component added to a node beside its own, or stops counting it, leaving the node and its depth alone
+    pub(crate) fn count_added(&mut self, variant: usize, add: bool) {
+        let count = self.variant_mut(Some(variant));
+        *count = if add { *count + 1 } else { count.saturating_sub(1) };
+    }
+
+    // follows the capacity of a children list changing from one size to another
+    pub(crate) fn resize_children(&mut self, old: usize, new: usize) {
+        self.children_capacity = self.children_capacity.saturating_sub(old) + new;
+    }
+
+    // the number of nodes holding the given variant
+    pub(crate) fn variant(&self, variant: usize) -> usize { self.components.get(variant).copied().unwrap_or(0) }
+
+    pub(crate) fn to_stats(&self, variant_names: &[&'static str]) -> TreeStats {
+        let mut stats = TreeStats::new(variant_names);
+        stats.nodes = self.nodes;
+        stats.max_depth = self.depths.len().saturating_sub(1);
+        stats.empty = self.empty;
+        stats.children_capacity = self.children_capacity;
+        stats.components.iter_mut().zip(&self.components).for_each(|((_, count), counted)| *count = *counted);
+        stats
+    }
+
+    fn variant_mut(&mut self, variant: Option<usize>) -> &mut usize {
+        let Some(variant) = variant else { return &mut self.empty };
+        if self.components.len() <= variant { self.components.resize(variant + 1, 0); }
+        &mut self.components[variant]
+    }
+}
@@ -0,0 +1,89 @@
+use std::{collections::VecDeque, path::Path, thread::{self, JoinHandle}};
+
+use crate::{
+    ids::NodeId,
+    nodes::{ComponentDef, Node, World},
+    scenes::{SceneComponent, SceneError, SceneFile, SceneNode}
+};
+
+/// A scene being loaded over several frames, see `World::load_scene_async`.
+///
+/// The file is read and parsed on a worker thread.  Once it is parsed, each `poll` spawns a limited number of nodes as
+/// placeholders with empty components, and then swaps the real components into the placeholders, firing their added
+/// functions.  A load only makes progress while it is polled.
+pub struct SceneLoad {
+    parent: NodeId,
+    parsing: Option<JoinHandle<Result<SceneFile, SceneError>>>,
+    nodes: VecDeque<(NodeId, SceneNode)>,
+    components: VecDeque<(NodeId, SceneComponent)>,
+    root: Option<NodeId>
+}
+
+impl SceneLoad {
+    /// Starts reading the scene file at the given path on a worker thread, see `SceneFile::read`.  Its root will be
+    /// spawned under the given parent.
+    pub fn start(path: impl AsRef<Path>, parent: NodeId) -> Self {
+        let path = path.as_ref().to_path_buf();
+        Self {
+            parent,
+            parsing: Some(thread::spawn(move || SceneFile::read(path))),
+            nodes: VecDeque::new(),
+            components: VecDeque::new(),
+            root: None
+        }
+    }
+
+    /// Checks if the file is still being read and parsed.
+    pub fn is_parsing(&self) -> bool { self.parsing.is_some() }
+
+    /// The id of the spawned root of the scene, None if it has not been spawned yet.
+    pub fn root(&self) -> Option<NodeId> { self.root }
+
+    /// The number of nodes left to spawn and components left to swap in, 0 while parsing.
+    pub fn remaining(&self) -> usize { self.nodes.len() + self.components.len() }
+
+    /// Checks if the whole scene has been spawned with its components.
+    pub fn is_done(&self) -> bool { !self.is_parsing() && self.root.is_some() && self.remaining() == 0 }
+
+    /// Continues loading, spawning at most the given number of placeholders or components into the world.  Nodes are
+    /// spawned top down, so a node is always spawned before its children.  Nodes despawned while the scene is loading
+    /// are skipped, along with their children.
+    ///
+    /// Returns the id of the root of the scene once everything has been loaded, or the first error found, after which
+    /// the load stops and the nodes spawned so far are left in the world.
+    pub fn poll<C: ComponentDef<A>, A>(&mut self, world: &mut World<C, A>, budget: usize) -> Result<Option<NodeId>, SceneError> {
+        if let Some(parsing) = &self.parsing {
+            if !parsing.is_finished() { return Ok(None) }
+            let scene = self.parsing.take().unwrap().join().unwrap_or_else(|_| Err(SceneError::Parse("the scene reader panicked".to_string())))?;
+            self.nodes.push_back((self.parent, scene.root));
+        }
+
+        let mut budget = budget;
+        while budget > 0 {
+            if let Some((parent, mut scene)) = self.nodes.pop_front() {
+                budget -= 1;
+                let children = std::mem::take(&mut scene.children);
+                let component = scene.component.take();
+                let Some(id) = world.spawn(parent, Node::from_scene(&scene)?) else { continue };
+
+                if self.root.is_none() { self.root = Some(id); }
+                if let Some(component) = component { self.components.push_back((id, component)); }
+                self.nodes.extend(children.into_iter().map(|child| (id, child)));
+            } else if let Some((id, component)) = self.components.pop_front() {
+                budget -= 1;
+                let component = C::load_data(&component.variant, &component.data)?;
+                world.set_component(id, component);
+            } else {
+                break
+            }
+        }
+
+        Ok(if self.is_done() { self.root } else { None })
+    }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Starts loading the scene file at the given path without blocking, spawning its root under the given parent as
+    /// the returned load is polled, see `SceneLoad`.
+    pub fn load_scene_async(&self, path: impl AsRef<Path>, parent: NodeId) -> SceneLoad { SceneLoad::start(path, parent) }
+}
@@ -0,0 +1,42 @@
+// the world shared by the tests of every module, headless and drawing into a pass that records what it was given.  Not
+// every generated item is used by the tests.
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+
+pub struct TestApp;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Marker(pub u32);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Other;
+
+// records the ids of the markers drawn into it
+#[derive(Default)]
+pub struct TestPass<'a>(pub Vec<u32>, pub std::marker::PhantomData<&'a ()>);
+
+thread_local! {
+    static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn log(event: &str, node: &Node) {
+    let marker = node.get::<Marker>().unwrap().0;
+    LOG.with(|log| log.borrow_mut().push(format!("{event} {marker}")));
+}
+
+// takes the added and removed functions of the markers fired on this thread so far, IE `["added 1", "removed 1"]`
+pub fn take_log() -> Vec<String> { LOG.with(|log| std::mem::take(&mut *log.borrow_mut())) }
+
+pub fn marker(id: u32) -> Node { Node::with_marker(Marker(id)) }
+
+crate::define_world!(TestApp, [
+    Marker => {
+        DATA => Marker,
+        ADDED => |node: &mut Node| log("added", node),
+        UPDATE => |node: &mut Node| if node.name.as_deref() == Some("panic") { panic!("boom") },
+        RENDER => |pass: &mut TestPass<'a>, _: &'b TestApp, data: &'b Marker| pass.0.push(data.0),
+        REMOVED => |node: &mut Node| log("removed", node)
+    },
+    Other => { DATA => Other, CASTS_SHADOW => false }
+], DERIVE => [Clone], PASS => TestPass<'a>);
@@ -0,0 +1,270 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    errors::WorldError,
+    ids::NodeId,
+    names::NameCollisionPolicy,
+    nodes::{ComponentDef, Insertion, Node, World}
+};
+
+// a structural change waiting in a transaction
+enum StagedOp<C, A> {
+    Spawn { parent: NodeId, node: Box<Node<C, A>>, id: NodeId },
+    Despawn(NodeId),
+    Reparent { child: NodeId, parent: NodeId }
+}
+
+// the shape of a node as a transaction sees it, with its earlier staged changes applied
+struct StagedNode {
+    parent: Option<NodeId>,
+    children: usize,
+    despawned: bool
+}
+
+/// The structural changes of a `World::transaction`, which are checked against the world as it will be once every
+/// earlier change of the transaction is applied, but only applied once the whole transaction succeeds.
+pub struct WorldTx<'w, C, A> {
+    world: &'w mut World<C, A>,
+    ops: Vec<StagedOp<C, A>>,
+    // every node the transaction has looked at or spawned, nodes it has not are as they are in the world
+    staged: HashMap<NodeId, StagedNode>,
+    // the names spawned under each parent, checked along with the names in the world under the `Reject` policy
+    names: HashSet<(NodeId, String)>,
+    reserved: Vec<NodeId>,
+    // the nodes below the roots of the staged spawns, which have no ids reserved yet but count towards `max_nodes`
+    pending: usize
+}
+
+impl<C: ComponentDef<A>, A> WorldTx<'_, C, A> {
+    /// Stages spawning the given node as the last child of the parent, see `World::spawn`.  The id of the node is given
+    /// out right away, so later changes of the transaction can name it.
+    ///
+    /// Returns `NotFound` if the parent does not exist or is staged to be despawned, `TooManyChildren` if it is full,
+    /// `NameTaken` if it has a child with the same name in the world or in this transaction under the `Reject` policy,
+    /// or `TooManyNodes` or `TooDeep` if it does not fit within the limits of the config.  Under `Rename`, the node is
+    /// renamed as it is applied.
+    pub fn spawn(&mut self, parent: NodeId, node: Node<C, A>) -> Result<NodeId, WorldError> {
+        self.check_alive(parent)?;
+        if self.node(parent)?.children > u16::MAX as usize { return Err(WorldError::TooManyChildren(parent)) }
+        let depth = self.ancestors_of(parent)?.len();
+        self.world.check_limits(parent, depth, &node, self.pending)?;
+        if let Some(name) = node.name.as_ref().filter(|_| self.world.name_collision_policy() == NameCollisionPolicy::Reject) {
+            if self.world.is_name_taken(parent, name) || !self.names.insert((parent, name.clone())) { return Err(WorldError::NameTaken(parent, name.clone())) }
+        }
+
+        // reserved ids are not in the tree, so the ids must be up to date before the first one to stop a refresh
+        // freeing them while the transaction is applied
+        if self.reserved.is_empty() { self.world.refresh_ids(); }
+        let id = self.world.reserve_id();
+        self.reserved.push(id);
+        self.pending += node.count_nodes() - 1;
+        self.node(parent)?.children += 1;
+        self.staged.insert(id, StagedNode { parent: Some(parent), children: node.children().len(), despawned: false });
+        self.ops.push(StagedOp::Spawn { parent, node: Box::new(node), id });
+        Ok(id)
+    }
+
+    /// Stages despawning the node and its children, see `World::despawn`.
+    ///
+    /// Returns `NotFound` if the node does not exist or is already staged to be despawned, or `RootImmutable` for the
+    /// root.
+    pub fn despawn(&mut self, id: NodeId) -> Result<(), WorldError> {
+        if id == self.world.root_id() { return Err(WorldError::RootImmutable) }
+        self.check_alive(id)?;
+
+        let node = self.node(id)?;
+        node.despawned = true;
+        let parent = node.parent;
+        if let Some(parent) = parent { self.node(parent)?.children -= 1; }
+        self.ops.push(StagedOp::Despawn(id));
+        Ok(())
+    }
+
+    /// Stages moving the node to the end of the children of the new parent, see `World::reparent`.
+    ///
+    /// Returns `NotFound` if either node does not exist or is staged to be despawned, `RootImmutable` for the root,
+    /// `WouldCycle` if the new parent is the node or one of its descendants, `TooManyChildren` if the new parent is
+    /// full, or `TooDeep` if the node would end up deeper than the `max_depth` of the config.
+    pub fn reparent(&mut self, child: NodeId, parent: NodeId) -> Result<(), WorldError> {
+        if child == self.world.root_id() { return Err(WorldError::RootImmutable) }
+        self.check_alive(child)?;
+        self.check_alive(parent)?;
+        let depth = self.ancestors_of(parent)?;
+        if depth.contains(&child) || child == parent { return Err(WorldError::WouldCycle(child, parent)) }
+        if self.node(parent)?.children > u16::MAX as usize { return Err(WorldError::TooManyChildren(parent)) }
+        if let Some(max) = self.world.config.max_depth {
+            if depth.len() + 1 + self.staged_height(child)? > max { return Err(WorldError::TooDeep(parent)) }
+        }
+
+        let old = self.node(child)?.parent.replace(parent);
+        if let Some(old) = old { self.node(old)?.children -= 1; }
+        self.node(parent)?.children += 1;
+        self.ops.push(StagedOp::Reparent { child, parent });
+        Ok(())
+    }
+
+    /// Checks if the node with the given id will be in the world once the changes staged so far are applied.
+    pub fn is_alive(&mut self, id: NodeId) -> bool { self.check_alive(id).is_ok() }
+
+    // the staged shape of the given node, loaded from the world the first time it is needed
+    fn node(&mut self, id: NodeId) -> Result<&mut StagedNode, WorldError> {
+        if !self.staged.contains_key(&id) {
+            let node = self.world.get(id).ok_or(WorldError::NotFound(id))?;
+            let children = node.children().len();
+            let parent = self.world.parent(id).and_then(|parent| parent.id());
+            self.staged.insert(id, StagedNode { parent, children, despawned: false });
+        }
+        Ok(self.staged.get_mut(&id).unwrap())
+    }
+
+    // the staged ancestors of the given node, from its parent up to the root
+    fn ancestors_of(&mut self, id: NodeId) -> Result<Vec<NodeId>, WorldError> {
+        let mut ancestors = Vec::new();
+        let mut current = self.node(id)?.parent;
+        while let Some(parent) = current {
+            ancestors.push(parent);
+            current = self.node(parent)?.parent;
+        }
+        Ok(ancestors)
+    }
+
+    // the height of the given node with the staged changes applied, which may count nodes staged to move away but never
+    // misses nodes staged to move or spawn below it
+    fn staged_height(&mut self, id: NodeId) -> Result<usize, WorldError> {
+        let mut height = self.subtree_height(id);
+        let below: Vec<NodeId> = self.staged.iter().filter(|(other, node)| **other != id && !node.despawned).map(|(other, _)| *other).collect();
+        for other in below {
+            let Some(position) = self.ancestors_of(other)?.iter().position(|ancestor| *ancestor == id) else { continue };
+            height = height.max(position + 1 + self.subtree_height(other));
+        }
+        Ok(height)
+    }
+
+    // the height of the given node as it is in the world, or as it was staged to be spawned
+    fn subtree_height(&self, id: NodeId) -> usize {
+        if let Some(node) = self.world.get(id) { return node.height() }
+        self.ops.iter()
+            .find_map(|op| match op { StagedOp::Spawn { node, id: spawned, .. } if *spawned == id => Some(node.height()), _ => None })
+            .unwrap_or(0)
+    }
+
+    // a node is gone once it or any of its staged ancestors is staged to be despawned
+    fn check_alive(&mut self, id: NodeId) -> Result<(), WorldError> {
+        let alive = !self.node(id)?.despawned && self.ancestors_of(id)?.iter().all(|ancestor| !self.staged[ancestor].despawned);
+        if alive { Ok(()) } else { Err(WorldError::NotFound(id)) }
+    }
+}
+
+impl<C: ComponentDef<A>, A> World<C, A> {
+    /// Runs the given function with a `WorldTx` staging structural changes to this world, IE so pasting many nodes
+    /// and moving others either happens completely or not at all.  Each change is checked against the world with the
+    /// earlier changes of the transaction applied, so a node spawned by the transaction can be moved by it right away.
+    ///
+    /// If the function returns Ok, the changes are applied in the order they were staged, firing the added and remove
+    /// functions of the nodes they change once each.  If it returns an error, nothing is applied and no callbacks fire,
+    /// the error is returned, and the ids given out by the transaction never resolve.
+    pub fn transaction(&mut self, f: impl FnOnce(&mut WorldTx<'_, C, A>) -> Result<(), WorldError>) -> Result<(), WorldError> {
+        let mut tx = WorldTx { world: self, ops: Vec::new(), staged: HashMap::new(), names: HashSet::new(), reserved: Vec::new(), pending: 0 };
+        if let Err(error) = f(&mut tx) {
+            tx.reserved.into_iter().for_each(|id| { tx.world.ids.free(id); });
+            return Err(error)
+        }
+
+        // names were already checked against the `Reject` policy as they were staged
+        let ops = tx.ops;
+        let policy = match self.name_collision_policy() { NameCollisionPolicy::Reject => NameCollisionPolicy::Allow, policy => policy };
+        self.observed(|world| {
+            for op in ops {
+                let applied = match op {
+                    StagedOp::Spawn { parent, node, id } => world.insert_inner(parent, *node, Insertion { reserved: Some(id), policy, limited: false, ..Insertion::new(&world.config) }).is_ok(),
+                    StagedOp::Despawn(id) => world.despawn_inner(id),
+                    StagedOp::Reparent { child, parent } => world.reparent(child, parent)
+                };
+                debug_assert!(applied, "a staged change was checked but could not be applied");
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{errors::WorldError, ids::NodeId};
+
+    use crate::test_support::*;
+
+    // the id, parent id and marker of every node in pre-order
+    fn skeleton(world: &World) -> Vec<(Option<NodeId>, Option<NodeId>, Option<u32>)> {
+        world.iter(true)
+            .map(|node| {
+                let parent = node.id().and_then(|id| world.parent(id)).and_then(|parent| parent.id());
+                (node.id(), parent, node.get::<Marker>().map(|marker| marker.0))
+            })
+            .collect()
+    }
+
+    fn markers() -> (World, NodeId, NodeId) {
+        let mut world = World::new();
+        let a = world.spawn(world.root_id(), Node::with_marker(Marker(1))).unwrap();
+        let b = world.spawn(a, Node::with_marker(Marker(2))).unwrap();
+        take_log();
+        (world, a, b)
+    }
+
+    #[test]
+    fn failed_transactions_change_nothing() {
+        let (mut world, a, b) = markers();
+        let before = skeleton(&world);
+        let root = world.root_id();
+
+        let mut spawned = None;
+        let result = world.transaction(|tx| {
+            let new = tx.spawn(b, Node::with_marker(Marker(3)))?;
+            spawned = Some(new);
+            tx.reparent(new, root)?;
+            tx.despawn(a)?;
+            assert!(!tx.is_alive(b));
+
+            // b went with a
+            tx.reparent(b, root)
+        });
+        assert_eq!(result, Err(WorldError::NotFound(b)));
+        assert_eq!(skeleton(&world), before);
+        assert!(take_log().is_empty());
+        assert!(!world.is_alive(spawned.unwrap()));
+
+        // each failing check
+        assert_eq!(world.transaction(|tx| tx.despawn(root)), Err(WorldError::RootImmutable));
+        assert_eq!(world.transaction(|tx| tx.reparent(root, a)), Err(WorldError::RootImmutable));
+        assert_eq!(world.transaction(|tx| tx.reparent(a, b)), Err(WorldError::WouldCycle(a, b)));
+        assert_eq!(world.transaction(|tx| { tx.reparent(b, root)?; tx.reparent(a, b) }), Ok(()));
+        assert_eq!(world.parent(a).unwrap().id(), Some(b));
+    }
+
+    #[test]
+    fn callbacks_fire_once_in_order() {
+        let (mut world, a, b) = markers();
+        let root = world.root_id();
+
+        let mut ids = Vec::new();
+        let result = world.transaction(|tx| {
+            let c = tx.spawn(root, Node::with_marker(Marker(3)))?;
+            let d = tx.spawn(c, Node::with_marker(Marker(4)))?;
+            tx.reparent(b, d)?;
+            tx.reparent(d, root)?;
+            tx.despawn(a)?;
+            ids.extend([c, d]);
+            Ok(())
+        });
+        assert_eq!(result, Ok(()));
+        assert_eq!(take_log(), ["added 3", "added 4", "removed 1"]);
+
+        // the ids given out resolve to the spawned nodes
+        let [c, d] = ids[..] else { panic!() };
+        assert_eq!(world.get(c).unwrap().get::<Marker>().unwrap().0, 3);
+        assert_eq!(world.parent(d).unwrap().id(), Some(root));
+        assert_eq!(world.parent(b).unwrap().id(), Some(d));
+        assert!(!world.is_alive(a));
+        assert_eq!(world.id_count(), 4);
+    }
+}
@@ -0,0 +1,125 @@
+use std::{fmt, sync::atomic::{AtomicU64, Ordering}};
+
+use cgmath::{Quaternion, Vector3};
+use forte_engine::math::transforms::Transform;
+
+use crate::{easing::Easing, math::slerp};
+
+static NEXT_HANDLE: AtomicU64 = AtomicU64::new(0);
+
+/// A function called each step of a custom tween with the node and the eased progress.
+pub type TweenFn<N> = Box<dyn FnMut(&mut N, f32) + Send + Sync>;
+
+// a function called on the node once a tween finishes
+type CompleteFn<N> = Box<dyn FnOnce(&mut N) + Send + Sync>;
+
+/// Identifies a tween so that it can be cancelled.  Chained tweens share the handle of the first tween in the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TweenHandle(pub u64);
+
+impl TweenHandle {
+    // creates a handle that has not been given out before
+    fn next() -> Self { Self(NEXT_HANDLE.fetch_add(1, Ordering::Relaxed)) }
+}
+
+/// What a tween animates.  The transform targets animate from whatever the value is when the tween starts.
+pub enum TweenTarget<N> {
+    /// Moves the local position to the given position.
+    Position(Vector3<f32>),
+
+    /// Turns the local rotation to the given rotation along the shortest path.
+    Rotation(Quaternion<f32>),
+
+    /// Scales the local scale to the given scale.
+    Scale(Vector3<f32>),
+
+    /// Calls the given function each step with the eased progress.
+    Custom(TweenFn<N>)
+}
+
+/// A fire and forget animation of a single node, see `Node::tween`.
+pub struct Tween<N> {
+    handle: TweenHandle,
+    target: TweenTarget<N>,
+    duration: f32,
+    easing: Easing,
+    elapsed: f32,
+    start: Option<Transform>,
+    on_complete: Option<CompleteFn<N>>,
+    next: Option<Box<Tween<N>>>
+}
+
+impl<N> Tween<N> {
+    /// Creates a new tween that has not started.
+    ///
+    /// Arguments:
+    /// * target: TweenTarget<N> - What to animate.
+    /// * duration: f32 - How long the tween takes in seconds.
+    /// * easing: Easing - The easing curve to animate along.
+    ///
+    /// Returns the new tween.
+    pub fn new(target: TweenTarget<N>, duration: f32, easing: Easing) -> Self {
+        Self { handle: TweenHandle::next(), target, duration, easing, elapsed: 0.0, start: None, on_complete: None, next: None }
+    }
+
+    /// Gives this tween a function to call on its node once it finishes.
+    pub fn on_complete(mut self, callback: impl FnOnce(&mut N) + Send + Sync + 'static) -> Self {
+        self.on_complete = Some(Box::new(callback));
+        self
+    }
+
+    /// Adds a tween to start once this tween, and any tweens already chained after it, finish.
+    pub fn then(mut self, next: Tween<N>) -> Self {
+        let mut last = &mut self;
+        while last.next.is_some() { last = last.next.as_mut().unwrap(); }
+        last.next = Some(Box::new(next));
+        self
+    }
+
+    /// The handle of this tween.
+    pub fn handle(&self) -> TweenHandle { self.handle }
+
+    /// Advances this tween and applies it to the given node.
+    ///
+    /// Arguments:
+    /// * node: &mut N - The node being animated.
+    /// * transform: fn(&mut N) -> &mut Transform - Gets the local transform of the node.
+    /// * dt: f32 - The time since the last step in seconds.
+    ///
+    /// Returns true if this tween and every tween chained after it has finished.
+    pub fn step(&mut self, node: &mut N, transform: fn(&mut N) -> &mut Transform, mut dt: f32) -> bool {
+        loop {
+            let start = *self.start.get_or_insert_with(|| *transform(node));
+            let remaining = self.duration - self.elapsed;
+            self.elapsed = (self.elapsed + dt.max(0.0)).min(self.duration);
+            let progress = if self.duration > 0.0 { self.elapsed / self.duration } else { 1.0 };
+            let eased = self.easing.ease(progress);
+
+            match &mut self.target {
+                TweenTarget::Position(to) => transform(node).position = start.position + (*to - start.position) * eased,
+                TweenTarget::Rotation(to) => transform(node).rotation = slerp(start.rotation, *to, eased),
+                TweenTarget::Scale(to) => transform(node).scale = start.scale + (*to - start.scale) * eased,
+                TweenTarget::Custom(apply) => apply(node, eased)
+            }
+            if progress < 1.0 { return false }
+
+            // finished, so start the next tween in the chain with whatever time is left over
+            if let Some(callback) = self.on_complete.take() { callback(node); }
+            let Some(next) = self.next.take() else { return true };
+            *self = Tween { handle: self.handle, ..*next };
+            dt = (dt - remaining.max(0.0)).max(0.0);
+        }
+    }
+}
+
+impl<N> fmt::Debug for Tween<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tween")
+            .field("handle", &self.handle)
+            .field("duration", &self.duration)
+            .field("easing", &self.easing)
+            .field("elapsed", &self.elapsed)
+            .field("chained", &self.next.is_some())
+            .finish()
+    }
+}
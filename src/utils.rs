@@ -0,0 +1,41 @@
+/// Estimates how large a sphere appears on screen.
+/// 
+/// Arguments:
+/// * world_radius: f32 - The radius of the sphere in world units.
+/// * distance: f32 - The distance from the camera to the center of the sphere.
+/// * fov_y: f32 - The vertical field of view of the camera in radians.
+/// * viewport_height: u32 - The height of the viewport in pixels.
+/// 
+/// Returns the projected radius of the sphere in pixels.  If the camera is inside the sphere this is infinite, as the
+/// sphere covers the whole screen, so a distance of zero never divides by zero.
+pub fn projected_radius(world_radius: f32, distance: f32, fov_y: f32, viewport_height: u32) -> f32 {
+    if distance <= world_radius || distance <= 0.0 { return f32::INFINITY }
+    world_radius / (distance * (fov_y * 0.5).tan()) * (viewport_height as f32 * 0.5)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::projected_radius;
+
+    #[test]
+    fn projected_radius_shrinks_with_distance() {
+        // with a 90 degree field of view, a radius of 1 at a distance of 1 would reach the top of the screen
+        assert!((projected_radius(1.0, 10.0, FRAC_PI_2, 1000) - 50.0).abs() < 1e-3);
+        assert!((projected_radius(1.0, 20.0, FRAC_PI_2, 1000) - 25.0).abs() < 1e-3);
+        assert!((projected_radius(2.0, 20.0, FRAC_PI_2, 1000) - 50.0).abs() < 1e-3);
+        assert_eq!(projected_radius(0.0, 10.0, FRAC_PI_2, 1000), 0.0);
+    }
+
+    #[test]
+    fn projected_radius_near_and_inside() {
+        // just outside the sphere it is huge but finite
+        let close = projected_radius(1.0, 1.001, FRAC_PI_2, 1000);
+        assert!(close.is_finite() && close > 499.0);
+
+        // on the surface, inside, at the center and behind the camera it covers the whole screen
+        for distance in [1.0, 0.5, 0.0, -3.0] { assert_eq!(projected_radius(1.0, distance, FRAC_PI_2, 1000), f32::INFINITY); }
+        assert_eq!(projected_radius(0.0, 0.0, FRAC_PI_2, 1000), f32::INFINITY);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::paths::NodePath;
+
+/// How serious a validation warning is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The node will work but probably not as intended.
+    Warning,
+
+    /// The node, and possibly its children, will be broken.
+    Error
+}
+
+/// The kinds of mistakes that `Node::validate` looks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationKind {
+    /// The node has a component but its rel_min_dimensions are empty, so it will be invisible to culling and queries.
+    ZeroDimensions,
+
+    /// The from point of the nodes rel_min_dimensions is larger than the to point on at least one axis.
+    InvertedDimensions,
+
+    /// The nodes position is NaN or infinite.
+    NonFinitePosition,
+
+    /// The nodes rotation is NaN or infinite.
+    NonFiniteRotation,
+
+    /// The nodes scale is NaN or infinite.
+    NonFiniteScale,
+
+    /// The nodes scale is 0 on at least one axis, collapsing the node and all of its children.
+    ZeroScale,
+
+    /// The nodes scale is negative on at least one axis.
+    NegativeScale,
+
+    /// The node has the same name as an earlier sibling, so finding it by name finds the other.
+    DuplicateName
+}
+
+impl ValidationKind {
+    /// The severity of this kind of mistake.
+    pub fn severity(&self) -> Severity {
+        match self {
+            ValidationKind::ZeroDimensions | ValidationKind::NegativeScale | ValidationKind::DuplicateName => Severity::Warning,
+            _ => Severity::Error
+        }
+    }
+
+    /// A human readable description of this kind of mistake.
+    pub fn message(&self) -> &'static str {
+        match self {
+            ValidationKind::ZeroDimensions => "node has a component but empty rel_min_dimensions, it will be invisible to culling and queries",
+            ValidationKind::InvertedDimensions => "rel_min_dimensions from is larger than to on at least one axis",
+            ValidationKind::NonFinitePosition => "transform position is NaN or infinite",
+            ValidationKind::NonFiniteRotation => "transform rotation is NaN or infinite",
+            ValidationKind::NonFiniteScale => "transform scale is NaN or infinite",
+            ValidationKind::ZeroScale => "transform scale is zero on at least one axis, this collapses all children",
+            ValidationKind::NegativeScale => "transform scale is negative on at least one axis",
+            ValidationKind::DuplicateName => "node has the same name as an earlier sibling"
+        }
+    }
+}
+
+/// A single mistake found by `Node::validate`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationWarning {
+    pub path: NodePath,
+    pub kind: ValidationKind,
+    pub severity: Severity,
+    pub message: String
+}
+
+impl ValidationWarning {
+    /// Creates a new warning with the default severity and message of the given kind.
+    pub fn new(path: NodePath, kind: ValidationKind) -> Self {
+        Self { path, kind, severity: kind.severity(), message: kind.message().to_string() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Quaternion, Vector3};
+
+    use crate::{dimensions::Dimensions, paths::NodePath};
+
+    use super::{Severity, ValidationKind};
+    use crate::test_support::*;
+
+    // the kinds and paths of every warning given for the given node placed under an otherwise valid root
+    fn warnings(node: Node) -> Vec<(NodePath, ValidationKind)> {
+        let mut root = Node::default();
+        root.add_child(node);
+        root.validate().into_iter().map(|warning| (warning.path, warning.kind)).collect()
+    }
+
+    fn child_path() -> NodePath { NodePath::from(&[0][..]) }
+
+    #[test]
+    fn valid_tree_has_no_warnings() {
+        let mut node = marker(0);
+        node.rel_min_dimensions = Dimensions { from: Vector3::new(-1.0, -1.0, -1.0), to: Vector3::new(1.0, 1.0, 1.0) };
+        assert_eq!(warnings(node), []);
+        assert_eq!(warnings(Node::default()), []);
+    }
+
+    #[test]
+    fn non_finite_transforms() {
+        let mut node = Node::default();
+        node.transform.position.y = f32::NAN;
+        node.transform.rotation = Quaternion::new(f32::INFINITY, 0.0, 0.0, 0.0);
+        node.transform.scale.z = f32::NEG_INFINITY;
+        assert_eq!(warnings(node), [
+            (child_path(), ValidationKind::NonFinitePosition),
+            (child_path(), ValidationKind::NonFiniteRotation),
+            (child_path(), ValidationKind::NonFiniteScale)
+        ]);
+    }
+
+    #[test]
+    fn zero_and_negative_scales() {
+        let mut node = Node::default();
+        node.transform.scale = Vector3::new(0.0, -1.0, 1.0);
+        assert_eq!(warnings(node), [(child_path(), ValidationKind::ZeroScale), (child_path(), ValidationKind::NegativeScale)]);
+        assert_eq!(ValidationKind::ZeroScale.severity(), Severity::Error);
+        assert_eq!(ValidationKind::NegativeScale.severity(), Severity::Warning);
+    }
+
+    #[test]
+    fn inverted_and_zero_dimensions() {
+        let mut node = Node::default();
+        node.rel_min_dimensions = Dimensions { from: Vector3::new(1.0, 0.0, 0.0), to: Vector3::new(0.0, 1.0, 1.0) };
+        assert_eq!(warnings(node), [(child_path(), ValidationKind::InvertedDimensions)]);
+
+        // an empty node is not drawn or queried, so its empty dimensions are fine
+        assert_eq!(warnings(marker(0)), [(child_path(), ValidationKind::ZeroDimensions)]);
+    }
+
+    #[test]
+    fn duplicate_sibling_names() {
+        let named = |name: &str| {
+            let mut node = Node::default();
+            node.name = Some(name.to_string());
+            node
+        };
+        let mut parent = named("a");
+        [named("b"), named("c"), named("b"), Node::default(), Node::default(), named("b")].into_iter().for_each(|child| parent.add_child(child));
+
+        // cousins with the same name are fine
+        let mut root = Node::default();
+        root.add_child(parent);
+        root.add_child(named("b"));
+        let found: Vec<_> = root.validate().into_iter().map(|warning| (warning.path, warning.kind, warning.severity)).collect();
+        assert_eq!(found, [
+            (NodePath::from(&[0, 2][..]), ValidationKind::DuplicateName, Severity::Warning),
+            (NodePath::from(&[0, 5][..]), ValidationKind::DuplicateName, Severity::Warning)
+        ]);
+    }
+}
@@ -0,0 +1,66 @@
+use std::{alloc::{GlobalAlloc, Layout, System}, cell::Cell};
+
+use forte_world::define_world;
+
+// counts the allocations made on each thread, so a test can check a call does not allocate
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) { System.dealloc(ptr, layout) }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize { ALLOCATIONS.with(|count| count.get()) }
+
+pub struct HeadlessApp;
+
+#[derive(Debug)]
+pub struct Sprite { layer: u64 }
+
+define_world!(
+    HeadlessApp,
+    [
+        Sprite => {
+            DATA => Sprite,
+            RENDER => |_: &mut wgpu::RenderPass<'a>, _: &'b HeadlessApp, _: &'b Sprite| {},
+            PRIORITY => 1,
+            SORT_KEY => |data: &Sprite| data.layer
+        }
+    ]
+);
+
+#[test]
+fn recycled_frames_do_not_allocate() {
+    let mut world = World::new();
+    let root = world.root_id();
+    for layer in 0..64 {
+        let parent = world.spawn(root, Node::with_sprite(Sprite { layer: 64 - layer })).unwrap();
+        world.spawn(parent, Node::with_sprite(Sprite { layer })).unwrap();
+    }
+    world.update(&mut HeadlessApp, 0.0);
+
+    let payload = |component: &Component| match component {
+        Component::Sprite(sprite) => Some(sprite.layer),
+        _ => None
+    };
+    let first = world.finalize_frame_with(None, payload);
+    assert_eq!(first.len(), 128);
+    world.recycle_frame(first);
+
+    let before = allocations();
+    let second = world.finalize_frame_with(None, payload);
+    assert_eq!(allocations(), before);
+    assert_eq!(second.len(), 128);
+    assert!(second.items().windows(2).all(|pair| pair[0].sort_key <= pair[1].sort_key));
+}
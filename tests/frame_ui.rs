@@ -0,0 +1,6 @@
+// payloads of finalized frames must own their data and be sendable, so they cannot borrow the world or share an Rc
+#[test]
+fn unsendable_frame_payloads() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/frame_*.rs");
+}
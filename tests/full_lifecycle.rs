@@ -0,0 +1,132 @@
+use std::cell::Cell;
+
+use cgmath::Vector3;
+use forte_engine::math::transforms::Transform;
+use forte_world::{define_world, dimensions::Dimensions, scene};
+
+// a headless app, the world never needs a window or a gpu device
+pub struct HeadlessApp;
+
+#[derive(Debug)]
+pub struct Spinner { speed: f32, ticks: usize }
+
+#[derive(Debug)]
+pub struct Marker { id: u32 }
+
+thread_local! {
+    static ADDED: Cell<usize> = const { Cell::new(0) };
+    static UPDATED: Cell<usize> = const { Cell::new(0) };
+    static REMOVED: Cell<usize> = const { Cell::new(0) };
+}
+
+fn bump(counter: &'static std::thread::LocalKey<Cell<usize>>) { counter.with(|count| count.set(count.get() + 1)); }
+fn read(counter: &'static std::thread::LocalKey<Cell<usize>>) -> usize { counter.with(|count| count.get()) }
+
+define_world!(
+    HeadlessApp,
+    [
+        Spinner => {
+            DATA => Spinner,
+            ADDED => |_: &mut Node| { bump(&ADDED); },
+            UPDATE => |node: &mut Node| {
+                bump(&UPDATED);
+                if let Component::Spinner(spinner) = &mut node.component {
+                    spinner.ticks += 1;
+                    node.transform.position.y += spinner.speed;
+                }
+            },
+            RENDER => |_: &mut wgpu::RenderPass<'a>, _: &'b HeadlessApp, _: &'b Spinner| {},
+            REMOVED => |_: &mut Node| { bump(&REMOVED); }
+        },
+        Marker => {
+            DATA => Marker,
+            ADDED => |_: &mut Node| { bump(&ADDED); },
+            UPDATE => |_: &mut Node| { bump(&UPDATED); },
+            RENDER => |_: &mut wgpu::RenderPass<'a>, _: &'b HeadlessApp, _: &'b Marker| {},
+            REMOVED => |_: &mut Node| { bump(&REMOVED); }
+        }
+    ]
+);
+
+fn unit_box() -> Dimensions { Dimensions { from: Vector3::new(-0.5, -0.5, -0.5), to: Vector3::new(0.5, 0.5, 0.5) } }
+
+fn marker(id: u32, x: f32) -> Node {
+    scene! { component: Component::Marker(Marker { id }), position: (x, 0.0, 0.0), rel_min_dimensions: unit_box() }
+}
+
+// the component kinds of a tree in pre-order, with the depth of each node
+fn skeleton(node: &Node, depth: usize, out: &mut Vec<(usize, String)>) {
+    let kind = match &node.component {
+        Component::Empty => "Empty".to_string(),
+        Component::Spinner(_) => "Spinner".to_string(),
+        Component::Marker(marker) => format!("Marker{}", marker.id)
+    };
+    out.push((depth, kind));
+    node.children().iter().for_each(|child| skeleton(child, depth + 1, out));
+}
+
+#[test]
+fn full_lifecycle() {
+    let mut root = Node::default();
+    root.add_child(scene! {
+        component: Component::Spinner(Spinner { speed: 0.1, ticks: 0 }),
+        rel_min_dimensions: unit_box(),
+        children: [
+            { component: Component::Marker(Marker { id: 1 }), position: (2.0, 0.0, 0.0), rel_min_dimensions: unit_box() },
+            { component: Component::Marker(Marker { id: 2 }), position: (-2.0, 0.0, 0.0), rel_min_dimensions: unit_box() }
+        ]
+    });
+    assert_eq!(read(&ADDED), 3);
+
+    // run 100 updates, spawning a marker every 10 frames and despawning every other one
+    let mut spawned = 0;
+    for frame in 0..100 {
+        if frame % 10 == 0 {
+            spawned += 1;
+            root.add_child(marker(100 + spawned, 10.0 + spawned as f32 * 2.0));
+        }
+        if frame % 20 == 19 { root.remove_child(root.children().len() - 1); }
+        root.update(&Transform::default(), &mut HeadlessApp, 1.0 / 60.0);
+    }
+
+    // 3 starting nodes, 10 spawned and 5 despawned
+    assert_eq!(read(&ADDED), 13);
+    assert_eq!(read(&REMOVED), 5);
+    assert_eq!(root.count_nodes(), 9);
+
+    // the spinner ran every frame and moved up by its speed each time, its update runs after its global transform is
+    // found so the global transform is one frame behind
+    let (spinner_node, spinner) = root.find_data_spinner(|_| true).expect("spinner should survive");
+    assert_eq!(spinner.ticks, 100);
+    assert!((spinner_node.transform.position.y - 10.0).abs() < 1e-3);
+    assert!((spinner_node.global_transform().position.y - 9.9).abs() < 1e-3);
+
+    // children follow the spinner up
+    let (child, _) = root.find_data_marker(|marker| marker.id == 1).unwrap();
+    assert!((child.global_transform().position.y - 9.9).abs() < 1e-3);
+
+    // queries and raycasts see the final transforms
+    let nearest = root.k_nearest(Vector3::new(11.0, 0.0, 0.0), 2, f32::INFINITY, |node| matches!(node.component, Component::Marker(_)));
+    assert_eq!(nearest.len(), 2);
+    assert!(nearest[0].1 <= nearest[1].1);
+
+    let hit = root.raycast_path(Vector3::new(2.0, 20.0, 0.0), -Vector3::unit_y(), 100.0).expect("ray should hit marker 1");
+    let hit_node = root.resolve_path(&hit.0).unwrap();
+    assert!(matches!(hit_node.component, Component::Marker(Marker { id: 1 })));
+    assert!((hit.1 - 9.6).abs() < 1e-3);
+
+    let overlapping = root.query_overlapping_paths(&Dimensions { from: Vector3::new(-3.0, 9.0, -1.0), to: Vector3::new(3.0, 11.0, 1.0) });
+    assert_eq!(overlapping.len(), 3);
+
+    // the tree has the expected shape and no mistakes
+    let mut shape = Vec::new();
+    skeleton(&root, 0, &mut shape);
+    let expected: Vec<(usize, String)> = [(0, "Empty"), (1, "Spinner"), (2, "Marker1"), (2, "Marker2"), (1, "Marker101"), (1, "Marker103"), (1, "Marker105"), (1, "Marker107"), (1, "Marker109")]
+        .into_iter().map(|(depth, kind)| (depth, kind.to_string())).collect();
+    assert_eq!(shape, expected);
+    assert!(root.validate().is_empty(), "{:?}", root.validate());
+
+    // removing everything fires every remaining remove function
+    while !root.children().is_empty() { root.remove_child(0); }
+    assert_eq!(read(&REMOVED), read(&ADDED));
+}
@@ -0,0 +1,6 @@
+// each malformed scene! key must fail with the named compile_error, not an error from deep inside the macro
+#[test]
+fn malformed_scene_keys() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/scene_*.rs");
+}
@@ -0,0 +1,20 @@
+use std::rc::Rc;
+
+use forte_world::define_world;
+
+pub struct App;
+
+#[derive(Debug)]
+pub struct Sprite(u32);
+
+define_world!(App, [
+    Sprite => { DATA => Sprite, RENDER => |_: &mut wgpu::RenderPass<'a>, _: &'b App, _: &'b Sprite| {} }
+]);
+
+fn main() {
+    let mut world = World::new();
+    let _ = world.finalize_frame_with(None, |component: &Component| match component {
+        Component::Sprite(sprite) => Some(Rc::new(sprite.0)),
+        _ => None
+    });
+}
@@ -0,0 +1,9 @@
+use forte_world::{define_world, scene};
+
+pub struct App;
+
+define_world!(App, []);
+
+fn main() {
+    let _ = scene! { children: [ name: "child" ] };
+}
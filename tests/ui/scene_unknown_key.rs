@@ -0,0 +1,9 @@
+use forte_world::{define_world, scene};
+
+pub struct App;
+
+define_world!(App, []);
+
+fn main() {
+    let _ = scene! { name: "root", colour: (1.0, 0.0, 0.0) };
+}